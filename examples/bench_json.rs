@@ -0,0 +1,49 @@
+//! Benchmarks decoding of GLB fixtures and prints a JSON report.
+//!
+//! cargo run --release --example bench_json -- [--iterations N] [--baseline report.json]
+//!     [--tolerance 0.1] fixture.glb...
+//!
+//! With `--baseline`, exits with status 1 if any fixture regressed beyond the tolerance.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut iterations = 10;
+    let mut baseline = None;
+    let mut tolerance = 0.1;
+    let mut fixtures = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--iterations" => iterations = args.next().ok_or("missing value")?.parse()?,
+            "--baseline" => baseline = Some(args.next().ok_or("missing value")?),
+            "--tolerance" => tolerance = args.next().ok_or("missing value")?.parse()?,
+            _ => fixtures.push(arg),
+        }
+    }
+
+    let mut report = draco_gltf_rs::BenchReport::default();
+    for path in &fixtures {
+        let mut file = std::fs::File::open(path)?;
+        let glb = gltf::Gltf::from_reader_without_validation(&mut file)?;
+        let buffers = gltf::import_buffers(&glb.document, None, glb.blob)?;
+        report.fixtures.push(draco_gltf_rs::bench_document(
+            path,
+            &glb.document,
+            &buffers,
+            iterations,
+        )?);
+    }
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    if let Some(path) = baseline {
+        let baseline: draco_gltf_rs::BenchReport = serde_json::from_slice(&std::fs::read(path)?)?;
+        let regressions = report.regressions(&baseline, tolerance);
+        for r in &regressions {
+            eprintln!("regression: {r}");
+        }
+        if !regressions.is_empty() {
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use crate::*;
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+
+/// Cancels decodes from another task or thread, e.g. when a scene is unloaded.
+///
+/// Dropping a decode future already stops it between primitives and releases any
+/// [`set_max_concurrent_decodes`] slot it waits for; a single Draco call runs to completion
+/// once started, since the backend has no abort hook. The handle lets code that doesn't own
+/// the future, such as the worker threads of [`DocumentIndex::decode_parallel_abortable`],
+/// stop early. Clones share state.
+#[derive(Debug, Clone, Default)]
+pub struct AbortHandle(Arc<Shared>);
+
+#[derive(Debug, Default)]
+struct Shared {
+    aborted: AtomicBool,
+    /// Wakers of pending [`abortable`] calls, keyed by call so each removes its own.
+    wakers: Mutex<Vec<(u64, Waker)>>,
+    next_id: AtomicU64,
+}
+
+impl AbortHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks every decode using this handle as aborted and wakes the waiting ones.
+    pub fn abort(&self) {
+        self.0.aborted.store(true, Ordering::Release);
+        let wakers = std::mem::take(&mut *self.0.lock());
+        for (_, w) in wakers {
+            w.wake();
+        }
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.0.aborted.load(Ordering::Acquire)
+    }
+
+    /// Number of pending [`abortable`] calls registered with this handle.
+    pub fn pending(&self) -> usize {
+        self.0.lock().len()
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<(u64, Waker)>> {
+        self.wakers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Unregisters an [`abortable`] call's waker when it completes or is dropped, so a
+/// long-lived handle doesn't collect wakers of cancelled decodes.
+struct Registration<'a>(&'a Shared, u64);
+
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        self.0.lock().retain(|(id, _)| *id != self.1);
+    }
+}
+
+/// Runs `fut` until it completes or `handle` is aborted, in which case `fut` is dropped at
+/// its next suspension point and [`DracoLoadError::Aborted`] is returned.
+pub async fn abortable<T>(
+    handle: &AbortHandle,
+    fut: impl Future<Output = Result<T, DracoLoadError>>,
+) -> Result<T, DracoLoadError> {
+    let mut fut = std::pin::pin!(fut);
+    let id = handle.0.next_id.fetch_add(1, Ordering::Relaxed);
+    let registration = Registration(&handle.0, id);
+    std::future::poll_fn(|cx| {
+        if handle.is_aborted() {
+            return Poll::Ready(Err(DracoLoadError::Aborted));
+        }
+        if let Poll::Ready(out) = fut.as_mut().poll(cx) {
+            return Poll::Ready(out);
+        }
+        let mut wakers = registration.0.lock();
+        match wakers.iter_mut().find(|(i, _)| *i == id) {
+            Some((_, w)) => w.clone_from(cx.waker()),
+            None => wakers.push((id, cx.waker().clone())),
+        }
+        drop(wakers);
+        // an abort between the first check and registering would otherwise be missed
+        if handle.is_aborted() {
+            return Poll::Ready(Err(DracoLoadError::Aborted));
+        }
+        Poll::Pending
+    })
+    .await
+}
@@ -0,0 +1,169 @@
+use crate::*;
+
+use gltf::json;
+use gltf::json::validation::{Checked, USize64};
+
+/// glTF JSON for one decoded primitive: a packed byte blob plus the bufferViews and accessors
+/// describing it, ready to splice into a document.
+///
+/// View `i` is `first_view + i` in the target document and accessor `i` uses view `i`;
+/// accessor indices in `attributes`/`indices` are relative to the start of `accessors`.
+#[derive(Debug, Clone)]
+pub struct GeneratedAccessors {
+    pub bytes: Vec<u8>,
+    pub views: Vec<json::buffer::View>,
+    pub accessors: Vec<json::Accessor>,
+    pub attributes: Vec<(gltf::Semantic, usize)>,
+    pub indices: Option<usize>,
+}
+
+/// Packs `d` into `buffer` and describes it with one tightly packed view per stream.
+///
+/// Views start 4-byte aligned, indices use `u16` when every index fits (`u32` otherwise),
+/// and every float accessor gets `min`/`max`, which glTF requires for `POSITION`.
+pub fn build_accessors(d: &DecodedPrimitive, buffer: u32, first_view: u32) -> GeneratedAccessors {
+    let mut out = GeneratedAccessors {
+        bytes: Vec::new(),
+        views: Vec::new(),
+        accessors: Vec::new(),
+        attributes: Vec::new(),
+        indices: None,
+    };
+
+    if !d.indices.is_empty() {
+        let packed = pack_indices(&d.indices);
+        let ct = if packed.is_wide() {
+            json::accessor::ComponentType::U32
+        } else {
+            json::accessor::ComponentType::U16
+        };
+        let stream = Stream {
+            bytes: packed.as_bytes(),
+            count: d.indices.len(),
+            ct,
+            ty: json::accessor::Type::Scalar,
+            target: json::buffer::Target::ElementArrayBuffer,
+            bounds: None,
+        };
+        out.indices = Some(out.push(stream, buffer, first_view));
+    }
+
+    if let Some(v) = &d.positions {
+        out.push_floats(gltf::Semantic::Positions, v, buffer, first_view);
+    }
+    if let Some(v) = &d.normals {
+        out.push_floats(gltf::Semantic::Normals, v, buffer, first_view);
+    }
+    if let Some(v) = &d.tangents {
+        out.push_floats(gltf::Semantic::Tangents, v, buffer, first_view);
+    }
+    for (set, v) in sorted(&d.texcoords) {
+        out.push_floats(gltf::Semantic::TexCoords(set), v, buffer, first_view);
+    }
+    for (set, v) in sorted(&d.colors) {
+        out.push_floats(gltf::Semantic::Colors(set), v, buffer, first_view);
+    }
+    for (set, v) in sorted(&d.joints) {
+        let stream = Stream {
+            bytes: bytemuck::cast_slice(v),
+            count: v.len(),
+            ct: json::accessor::ComponentType::U16,
+            ty: json::accessor::Type::Vec4,
+            target: json::buffer::Target::ArrayBuffer,
+            bounds: None,
+        };
+        let i = out.push(stream, buffer, first_view);
+        out.attributes.push((gltf::Semantic::Joints(set), i));
+    }
+    for (set, v) in sorted(&d.weights) {
+        out.push_floats(gltf::Semantic::Weights(set), v, buffer, first_view);
+    }
+    out
+}
+
+struct Stream<'a> {
+    bytes: &'a [u8],
+    count: usize,
+    ct: json::accessor::ComponentType,
+    ty: json::accessor::Type,
+    target: json::buffer::Target,
+    bounds: Option<(Vec<f32>, Vec<f32>)>,
+}
+
+impl GeneratedAccessors {
+    fn push_floats<const N: usize>(
+        &mut self,
+        semantic: gltf::Semantic,
+        v: &[[f32; N]],
+        buffer: u32,
+        first_view: u32,
+    ) {
+        let ty = match N {
+            1 => json::accessor::Type::Scalar,
+            2 => json::accessor::Type::Vec2,
+            3 => json::accessor::Type::Vec3,
+            _ => json::accessor::Type::Vec4,
+        };
+        let stream = Stream {
+            bytes: bytemuck::cast_slice(v.as_flattened()),
+            count: v.len(),
+            ct: json::accessor::ComponentType::F32,
+            ty,
+            target: json::buffer::Target::ArrayBuffer,
+            bounds: float_bounds(v),
+        };
+        let i = self.push(stream, buffer, first_view);
+        self.attributes.push((semantic, i));
+    }
+
+    fn push(&mut self, s: Stream<'_>, buffer: u32, first_view: u32) -> usize {
+        while !self.bytes.len().is_multiple_of(4) {
+            self.bytes.push(0);
+        }
+        let offset = self.bytes.len();
+        self.bytes.extend_from_slice(s.bytes);
+
+        let view = self.views.len() as u32;
+        self.views.push(json::buffer::View {
+            buffer: json::Index::new(buffer),
+            byte_length: USize64(s.bytes.len() as u64),
+            byte_offset: Some(USize64(offset as u64)),
+            byte_stride: None,
+            name: None,
+            target: Some(Checked::Valid(s.target)),
+            extensions: None,
+            extras: Default::default(),
+        });
+        let (min, max) = match s.bounds {
+            Some((lo, hi)) => (Some(lo.into()), Some(hi.into())),
+            None => (None, None),
+        };
+        self.accessors.push(json::Accessor {
+            buffer_view: Some(json::Index::new(first_view + view)),
+            byte_offset: None,
+            count: USize64(s.count as u64),
+            component_type: Checked::Valid(json::accessor::GenericComponentType(s.ct)),
+            extensions: None,
+            extras: Default::default(),
+            type_: Checked::Valid(s.ty),
+            min,
+            max,
+            name: None,
+            normalized: false,
+            sparse: None,
+        });
+        self.accessors.len() - 1
+    }
+}
+
+pub(crate) fn float_bounds<const N: usize>(v: &[[f32; N]]) -> Option<(Vec<f32>, Vec<f32>)> {
+    let first = v.first()?;
+    let (lo, hi) = v.iter().fold((*first, *first), |(mut lo, mut hi), x| {
+        for k in 0..N {
+            lo[k] = lo[k].min(x[k]);
+            hi[k] = hi[k].max(x[k]);
+        }
+        (lo, hi)
+    });
+    Some((lo.to_vec(), hi.to_vec()))
+}
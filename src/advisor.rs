@@ -0,0 +1,147 @@
+use crate::*;
+
+/// Largest quantization Draco's encoder accepts for any attribute.
+pub const MAX_QUANTIZATION_BITS: u8 = 30;
+
+/// Maximum acceptable position error for [`recommend_quantization`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorTarget {
+    /// Absolute error in model units.
+    ModelUnits(f64),
+    /// Error in pixels for a perspective camera `distance` units away, with vertical field
+    /// of view `fov_y` (radians) over `viewport_height` pixels.
+    ScreenSpace {
+        pixels: f64,
+        distance: f64,
+        fov_y: f64,
+        viewport_height: f64,
+    },
+}
+
+impl ErrorTarget {
+    pub fn model_units(self) -> f64 {
+        match self {
+            ErrorTarget::ModelUnits(e) => e,
+            ErrorTarget::ScreenSpace {
+                pixels,
+                distance,
+                fov_y,
+                viewport_height,
+            } => pixels * 2.0 * distance * (fov_y / 2.0).tan() / viewport_height,
+        }
+    }
+}
+
+/// Error budgets per attribute kind. Positions use `position`; the rest are fixed units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdvisorTargets {
+    pub position: ErrorTarget,
+    /// Degrees.
+    pub normal_angle: f64,
+    /// UV units; `0.5 / texture_size` keeps texels in place.
+    pub texcoord: f64,
+    pub color: f64,
+    pub weight: f64,
+}
+
+impl AdvisorTargets {
+    pub fn new(position: ErrorTarget) -> Self {
+        Self {
+            position,
+            normal_angle: 1.0,
+            texcoord: 0.5 / 4096.0,
+            color: 0.5 / 255.0,
+            weight: 0.5 / 255.0,
+        }
+    }
+}
+
+/// Recommended Draco quantization bits per glTF semantic, keyed like the extension's
+/// attribute map (`POSITION`, `TEXCOORD_0`, ...).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct QuantizationAdvice {
+    pub bits: std::collections::BTreeMap<String, u8>,
+    /// Bits the decoded positions already appear to be quantized to, from the smallest gap
+    /// between distinct coordinates; re-encoding with more gains nothing.
+    pub current_position_bits: Option<u8>,
+}
+
+/// Analyzes `d` and recommends the fewest bits that keep each attribute within `targets`.
+///
+/// Draco quantizes each attribute over its value range, so the maximum error with `q` bits
+/// is half a step, `range / (2^q - 1) / 2`. Normals are octahedral-encoded, which this
+/// approximates as a range of pi radians. Joints are integers and never quantized.
+pub fn recommend_quantization(
+    d: &DecodedPrimitive,
+    targets: &AdvisorTargets,
+) -> QuantizationAdvice {
+    let mut advice = QuantizationAdvice::default();
+    if let Some(p) = &d.positions {
+        let bits = bits_for(max_range(p), targets.position.model_units());
+        advice.bits.insert("POSITION".into(), bits);
+        advice.current_position_bits = current_bits(p);
+    }
+    if d.normals.is_some() {
+        let bits = bits_for(std::f64::consts::PI, targets.normal_angle.to_radians());
+        advice.bits.insert("NORMAL".into(), bits);
+    }
+    if let Some(t) = &d.tangents {
+        let bits = bits_for(max_range(t), targets.normal_angle.to_radians());
+        advice.bits.insert("TANGENT".into(), bits);
+    }
+    for (set, v) in sorted(&d.texcoords) {
+        let bits = bits_for(max_range(v), targets.texcoord);
+        advice.bits.insert(format!("TEXCOORD_{set}"), bits);
+    }
+    for (set, v) in sorted(&d.colors) {
+        let bits = bits_for(max_range(v), targets.color);
+        advice.bits.insert(format!("COLOR_{set}"), bits);
+    }
+    for (set, v) in sorted(&d.weights) {
+        let bits = bits_for(max_range(v), targets.weight);
+        advice.bits.insert(format!("WEIGHTS_{set}"), bits);
+    }
+    advice
+}
+
+/// Fewest bits whose half step over `range` stays within `max_error`.
+pub fn bits_for(range: f64, max_error: f64) -> u8 {
+    if range <= 0.0 || max_error <= 0.0 || !range.is_finite() {
+        return if range > 0.0 {
+            MAX_QUANTIZATION_BITS
+        } else {
+            1
+        };
+    }
+    let steps = (range / (2.0 * max_error)).ceil();
+    let bits = (steps + 1.0).log2().ceil() as u8;
+    bits.clamp(1, MAX_QUANTIZATION_BITS)
+}
+
+/// Largest per-component extent, the range Draco quantizes over.
+fn max_range<const N: usize>(v: &[[f32; N]]) -> f64 {
+    (0..N)
+        .map(|k| {
+            let (lo, hi) = v
+                .iter()
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), x| {
+                    (lo.min(x[k]), hi.max(x[k]))
+                });
+            (hi - lo) as f64
+        })
+        .filter(|r| r.is_finite())
+        .fold(0.0, f64::max)
+}
+
+fn current_bits(p: &[[f32; 3]]) -> Option<u8> {
+    let range = max_range(p);
+    let gap = (0..3)
+        .filter_map(|k| {
+            let mut c: Vec<f32> = p.iter().map(|x| x[k]).collect();
+            c.sort_by(f32::total_cmp);
+            c.dedup();
+            c.windows(2).map(|w| (w[1] - w[0]) as f64).reduce(f64::min)
+        })
+        .reduce(f64::min)?;
+    Some(bits_for(range, gap / 2.0))
+}
@@ -0,0 +1,131 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// How many [`AllocScope`]s can track their own peak at once.
+const SCOPE_SLOTS: usize = 64;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static ACTIVE_SCOPES: AtomicU64 = AtomicU64::new(0); // one bit per taken slot
+static SCOPE_PEAKS: [AtomicUsize; SCOPE_SLOTS] = [const { AtomicUsize::new(0) }; SCOPE_SLOTS];
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// A [`System`] wrapper that counts live and peak heap bytes. Install it in the binary:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOC: draco_gltf_rs::TrackingAllocator = draco_gltf_rs::TrackingAllocator;
+/// ```
+///
+/// Counters are process-wide, so measurements include any other thread allocating at the
+/// same time.
+pub struct TrackingAllocator;
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new.is_null() {
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+            grow(new_size);
+        }
+        new
+    }
+}
+
+fn grow(size: usize) {
+    let now = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    let mut active = ACTIVE_SCOPES.load(Ordering::Relaxed);
+    while active != 0 {
+        SCOPE_PEAKS[active.trailing_zeros() as usize].fetch_max(now, Ordering::Relaxed);
+        active &= active - 1;
+    }
+    ALLOCATED.fetch_add(size, Ordering::Relaxed);
+    COUNT.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Heap usage between [`AllocScope::begin`] and [`AllocScope::finish`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct AllocStats {
+    pub peak_bytes: usize,      // high-water mark above the starting live size
+    pub allocated_bytes: usize, // sum of all allocation sizes
+    pub allocations: usize,
+    pub retained_bytes: isize, // live bytes at finish minus live bytes at begin
+}
+
+/// Measures heap usage of the work done while it is alive, e.g. one document decode.
+/// Only meaningful when [`TrackingAllocator`] is the global allocator; otherwise all zero.
+///
+/// Each scope keeps its own high-water mark, so scopes can nest or overlap. Past 64 live
+/// scopes the extra ones can't, and report the larger of the live sizes at begin and finish
+/// as their peak.
+pub struct AllocScope {
+    live: usize,
+    allocated: usize,
+    count: usize,
+    slot: Option<usize>,
+}
+
+impl AllocScope {
+    pub fn begin() -> Self {
+        let live = CURRENT.load(Ordering::Relaxed);
+        let mut active = ACTIVE_SCOPES.load(Ordering::Relaxed);
+        let slot = loop {
+            let free = (!active).trailing_zeros() as usize;
+            if free == SCOPE_SLOTS {
+                break None;
+            }
+            // the baseline goes in before the slot is visible to `grow`
+            SCOPE_PEAKS[free].store(live, Ordering::Relaxed);
+            match ACTIVE_SCOPES.compare_exchange_weak(
+                active,
+                active | 1 << free,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break Some(free),
+                Err(now) => active = now,
+            }
+        };
+        Self {
+            live,
+            allocated: ALLOCATED.load(Ordering::Relaxed),
+            count: COUNT.load(Ordering::Relaxed),
+            slot,
+        }
+    }
+
+    pub fn finish(self) -> AllocStats {
+        let live = CURRENT.load(Ordering::Relaxed);
+        let peak = match self.slot {
+            Some(slot) => SCOPE_PEAKS[slot].load(Ordering::Relaxed).max(live),
+            None => live.max(self.live),
+        };
+        AllocStats {
+            peak_bytes: peak.saturating_sub(self.live),
+            allocated_bytes: ALLOCATED.load(Ordering::Relaxed) - self.allocated,
+            allocations: COUNT.load(Ordering::Relaxed) - self.count,
+            retained_bytes: live as isize - self.live as isize,
+        }
+    }
+}
+
+impl Drop for AllocScope {
+    fn drop(&mut self) {
+        if let Some(slot) = self.slot {
+            ACTIVE_SCOPES.fetch_and(!(1 << slot), Ordering::AcqRel);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::*;
+
+use std::collections::HashMap;
+
+/// Per-triangle areas from [`DecodedPrimitive::triangle_areas`] or
+/// [`ScenePrimitive::triangle_areas`], indexed like `indices.chunks_exact(3)`.
+///
+/// Triangles referencing missing vertices have area zero.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct TriangleAreas {
+    /// Surface area in mesh units squared, or world units for a scene primitive.
+    pub surface: Vec<f32>,
+    /// Area in UV space per texcoord set, where 1 is the whole texture. Empty unless
+    /// asked for.
+    pub uv: HashMap<u32, Vec<f32>>,
+}
+
+impl TriangleAreas {
+    pub fn total_surface(&self) -> f64 {
+        self.surface.iter().map(|&a| a as f64).sum()
+    }
+
+    /// Summed UV area of texcoord set `set`; overlapping or repeating charts count each
+    /// time they're used.
+    pub fn total_uv(&self, set: u32) -> Option<f64> {
+        Some(self.uv.get(&set)?.iter().map(|&a| a as f64).sum())
+    }
+}
+
+impl DecodedPrimitive {
+    /// Surface area of every triangle, plus its UV area in every texcoord set when
+    /// `with_uv` is set. Streaming and virtual-texturing heuristics (texels per screen area,
+    /// mip selection) need nothing else; to get the tables with the decode instead of
+    /// traversing the primitive again, set [`DecodeOptions::triangle_areas`].
+    pub fn triangle_areas(&self, with_uv: bool) -> TriangleAreas {
+        triangle_areas(self, None, with_uv)
+    }
+
+    /// Recomputes [`areas`](Self::areas), if present, after the geometry changed.
+    pub(crate) fn refresh_areas(&mut self) {
+        if let Some(areas) = &self.areas {
+            self.areas = Some(self.triangle_areas(!areas.uv.is_empty()));
+        }
+    }
+}
+
+impl ScenePrimitive {
+    /// [`DecodedPrimitive::triangle_areas`] with surface areas measured after
+    /// `world_transform`, so instances scaled differently report their real size.
+    pub fn triangle_areas(&self, with_uv: bool) -> TriangleAreas {
+        triangle_areas(&self.decoded, Some(&self.world_transform), with_uv)
+    }
+}
+
+fn triangle_areas(d: &DecodedPrimitive, transform: Option<&Mat4>, with_uv: bool) -> TriangleAreas {
+    let place = |p: [f32; 3]| -> [f64; 3] {
+        let Some(m) = transform else {
+            return p.map(f64::from);
+        };
+        [0, 1, 2]
+            .map(|r| (0..3).map(|c| m[c][r] as f64 * p[c] as f64).sum::<f64>() + m[3][r] as f64)
+    };
+    let positions = d.positions.as_deref().unwrap_or_default();
+    let sets: Vec<(u32, &[[f32; 2]])> = if with_uv {
+        d.texcoords
+            .iter()
+            .map(|(s, v)| (*s, v.as_slice()))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let triangles = d.indices.len() / 3;
+    let mut surface = Vec::with_capacity(triangles);
+    let mut uv = vec![Vec::with_capacity(triangles); sets.len()];
+    for tri in d.indices.chunks_exact(3) {
+        let idx = [0, 1, 2].map(|k| tri[k] as usize);
+        let area = match idx.map(|i| positions.get(i)) {
+            [Some(a), Some(b), Some(c)] => {
+                let [a, b, c] = [*a, *b, *c].map(place);
+                let e1 = [0, 1, 2].map(|k| b[k] - a[k]);
+                let e2 = [0, 1, 2].map(|k| c[k] - a[k]);
+                let n = [
+                    e1[1] * e2[2] - e1[2] * e2[1],
+                    e1[2] * e2[0] - e1[0] * e2[2],
+                    e1[0] * e2[1] - e1[1] * e2[0],
+                ];
+                0.5 * (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt()
+            }
+            _ => 0.0,
+        };
+        surface.push(area as f32);
+        for ((_, uvs), out) in sets.iter().zip(&mut uv) {
+            let area = match idx.map(|i| uvs.get(i)) {
+                [Some(a), Some(b), Some(c)] => {
+                    let cross = (b[0] - a[0]) as f64 * (c[1] - a[1]) as f64
+                        - (c[0] - a[0]) as f64 * (b[1] - a[1]) as f64;
+                    0.5 * cross.abs()
+                }
+                _ => 0.0,
+            };
+            out.push(area as f32);
+        }
+    }
+    TriangleAreas {
+        surface,
+        uv: sets.iter().map(|(set, _)| *set).zip(uv).collect(),
+    }
+}
@@ -0,0 +1,43 @@
+use crate::*;
+
+use std::path::Path;
+
+/// A glTF or GLB file and its buffers, from [`load_glb_async`].
+#[derive(Debug)]
+pub struct LoadedGltf {
+    pub document: gltf::Document,
+    pub buffers: Vec<gltf::buffer::Data>,
+}
+
+/// Loads a GLB (or `.gltf`) file and every buffer it references without blocking the
+/// executor: files are read with `tokio::fs` and the JSON is parsed on the blocking pool,
+/// so a server loading multi-hundred-MB assets keeps its worker threads free. Needs a
+/// tokio runtime.
+///
+/// External buffers resolve against the file's directory; use
+/// [`load_glb_async_with`] to sandbox them.
+pub async fn load_glb_async(path: impl AsRef<Path>) -> Result<LoadedGltf, DracoLoadError> {
+    let path = path.as_ref();
+    let resolver = UriResolver::new(path.parent().unwrap_or(Path::new(".")));
+    load_glb_async_with(path, &resolver).await
+}
+
+/// [`load_glb_async`] resolving buffer URIs with `resolver`.
+pub async fn load_glb_async_with(
+    path: impl AsRef<Path>,
+    resolver: &UriResolver,
+) -> Result<LoadedGltf, DracoLoadError> {
+    let bytes = tokio::fs::read(path).await.map_err(gltf::Error::Io)?;
+    // Draco files fail gltf's validation (accessors without bufferViews), so skip it
+    let gltf =
+        tokio::task::spawn_blocking(move || gltf::Gltf::from_slice_without_validation(&bytes))
+            .await
+            .map_err(|e| gltf::Error::Io(std::io::Error::other(e)))??;
+    let buffers = resolver
+        .load_buffers_async(&gltf.document, gltf.blob)
+        .await?;
+    Ok(LoadedGltf {
+        document: gltf.document,
+        buffers,
+    })
+}
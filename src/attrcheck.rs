@@ -0,0 +1,119 @@
+use crate::*;
+
+/// One attribute whose caller-supplied [`AttrInfo`] disagrees with what the primitive
+/// stores, reported by [`DracoLoadError::AttributeMismatch`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AttributeConflict {
+    pub unique_id: u32,
+    /// Extension attribute key for the id, `None` if the extension doesn't map it.
+    pub semantic: Option<String>,
+    /// The info passed to the decode call.
+    pub given: AttrInfo,
+    /// The layout stored for that id: the stream header's, or the accessor's where the
+    /// header can't be read. `None` if there is none.
+    pub stored: Option<AttrInfo>,
+}
+
+impl std::fmt::Display for AttributeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = self.semantic.as_deref().unwrap_or("?");
+        write!(
+            f,
+            "id {} ({name}): given dim {} type {}",
+            self.unique_id, self.given.dim, self.given.data_type
+        )?;
+        match &self.stored {
+            Some(s) => write!(f, ", stored dim {} type {}", s.dim, s.data_type),
+            None => write!(f, ", not stored"),
+        }
+    }
+}
+
+/// Compares `infos` with the layouts the primitive stores for the same Draco ids: those
+/// in the stream's own attribute header, or the accessors' where the header can't be read
+/// (see [`stored_attr_infos`]). Ids the caller leaves out are fine (a subset decode); ids
+/// with a different component count or decoder data type, or that aren't stored, are
+/// conflicts.
+pub fn attribute_conflicts(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    infos: &[AttrInfo],
+    quirks: &VendorQuirks,
+) -> Result<Vec<AttributeConflict>, DracoLoadError> {
+    let ext = draco_extension_with_quirks(p, quirks)?;
+    let stored = stored_attr_infos(p, document, buffers, quirks)?;
+    Ok(conflicts_with(infos, &stored, |id| ext.semantic_of(id)))
+}
+
+/// The attribute layouts a Draco primitive stores, in decoder order: from the stream's
+/// attribute header, or where that can't be read, from the accessors.
+pub fn stored_attr_infos(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    quirks: &VendorQuirks,
+) -> Result<Vec<AttrInfo>, DracoLoadError> {
+    let ext = draco_extension_with_quirks(p, quirks)?;
+    match stream_attr_infos(get_buffer(document, buffers, ext.buffer_view)?) {
+        Some(stored) => Ok(stored),
+        None => attr_infos_with_quirks(p, quirks),
+    }
+}
+
+/// The infos to configure the decoder with for `draco_bytes`: the stream header's own once
+/// `given` agrees with them, or `given` itself, checked against `accessors`, when the
+/// header can't be read. Disagreement is [`DracoLoadError::AttributeMismatch`].
+pub(crate) fn decoder_infos(
+    draco_bytes: &[u8],
+    given: &[AttrInfo],
+    accessors: impl FnOnce() -> Result<Vec<AttrInfo>, DracoLoadError>,
+    semantic: impl Fn(u32) -> Option<String>,
+) -> Result<Vec<AttrInfo>, DracoLoadError> {
+    let header = stream_attr_infos(draco_bytes);
+    let stored = match &header {
+        Some(stored) => stored,
+        None => &accessors()?,
+    };
+    let conflicts = conflicts_with(given, stored, semantic);
+    if !conflicts.is_empty() {
+        return Err(DracoLoadError::AttributeMismatch(conflicts));
+    }
+    Ok(header.unwrap_or_else(|| given.to_vec()))
+}
+
+/// The `given` infos whose component count or decoder data type differs from the `stored`
+/// one with the same id, or that have none. Raw type bytes the decoder reads alike (7 and
+/// 9 both decode as f32, see [`map_draco_dt`]) agree.
+pub(crate) fn conflicts_with(
+    given: &[AttrInfo],
+    stored: &[AttrInfo],
+    semantic: impl Fn(u32) -> Option<String>,
+) -> Vec<AttributeConflict> {
+    let mut out = Vec::new();
+    for given in given {
+        let stored = stored.iter().find(|s| s.unique_id == given.unique_id);
+        let agrees = stored.is_some_and(|s| {
+            s.dim == given.dim && s.attribute_data_type() == given.attribute_data_type()
+        });
+        if !agrees {
+            out.push(AttributeConflict {
+                unique_id: given.unique_id,
+                semantic: semantic(given.unique_id),
+                given: given.clone(),
+                stored: stored.cloned(),
+            });
+        }
+    }
+    out
+}
+
+impl DracoExt {
+    /// The extension's attribute key for Draco id `id`.
+    pub(crate) fn semantic_of(&self, id: u32) -> Option<String> {
+        self.attributes
+            .iter()
+            .find(|(_, i)| *i == id)
+            .map(|(key, _)| key.to_string())
+    }
+}
@@ -0,0 +1,167 @@
+use crate::*;
+
+/// How the upper 3x3 of a world matrix scales geometry, from [`transform_scale`].
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct TransformScale {
+    /// Lengths of the transformed X, Y and Z axes.
+    pub scale: [f32; 3],
+    /// Equal scale on orthogonal axes: normals can go through the matrix itself. Shear
+    /// counts as non-uniform.
+    pub uniform: bool,
+    pub determinant: f32,
+}
+
+impl TransformScale {
+    /// The matrix mirrors geometry, so baking it reverses triangle winding.
+    pub fn mirrored(&self) -> bool {
+        self.determinant < 0.0
+    }
+
+    /// The matrix flattens geometry onto a plane, line or point; normals can't follow.
+    pub fn singular(&self) -> bool {
+        self.determinant.abs() <= 1e-6 * self.scale.iter().product::<f32>()
+    }
+}
+
+/// Classifies the scale of `m` (column-major, as in glTF).
+pub fn transform_scale(m: &Mat4) -> TransformScale {
+    let axes = [0, 1, 2].map(|c| [m[c][0], m[c][1], m[c][2]]);
+    let scale = axes.map(|a| dot(a, a).sqrt());
+    let largest = scale.iter().fold(0.0f32, |a, &s| a.max(s));
+    let tolerance = 1e-4 * largest * largest;
+    // M^T M is s^2 I exactly when M is a uniform scale times a rotation
+    let uniform = largest > 0.0
+        && (0..3).all(|i| {
+            (0..3).all(|j| {
+                let expected = if i == j { largest * largest } else { 0.0 };
+                (dot(axes[i], axes[j]) - expected).abs() <= tolerance
+            })
+        });
+    TransformScale {
+        scale,
+        uniform,
+        determinant: dot(axes[0], cross(axes[1], axes[2])),
+    }
+}
+
+impl DecodedPrimitive {
+    /// Bakes `m` into the vertex data, returning its [`TransformScale`].
+    ///
+    /// Positions go through `m`. Normals go through its inverse-transpose (only needed,
+    /// and only computed, under non-uniform scale or shear) and tangents through its upper
+    /// 3x3, both renormalized. A mirroring matrix also reverses every triangle and flips
+    /// the tangent handedness in `w`, so front faces and bitangents survive. Under a
+    /// singular matrix normals and tangents are left alone.
+    pub fn bake_transform(&mut self, m: &Mat4) -> TransformScale {
+        let ts = transform_scale(m);
+        let linear = |v: [f32; 3]| -> [f32; 3] {
+            [0, 1, 2].map(|r| m[0][r] * v[0] + m[1][r] * v[1] + m[2][r] * v[2])
+        };
+
+        if let Some(positions) = &mut self.positions {
+            for p in positions.iter_mut() {
+                let v = linear(*p);
+                *p = [v[0] + m[3][0], v[1] + m[3][1], v[2] + m[3][2]];
+            }
+        }
+        self.refresh_areas();
+        if ts.singular() {
+            return ts;
+        }
+        if let Some(normals) = &mut self.normals {
+            let normal_matrix = |n: [f32; 3]| -> [f32; 3] {
+                if ts.uniform {
+                    return linear(n);
+                }
+                // the rows of det * M^-1 are cross products of M's columns; the sign keeps
+                // mirrored normals pointing out
+                let axes = [0, 1, 2].map(|c| [m[c][0], m[c][1], m[c][2]]);
+                let cof = [
+                    cross(axes[1], axes[2]),
+                    cross(axes[2], axes[0]),
+                    cross(axes[0], axes[1]),
+                ];
+                let sign = ts.determinant.signum();
+                [0, 1, 2].map(|r| sign * (0..3).map(|c| cof[c][r] * n[c]).sum::<f32>())
+            };
+            for n in normals.iter_mut() {
+                *n = normalize(normal_matrix(*n));
+            }
+        }
+        if let Some(tangents) = &mut self.tangents {
+            for t in tangents.iter_mut() {
+                let v = normalize(linear([t[0], t[1], t[2]]));
+                let w = if ts.mirrored() { -t[3] } else { t[3] };
+                *t = [v[0], v[1], v[2], w];
+            }
+        }
+        if ts.mirrored() {
+            for tri in self.indices.chunks_exact_mut(3) {
+                tri.swap(1, 2);
+            }
+        }
+        ts
+    }
+}
+
+/// Something [`bake_scene`] left alone or couldn't bake correctly.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub enum BakeWarning {
+    /// The node has a skin and a non-identity world matrix. glTF ignores the transform of
+    /// a skinned mesh's node (joints place it), so the primitive was not baked; a viewer
+    /// that applies the node transform anyway shows it transformed twice.
+    SkinnedTransform {
+        node: usize,
+        mesh: usize,
+        primitive: usize,
+    },
+    /// The world matrix has no inverse, so normals and tangents were kept as they were.
+    SingularTransform {
+        node: usize,
+        mesh: usize,
+        primitive: usize,
+    },
+}
+
+/// A scene with world transforms baked into its primitives, from [`bake_scene`].
+#[derive(Debug, Clone, Default)]
+pub struct BakedScene {
+    /// Every primitive of the scene; baked ones carry an identity `world_transform`.
+    pub primitives: Vec<ScenePrimitive>,
+    pub warnings: Vec<BakeWarning>,
+}
+
+/// Bakes each primitive's world transform into its vertices with
+/// [`DecodedPrimitive::bake_transform`], flattening the scene into one coordinate space.
+/// Primitives on skinned nodes keep their bind-pose vertices and transform.
+pub fn bake_scene(scene: &DecodedScene, document: &gltf::Document) -> BakedScene {
+    let mut out = BakedScene::default();
+    for sp in &scene.primitives {
+        let mut sp = sp.clone();
+        let skinned = document
+            .nodes()
+            .nth(sp.node)
+            .is_some_and(|n| n.skin().is_some());
+        if skinned {
+            if sp.world_transform != IDENTITY {
+                out.warnings.push(BakeWarning::SkinnedTransform {
+                    node: sp.node,
+                    mesh: sp.mesh,
+                    primitive: sp.primitive,
+                });
+            }
+            out.primitives.push(sp);
+            continue;
+        }
+        if sp.decoded.bake_transform(&sp.world_transform).singular() {
+            out.warnings.push(BakeWarning::SingularTransform {
+                node: sp.node,
+                mesh: sp.mesh,
+                primitive: sp.primitive,
+            });
+        }
+        sp.world_transform = IDENTITY;
+        out.primitives.push(sp);
+    }
+    out
+}
@@ -0,0 +1,98 @@
+use crate::*;
+
+use std::path::{Path, PathBuf};
+
+/// Outcome of [`decompress_files`], serializable as a build-farm run report.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BatchReport {
+    /// One entry per job, in job order.
+    pub files: Vec<BatchFile>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BatchFile {
+    pub input: PathBuf,
+    /// The store key written.
+    pub output: String,
+    /// Draco primitives decompressed.
+    pub primitives: usize,
+    pub input_bytes: u64,
+    pub output_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BatchReport {
+    pub fn failed(&self) -> impl Iterator<Item = &BatchFile> {
+        self.files.iter().filter(|f| f.error.is_some())
+    }
+}
+
+/// Rewrites each `(input, key)` GLB/glTF pair as a GLB with every Draco primitive
+/// decompressed (see [`strip_draco`]), written to `store` under `key`, on up to `threads`
+/// OS threads. A failing file is reported and doesn't stop the others; a job whose key an
+/// earlier job already writes (`a.gltf` and `a.glb` both mapped to `a.glb`, say) fails
+/// instead of overwriting it.
+pub fn decompress_files<S: OutputStore + Sync>(
+    jobs: &[(PathBuf, String)],
+    store: &S,
+    threads: usize,
+) -> BatchReport {
+    let mut first = std::collections::HashMap::new();
+    let earlier: Vec<usize> = (0..jobs.len())
+        .map(|i| *first.entry(jobs[i].1.as_str()).or_insert(i))
+        .collect();
+
+    let mut files: Vec<Option<BatchFile>> = jobs.iter().map(|_| None).collect();
+    let convert = |i: usize, (input, key): &(PathBuf, String)| {
+        if earlier[i] == i {
+            return decompress_file(input, key, store);
+        }
+        let mut file = BatchFile::new(input, key);
+        let other = jobs[earlier[i]].0.display();
+        file.error = Some(format!("output {key} is also written for {other}"));
+        file
+    };
+    run_parallel(jobs, threads, convert, |i, file| files[i] = Some(file));
+    BatchReport {
+        files: files.into_iter().flatten().collect(),
+    }
+}
+
+impl BatchFile {
+    fn new(input: &Path, key: &str) -> Self {
+        Self {
+            input: input.to_path_buf(),
+            output: key.to_string(),
+            primitives: 0,
+            input_bytes: std::fs::metadata(input).map_or(0, |m| m.len()),
+            output_bytes: 0,
+            error: None,
+        }
+    }
+}
+
+fn decompress_file(input: &Path, key: &str, store: &impl OutputStore) -> BatchFile {
+    let mut file = BatchFile::new(input, key);
+    let result = (|| -> Result<(), DracoLoadError> {
+        let io = |e| DracoLoadError::Gltf(gltf::Error::Io(e));
+        let mut reader = std::fs::File::open(input).map_err(io)?;
+        let gltf = gltf::Gltf::from_reader_without_validation(&mut reader)?;
+        let base = input.parent().unwrap_or(Path::new("."));
+        let buffers = UriResolver::new(base).load_buffers(&gltf.document, gltf.blob)?;
+        file.primitives = gltf
+            .document
+            .meshes()
+            .flat_map(|m| m.primitives())
+            .filter(|p| p.extension_value("KHR_draco_mesh_compression").is_some())
+            .count();
+        let stripped = block_on(strip_draco(&gltf.document, &buffers, |_, _| true))?;
+        let glb = stripped.to_glb()?;
+        let len = glb.len() as u64;
+        block_on(store.put(key, glb)).map_err(io)?;
+        file.output_bytes = len;
+        Ok(())
+    })();
+    file.error = result.err().map(|e| e.to_string());
+    file
+}
@@ -0,0 +1,106 @@
+use crate::*;
+
+use std::time::Instant;
+
+/// Decode throughput for a set of fixtures, serializable for CI gating. See
+/// `examples/bench_json.rs` for a runner over GLB files.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BenchReport {
+    pub fixtures: Vec<FixtureBench>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FixtureBench {
+    pub name: String,
+    pub iterations: u32,
+    pub primitives: usize,
+    pub vertices: usize,
+    /// Compressed Draco bytes decoded per iteration.
+    pub stored_bytes: usize,
+    pub mean_ms: f64,
+    pub min_ms: f64,
+    pub vertices_per_sec: f64,
+    pub megabytes_per_sec: f64,
+    /// Heap high-water mark of one iteration; needs the `alloc-metrics` feature and
+    /// [`TrackingAllocator`](crate::TrackingAllocator) installed.
+    pub peak_bytes: Option<usize>,
+}
+
+/// Decodes every Draco primitive of the document `iterations` times and records timings.
+pub fn bench_document(
+    name: &str,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    iterations: u32,
+) -> Result<FixtureBench, DracoLoadError> {
+    let index = DocumentIndex::build(document)?;
+    let decode_all = || -> Result<(), DracoLoadError> {
+        for entry in &index.primitives {
+            parallel::block_on(entry.decode(buffers, &DecodeOptions::default()))?;
+        }
+        Ok(())
+    };
+
+    #[cfg(feature = "alloc-metrics")]
+    let peak_bytes = {
+        let scope = AllocScope::begin();
+        decode_all()?;
+        Some(scope.finish().peak_bytes)
+    };
+    #[cfg(not(feature = "alloc-metrics"))]
+    let peak_bytes = None;
+
+    let iterations = iterations.max(1);
+    let mut times = Vec::with_capacity(iterations as usize);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        decode_all()?;
+        times.push(start.elapsed().as_secs_f64());
+    }
+    let mean = times.iter().sum::<f64>() / times.len() as f64;
+    let min = times.iter().copied().fold(f64::INFINITY, f64::min);
+
+    let vertices = index.primitives.iter().map(|p| p.vertex_count).sum();
+    let stored_bytes = index.primitives.iter().map(|p| p.byte_length).sum();
+    let per_sec = |x: usize| if mean > 0.0 { x as f64 / mean } else { 0.0 };
+    Ok(FixtureBench {
+        name: name.to_string(),
+        iterations,
+        primitives: index.primitives.len(),
+        vertices,
+        stored_bytes,
+        mean_ms: mean * 1e3,
+        min_ms: min * 1e3,
+        vertices_per_sec: per_sec(vertices),
+        megabytes_per_sec: per_sec(stored_bytes) / 1e6,
+        peak_bytes,
+    })
+}
+
+impl BenchReport {
+    /// Fixtures whose mean time or peak memory grew by more than `tolerance` (0.1 = 10%)
+    /// over the same-named fixture in `baseline`. Fixtures missing from either are skipped.
+    pub fn regressions(&self, baseline: &BenchReport, tolerance: f64) -> Vec<String> {
+        let mut out = Vec::new();
+        for f in &self.fixtures {
+            let Some(b) = baseline.fixtures.iter().find(|b| b.name == f.name) else {
+                continue;
+            };
+            if f.mean_ms > b.mean_ms * (1.0 + tolerance) {
+                out.push(format!(
+                    "{}: mean {:.3} ms vs baseline {:.3} ms",
+                    f.name, f.mean_ms, b.mean_ms
+                ));
+            }
+            if let (Some(now), Some(base)) = (f.peak_bytes, b.peak_bytes)
+                && now as f64 > base as f64 * (1.0 + tolerance)
+            {
+                out.push(format!(
+                    "{}: peak {now} bytes vs baseline {base} bytes",
+                    f.name
+                ));
+            }
+        }
+        out
+    }
+}
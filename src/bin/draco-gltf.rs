@@ -0,0 +1,357 @@
+//! Command-line tools over the library; build with `--features cli`.
+//!
+//! draco-gltf inspect <file> [--json] [--max-vertices N] [--max-indices N] [--require-draco]
+//! draco-gltf decompress <pattern>... --out <dir> [--jobs N] [--report report.json]
+//!
+//! Patterns support `*`, `?` and `**` (any number of directories); quote them so the shell
+//! doesn't expand them. Outputs mirror each input's path below the pattern's fixed prefix.
+//!
+//! Exit codes: 0 on success, 1 when an asset breaks a policy flag or fails to convert, 2 on
+//! usage or load errors.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: draco-gltf inspect <file.glb|file.gltf> [--json] \
+                     [--max-vertices N] [--max-indices N] [--require-draco]\n       \
+                     draco-gltf decompress <pattern>... --out <dir> [--jobs N] \
+                     [--report report.json]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("inspect") => inspect(&args[1..], &mut std::io::stdout().lock()),
+        Some("decompress") => decompress(&args[1..]),
+        _ => Err(USAGE.into()),
+    };
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+type CliResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+fn load(path: &str) -> CliResult<(gltf::Document, Vec<gltf::buffer::Data>)> {
+    let mut file = std::fs::File::open(path)?;
+    let gltf = gltf::Gltf::from_reader_without_validation(&mut file)?;
+    let base = Path::new(path).parent().unwrap_or(Path::new("."));
+    let buffers = draco_gltf_rs::UriResolver::new(base).load_buffers(&gltf.document, gltf.blob)?;
+    Ok((gltf.document, buffers))
+}
+
+fn value<T: std::str::FromStr>(args: &mut std::slice::Iter<'_, String>, flag: &str) -> CliResult<T>
+where
+    T::Err: std::error::Error + 'static,
+{
+    let v = args.next().ok_or_else(|| format!("{flag} needs a value"))?;
+    Ok(v.parse()?)
+}
+
+fn inspect(args: &[String], out: &mut impl std::io::Write) -> CliResult<ExitCode> {
+    let (mut path, mut json) = (None, false);
+    let (mut max_vertices, mut max_indices, mut require_draco) = (None, None, false);
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--max-vertices" => max_vertices = Some(value::<usize>(&mut it, arg)?),
+            "--max-indices" => max_indices = Some(value::<usize>(&mut it, arg)?),
+            "--require-draco" => require_draco = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown flag {flag}").into()),
+            _ if path.is_none() => path = Some(arg.clone()),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    let path = path.ok_or(USAGE)?;
+    let (document, buffers) = load(&path)?;
+    let report = draco_gltf_rs::asset_report(&document, &buffers);
+
+    let mut violations = Vec::new();
+    for p in &report.primitives {
+        let at = format!("mesh {} primitive {}", p.mesh, p.primitive);
+        if let Some(max) = max_vertices.filter(|&max| p.vertex_count > max) {
+            violations.push(format!("{at}: {} vertices > {max}", p.vertex_count));
+        }
+        if let Some(max) = max_indices.filter(|&max| p.index_count > max) {
+            violations.push(format!("{at}: {} indices > {max}", p.index_count));
+        }
+        if require_draco && !p.draco {
+            violations.push(format!("{at}: not Draco-compressed"));
+        }
+    }
+
+    if json {
+        let json = serde_json::json!({
+            "file": path,
+            "report": report,
+            "violations": violations,
+        });
+        writeln!(out, "{}", serde_json::to_string_pretty(&json)?)?;
+    } else {
+        print_tree(out, &path, &document, &report)?;
+        for v in &violations {
+            writeln!(out, "violation: {v}")?;
+        }
+    }
+    Ok(if violations.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+fn decompress(args: &[String]) -> CliResult<ExitCode> {
+    let (mut patterns, mut out, mut report_path) = (Vec::new(), None, None);
+    let mut jobs = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--out" => out = Some(PathBuf::from(value::<String>(&mut it, arg)?)),
+            "--jobs" => jobs = value(&mut it, arg)?,
+            "--report" => report_path = Some(value::<String>(&mut it, arg)?),
+            flag if flag.starts_with("--") => return Err(format!("unknown flag {flag}").into()),
+            _ => patterns.push(arg.clone()),
+        }
+    }
+    let out = out.ok_or("--out is required")?;
+    if patterns.is_empty() {
+        return Err(USAGE.into());
+    }
+
+    let mut work = Vec::new();
+    for pattern in &patterns {
+        let (base, matches) = expand(pattern)?;
+        if matches.is_empty() {
+            eprintln!("warning: {pattern} matched no files");
+        }
+        for input in matches {
+            let rel = input
+                .strip_prefix(&base)
+                .unwrap_or(&input)
+                .with_extension("glb");
+            let key: Vec<_> = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect();
+            work.push((input, key.join("/")));
+        }
+    }
+
+    let store = draco_gltf_rs::FsStore::new(&out);
+    let report = draco_gltf_rs::decompress_files(&work, &store, jobs);
+    for f in &report.files {
+        match &f.error {
+            Some(e) => eprintln!("failed {}: {e}", f.input.display()),
+            None => println!("{} -> {}", f.input.display(), out.join(&f.output).display()),
+        }
+    }
+    if let Some(path) = report_path {
+        std::fs::write(path, serde_json::to_vec_pretty(&report)?)?;
+    }
+    Ok(if report.failed().next().is_none() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+/// The directory before the first wildcard component, and the files below it matching
+/// `pattern`, sorted.
+fn expand(pattern: &str) -> CliResult<(PathBuf, Vec<PathBuf>)> {
+    let parts: Vec<&str> = pattern
+        .split(|c| c == '/' || std::path::is_separator(c))
+        .collect();
+    let fixed = parts
+        .iter()
+        .position(|p| p.contains(['*', '?']))
+        .unwrap_or(parts.len());
+    if fixed == parts.len() {
+        let path = PathBuf::from(pattern);
+        let base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        return Ok((base, vec![path]));
+    }
+    let base = PathBuf::from(parts[..fixed].join("/"));
+    let mut found = Vec::new();
+    let root = if base.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        &base
+    };
+    walk(root, &mut Vec::new(), &mut |rel, path| {
+        if glob_match(&parts[fixed..], rel) {
+            found.push(path.to_path_buf());
+        }
+    })?;
+    found.sort();
+    let found = found
+        .into_iter()
+        .map(|p| p.strip_prefix("./").map(Path::to_path_buf).unwrap_or(p))
+        .collect();
+    Ok((base, found))
+}
+
+fn walk(
+    dir: &Path,
+    rel: &mut Vec<String>,
+    on_file: &mut impl FnMut(&[String], &Path),
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        rel.push(entry.file_name().to_string_lossy().into_owned());
+        if entry.file_type()?.is_dir() {
+            walk(&entry.path(), rel, on_file)?;
+        } else {
+            on_file(rel, &entry.path());
+        }
+        rel.pop();
+    }
+    Ok(())
+}
+
+fn glob_match(pattern: &[&str], path: &[String]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            glob_match(&pattern[1..], path) || (!path.is_empty() && glob_match(pattern, &path[1..]))
+        }
+        (Some(p), Some(name)) => {
+            segment_match(p.as_bytes(), name.as_bytes()) && glob_match(&pattern[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+fn segment_match(p: &[u8], s: &[u8]) -> bool {
+    match (p.first(), s.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            segment_match(&p[1..], s) || (!s.is_empty() && segment_match(p, &s[1..]))
+        }
+        (Some(b'?'), Some(_)) => segment_match(&p[1..], &s[1..]),
+        (Some(a), Some(b)) => a == b && segment_match(&p[1..], &s[1..]),
+        _ => false,
+    }
+}
+
+fn print_tree(
+    out: &mut impl std::io::Write,
+    path: &str,
+    document: &gltf::Document,
+    report: &draco_gltf_rs::AssetReport,
+) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "{path}: {} meshes, geometry {} stored / {} raw, textures {}",
+        document.meshes().len(),
+        size(report.stored_geometry_bytes()),
+        size(report.uncompressed_geometry_bytes()),
+        size(report.texture_bytes()),
+    )?;
+    for mesh in document.meshes() {
+        let name = mesh.name().map(|n| format!(" {n:?}")).unwrap_or_default();
+        writeln!(out, "mesh {}{name}", mesh.index())?;
+        for p in mesh.primitives() {
+            let Some(stats) = report
+                .primitives
+                .iter()
+                .find(|s| s.mesh == mesh.index() && s.primitive == p.index())
+            else {
+                continue;
+            };
+            writeln!(
+                out,
+                "  primitive {} {}: {} vertices, {} indices, {} stored / {} raw",
+                p.index(),
+                if stats.draco { "draco" } else { "plain" },
+                stats.vertex_count,
+                stats.index_count,
+                size(stats.stored_bytes),
+                size(stats.uncompressed_bytes),
+            )?;
+            let mut attributes: Vec<_> = p.attributes().collect();
+            attributes.sort_by_key(|(_, a)| a.index());
+            for (semantic, a) in attributes {
+                writeln!(
+                    out,
+                    "    {:<12} {:?} {:?} x{}",
+                    semantic.to_string(),
+                    a.dimensions(),
+                    a.data_type(),
+                    a.count()
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn size(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1 << 20 => format!("{:.1} MiB", b as f64 / (1 << 20) as f64),
+        b if b >= 1 << 10 => format!("{:.1} KiB", b as f64 / 1024.0),
+        b => format!("{b} B"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASSET: &str = "examples/test.glb";
+
+    fn run(args: &[&str]) -> CliResult<(ExitCode, String)> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let mut out = Vec::new();
+        let code = inspect(&args, &mut out)?;
+        Ok((code, String::from_utf8(out)?))
+    }
+
+    #[test]
+    fn test_inspect_tree() -> CliResult<()> {
+        let (code, out) = run(&[ASSET])?;
+        assert_eq!(code, ExitCode::SUCCESS);
+        assert!(out.starts_with(&format!("{ASSET}: 1 meshes")), "{out}");
+        assert!(out.contains("  primitive 0 draco: "), "{out}");
+        assert!(out.contains("    POSITION "), "{out}");
+        assert!(!out.contains("violation"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect_json_reports_violations() -> CliResult<()> {
+        let (code, out) = run(&[ASSET, "--json", "--max-vertices", "1", "--require-draco"])?;
+        assert_eq!(code, ExitCode::from(1));
+        let json: serde_json::Value = serde_json::from_str(&out)?;
+        assert_eq!(json["file"], ASSET);
+        let primitive = &json["report"]["primitives"][0];
+        assert_eq!(primitive["draco"], true);
+        let vertices = primitive["vertex_count"]
+            .as_u64()
+            .ok_or("no vertex_count")?;
+        assert!(vertices > 1);
+        assert_eq!(
+            json["violations"],
+            serde_json::json!([format!("mesh 0 primitive 0: {vertices} vertices > 1")])
+        );
+
+        let limit = vertices.to_string();
+        let (code, out) = run(&[ASSET, "--json", "--max-vertices", &limit])?;
+        assert_eq!(code, ExitCode::SUCCESS);
+        let json: serde_json::Value = serde_json::from_str(&out)?;
+        assert_eq!(json["violations"], serde_json::json!([]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect_usage_errors() {
+        assert!(run(&[]).is_err());
+        assert!(run(&[ASSET, "--max-indices"]).is_err());
+        assert!(run(&[ASSET, "--max-indices", "many"]).is_err());
+        assert!(run(&[ASSET, "--strict"]).is_err());
+        assert!(run(&[ASSET, ASSET]).is_err());
+    }
+}
@@ -0,0 +1,599 @@
+use crate::*;
+
+use std::ops::RangeInclusive;
+
+/// A Draco bitstream version, `major.minor` from the stream header.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct BitstreamVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl BitstreamVersion {
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+}
+
+impl std::fmt::Display for BitstreamVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Mesh bitstream versions the bundled decoder reads: everything from 1.0 up to 2.2, the
+/// version current Draco encoders write. Newer streams need a newer decoder.
+pub fn supported_versions() -> RangeInclusive<BitstreamVersion> {
+    BitstreamVersion::new(1, 0)..=BitstreamVersion::new(2, 2)
+}
+
+/// The version in the header of a Draco stream, `None` if `bytes` doesn't start with the
+/// `DRACO` magic.
+pub fn bitstream_version(bytes: &[u8]) -> Option<BitstreamVersion> {
+    match bytes {
+        [b'D', b'R', b'A', b'C', b'O', major, minor, ..] => {
+            Some(BitstreamVersion::new(*major, *minor))
+        }
+        _ => None,
+    }
+}
+
+/// The bitstream version of a Draco primitive's compressed buffer view.
+pub fn primitive_bitstream_version(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+) -> Result<Option<BitstreamVersion>, DracoLoadError> {
+    let ext = draco_extension(p)?;
+    let bytes = get_buffer(document, buffers, ext.buffer_view)?;
+    Ok(bitstream_version(bytes))
+}
+
+/// Fails with [`DracoLoadError::UnsupportedBitstreamVersion`] before the decoder sees a
+/// stream it can't read. Bytes without a header are left for the decoder to reject.
+pub(crate) fn check_bitstream_version(bytes: &[u8]) -> Result<(), DracoLoadError> {
+    let supported = supported_versions();
+    match bitstream_version(bytes) {
+        Some(found) if !supported.contains(&found) => {
+            Err(DracoLoadError::UnsupportedBitstreamVersion { found, supported })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Vertex and face counts read from the connectivity header of a Draco mesh stream,
+/// without decoding it, so a scheduler can budget memory and order work up front.
+///
+/// Faces are exact. Vertices are exact for sequentially encoded meshes; for edgebreaker
+/// (the usual encoding) they count connectivity vertices, which attribute seams split
+/// further, so the decoded count lies between that and `3 * faces`. The glTF `POSITION`
+/// accessor has the exact figure when the document is at hand.
+///
+/// `None` for bytes that aren't a supported mesh stream.
+pub fn estimate_counts(draco_bytes: &[u8]) -> Option<(usize, usize)> {
+    let (mut r, method) = HeaderReader::open(draco_bytes)?;
+    match method {
+        SEQUENTIAL => {
+            let (faces, vertices) = if r.before(2, 2) {
+                (r.u32()?, r.u32()?)
+            } else {
+                (r.varint()?, r.varint()?)
+            };
+            Some((vertices as usize, faces as usize))
+        }
+        EDGEBREAKER => {
+            let _traversal = r.u8()?;
+            if r.before(2, 2) {
+                let _new_vertices = r.count()?;
+            }
+            let vertices = r.count()?;
+            let faces = r.count()?;
+            Some((vertices as usize, faces as usize))
+        }
+        _ => None,
+    }
+}
+
+/// One attribute as a Draco stream describes it in its own attribute header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StreamAttribute {
+    pub unique_id: u32,
+    /// `draco::GeometryAttribute::Type`: 0 position, 1 normal, 2 color, 3 texcoord,
+    /// 4 generic.
+    pub attribute_type: u8,
+    /// `draco::DataType`, as in [`AttrInfo::data_type`].
+    pub data_type: u8,
+    pub components: u8,
+    pub normalized: bool,
+}
+
+impl StreamAttribute {
+    pub fn info(&self) -> AttrInfo {
+        AttrInfo {
+            unique_id: self.unique_id,
+            dim: self.components as u32,
+            data_type: self.data_type,
+        }
+    }
+}
+
+/// The attributes of a Draco mesh stream in the order the decoder writes them, read from
+/// the compressed bytes without decoding any geometry.
+///
+/// The attribute header follows the connectivity, so the parser walks past it: entropy
+/// coded sections are skipped by their stored sizes, and only the tags of tagged symbol
+/// streams get a (small) rANS decode to learn how many bits follow them.
+///
+/// `None` for bytes that aren't a supported mesh stream or don't parse.
+pub fn stream_attributes(draco_bytes: &[u8]) -> Option<Vec<StreamAttribute>> {
+    let (mut r, method) = HeaderReader::open(draco_bytes)?;
+    match method {
+        SEQUENTIAL => skip_sequential_connectivity(&mut r)?,
+        EDGEBREAKER => skip_edgebreaker_connectivity(&mut r)?,
+        _ => return None,
+    }
+
+    let decoders = r.u8()?;
+    if method == EDGEBREAKER {
+        for _ in 0..decoders {
+            let (_data_id, _decoder_type) = (r.u8()?, r.u8()?);
+            if !r.before(1, 2) {
+                let _traversal = r.u8()?;
+            }
+        }
+    }
+    let mut out = Vec::new();
+    for _ in 0..decoders {
+        let attributes = r.count()?;
+        for _ in 0..attributes {
+            let (attribute_type, data_type, components, normalized) =
+                (r.u8()?, r.u8()?, r.u8()?, r.u8()?);
+            let unique_id = if r.before(1, 3) {
+                r.u16()? as u32
+            } else {
+                r.varint()?
+            };
+            out.push(StreamAttribute {
+                unique_id,
+                attribute_type,
+                data_type,
+                components,
+                normalized: normalized != 0,
+            });
+        }
+        // every mesh attribute decoder is a sequential controller, which then names the
+        // prediction/quantization decoder of each attribute
+        r.skip(attributes as u64)?;
+    }
+    Some(out)
+}
+
+/// [`stream_attributes`] as decoder infos.
+pub(crate) fn stream_attr_infos(draco_bytes: &[u8]) -> Option<Vec<AttrInfo>> {
+    Some(
+        stream_attributes(draco_bytes)?
+            .iter()
+            .map(StreamAttribute::info)
+            .collect(),
+    )
+}
+
+const MESH: u8 = 1;
+const SEQUENTIAL: u8 = 0;
+const EDGEBREAKER: u8 = 1;
+const METADATA_FLAG: u16 = 0x8000;
+/// Draco writes metadata a level or two deep; anything deeper is rejected rather than
+/// recursed into.
+const MAX_METADATA_DEPTH: u32 = 32;
+
+fn skip_sequential_connectivity(r: &mut HeaderReader<'_>) -> Option<()> {
+    let (faces, points) = if r.before(2, 2) {
+        (r.u32()?, r.u32()?)
+    } else {
+        (r.varint()?, r.varint()?)
+    };
+    let indices = faces.checked_mul(3)?;
+    if r.u8()? == 0 {
+        return skip_symbols(r, indices, 1);
+    }
+    if (1 << 16..1 << 21).contains(&points) && !r.before(2, 2) {
+        // a varint takes at least a byte
+        if indices as usize > r.remaining() {
+            return None;
+        }
+        for _ in 0..indices {
+            r.varint()?;
+        }
+        return Some(());
+    }
+    let size = match points {
+        0..256 => 1,
+        256..65536 => 2,
+        _ => 4,
+    };
+    r.skip(indices as u64 * size)
+}
+
+fn skip_edgebreaker_connectivity(r: &mut HeaderReader<'_>) -> Option<()> {
+    const STANDARD: u8 = 0;
+    const PREDICTIVE: u8 = 1;
+    const VALENCE: u8 = 2;
+
+    let traversal = r.u8()?;
+    if r.before(2, 2) {
+        let _new_vertices = r.count()?;
+    }
+    let (_vertices, _faces) = (r.count()?, r.count()?);
+    let attribute_data = r.u8()?;
+    let (_symbols, _split_symbols) = (r.count()?, r.count()?);
+
+    // before 2.2 the events sit after the traversal data and are skipped after it
+    let mut event_bytes = 0;
+    if r.before(2, 2) {
+        let size = r.count()? as usize;
+        let mut events = HeaderReader {
+            at: r.at.checked_add(size)?,
+            ..*r
+        };
+        skip_events(&mut events)?;
+        event_bytes = (events.at - r.at - size) as u64;
+    } else {
+        skip_events(r)?;
+    }
+
+    match traversal {
+        STANDARD | PREDICTIVE => {
+            r.skip_sized()?;
+            skip_start_faces(r)?;
+            for _ in 0..attribute_data {
+                r.skip_rans_bits()?;
+            }
+            if traversal == PREDICTIVE {
+                let _split_symbols = r.u32()?;
+                r.skip_rans_bits()?;
+            }
+        }
+        VALENCE => {
+            if r.before(2, 2) {
+                r.skip_sized()?;
+            }
+            skip_start_faces(r)?;
+            for _ in 0..attribute_data {
+                r.skip_rans_bits()?;
+            }
+            if r.before(2, 2) {
+                let _split_symbols = r.count()?;
+                // only the 2..=7 valence mode exists
+                if r.u8()? != 0 {
+                    return None;
+                }
+            }
+            for _ in 2..=7 {
+                let symbols = r.varint()?;
+                skip_symbols(r, symbols, 1)?;
+            }
+        }
+        _ => return None,
+    }
+    r.skip(event_bytes)
+}
+
+fn skip_events(r: &mut HeaderReader<'_>) -> Option<()> {
+    let splits = r.count()? as u64;
+    if splits > 0 {
+        if r.before(1, 2) {
+            r.skip(splits * 9)?;
+        } else {
+            for _ in 0..splits {
+                r.varint()?;
+                r.varint()?;
+            }
+            let edge_bits = if r.before(2, 2) { 2 } else { 1 };
+            r.skip_bits(splits * edge_bits)?;
+        }
+    }
+    let holes = if r.before(2, 0) {
+        r.u32()?
+    } else if r.before(2, 1) {
+        r.varint()?
+    } else {
+        0
+    };
+    if r.before(1, 2) {
+        return r.skip(holes as u64 * 4);
+    }
+    for _ in 0..holes {
+        r.varint()?;
+    }
+    Some(())
+}
+
+fn skip_start_faces(r: &mut HeaderReader<'_>) -> Option<()> {
+    if r.before(2, 2) {
+        r.skip_sized()
+    } else {
+        r.skip_rans_bits()
+    }
+}
+
+/// Skips a `draco::DecodeSymbols` stream of `values` values.
+fn skip_symbols(r: &mut HeaderReader<'_>, values: u32, components: u32) -> Option<()> {
+    const TAGGED: u8 = 0;
+    const RAW: u8 = 1;
+
+    if values == 0 {
+        return Some(());
+    }
+    match r.u8()? {
+        TAGGED => {
+            // each tag is the bit length, at least one, of the next `components` raw
+            // values, which follow the tags
+            if values as u64 > r.remaining() as u64 * 8 {
+                return None;
+            }
+            let mut tags = r.rans_symbols(5)?;
+            let available = r.remaining() as u64 * 8;
+            let mut bits = 0u64;
+            for _ in (0..values).step_by(components.max(1) as usize) {
+                bits += tags.symbol() as u64 * components as u64;
+                if bits > available {
+                    return None;
+                }
+            }
+            r.skip_bits(bits)
+        }
+        RAW => {
+            let max_bit_length = r.u8()?;
+            if !(1..=18).contains(&max_bit_length) {
+                return None;
+            }
+            r.rans_symbols(max_bit_length).map(drop)
+        }
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy)]
+struct HeaderReader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+    version: BitstreamVersion,
+}
+
+impl<'a> HeaderReader<'a> {
+    /// Reads the stream header and skips any metadata, leaving the reader at the
+    /// connectivity. Returns the encoding method.
+    fn open(bytes: &'a [u8]) -> Option<(Self, u8)> {
+        let version = bitstream_version(bytes)?;
+        if !supported_versions().contains(&version) {
+            return None;
+        }
+        let mut r = HeaderReader {
+            bytes,
+            at: 7,
+            version,
+        };
+        let (encoder, method) = (r.u8()?, r.u8()?);
+        let flags = r.u16()?;
+        if encoder != MESH {
+            return None;
+        }
+        if flags & METADATA_FLAG != 0 && !r.before(1, 3) {
+            for _ in 0..r.varint()? {
+                let _unique_id = r.varint()?;
+                r.skip_metadata(0)?;
+            }
+            r.skip_metadata(0)?;
+        }
+        Some((r, method))
+    }
+
+    fn before(&self, major: u8, minor: u8) -> bool {
+        self.version < BitstreamVersion::new(major, minor)
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.at
+    }
+
+    fn take(&mut self, len: u64) -> Option<&'a [u8]> {
+        let end = self.at.checked_add(usize::try_from(len).ok()?)?;
+        let b = self.bytes.get(self.at..end)?;
+        self.at = end;
+        Some(b)
+    }
+
+    fn skip(&mut self, len: u64) -> Option<()> {
+        self.take(len).map(drop)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        let b = *self.bytes.get(self.at)?;
+        self.at += 1;
+        Some(b)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let b = self.take(2)?;
+        Some(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let b = self.take(4)?;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        let b = self.take(8)?;
+        Some(u64::from_le_bytes(b.try_into().ok()?))
+    }
+
+    /// Draco's unsigned LEB128 varint.
+    fn varint(&mut self) -> Option<u32> {
+        u32::try_from(self.varint64()?).ok()
+    }
+
+    fn varint64(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.u8()?;
+            value |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// A count: `u32` before 2.0, a varint since.
+    fn count(&mut self) -> Option<u32> {
+        if self.before(2, 0) {
+            self.u32()
+        } else {
+            self.varint()
+        }
+    }
+
+    /// Skips `bits` bits of a bit-packed section, rounded up to whole bytes.
+    fn skip_bits(&mut self, bits: u64) -> Option<()> {
+        self.skip(bits.div_ceil(8))
+    }
+
+    /// Skips a bit-packed section stored with its byte size in front.
+    fn skip_sized(&mut self) -> Option<()> {
+        let size = if self.before(2, 2) {
+            self.u64()?
+        } else {
+            self.varint64()?
+        };
+        self.skip(size)
+    }
+
+    /// Skips a `draco::RAnsBitDecoder` stream.
+    fn skip_rans_bits(&mut self) -> Option<()> {
+        let _prob_zero = self.u8()?;
+        let size = if self.before(2, 2) {
+            self.u32()?
+        } else {
+            self.varint()?
+        };
+        self.skip(size as u64)
+    }
+
+    /// Reads a `draco::RAnsSymbolDecoder` for symbols of up to `symbol_bits` bits, leaving
+    /// the reader behind its data.
+    fn rans_symbols(&mut self, symbol_bits: u8) -> Option<RansSymbols<'a>> {
+        let num_symbols = self.count()?;
+        if num_symbols == 0 || num_symbols as usize / 64 > self.remaining() {
+            return None;
+        }
+        let mut probs = Vec::with_capacity(num_symbols as usize);
+        while probs.len() < num_symbols as usize {
+            let data = self.u8()?;
+            if data & 3 == 3 {
+                let zeros = (data >> 2) as usize + 1;
+                if probs.len() + zeros > num_symbols as usize {
+                    return None;
+                }
+                probs.resize(probs.len() + zeros, 0);
+            } else {
+                let mut prob = (data >> 2) as u32;
+                for b in 0..(data & 3) as u32 {
+                    prob |= (self.u8()? as u32) << (8 * (b + 1) - 2);
+                }
+                probs.push(prob);
+            }
+        }
+        let size = if self.before(2, 0) {
+            self.u64()?
+        } else {
+            self.varint64()?
+        };
+        let precision_bits = (3 * symbol_bits as u32 / 2).clamp(12, 20);
+        RansSymbols::new(self.take(size)?, precision_bits, &probs)
+    }
+
+    /// Skips one level of Draco metadata and its nested levels, `depth` levels down.
+    fn skip_metadata(&mut self, depth: u32) -> Option<()> {
+        if depth > MAX_METADATA_DEPTH {
+            return None;
+        }
+        for _ in 0..self.varint()? {
+            let name = self.u8()?;
+            self.skip(name as u64)?;
+            let size = self.varint()?;
+            self.skip(size as u64)?;
+        }
+        for _ in 0..self.varint()? {
+            let name = self.u8()?;
+            self.skip(name as u64)?;
+            self.skip_metadata(depth + 1)?;
+        }
+        Some(())
+    }
+}
+
+/// Draco's rANS symbol decoder, reading its data back to front.
+struct RansSymbols<'a> {
+    data: &'a [u8],
+    offset: usize,
+    state: u32,
+    precision: u32,
+    probs: Vec<u32>,
+    cumulative: Vec<u32>,
+    lookup: Vec<u32>,
+}
+
+impl<'a> RansSymbols<'a> {
+    const IO_BASE: u32 = 256;
+
+    fn new(data: &'a [u8], precision_bits: u32, probs: &[u32]) -> Option<Self> {
+        let precision = 1 << precision_bits;
+        let mut cumulative = Vec::with_capacity(probs.len());
+        let mut lookup = Vec::with_capacity(precision as usize);
+        let mut total = 0u32;
+        for (symbol, &prob) in probs.iter().enumerate() {
+            cumulative.push(total);
+            total = total.checked_add(prob).filter(|&t| t <= precision)?;
+            lookup.resize(total as usize, symbol as u32);
+        }
+        if total != precision {
+            return None;
+        }
+
+        // the top two bits of the last byte give the width of the initial state
+        let last = *data.last()?;
+        let width = (last >> 6) as usize + 1;
+        let offset = data.len().checked_sub(width)?;
+        let mut le = [0u8; 4];
+        le[..width].copy_from_slice(&data[offset..]);
+        let mask = (1u32 << (8 * width - 2)) - 1;
+        let state = (u32::from_le_bytes(le) & mask) + 4 * precision;
+        if state >= 4 * precision * Self::IO_BASE {
+            return None;
+        }
+        Some(Self {
+            data,
+            offset,
+            state,
+            precision,
+            probs: probs.to_vec(),
+            cumulative,
+            lookup,
+        })
+    }
+
+    fn symbol(&mut self) -> u32 {
+        while self.state < 4 * self.precision && self.offset > 0 {
+            self.offset -= 1;
+            self.state = self.state * Self::IO_BASE + self.data[self.offset] as u32;
+        }
+        let (quo, rem) = (self.state / self.precision, self.state % self.precision);
+        let symbol = self.lookup[rem as usize];
+        let s = symbol as usize;
+        self.state = quo * self.probs[s] + rem - self.cumulative[s];
+        symbol
+    }
+}
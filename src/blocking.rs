@@ -0,0 +1,33 @@
+use crate::*;
+
+/// [`decode_draco`] run to completion on the calling thread, for callers without an async
+/// runtime. The pipeline is the same; natively the decoder's futures finish on their first
+/// poll, so nothing is spawned. The thread only parks while waiting for a slot under
+/// [`set_max_concurrent_decodes`].
+///
+/// Not for `wasm32`, where the decoder completes asynchronously; use the async variants
+/// there.
+pub fn decode_draco_blocking(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    infos: &[AttrInfo],
+) -> Result<DecodedPrimitive, DracoLoadError> {
+    let options = DecodeOptions::default();
+    parallel::block_on(decode_draco_with_options(
+        p, document, buffers, infos, &options,
+    ))
+}
+
+/// [`decode_draco_with_options`] on the calling thread; see [`decode_draco_blocking`].
+pub fn decode_draco_blocking_with_options(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    infos: &[AttrInfo],
+    options: &DecodeOptions,
+) -> Result<DecodedPrimitive, DracoLoadError> {
+    parallel::block_on(decode_draco_with_options(
+        p, document, buffers, infos, options,
+    ))
+}
@@ -0,0 +1,96 @@
+use crate::*;
+
+/// Which vertex and index order decoding produces; see [`DecodeOptions::ordering`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StableOrdering {
+    /// Whatever the Draco decoder emits. Fixed for a given decoder version, but an update
+    /// of the decoder may change it, and so may re-encoding the asset.
+    #[default]
+    Decoder,
+    /// [`DecodedPrimitive::canonicalize`]: derived from the vertex data alone, so it doesn't
+    /// depend on the order the decoder emits, and re-encodes that keep the values (with any
+    /// Draco connectivity method) decode to the same order. Safe to key baked per-vertex
+    /// sidecar data on.
+    Canonical,
+}
+
+impl DecodedPrimitive {
+    /// Puts vertices and triangles into an order that depends only on their values.
+    ///
+    /// Vertices are sorted by the bits of every stream (positions, normals, tangents, then
+    /// texcoord, color, weight and joint sets by set number), with `-0.0` treated as `0.0`.
+    /// Equal vertices are ordered by the values of their triangles' other corners, and any
+    /// still indistinguishable keep their relative order. Each triangle is then rotated to
+    /// start at its smallest index, keeping its winding, and triangles are sorted.
+    ///
+    /// This order is part of the crate's stability guarantee and won't change in minor
+    /// versions. Primitives whose indices point past the vertex streams are left as is.
+    pub fn canonicalize(&mut self) {
+        let count = self.positions.as_ref().map_or(0, Vec::len);
+        if self.indices.iter().any(|&i| i as usize >= count) {
+            return;
+        }
+
+        let mut keys: Vec<Vec<u32>> = vec![Vec::new(); count];
+        let mut add = |components: &mut dyn Iterator<Item = &[f32]>| {
+            for (key, v) in keys.iter_mut().zip(components) {
+                key.extend(v.iter().map(|x| (x + 0.0).to_bits()));
+            }
+        };
+        add(&mut self.positions.iter().flatten().map(|v| &v[..]));
+        add(&mut self.normals.iter().flatten().map(|v| &v[..]));
+        add(&mut self.tangents.iter().flatten().map(|v| &v[..]));
+        for (_, v) in sorted(&self.texcoords) {
+            add(&mut v.iter().map(|v| &v[..]));
+        }
+        for (_, v) in sorted(&self.colors) {
+            add(&mut v.iter().map(|v| &v[..]));
+        }
+        for (_, v) in sorted(&self.weights) {
+            add(&mut v.iter().map(|v| &v[..]));
+        }
+        for (_, v) in sorted(&self.joints) {
+            for (key, j) in keys.iter_mut().zip(v) {
+                key.extend(j.iter().map(|&j| j as u32));
+            }
+        }
+
+        // vertices with equal values are told apart by the values around them: the other
+        // two corners of each triangle using them, in winding order
+        let mut order: Vec<usize> = (0..count).collect();
+        order.sort_by(|&a, &b| keys[a].cmp(&keys[b]));
+        let mut class = vec![0u32; count];
+        for w in 1..count {
+            let same = keys[order[w]] == keys[order[w - 1]];
+            class[order[w]] = class[order[w - 1]] + u32::from(!same);
+        }
+        let mut corners: Vec<Vec<[u32; 2]>> = vec![Vec::new(); count];
+        for t in self.indices.chunks_exact(3) {
+            for k in 0..3 {
+                let next = [t[(k + 1) % 3], t[(k + 2) % 3]].map(|i| class[i as usize]);
+                corners[t[k] as usize].push(next);
+            }
+        }
+        corners.iter_mut().for_each(|c| c.sort_unstable());
+        order.sort_by(|&a, &b| (class[a], &corners[a]).cmp(&(class[b], &corners[b])));
+        let mut perm = vec![0; count];
+        for (new, &old) in order.iter().enumerate() {
+            perm[old] = new;
+        }
+        apply_permutation(self, &perm);
+
+        let mut triangles: Vec<[u32; 3]> = self
+            .indices
+            .chunks_exact(3)
+            .map(|t| {
+                let start = (0..3).min_by_key(|&k| t[k]).unwrap_or(0);
+                [t[start], t[(start + 1) % 3], t[(start + 2) % 3]]
+            })
+            .collect();
+        triangles.sort_unstable();
+        let tail = self.indices.len() / 3 * 3;
+        let rest = self.indices[tail..].to_vec();
+        self.indices = triangles.into_iter().flatten().chain(rest).collect();
+        self.refresh_areas();
+    }
+}
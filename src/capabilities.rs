@@ -0,0 +1,150 @@
+use crate::*;
+
+use gltf::accessor::DataType;
+use std::ops::RangeInclusive;
+
+/// glTF extensions that bear on whether this crate can take an asset, by name in
+/// [`Extension::name`]. Anything else is unknown to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Extension {
+    DracoMeshCompression,
+    /// Integer vertex streams, widened according to [`DecodeOptions::promotions`].
+    MeshQuantization,
+    LightsPunctual,
+    MaterialsClearcoat,
+    MaterialsEmissiveStrength,
+    MaterialsIor,
+    MaterialsTransmission,
+    MaterialsUnlit,
+    MaterialsVolume,
+    StructuralMetadata,
+    /// Not supported: meshopt-compressed buffer views aren't decoded.
+    MeshoptCompression,
+    /// Not supported: instanced nodes are read as a single instance.
+    MeshGpuInstancing,
+}
+
+impl Extension {
+    pub const ALL: [Extension; 12] = [
+        Extension::DracoMeshCompression,
+        Extension::MeshQuantization,
+        Extension::LightsPunctual,
+        Extension::MaterialsClearcoat,
+        Extension::MaterialsEmissiveStrength,
+        Extension::MaterialsIor,
+        Extension::MaterialsTransmission,
+        Extension::MaterialsUnlit,
+        Extension::MaterialsVolume,
+        Extension::StructuralMetadata,
+        Extension::MeshoptCompression,
+        Extension::MeshGpuInstancing,
+    ];
+
+    /// The name as it appears in `extensionsUsed`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Extension::DracoMeshCompression => "KHR_draco_mesh_compression",
+            Extension::MeshQuantization => "KHR_mesh_quantization",
+            Extension::LightsPunctual => "KHR_lights_punctual",
+            Extension::MaterialsClearcoat => "KHR_materials_clearcoat",
+            Extension::MaterialsEmissiveStrength => "KHR_materials_emissive_strength",
+            Extension::MaterialsIor => "KHR_materials_ior",
+            Extension::MaterialsTransmission => "KHR_materials_transmission",
+            Extension::MaterialsUnlit => "KHR_materials_unlit",
+            Extension::MaterialsVolume => "KHR_materials_volume",
+            Extension::StructuralMetadata => "EXT_structural_metadata",
+            Extension::MeshoptCompression => "EXT_meshopt_compression",
+            Extension::MeshGpuInstancing => "EXT_mesh_gpu_instancing",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.name() == name)
+    }
+}
+
+impl std::fmt::Display for Extension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What this build of the crate handles, from [`features`], so a host can route an asset
+/// here or to another decoder up front instead of finding out from a failed decode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Primitive modes that decode; anything else is
+    /// [`DracoLoadError::UnsupportedMode`].
+    pub modes: Vec<gltf::mesh::Mode>,
+    pub extensions: Vec<Extension>,
+    pub index_types: Vec<DataType>,
+    /// Vertex stream component types, widened to the types of [`DecodedPrimitive`].
+    pub attribute_types: Vec<DataType>,
+    pub bitstream_versions: RangeInclusive<BitstreamVersion>,
+    /// Optional Cargo features compiled in.
+    pub cargo_features: Vec<&'static str>,
+}
+
+/// The capabilities of this build.
+pub fn features() -> Capabilities {
+    let cargo_features = [
+        ("watch", cfg!(feature = "watch")),
+        ("gpu", cfg!(feature = "gpu")),
+        ("render-offscreen", cfg!(feature = "render-offscreen")),
+        ("mmap", cfg!(feature = "mmap")),
+        ("arrow", cfg!(feature = "arrow")),
+        ("alloc-metrics", cfg!(feature = "alloc-metrics")),
+        ("cli", cfg!(feature = "cli")),
+        ("tokio", cfg!(feature = "tokio")),
+        ("plugins", cfg!(feature = "plugins")),
+        ("sync", cfg!(feature = "sync")),
+        ("zip", cfg!(feature = "zip")),
+    ];
+    Capabilities {
+        modes: vec![gltf::mesh::Mode::Triangles],
+        extensions: Extension::ALL
+            .into_iter()
+            .filter(|e| {
+                !matches!(
+                    e,
+                    Extension::MeshoptCompression | Extension::MeshGpuInstancing
+                )
+            })
+            .collect(),
+        index_types: vec![DataType::U8, DataType::U16, DataType::U32],
+        attribute_types: vec![
+            DataType::I8,
+            DataType::U8,
+            DataType::I16,
+            DataType::U16,
+            DataType::U32,
+            DataType::F32,
+        ],
+        bitstream_versions: supported_versions(),
+        cargo_features: cargo_features
+            .into_iter()
+            .filter_map(|(name, on)| on.then_some(name))
+            .collect(),
+    }
+}
+
+/// Whether this build handles `extension`.
+pub fn supports(extension: Extension) -> bool {
+    features().supports(extension)
+}
+
+impl Capabilities {
+    pub fn supports(&self, extension: Extension) -> bool {
+        self.extensions.contains(&extension)
+    }
+
+    /// The document's `extensionsRequired` this build doesn't handle, unknown ones
+    /// included; empty when the asset can be routed here.
+    pub fn unsupported_required(&self, document: &gltf::Document) -> Vec<String> {
+        document
+            .extensions_required()
+            .filter(|name| !Extension::from_name(name).is_some_and(|e| self.supports(e)))
+            .map(str::to_string)
+            .collect()
+    }
+}
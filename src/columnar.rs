@@ -0,0 +1,71 @@
+use crate::*;
+
+use arrow_array::{
+    Array, ArrayRef, FixedSizeListArray, Float32Array, RecordBatch, UInt16Array, UInt32Array,
+};
+use arrow_schema::{ArrowError, Field};
+use std::sync::Arc;
+
+impl DecodedPrimitive {
+    /// One row per vertex; each stream becomes a fixed-size list column named by its glTF
+    /// attribute key (`POSITION`, `TEXCOORD_0`, ...). Sets are emitted in ascending order.
+    pub fn to_arrow(&self) -> Result<RecordBatch, ArrowError> {
+        let mut columns: Vec<(String, ArrayRef)> = Vec::new();
+        if let Some(v) = &self.positions {
+            columns.push(("POSITION".into(), f32_list(v)?));
+        }
+        if let Some(v) = &self.normals {
+            columns.push(("NORMAL".into(), f32_list(v)?));
+        }
+        if let Some(v) = &self.tangents {
+            columns.push(("TANGENT".into(), f32_list(v)?));
+        }
+        for (set, v) in sorted(&self.texcoords) {
+            columns.push((format!("TEXCOORD_{set}"), f32_list(v)?));
+        }
+        for (set, v) in sorted(&self.colors) {
+            columns.push((format!("COLOR_{set}"), f32_list(v)?));
+        }
+        for (set, v) in sorted(&self.joints) {
+            let values = UInt16Array::from(v.as_flattened().to_vec());
+            columns.push((format!("JOINTS_{set}"), list(values, 4)?));
+        }
+        for (set, v) in sorted(&self.weights) {
+            columns.push((format!("WEIGHTS_{set}"), f32_list(v)?));
+        }
+        RecordBatch::try_from_iter(columns)
+    }
+
+    /// One row per triangle with its three vertex indices in column `indices`.
+    pub fn triangles_to_arrow(&self) -> Result<RecordBatch, ArrowError> {
+        let whole = self.indices.len() / 3 * 3;
+        let values = UInt32Array::from(self.indices[..whole].to_vec());
+        RecordBatch::try_from_iter([("indices", list(values, 3)?)])
+    }
+
+    /// Writes [`to_arrow`](Self::to_arrow) as a single-row-group Parquet file.
+    pub fn write_parquet<W: std::io::Write + Send>(
+        &self,
+        writer: W,
+    ) -> Result<(), parquet::errors::ParquetError> {
+        let batch = self.to_arrow()?;
+        let mut w = parquet::arrow::ArrowWriter::try_new(writer, batch.schema(), None)?;
+        w.write(&batch)?;
+        w.close()?;
+        Ok(())
+    }
+}
+
+fn f32_list<const N: usize>(v: &[[f32; N]]) -> Result<ArrayRef, ArrowError> {
+    list(Float32Array::from(v.as_flattened().to_vec()), N)
+}
+
+fn list(values: impl Array + 'static, size: usize) -> Result<ArrayRef, ArrowError> {
+    let item = Arc::new(Field::new("item", values.data_type().clone(), false));
+    Ok(Arc::new(FixedSizeListArray::try_new(
+        item,
+        size as i32,
+        Arc::new(values),
+        None,
+    )?))
+}
@@ -0,0 +1,171 @@
+use crate::*;
+
+/// A decoded primitive packed for keeping in memory while not drawn, from
+/// [`DecodedPrimitive::compress_in_memory`].
+///
+/// Indices are stored as zigzag varints of the difference to the previous index (exact);
+/// float streams as 16-bit values over each component's range (lossy: at most
+/// `range / 131070` per component); joints unchanged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactPrimitive {
+    index_count: usize,
+    indices: Vec<u8>,
+    streams: Vec<(CompactStream, Quantized)>,
+    joints: Vec<(u32, Vec<[u16; 4]>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompactStream {
+    Positions,
+    Normals,
+    Tangents,
+    TexCoords(u32),
+    Colors(u32),
+    Weights(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Quantized {
+    min: Vec<f32>,
+    step: Vec<f32>,
+    values: Vec<u16>,
+}
+
+impl DecodedPrimitive {
+    pub fn compress_in_memory(&self) -> CompactPrimitive {
+        let mut indices = Vec::with_capacity(self.indices.len());
+        let mut prev = 0i64;
+        for &i in &self.indices {
+            let delta = i as i64 - prev;
+            prev = i as i64;
+            let mut zigzag = ((delta << 1) ^ (delta >> 63)) as u64;
+            while zigzag >= 0x80 {
+                indices.push(zigzag as u8 | 0x80);
+                zigzag >>= 7;
+            }
+            indices.push(zigzag as u8);
+        }
+
+        let mut streams = Vec::new();
+        if let Some(v) = &self.positions {
+            streams.push((CompactStream::Positions, quantize(v)));
+        }
+        if let Some(v) = &self.normals {
+            streams.push((CompactStream::Normals, quantize(v)));
+        }
+        if let Some(v) = &self.tangents {
+            streams.push((CompactStream::Tangents, quantize(v)));
+        }
+        for (set, v) in sorted(&self.texcoords) {
+            streams.push((CompactStream::TexCoords(set), quantize(v)));
+        }
+        for (set, v) in sorted(&self.colors) {
+            streams.push((CompactStream::Colors(set), quantize(v)));
+        }
+        for (set, v) in sorted(&self.weights) {
+            streams.push((CompactStream::Weights(set), quantize(v)));
+        }
+        CompactPrimitive {
+            index_count: self.indices.len(),
+            indices,
+            streams,
+            joints: sorted(&self.joints)
+                .into_iter()
+                .map(|(set, v)| (set, v.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl CompactPrimitive {
+    /// Heap bytes held, to compare against the decoded primitive.
+    pub fn byte_size(&self) -> usize {
+        let streams: usize = self
+            .streams
+            .iter()
+            .map(|(_, q)| q.values.len() * 2 + (q.min.len() + q.step.len()) * 4)
+            .sum();
+        let joints: usize = self.joints.iter().map(|(_, v)| v.len() * 8).sum();
+        self.indices.len() + streams + joints
+    }
+
+    pub fn decompress(&self) -> DecodedPrimitive {
+        let mut out = DecodedPrimitive {
+            indices: Vec::with_capacity(self.index_count),
+            ..Default::default()
+        };
+        let (mut prev, mut acc, mut shift) = (0i64, 0u64, 0);
+        for &b in &self.indices {
+            acc |= ((b & 0x7f) as u64) << shift;
+            shift += 7;
+            if b & 0x80 == 0 {
+                prev += (acc >> 1) as i64 ^ -((acc & 1) as i64);
+                out.indices.push(prev as u32);
+                (acc, shift) = (0, 0);
+            }
+        }
+
+        for (stream, q) in &self.streams {
+            match *stream {
+                CompactStream::Positions => out.positions = Some(dequantize(q)),
+                CompactStream::Normals => out.normals = Some(dequantize(q)),
+                CompactStream::Tangents => out.tangents = Some(dequantize(q)),
+                CompactStream::TexCoords(set) => {
+                    out.texcoords.insert(set, dequantize(q));
+                }
+                CompactStream::Colors(set) => {
+                    out.colors.insert(set, dequantize(q));
+                }
+                CompactStream::Weights(set) => {
+                    out.weights.insert(set, dequantize(q));
+                }
+            }
+        }
+        out.joints = self.joints.iter().cloned().collect();
+        out
+    }
+}
+
+fn quantize<const N: usize>(v: &[[f32; N]]) -> Quantized {
+    let mut min = [f32::INFINITY; N];
+    let mut max = [f32::NEG_INFINITY; N];
+    for x in v {
+        for k in 0..N {
+            min[k] = min[k].min(x[k]);
+            max[k] = max[k].max(x[k]);
+        }
+    }
+    let step: Vec<f32> = (0..N)
+        .map(|k| {
+            let range = max[k] - min[k];
+            if range > 0.0 {
+                range / u16::MAX as f32
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let values = v
+        .iter()
+        .flat_map(|x| (0..N).map(move |k| (x[k], k)))
+        .map(|(x, k)| {
+            if step[k] > 0.0 {
+                ((x - min[k]) / step[k]).round() as u16
+            } else {
+                0
+            }
+        })
+        .collect();
+    Quantized {
+        min: min.map(|m| if m.is_finite() { m } else { 0.0 }).to_vec(),
+        step,
+        values,
+    }
+}
+
+fn dequantize<const N: usize>(q: &Quantized) -> Vec<[f32; N]> {
+    q.values
+        .chunks_exact(N)
+        .map(|c| std::array::from_fn(|k| q.min[k] + c[k] as f32 * q.step[k]))
+        .collect()
+}
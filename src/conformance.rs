@@ -0,0 +1,263 @@
+use crate::*;
+
+/// Machine-readable result of [`check_conformance`], serializable for nightly catalog runs.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ConformanceReport {
+    pub mesh: usize,
+    pub primitive: usize,
+    pub passed: bool,
+    pub checks: Vec<ConformanceCheck>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConformanceCheck {
+    pub attribute: String, // glTF attribute key, or "indices"
+    pub check: CheckKind,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    Present,
+    Count,
+    Components,
+    IndexRange,
+    Triangles,
+    Bounds,
+    NormalizedRange,
+    UnitLength,
+    TangentSign,
+    WeightSum,
+}
+
+/// Relative to the accessor extent: exporters take min/max from the mesh before Draco
+/// quantization, so decoded positions can overshoot by a fraction of a quantization step.
+const BOUNDS_TOLERANCE: f32 = 1.0 / 256.0;
+const UNIT_TOLERANCE: f32 = 1e-2;
+
+/// Checks a decoded primitive against the glTF spec invariants its accessors imply:
+/// element counts, component counts after conversion, index range, POSITION bounds,
+/// normalized value ranges, unit normals, tangent handedness and weight sums.
+pub fn check_conformance(
+    decoded: &DecodedPrimitive,
+    p: &gltf::mesh::Primitive<'_>,
+    mesh: usize,
+) -> ConformanceReport {
+    let mut r = ConformanceReport {
+        mesh,
+        primitive: p.index(),
+        ..Default::default()
+    };
+    let vertex_count = vertex_count(decoded);
+
+    if let Some(acc) = p.indices() {
+        r.push(
+            "indices",
+            CheckKind::Count,
+            decoded.indices.len() == acc.count(),
+            || {
+                format!(
+                    "accessor {} vs decoded {}",
+                    acc.count(),
+                    decoded.indices.len()
+                )
+            },
+        );
+    }
+    r.push(
+        "indices",
+        CheckKind::Triangles,
+        decoded.indices.len().is_multiple_of(3),
+        || format!("{} indices", decoded.indices.len()),
+    );
+    let bad = decoded
+        .indices
+        .iter()
+        .position(|&i| i as usize >= vertex_count);
+    r.push("indices", CheckKind::IndexRange, bad.is_none(), || {
+        format!("index #{} out of {vertex_count} vertices", bad.unwrap_or(0))
+    });
+
+    for (sem, acc) in p.attributes() {
+        let key = sem.to_string();
+        let Some((dim, values)) = stream(decoded, &sem) else {
+            r.push(&key, CheckKind::Present, false, || {
+                "not decoded".to_string()
+            });
+            continue;
+        };
+        let count = values.len() / dim;
+        r.push(&key, CheckKind::Count, count == acc.count(), || {
+            format!("accessor {} vs decoded {count}", acc.count())
+        });
+        let acc_dims = dims_count(acc.dimensions());
+        // COLOR_n may be VEC3 in glTF and is widened to RGBA on output
+        let dims_ok = acc_dims == dim || matches!(sem, gltf::Semantic::Colors(_)) && acc_dims == 3;
+        r.push(&key, CheckKind::Components, dims_ok, || {
+            format!("accessor has {acc_dims} components, output has {dim}")
+        });
+
+        match sem {
+            gltf::Semantic::Positions => check_bounds(&mut r, &key, &acc, &values, dim),
+            gltf::Semantic::Normals => check_unit(&mut r, &key, &values, 3),
+            gltf::Semantic::Tangents => {
+                check_unit_xyz(&mut r, &key, &values);
+                let bad = values
+                    .chunks_exact(4)
+                    .position(|t| (t[3].abs() - 1.0).abs() > UNIT_TOLERANCE);
+                r.push(&key, CheckKind::TangentSign, bad.is_none(), || {
+                    format!("vertex {} has w not +-1", bad.unwrap_or(0))
+                });
+            }
+            gltf::Semantic::Colors(_) => check_range(&mut r, &key, &values, 0.0),
+            gltf::Semantic::Weights(_) => {
+                check_range(&mut r, &key, &values, 0.0);
+                let bad = values
+                    .chunks_exact(4)
+                    .position(|w| (w.iter().sum::<f32>() - 1.0).abs() > UNIT_TOLERANCE);
+                r.push(&key, CheckKind::WeightSum, bad.is_none(), || {
+                    format!("vertex {} weights do not sum to 1", bad.unwrap_or(0))
+                });
+            }
+            gltf::Semantic::TexCoords(_) if acc.normalized() => {
+                let lo = if signed(acc.data_type()) { -1.0 } else { 0.0 };
+                check_range(&mut r, &key, &values, lo)
+            }
+            _ => {}
+        }
+    }
+
+    r.passed = r.checks.iter().all(|c| c.passed);
+    r
+}
+
+impl ConformanceReport {
+    pub fn failures(&self) -> impl Iterator<Item = &ConformanceCheck> {
+        self.checks.iter().filter(|c| !c.passed)
+    }
+
+    /// Panics with every failed check, for use in tests.
+    pub fn assert_passed(&self) {
+        let failed: Vec<String> = self
+            .failures()
+            .map(|c| {
+                format!(
+                    "{} {:?}: {}",
+                    c.attribute,
+                    c.check,
+                    c.detail.as_deref().unwrap_or("")
+                )
+            })
+            .collect();
+        assert!(
+            failed.is_empty(),
+            "mesh {} primitive {} failed conformance:\n{}",
+            self.mesh,
+            self.primitive,
+            failed.join("\n")
+        );
+    }
+
+    fn push(
+        &mut self,
+        attribute: &str,
+        check: CheckKind,
+        passed: bool,
+        detail: impl FnOnce() -> String,
+    ) {
+        self.checks.push(ConformanceCheck {
+            attribute: attribute.to_string(),
+            check,
+            passed,
+            detail: (!passed).then(detail),
+        });
+    }
+}
+
+/// Flattened components of the decoded stream for `sem`, with its component count.
+fn stream(d: &DecodedPrimitive, sem: &gltf::Semantic) -> Option<(usize, Vec<f32>)> {
+    fn flat<const N: usize>(v: &[[f32; N]]) -> (usize, Vec<f32>) {
+        (N, v.iter().flatten().copied().collect())
+    }
+    match sem {
+        gltf::Semantic::Positions => d.positions.as_deref().map(flat),
+        gltf::Semantic::Normals => d.normals.as_deref().map(flat),
+        gltf::Semantic::Tangents => d.tangents.as_deref().map(flat),
+        gltf::Semantic::TexCoords(s) => d.texcoords.get(s).map(|v| flat(v)),
+        gltf::Semantic::Colors(s) => d.colors.get(s).map(|v| flat(v)),
+        gltf::Semantic::Weights(s) => d.weights.get(s).map(|v| flat(v)),
+        gltf::Semantic::Joints(s) => d
+            .joints
+            .get(s)
+            .map(|v| (4, v.iter().flatten().map(|&j| j as f32).collect())),
+        gltf::Semantic::Extras(name) => d
+            .custom
+            .get(&format!("_{name}"))
+            .map(|c| (c.components, c.values.to_f32())),
+    }
+}
+
+fn check_bounds(
+    r: &mut ConformanceReport,
+    key: &str,
+    acc: &gltf::Accessor<'_>,
+    values: &[f32],
+    dim: usize,
+) {
+    let as_vec = |v: Option<serde_json::Value>| -> Option<Vec<f32>> {
+        v?.as_array()?
+            .iter()
+            .map(|x| x.as_f64().map(|f| f as f32))
+            .collect()
+    };
+    let (Some(min), Some(max)) = (as_vec(acc.min()), as_vec(acc.max())) else {
+        return;
+    };
+    let bad = values.chunks_exact(dim).position(|v| {
+        v.iter().enumerate().any(|(k, x)| {
+            let tol = BOUNDS_TOLERANCE * (max[k] - min[k]).abs().max(1.0);
+            *x < min[k] - tol || *x > max[k] + tol
+        })
+    });
+    r.push(key, CheckKind::Bounds, bad.is_none(), || {
+        format!("vertex {} outside accessor min/max", bad.unwrap_or(0))
+    });
+}
+
+fn check_unit(r: &mut ConformanceReport, key: &str, values: &[f32], dim: usize) {
+    let bad = values
+        .chunks_exact(dim)
+        .position(|v| (v.iter().map(|x| x * x).sum::<f32>().sqrt() - 1.0).abs() > UNIT_TOLERANCE);
+    r.push(key, CheckKind::UnitLength, bad.is_none(), || {
+        format!("vertex {} is not unit length", bad.unwrap_or(0))
+    });
+}
+
+fn check_unit_xyz(r: &mut ConformanceReport, key: &str, values: &[f32]) {
+    let xyz: Vec<f32> = values
+        .chunks_exact(4)
+        .flat_map(|t| [t[0], t[1], t[2]])
+        .collect();
+    check_unit(r, key, &xyz, 3);
+}
+
+fn check_range(r: &mut ConformanceReport, key: &str, values: &[f32], lo: f32) {
+    let bad = values.iter().position(|x| !(lo..=1.0).contains(x));
+    r.push(key, CheckKind::NormalizedRange, bad.is_none(), || {
+        format!(
+            "component {} = {} outside [{lo}, 1]",
+            bad.unwrap_or(0),
+            values[bad.unwrap_or(0)]
+        )
+    });
+}
+
+fn signed(dt: gltf::accessor::DataType) -> bool {
+    matches!(
+        dt,
+        gltf::accessor::DataType::I8 | gltf::accessor::DataType::I16
+    )
+}
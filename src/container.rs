@@ -0,0 +1,326 @@
+use crate::*;
+
+use std::borrow::Cow;
+use std::path::{Component, Path};
+
+/// How a [`GlbContainer`] packs its GLBs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerKind {
+    /// GLBs back to back, as streamed by some delivery systems; zero padding between them
+    /// is skipped.
+    Concatenated,
+    /// A ZIP archive whose `.glb` entries are the GLBs. Stored entries are read in place,
+    /// deflated ones need the `zip` feature; ZIP64 and encrypted archives aren't read.
+    Zip,
+}
+
+/// Largest size a deflated ZIP entry may inflate to unless
+/// [`GlbContainer::with_max_inflated_size`] says otherwise, 256 MiB.
+pub const DEFAULT_MAX_INFLATED_SIZE: usize = 256 << 20;
+
+/// A bundle of GLBs in one byte buffer, enumerated by [`GlbContainer::parse`] without
+/// decoding anything.
+#[derive(Debug, Clone)]
+pub struct GlbContainer<'a> {
+    kind: ContainerKind,
+    files: Vec<ContainerFile<'a>>,
+    max_inflated_size: usize,
+}
+
+#[derive(Debug, Clone)]
+struct ContainerFile<'a> {
+    name: Option<String>,
+    offset: usize,
+    data: &'a [u8],               // as stored
+    inflated_size: Option<usize>, // for deflated entries
+}
+
+/// One GLB of a [`GlbContainer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedGlb {
+    /// The entry's path in a ZIP archive; `None` in a concatenated stream.
+    pub name: Option<String>,
+    /// Where the GLB's (stored) bytes start in the container.
+    pub offset: usize,
+    file: usize,
+}
+
+/// A GLB of a container decoded by [`GlbContainer::decode_all`].
+#[derive(Debug)]
+pub struct ContainerEntry {
+    pub glb: EmbeddedGlb,
+    /// The GLB's default scene with every primitive, as [`decode_document`] returns it.
+    pub scene: Result<DecodedScene, DracoLoadError>,
+}
+
+impl<'a> GlbContainer<'a> {
+    /// Recognizes a ZIP archive by its signature and anything starting with a GLB header
+    /// as concatenated GLBs.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, DracoLoadError> {
+        if bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06") {
+            Ok(Self {
+                kind: ContainerKind::Zip,
+                files: zip_files(bytes)?,
+                max_inflated_size: DEFAULT_MAX_INFLATED_SIZE,
+            })
+        } else if bytes.starts_with(b"glTF") {
+            Ok(Self {
+                kind: ContainerKind::Concatenated,
+                files: concatenated_files(bytes)?,
+                max_inflated_size: DEFAULT_MAX_INFLATED_SIZE,
+            })
+        } else {
+            Err(DracoLoadError::BadContainer(
+                0,
+                "neither concatenated GLBs nor a ZIP archive",
+            ))
+        }
+    }
+
+    /// Caps what one deflated entry may inflate to. The archive declares each entry's size
+    /// itself, so without a cap a small deflate bomb could claim gigabytes.
+    pub fn with_max_inflated_size(mut self, bytes: usize) -> Self {
+        self.max_inflated_size = bytes;
+        self
+    }
+
+    pub fn kind(&self) -> ContainerKind {
+        self.kind
+    }
+
+    /// The GLBs in container order; for a ZIP archive, the entries named `*.glb`.
+    pub fn glbs(&self) -> Vec<EmbeddedGlb> {
+        self.files
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| match &f.name {
+                Some(name) => name.to_ascii_lowercase().ends_with(".glb"),
+                None => true,
+            })
+            .map(|(file, f)| EmbeddedGlb {
+                name: f.name.clone(),
+                offset: f.offset,
+                file,
+            })
+            .collect()
+    }
+
+    /// The GLB's bytes, inflated if the archive deflated them.
+    pub fn bytes(&self, glb: &EmbeddedGlb) -> Result<Cow<'a, [u8]>, DracoLoadError> {
+        self.files[glb.file].contents(self.max_inflated_size)
+    }
+
+    /// Parses the GLB and loads its buffers: the binary chunk, data URIs, and in a ZIP
+    /// archive other entries, resolved relative to the GLB's own path. Nothing outside the
+    /// container is read.
+    pub fn load(
+        &self,
+        glb: &EmbeddedGlb,
+    ) -> Result<(gltf::Document, Vec<gltf::buffer::Data>), DracoLoadError> {
+        let gltf = gltf::Gltf::from_slice_without_validation(&self.bytes(glb)?)?;
+        let dir = glb
+            .name
+            .as_deref()
+            .and_then(|n| Path::new(n).parent())
+            .unwrap_or(Path::new(""));
+        let resolver = UriResolver::new(dir);
+        let mut blob = gltf.blob;
+        let mut buffers = Vec::new();
+        for buffer in gltf.document.buffers() {
+            let data = match buffer.source() {
+                gltf::buffer::Source::Uri(uri) if !uri.starts_with("data:") => {
+                    let path = resolver.resolve(uri)?;
+                    self.sibling(&path)
+                        .ok_or_else(|| DracoLoadError::BadUri(uri.to_string()))?
+                        .contents(self.max_inflated_size)?
+                        .into_owned()
+                }
+                _ => inline_buffer(&buffer, &mut blob)?,
+            };
+            buffers.push(finish_buffer(&buffer, data)?);
+        }
+        Ok((gltf.document, buffers))
+    }
+
+    /// Loads and decodes every GLB; one that fails doesn't stop the others.
+    pub async fn decode_all(&self) -> Vec<ContainerEntry> {
+        let mut out = Vec::new();
+        for glb in self.glbs() {
+            let scene = match self.load(&glb) {
+                Ok((document, buffers)) => {
+                    decode_document(&document, &buffers, &DecodeOptions::default()).await
+                }
+                Err(e) => Err(e),
+            };
+            out.push(ContainerEntry { glb, scene });
+        }
+        out
+    }
+
+    /// The archive entry at `path`, which the resolver built from the GLB's directory;
+    /// absolute paths and paths climbing out of the archive have none.
+    fn sibling(&self, path: &Path) -> Option<&ContainerFile<'a>> {
+        let mut parts = Vec::new();
+        for c in path.components() {
+            match c {
+                Component::Normal(part) => parts.push(part.to_str()?),
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    parts.pop()?;
+                }
+                _ => return None,
+            }
+        }
+        let name = parts.join("/");
+        self.files
+            .iter()
+            .find(|f| f.name.as_deref() == Some(name.as_str()))
+    }
+}
+
+/// Decodes every GLB of a concatenated stream or ZIP archive; see [`GlbContainer`].
+pub async fn decode_container(bytes: &[u8]) -> Result<Vec<ContainerEntry>, DracoLoadError> {
+    Ok(GlbContainer::parse(bytes)?.decode_all().await)
+}
+
+impl<'a> ContainerFile<'a> {
+    fn contents(&self, max_inflated_size: usize) -> Result<Cow<'a, [u8]>, DracoLoadError> {
+        match self.inflated_size {
+            None => Ok(Cow::Borrowed(self.data)),
+            Some(size) if size > max_inflated_size => Err(DracoLoadError::BadContainer(
+                self.offset,
+                "deflated entry inflates past the container's limit",
+            )),
+            #[cfg(feature = "zip")]
+            Some(size) => miniz_oxide::inflate::decompress_to_vec_with_limit(self.data, size)
+                .ok()
+                .filter(|data| data.len() == size)
+                .map(Cow::Owned)
+                .ok_or(DracoLoadError::BadContainer(
+                    self.offset,
+                    "deflated entry doesn't inflate to its size",
+                )),
+            #[cfg(not(feature = "zip"))]
+            Some(_) => Err(DracoLoadError::BadContainer(
+                self.offset,
+                "deflated entries need the zip feature",
+            )),
+        }
+    }
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn concatenated_files(bytes: &[u8]) -> Result<Vec<ContainerFile<'_>>, DracoLoadError> {
+    let mut files = Vec::new();
+    let mut at = 0;
+    loop {
+        // exporters pad each GLB to a multiple of four, some streams to larger blocks
+        while bytes.get(at) == Some(&0) {
+            at += 1;
+        }
+        if at == bytes.len() {
+            return Ok(files);
+        }
+        if bytes.get(at..at + 4) != Some(b"glTF") {
+            return Err(DracoLoadError::BadContainer(at, "expected a GLB header"));
+        }
+        if u32_at(bytes, at + 4) != Some(2) {
+            return Err(DracoLoadError::BadContainer(at, "GLB version isn't 2"));
+        }
+        let length = u32_at(bytes, at + 8).map_or(0, |l| l as usize);
+        let data = (length >= 12)
+            .then(|| bytes.get(at..at + length))
+            .flatten()
+            .ok_or(DracoLoadError::BadContainer(
+                at,
+                "GLB length runs past the end of the stream",
+            ))?;
+        files.push(ContainerFile {
+            name: None,
+            offset: at,
+            data,
+            inflated_size: None,
+        });
+        at += length;
+    }
+}
+
+/// Entries from the central directory, which unlike the local headers always carries the
+/// sizes (streamed archives leave them out of the local headers).
+fn zip_files(bytes: &[u8]) -> Result<Vec<ContainerFile<'_>>, DracoLoadError> {
+    let bad = |at, reason| DracoLoadError::BadContainer(at, reason);
+    // the end of central directory record: 22 bytes and a comment of at most 64 KiB
+    let lowest = bytes.len().saturating_sub(22 + u16::MAX as usize);
+    let eocd = (lowest..=bytes.len().saturating_sub(22))
+        .rev()
+        .find(|&at| u32_at(bytes, at) == Some(0x0605_4b50))
+        .ok_or(bad(0, "ZIP archive has no end of central directory"))?;
+    let count = u16_at(bytes, eocd + 10).unwrap_or(0);
+    let dir = u32_at(bytes, eocd + 16).unwrap_or(0);
+    if count == u16::MAX || dir == u32::MAX {
+        return Err(bad(eocd, "ZIP64 archives aren't supported"));
+    }
+
+    let mut files = Vec::with_capacity(count as usize);
+    let mut at = dir as usize;
+    for _ in 0..count {
+        let field = |offset| u16_at(bytes, at + offset).map(usize::from);
+        if u32_at(bytes, at) != Some(0x0201_4b50) {
+            return Err(bad(at, "malformed ZIP central directory"));
+        }
+        let (Some(flags), Some(method), Some(name_len), Some(extra_len), Some(comment_len)) =
+            (field(8), field(10), field(28), field(30), field(32))
+        else {
+            return Err(bad(at, "malformed ZIP central directory"));
+        };
+        let stored = u32_at(bytes, at + 20).unwrap_or(0) as usize;
+        let size = u32_at(bytes, at + 24).unwrap_or(0) as usize;
+        let local = u32_at(bytes, at + 42).unwrap_or(0) as usize;
+        let name = bytes
+            .get(at + 46..at + 46 + name_len)
+            .ok_or(bad(at, "malformed ZIP central directory"))?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        at += 46 + name_len + extra_len + comment_len;
+        if name.ends_with('/') {
+            continue;
+        }
+        if flags & 1 != 0 {
+            return Err(bad(local, "encrypted ZIP entries aren't supported"));
+        }
+        let inflated_size = match method {
+            0 if stored == size => None,
+            0 => return Err(bad(local, "stored ZIP entry changes size")),
+            8 => Some(size),
+            _ => {
+                return Err(bad(
+                    local,
+                    "ZIP entry uses an unsupported compression method",
+                ));
+            }
+        };
+        if u32_at(bytes, local) != Some(0x0403_4b50) {
+            return Err(bad(local, "malformed ZIP local header"));
+        }
+        let start = local
+            + 30
+            + u16_at(bytes, local + 26).map_or(0, usize::from)
+            + u16_at(bytes, local + 28).map_or(0, usize::from);
+        let data = bytes
+            .get(start..start + stored)
+            .ok_or(bad(local, "ZIP entry runs past the end of the archive"))?;
+        files.push(ContainerFile {
+            name: Some(name),
+            offset: start,
+            data,
+            inflated_size,
+        });
+    }
+    Ok(files)
+}
@@ -0,0 +1,201 @@
+use crate::*;
+
+use std::collections::HashMap;
+
+/// `meshlet` of a primitive-level [`CullRecord`].
+pub const NO_MESHLET: u32 = u32::MAX;
+
+/// One cluster's world-space culling data, from [`build_culling_data`].
+///
+/// [`CullingData::to_bytes`] writes each record as 80 little-endian bytes, laid out to
+/// match a WGSL or std430 struct of `vec3<f32>` + scalar pairs:
+///
+/// | offset | field                      |
+/// |--------|----------------------------|
+/// | 0      | `aabb_min: vec3<f32>`      |
+/// | 12     | `node: u32`                |
+/// | 16     | `aabb_max: vec3<f32>`      |
+/// | 28     | `primitive: u32`           |
+/// | 32     | `cone_apex: vec3<f32>`     |
+/// | 44     | `cone_cutoff: f32`         |
+/// | 48     | `cone_axis: vec3<f32>`     |
+/// | 60     | `meshlet: u32`             |
+/// | 64     | `first_child: u32`         |
+/// | 68     | `child_count: u32`         |
+/// | 72     | 8 bytes of zero padding    |
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct CullRecord {
+    pub aabb_min: [f32; 3],
+    pub aabb_max: [f32; 3],
+    /// Cull when `dot(normalize(cone_apex - camera), cone_axis) >= cone_cutoff`, as for
+    /// [`MeshletBounds`]. Primitive records, and instances scaled non-uniformly, have a
+    /// cutoff of 1 and never cone-cull.
+    pub cone_apex: [f32; 3],
+    pub cone_axis: [f32; 3],
+    pub cone_cutoff: f32,
+    pub node: u32,
+    /// Index into [`DecodedScene::primitives`].
+    pub primitive: u32,
+    /// Index into the primitive's [`DecodedPrimitive::build_meshlets`] output, or
+    /// [`NO_MESHLET`] for the record covering the whole primitive.
+    pub meshlet: u32,
+    /// For a primitive record, where its meshlet records start in
+    /// [`CullingData::records`]; zero for meshlet records.
+    pub first_child: u32,
+    pub child_count: u32,
+}
+
+impl CullRecord {
+    pub const SIZE: usize = 80;
+
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut out = [0u8; Self::SIZE];
+        let mut put = |offset: usize, v: [u8; 4]| out[offset..offset + 4].copy_from_slice(&v);
+        for (base, v) in [
+            (0, self.aabb_min),
+            (16, self.aabb_max),
+            (32, self.cone_apex),
+            (48, self.cone_axis),
+        ] {
+            for (k, x) in v.iter().enumerate() {
+                put(base + 4 * k, x.to_le_bytes());
+            }
+        }
+        put(12, self.node.to_le_bytes());
+        put(28, self.primitive.to_le_bytes());
+        put(44, self.cone_cutoff.to_le_bytes());
+        put(60, self.meshlet.to_le_bytes());
+        put(64, self.first_child.to_le_bytes());
+        put(68, self.child_count.to_le_bytes());
+        out
+    }
+}
+
+/// A flat, two-level culling hierarchy: each scene primitive's record followed by the
+/// records of its meshlets.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct CullingData {
+    pub records: Vec<CullRecord>,
+}
+
+impl CullingData {
+    /// The records in the [`CullRecord`] layout, ready for a storage buffer.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.records.iter().flat_map(CullRecord::to_bytes).collect()
+    }
+}
+
+/// World-space bounds for every primitive instance of `scene`, plus one record per meshlet
+/// when `meshlets` gives `(max_verts, max_tris)` limits. Meshlet records are numbered as
+/// [`DecodedPrimitive::build_meshlets`] with the same limits numbers them, so the
+/// renderer can build the clusters itself and index them by these records.
+pub fn build_culling_data(scene: &DecodedScene, meshlets: Option<(usize, usize)>) -> CullingData {
+    // instances share their primitive's clusters
+    let mut clusters: HashMap<(usize, usize), Vec<LocalCluster>> = HashMap::new();
+    let mut records = Vec::new();
+    for (index, sp) in scene.primitives.iter().enumerate() {
+        let d = &sp.decoded;
+        let m = &sp.world_transform;
+        let positions = d.positions.as_deref().unwrap_or_default();
+        let (aabb_min, aabb_max) = world_aabb(positions.iter(), m);
+        let parent = records.len();
+        records.push(CullRecord {
+            aabb_min,
+            aabb_max,
+            cone_apex: [0.0; 3],
+            cone_axis: [0.0; 3],
+            cone_cutoff: 1.0,
+            node: sp.node as u32,
+            primitive: index as u32,
+            meshlet: NO_MESHLET,
+            first_child: 0,
+            child_count: 0,
+        });
+        let Some((max_verts, max_tris)) = meshlets else {
+            continue;
+        };
+
+        let local = clusters.entry((sp.mesh, sp.primitive)).or_insert_with(|| {
+            let built = d.build_meshlets(max_verts, max_tris);
+            built
+                .meshlets
+                .iter()
+                .map(|ml| {
+                    let points = built
+                        .meshlet_vertices(ml)
+                        .iter()
+                        .filter_map(|&v| positions.get(v as usize));
+                    let (min, max) = world_aabb(points, &IDENTITY);
+                    (min, max, ml.bounds)
+                })
+                .collect()
+        });
+        let scale = transform_scale(m);
+        records[parent].first_child = records.len() as u32;
+        records[parent].child_count = local.len() as u32;
+        for (i, (min, max, bounds)) in local.iter().enumerate() {
+            let corners = [*min, *max];
+            let (aabb_min, aabb_max) = world_aabb(corners.iter(), m);
+            let cone = (scale.uniform && bounds.cone_cutoff < 1.0).then(|| {
+                let axis = [0, 1, 2].map(|r| (0..3).map(|c| m[c][r] * bounds.cone_axis[c]).sum());
+                (transform_point(m, bounds.cone_apex), normalize(axis))
+            });
+            let (cone_apex, cone_axis, cone_cutoff) = match cone {
+                Some((apex, axis)) => (apex, axis, bounds.cone_cutoff),
+                None => ([0.0; 3], [0.0; 3], 1.0),
+            };
+            records.push(CullRecord {
+                aabb_min,
+                aabb_max,
+                cone_apex,
+                cone_axis,
+                cone_cutoff,
+                node: sp.node as u32,
+                primitive: index as u32,
+                meshlet: i as u32,
+                first_child: 0,
+                child_count: 0,
+            });
+        }
+    }
+    CullingData { records }
+}
+
+/// A meshlet's local box corners and its bounds.
+type LocalCluster = ([f32; 3], [f32; 3], MeshletBounds);
+
+/// World-space box around `points`, through the corners of their local box; all zeros
+/// for no points.
+fn world_aabb<'a>(points: impl Iterator<Item = &'a [f32; 3]>, m: &Mat4) -> ([f32; 3], [f32; 3]) {
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for p in points {
+        for k in 0..3 {
+            min[k] = min[k].min(p[k]);
+            max[k] = max[k].max(p[k]);
+        }
+    }
+    if min[0] > max[0] {
+        return ([0.0; 3], [0.0; 3]);
+    }
+    let mut out = ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]);
+    for corner in 0..8 {
+        let p = [0, 1, 2].map(|k| {
+            if corner & (1 << k) == 0 {
+                min[k]
+            } else {
+                max[k]
+            }
+        });
+        let w = transform_point(m, p);
+        out = (
+            [0, 1, 2].map(|k| out.0[k].min(w[k])),
+            [0, 1, 2].map(|k| out.1[k].max(w[k])),
+        );
+    }
+    out
+}
+
+fn transform_point(m: &Mat4, p: [f32; 3]) -> [f32; 3] {
+    [0, 1, 2].map(|r| (0..3).map(|c| m[c][r] * p[c]).sum::<f32>() + m[3][r])
+}
@@ -0,0 +1,57 @@
+use crate::*;
+
+use std::path::Path;
+
+impl DecodedPrimitive {
+    /// Writes `summary.json` plus one little-endian `<name>.bin` per stream into `dir`,
+    /// creating it if needed. Attach the directory to decode bug reports.
+    ///
+    /// The summary holds the crate version, counts, content hashes, per-stream layout
+    /// (as in [`ExportManifest`]) and `min`/`max` of every float stream.
+    pub fn dump_debug(&self, dir: impl AsRef<Path>) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let export = self.export_endian(Endian::Little);
+        for (entry, bytes) in export.manifest.streams.iter().zip(&export.streams) {
+            std::fs::write(dir.join(&entry.file), bytes)?;
+        }
+
+        let mut bounds = serde_json::Map::new();
+        let mut add = |name: String, b: Option<(Vec<f32>, Vec<f32>)>| {
+            if let Some((min, max)) = b {
+                bounds.insert(name, serde_json::json!({ "min": min, "max": max }));
+            }
+        };
+        if let Some(v) = &self.positions {
+            add("POSITION".into(), float_bounds(v));
+        }
+        if let Some(v) = &self.normals {
+            add("NORMAL".into(), float_bounds(v));
+        }
+        if let Some(v) = &self.tangents {
+            add("TANGENT".into(), float_bounds(v));
+        }
+        for (set, v) in sorted(&self.texcoords) {
+            add(format!("TEXCOORD_{set}"), float_bounds(v));
+        }
+        for (set, v) in sorted(&self.colors) {
+            add(format!("COLOR_{set}"), float_bounds(v));
+        }
+        for (set, v) in sorted(&self.weights) {
+            add(format!("WEIGHTS_{set}"), float_bounds(v));
+        }
+
+        let vertices = self.positions.as_ref().map_or(0, Vec::len);
+        let summary = serde_json::json!({
+            "crate_version": env!("CARGO_PKG_VERSION"),
+            "vertices": vertices,
+            "triangles": self.indices.len() / 3,
+            "max_index": self.indices.iter().max(),
+            "hashes": content_hashes(self),
+            "streams": export.manifest.streams,
+            "bounds": bounds,
+        });
+        let json = serde_json::to_vec_pretty(&summary).map_err(std::io::Error::other)?;
+        std::fs::write(dir.join("summary.json"), json)
+    }
+}
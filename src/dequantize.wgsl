@@ -0,0 +1,62 @@
+// Dequantizes one raw Draco attribute block into f32 components.
+// Mirrors RawAttribute::dequantize on the CPU.
+
+struct Params {
+    count: u32,      // number of components (vertices * dim)
+    data_type: u32,  // draco::DataType: 1 i8, 2 u8, 3 i16, 4 u16, 5 i32, 6 u32, 9 f32
+    normalized: u32, // 1 when the accessor is normalized
+    _pad: u32,
+};
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> input: array<u32>;
+@group(0) @binding(2) var<storage, read_write> output: array<f32>;
+
+fn scale(v: f32, max_value: f32, is_signed: bool) -> f32 {
+    if (params.normalized == 0u) {
+        return v;
+    }
+    if (is_signed) {
+        return max(v / max_value, -1.0);
+    }
+    return v / max_value;
+}
+
+@compute @workgroup_size(64)
+fn main(
+    @builtin(global_invocation_id) gid: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let i = gid.x + gid.y * groups.x * 64u;
+    if (i >= params.count) {
+        return;
+    }
+
+    switch params.data_type {
+        case 1u, 2u: {
+            let byte = (input[i / 4u] >> ((i % 4u) * 8u)) & 0xffu;
+            if (params.data_type == 1u) {
+                output[i] = scale(f32(i32(byte << 24u) >> 24u), 127.0, true);
+            } else {
+                output[i] = scale(f32(byte), 255.0, false);
+            }
+        }
+        case 3u, 4u: {
+            let half = (input[i / 2u] >> ((i % 2u) * 16u)) & 0xffffu;
+            if (params.data_type == 3u) {
+                output[i] = scale(f32(i32(half << 16u) >> 16u), 32767.0, true);
+            } else {
+                output[i] = scale(f32(half), 65535.0, false);
+            }
+        }
+        case 5u: {
+            output[i] = f32(bitcast<i32>(input[i]));
+        }
+        case 6u: {
+            output[i] = f32(input[i]);
+        }
+        default: {
+            output[i] = bitcast<f32>(input[i]);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::*;
+
+/// The `extras` member holding [`DecodeDirectives`].
+pub const DIRECTIVES_KEY: &str = "draco_gltf_rs";
+
+/// Per-primitive decode hints authored in the asset, e.g.
+/// `"extras": {"draco_gltf_rs": {"skip": true}}` on a mesh or a primitive. Read with
+/// [`decode_directives`]; scene decoding and [`DocumentIndex::build`] honor them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct DecodeDirectives {
+    /// Leave the primitive out, e.g. collision proxies or editor-only helpers.
+    pub skip: bool,
+}
+
+/// Directives as written on one object; fields left out inherit from the mesh, and ones
+/// this version doesn't know are ignored so newer assets still load.
+#[derive(Debug, Default, serde::Deserialize)]
+struct Authored {
+    skip: Option<bool>,
+}
+
+/// The directives of primitive `p` of `mesh`: the mesh's `extras` first, then the
+/// primitive's, field by field. Unknown fields are ignored; a known field of the wrong type
+/// fails with [`DracoLoadError::BadDirectives`] rather than being silently dropped.
+pub fn decode_directives(
+    mesh: &gltf::Mesh<'_>,
+    p: &gltf::mesh::Primitive<'_>,
+) -> Result<DecodeDirectives, DracoLoadError> {
+    let bad = |e: String| DracoLoadError::BadDirectives(mesh.index(), p.index(), e);
+    let mut out = DecodeDirectives::default();
+    for extras in [mesh.extras(), p.extras()] {
+        let Some(raw) = extras.as_deref() else {
+            continue;
+        };
+        let mut value: serde_json::Value =
+            serde_json::from_str(raw.get()).map_err(|e| bad(e.to_string()))?;
+        let Some(authored) = value.get_mut(DIRECTIVES_KEY).map(serde_json::Value::take) else {
+            continue;
+        };
+        let authored: Authored =
+            serde_json::from_value(authored).map_err(|e| bad(e.to_string()))?;
+        out.skip = authored.skip.unwrap_or(out.skip);
+    }
+    Ok(out)
+}
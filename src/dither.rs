@@ -0,0 +1,47 @@
+use crate::*;
+
+/// How to quantize float vertex colors to 8 bits in [`DecodedPrimitive::colors_u8`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorDither {
+    /// Round to nearest; smooth gradients band.
+    #[default]
+    None,
+    /// 4x4 Bayer thresholds, indexed by vertex number.
+    Ordered,
+    /// Thresholds from the golden-ratio (R1) sequence, a cheap blue-noise-like pattern with
+    /// no visible structure; each channel is offset so they don't dither in lockstep.
+    BlueNoise,
+}
+
+const BAYER_4X4: [u8; 16] = [0, 8, 2, 10, 12, 4, 14, 6, 3, 11, 1, 9, 15, 7, 13, 5];
+
+impl DecodedPrimitive {
+    /// Color set `set` as RGBA8 (unorm), dithered so banding in baked lighting averages out
+    /// across neighbouring vertices.
+    pub fn colors_u8(&self, set: u32, dither: ColorDither) -> Option<Vec<[u8; 4]>> {
+        let colors = self.colors.get(&set)?;
+        Some(
+            colors
+                .iter()
+                .enumerate()
+                .map(|(i, c)| std::array::from_fn(|k| to_u8(c[k], threshold(dither, i, k))))
+                .collect(),
+        )
+    }
+}
+
+/// Offset in `[0, 1)` added before truncation; 0.5 is plain rounding.
+fn threshold(dither: ColorDither, vertex: usize, channel: usize) -> f32 {
+    match dither {
+        ColorDither::None => 0.5,
+        ColorDither::Ordered => (BAYER_4X4[vertex % 16] as f32 + 0.5) / 16.0,
+        ColorDither::BlueNoise => {
+            const R1: f64 = 0.618_033_988_749_894_9;
+            (0.5 + vertex as f64 * R1 + channel as f64 * 0.25).fract() as f32
+        }
+    }
+}
+
+fn to_u8(x: f32, threshold: f32) -> u8 {
+    (x.clamp(0.0, 1.0) * 255.0 + threshold).floor().min(255.0) as u8
+}
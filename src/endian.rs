@@ -0,0 +1,131 @@
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// Raw attribute streams in a chosen byte order, plus a manifest describing them, from
+/// [`DecodedPrimitive::export_endian`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndianExport {
+    pub manifest: ExportManifest,
+    /// Stream bytes in manifest order.
+    pub streams: Vec<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ExportManifest {
+    pub endian: Endian,
+    pub streams: Vec<StreamEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StreamEntry {
+    /// `INDICES` or the glTF attribute name (`POSITION`, `TEXCOORD_0`, ...).
+    pub name: String,
+    /// File name relative to the manifest, `<name>.bin`.
+    pub file: String,
+    /// `f32`, `u32` or `u16`.
+    pub component: String,
+    /// Components per element, 1 for indices.
+    pub components: usize,
+    pub count: usize,
+    pub byte_length: usize,
+}
+
+impl DecodedPrimitive {
+    /// Serializes every stream with `endian` byte order, swapping each component (not each
+    /// element) for [`Endian::Big`].
+    pub fn export_endian(&self, endian: Endian) -> EndianExport {
+        let mut out = EndianExport {
+            manifest: ExportManifest {
+                endian,
+                streams: Vec::new(),
+            },
+            streams: Vec::new(),
+        };
+        let mut push = |name: String, component: &str, components, count, bytes: Vec<u8>| {
+            out.manifest.streams.push(StreamEntry {
+                file: format!("{name}.bin"),
+                name,
+                component: component.to_string(),
+                components,
+                count,
+                byte_length: bytes.len(),
+            });
+            out.streams.push(bytes);
+        };
+
+        let bytes = self.indices.iter().flat_map(|i| match endian {
+            Endian::Little => i.to_le_bytes(),
+            Endian::Big => i.to_be_bytes(),
+        });
+        let count = self.indices.len();
+        push("INDICES".into(), "u32", 1, count, bytes.collect());
+
+        let floats = |v: &[f32]| -> Vec<u8> {
+            v.iter()
+                .flat_map(|x| match endian {
+                    Endian::Little => x.to_le_bytes(),
+                    Endian::Big => x.to_be_bytes(),
+                })
+                .collect()
+        };
+        if let Some(v) = &self.positions {
+            let bytes = floats(v.as_flattened());
+            push("POSITION".into(), "f32", 3, v.len(), bytes);
+        }
+        if let Some(v) = &self.normals {
+            let bytes = floats(v.as_flattened());
+            push("NORMAL".into(), "f32", 3, v.len(), bytes);
+        }
+        if let Some(v) = &self.tangents {
+            let bytes = floats(v.as_flattened());
+            push("TANGENT".into(), "f32", 4, v.len(), bytes);
+        }
+        for (set, v) in sorted(&self.texcoords) {
+            let bytes = floats(v.as_flattened());
+            push(format!("TEXCOORD_{set}"), "f32", 2, v.len(), bytes);
+        }
+        for (set, v) in sorted(&self.colors) {
+            let bytes = floats(v.as_flattened());
+            push(format!("COLOR_{set}"), "f32", 4, v.len(), bytes);
+        }
+        for (set, v) in sorted(&self.joints) {
+            let bytes = v.as_flattened().iter().flat_map(|j| match endian {
+                Endian::Little => j.to_le_bytes(),
+                Endian::Big => j.to_be_bytes(),
+            });
+            push(format!("JOINTS_{set}"), "u16", 4, v.len(), bytes.collect());
+        }
+        for (set, v) in sorted(&self.weights) {
+            let bytes = floats(v.as_flattened());
+            push(format!("WEIGHTS_{set}"), "f32", 4, v.len(), bytes);
+        }
+        out
+    }
+}
+
+impl EndianExport {
+    /// Writes `manifest.json` and one file per stream under `prefix/` in `store`.
+    pub async fn write(
+        &self,
+        store: &impl OutputStore,
+        prefix: &str,
+    ) -> Result<(), DracoLoadError> {
+        let io = |e| DracoLoadError::Gltf(gltf::Error::Io(e));
+        for (entry, bytes) in self.manifest.streams.iter().zip(&self.streams) {
+            let key = format!("{prefix}/{}", entry.file);
+            store.put(&key, bytes.clone()).await.map_err(io)?;
+        }
+        let manifest = serde_json::to_vec_pretty(&self.manifest)
+            .map_err(|e| DracoLoadError::Gltf(gltf::Error::Deserialize(e)))?;
+        store
+            .put(&format!("{prefix}/manifest.json"), manifest)
+            .await
+            .map_err(io)
+    }
+}
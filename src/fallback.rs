@@ -0,0 +1,134 @@
+use crate::*;
+
+/// True if the Draco extension is optional for this document and every accessor of the
+/// primitive points at real (uncompressed) data.
+pub(crate) fn has_fallback(p: &gltf::mesh::Primitive<'_>, document: &gltf::Document) -> bool {
+    let required = document
+        .extensions_required()
+        .any(|e| e == "KHR_draco_mesh_compression");
+    if required {
+        return false;
+    }
+    p.indices().is_none_or(|a| a.view().is_some())
+        && p.attributes().all(|(_, a)| a.view().is_some())
+}
+
+/// Checks the Draco stream's header against the fallback accessors of `p`, which describe
+/// the same mesh. Faces must match; vertices may exceed the connectivity vertices of an
+/// edgebreaker stream, which seams split, but not three per face. Streams whose header
+/// doesn't parse aren't checked.
+pub(crate) fn check_fallback_counts(
+    p: &gltf::mesh::Primitive<'_>,
+    draco_bytes: &[u8],
+) -> Result<(), DracoLoadError> {
+    let Some((stream_vertices, stream_faces)) = estimate_counts(draco_bytes) else {
+        return Ok(());
+    };
+    let vertices = p.get(&gltf::Semantic::Positions).map_or(0, |a| a.count());
+    let indices = p.indices().map_or(vertices, |a| a.count());
+    if indices != stream_faces * 3 || !(stream_vertices..=stream_faces * 3).contains(&vertices) {
+        return Err(DracoLoadError::FallbackCountMismatch {
+            stream_vertices,
+            stream_faces,
+            vertices,
+            indices,
+        });
+    }
+    Ok(())
+}
+
+/// [`read_fallback`] with `options`' semantics, transforms and conventions applied, as the
+/// Draco path would.
+pub(crate) fn read_plain(
+    p: &gltf::mesh::Primitive<'_>,
+    buffers: &[gltf::buffer::Data],
+    options: &DecodeOptions,
+) -> Result<DecodedPrimitive, DracoLoadError> {
+    // from the accessor, which is there even when `semantics` leaves positions out
+    let vertex_count = p.get(&gltf::Semantic::Positions).map_or(0, |a| a.count());
+    options.check_vertex_count(vertex_count)?;
+    let mut out = read_fallback_with(p, buffers, |s| options.converts(s))?;
+    out.transform_fallback(p, &options.transforms);
+    options.apply_conventions(&mut out);
+    Ok(out)
+}
+
+/// The indices of a plain primitive, or `0..count` over its vertices when it has no indices
+/// accessor, which is how glTF draws a non-indexed primitive.
+pub(crate) fn plain_indices(
+    p: &gltf::mesh::Primitive<'_>,
+    buffers: &[gltf::buffer::Data],
+) -> Result<Vec<u32>, DracoLoadError> {
+    if p.indices().is_none() {
+        let count = p.attributes().next().map_or(0, |(_, a)| a.count());
+        return Ok((0..count as u32).collect());
+    }
+    let reader = p.reader(|b| buffers.get(b.index()).map(|d| &d.0[..]));
+    let indices = reader
+        .read_indices()
+        .ok_or(DracoLoadError::NoIndicesAccessor)?;
+    Ok(indices.into_u32().collect())
+}
+
+/// Reads a primitive from its plain accessors into the same layout the Draco path produces.
+pub(crate) fn read_fallback(
+    p: &gltf::mesh::Primitive<'_>,
+    buffers: &[gltf::buffer::Data],
+) -> Result<DecodedPrimitive, DracoLoadError> {
+    read_fallback_with(p, buffers, |_| true)
+}
+
+/// [`read_fallback`] reading only the attributes `converts` accepts; the others aren't read
+/// or allocated.
+fn read_fallback_with(
+    p: &gltf::mesh::Primitive<'_>,
+    buffers: &[gltf::buffer::Data],
+    converts: impl Fn(&gltf::Semantic) -> bool,
+) -> Result<DecodedPrimitive, DracoLoadError> {
+    use gltf::Semantic::*;
+    let reader = p.reader(|b| buffers.get(b.index()).map(|d| &d.0[..]));
+
+    let mut out = DecodedPrimitive {
+        indices: plain_indices(p, buffers)?,
+        positions: reader
+            .read_positions()
+            .filter(|_| converts(&Positions))
+            .map(|it| it.collect()),
+        normals: reader
+            .read_normals()
+            .filter(|_| converts(&Normals))
+            .map(|it| it.collect()),
+        tangents: reader
+            .read_tangents()
+            .filter(|_| converts(&Tangents))
+            .map(|it| it.collect()),
+        ..Default::default()
+    };
+
+    for (sem, _) in p.attributes().filter(|(sem, _)| converts(sem)) {
+        match sem {
+            TexCoords(set) => {
+                if let Some(it) = reader.read_tex_coords(set) {
+                    out.texcoords.insert(set, it.into_f32().collect());
+                }
+            }
+            Colors(set) => {
+                if let Some(it) = reader.read_colors(set) {
+                    out.colors.insert(set, it.into_rgba_f32().collect());
+                }
+            }
+            Joints(set) => {
+                if let Some(it) = reader.read_joints(set) {
+                    out.joints.insert(set, it.into_u16().collect());
+                }
+            }
+            Weights(set) => {
+                if let Some(it) = reader.read_weights(set) {
+                    out.weights.insert(set, it.into_f32().collect());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(out)
+}
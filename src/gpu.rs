@@ -0,0 +1,130 @@
+use crate::*;
+use wgpu::util::DeviceExt;
+
+const WORKGROUP_SIZE: u32 = 64;
+const MAX_GROUPS_PER_DIM: u32 = 65535;
+
+/// Compute pipeline that turns [`RawAttribute`] blocks into `f32` GPU buffers.
+///
+/// Create it once per device; each [`dequantize`](Self::dequantize) call records and
+/// submits one dispatch, leaving the CPU free of the per-component conversion loop.
+pub struct GpuDequantizer {
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl GpuDequantizer {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("draco-gltf-rs dequantize"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("dequantize.wgsl").into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("draco-gltf-rs dequantize"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        Self { pipeline }
+    }
+
+    /// Uploads the raw block and returns a buffer holding one `f32` per component.
+    ///
+    /// The returned buffer has `STORAGE | VERTEX | COPY_SRC` usage so it can be bound as
+    /// a vertex stream directly or copied into a larger arena.
+    pub fn dequantize(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        attr: &RawAttribute,
+    ) -> wgpu::Buffer {
+        let count = attr.bytes.len() / component_size(attr.data_type);
+
+        // storage buffers are read as u32 words; pad the upload to a multiple of 4
+        let mut words = attr.bytes.clone();
+        words.resize(attr.bytes.len().div_ceil(4).max(1) * 4, 0);
+        let input = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("draco-gltf-rs raw attribute"),
+            contents: &words,
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let params: Vec<u8> = [
+            count as u32,
+            attr.data_type as u32,
+            attr.normalized as u32,
+            0,
+        ]
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect();
+        let uniform = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("draco-gltf-rs dequantize params"),
+            contents: &params,
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let output = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("draco-gltf-rs dequantized attribute"),
+            size: (count.max(1) * 4) as u64,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("draco-gltf-rs dequantize"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: input.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: output.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("draco-gltf-rs dequantize"),
+        });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("draco-gltf-rs dequantize"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            let (x, y) = dispatch_size(count as u32);
+            pass.dispatch_workgroups(x, y, 1);
+        }
+        queue.submit(Some(encoder.finish()));
+        output
+    }
+}
+
+fn component_size(data_type: u8) -> usize {
+    match data_type {
+        1 | 2 => 1,
+        3 | 4 => 2,
+        _ => 4,
+    }
+}
+
+/// Spreads the workgroups over two dimensions once a single one would exceed the limit.
+fn dispatch_size(count: u32) -> (u32, u32) {
+    let groups = count.div_ceil(WORKGROUP_SIZE).max(1);
+    if groups <= MAX_GROUPS_PER_DIM {
+        (groups, 1)
+    } else {
+        (MAX_GROUPS_PER_DIM, groups.div_ceil(MAX_GROUPS_PER_DIM))
+    }
+}
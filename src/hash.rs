@@ -0,0 +1,106 @@
+use crate::*;
+
+/// Content hashes of decoded geometry, independent of how it was packaged: the same mesh
+/// hashes the same whether stored as Draco, uncompressed, or in a different GLB layout.
+///
+/// Hashes are 64-bit FNV-1a over little-endian stream data with `-0.0` folded into `0.0`,
+/// so they are stable across platforms and crate versions and can be stored.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ContentHashes {
+    /// Combined hash of every stream below, keyed by name.
+    pub primitive: u64,
+    /// `INDICES` plus the glTF attribute names (`POSITION`, `TEXCOORD_0`, ...).
+    pub streams: std::collections::BTreeMap<String, u64>,
+}
+
+pub fn content_hashes(d: &DecodedPrimitive) -> ContentHashes {
+    let mut streams = std::collections::BTreeMap::new();
+    let mut u32s = Fnv::new();
+    d.indices.iter().for_each(|i| u32s.write(&i.to_le_bytes()));
+    streams.insert("INDICES".to_string(), u32s.finish());
+
+    if let Some(v) = &d.positions {
+        streams.insert("POSITION".into(), hash_floats(v));
+    }
+    if let Some(v) = &d.normals {
+        streams.insert("NORMAL".into(), hash_floats(v));
+    }
+    if let Some(v) = &d.tangents {
+        streams.insert("TANGENT".into(), hash_floats(v));
+    }
+    for (set, v) in sorted(&d.texcoords) {
+        streams.insert(format!("TEXCOORD_{set}"), hash_floats(v));
+    }
+    for (set, v) in sorted(&d.colors) {
+        streams.insert(format!("COLOR_{set}"), hash_floats(v));
+    }
+    for (set, v) in sorted(&d.joints) {
+        let mut h = Fnv::new();
+        v.iter().flatten().for_each(|j| h.write(&j.to_le_bytes()));
+        streams.insert(format!("JOINTS_{set}"), h.finish());
+    }
+    for (set, v) in sorted(&d.weights) {
+        streams.insert(format!("WEIGHTS_{set}"), hash_floats(v));
+    }
+
+    let mut h = Fnv::new();
+    for (name, stream) in &streams {
+        h.write(name.as_bytes());
+        h.write(&stream.to_le_bytes());
+    }
+    ContentHashes {
+        primitive: h.finish(),
+        streams,
+    }
+}
+
+/// [`asset_report`] with [`PrimitiveStats::hashes`] filled in. Primitives are decoded one
+/// at a time and dropped after hashing; ones that fail to decode keep `hashes: None`.
+pub async fn asset_report_with_hashes(
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+) -> AssetReport {
+    let mut report = asset_report(document, buffers);
+    for stats in &mut report.primitives {
+        let Some(p) = document
+            .meshes()
+            .nth(stats.mesh)
+            .and_then(|m| m.primitives().nth(stats.primitive))
+        else {
+            continue;
+        };
+        let decoded = if stats.draco {
+            decode_draco_derived(&p, document, buffers).await
+        } else {
+            read_fallback(&p, buffers)
+        };
+        stats.hashes = decoded.ok().map(|d| content_hashes(&d));
+    }
+    report
+}
+
+fn hash_floats<const N: usize>(v: &[[f32; N]]) -> u64 {
+    let mut h = Fnv::new();
+    for x in v.iter().flatten() {
+        h.write(&(x + 0.0).to_le_bytes());
+    }
+    h.finish()
+}
+
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = (self.0 ^ *b as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,189 @@
+use crate::*;
+
+/// A serializable lookup table of every Draco primitive in a document.
+///
+/// Built once from the glTF JSON, it records where each primitive's compressed bytes live
+/// and which attributes the stream carries, so a runtime can later decode individual
+/// primitives with only the buffers at hand.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct DocumentIndex {
+    pub primitives: Vec<IndexedPrimitive>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IndexedPrimitive {
+    pub mesh: usize,
+    pub primitive: usize,
+    pub buffer_view: usize,
+    pub buffer: usize,
+    pub byte_offset: usize,
+    pub byte_length: usize,
+    pub vertex_count: usize,
+    pub index_count: usize,
+    pub index_size: usize, // bytes per decoded index (2 or 4)
+    pub attributes: Vec<IndexedAttribute>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IndexedAttribute {
+    pub semantic: String, // glTF attribute key, e.g. "TEXCOORD_0"
+    pub info: AttrInfo,
+    #[serde(default)]
+    pub normalized: bool, // from the glTF accessor
+}
+
+impl DocumentIndex {
+    /// Indexes all `KHR_draco_mesh_compression` primitives; other primitives, and those
+    /// whose [`DecodeDirectives`] say `skip`, are left out.
+    pub fn build(document: &gltf::Document) -> Result<Self, DracoLoadError> {
+        let mut primitives = Vec::new();
+        for mesh in document.meshes() {
+            for p in mesh.primitives() {
+                if p.extension_value("KHR_draco_mesh_compression").is_none()
+                    || decode_directives(&mesh, &p)?.skip
+                {
+                    continue;
+                }
+                primitives.push(IndexedPrimitive::build(document, &mesh, &p)?);
+            }
+        }
+        Ok(Self { primitives })
+    }
+
+    pub fn get(&self, mesh: usize, primitive: usize) -> Option<&IndexedPrimitive> {
+        self.primitives
+            .iter()
+            .find(|e| e.mesh == mesh && e.primitive == primitive)
+    }
+
+    pub async fn decode(
+        &self,
+        mesh: usize,
+        primitive: usize,
+        buffers: &[gltf::buffer::Data],
+        options: &DecodeOptions,
+    ) -> Result<DecodedPrimitive, DracoLoadError> {
+        self.get(mesh, primitive)
+            .ok_or(DracoLoadError::NotIndexed(mesh, primitive))?
+            .decode(buffers, options)
+            .await
+    }
+}
+
+impl IndexedPrimitive {
+    fn build(
+        document: &gltf::Document,
+        mesh: &gltf::Mesh<'_>,
+        p: &gltf::mesh::Primitive<'_>,
+    ) -> Result<Self, DracoLoadError> {
+        if p.mode() != gltf::mesh::Mode::Triangles {
+            return Err(DracoLoadError::UnsupportedMode(p.mode()));
+        }
+        let draco_ext = draco_extension(p)?;
+        let bv = buffer_view(document, draco_ext.buffer_view)?;
+
+        let vertex_count = p
+            .get(&gltf::Semantic::Positions)
+            .ok_or(DracoLoadError::NoPositionAccessor)?
+            .count();
+        let indices_accessor = p.indices().ok_or(DracoLoadError::NoIndicesAccessor)?;
+        let index_comp = index_comp_workaround(indices_accessor.data_type());
+
+        let attributes = derive_attr_infos(p, &draco_ext)?
+            .into_iter()
+            .map(|(semantic, info)| {
+                let normalized = dracokey_to_semantic(semantic)
+                    .and_then(|sem| p.get(&sem))
+                    .is_some_and(|a| a.normalized());
+                IndexedAttribute {
+                    semantic: semantic.to_string(),
+                    info,
+                    normalized,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            mesh: mesh.index(),
+            primitive: p.index(),
+            buffer_view: bv.index(),
+            buffer: bv.buffer().index(),
+            byte_offset: bv.offset(),
+            byte_length: bv.length(),
+            vertex_count,
+            index_count: indices_accessor.count(),
+            index_size: comp_size_bytes(index_comp),
+            attributes,
+        })
+    }
+
+    pub fn attr_infos(&self) -> Vec<AttrInfo> {
+        self.attributes.iter().map(|a| a.info.clone()).collect()
+    }
+
+    fn attribute(&self, unique_id: u32) -> Option<&IndexedAttribute> {
+        self.attributes
+            .iter()
+            .find(|a| a.info.unique_id == unique_id)
+    }
+
+    /// Decodes the entry's stream with `options`' vertex-count checks, concurrency cap,
+    /// conversion policies and conventions. The fallback policy, accessor order and vendor quirks need
+    /// the document and don't apply; the index was built from the standard extension layout.
+    pub async fn decode(
+        &self,
+        buffers: &[gltf::buffer::Data],
+        options: &DecodeOptions,
+    ) -> Result<DecodedPrimitive, DracoLoadError> {
+        let buf = buffers
+            .get(self.buffer)
+            .ok_or(DracoLoadError::BadBuffer(self.buffer))?;
+        let draco_bytes = view_bytes(buf, self.buffer_view, self.byte_offset, self.byte_length)?;
+        self.decode_bytes(draco_bytes, options).await
+    }
+
+    /// Decodes from the bufferView bytes directly, for callers that own the storage.
+    pub(crate) async fn decode_bytes(
+        &self,
+        draco_bytes: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<DecodedPrimitive, DracoLoadError> {
+        options.check_vertex_count(self.vertex_count)?;
+        let given = self.attr_infos();
+        let infos = decoder_infos(
+            draco_bytes,
+            &given,
+            || Ok(given.clone()),
+            |id| self.attribute(id).map(|a| a.semantic.clone()),
+        )?;
+        let cfg = decode_config(self.vertex_count, self.index_count, &infos);
+        let raw = decode_limited(draco_bytes, &cfg, options.max_concurrent_decodes).await?;
+
+        let mut dracoid_to_sem = SemanticMap::new();
+        for info in &infos {
+            let Some(a) = self.attribute(info.unique_id) else {
+                continue;
+            };
+            if let Some(sem) = dracokey_to_semantic(&a.semantic) {
+                dracoid_to_sem.insert(info.unique_id, (sem, info.dim as usize, a.normalized));
+            }
+        }
+
+        let index_comp = match self.index_size {
+            4 => gltf::accessor::DataType::U32,
+            _ => gltf::accessor::DataType::U16,
+        };
+        let mut out = prozes_out(
+            &raw,
+            index_comp,
+            self.index_count,
+            self.vertex_count,
+            &infos,
+            &dracoid_to_sem,
+            options,
+            &mut PrimitiveBuffers::default(),
+        )?;
+        options.apply_conventions(&mut out);
+        Ok(out)
+    }
+}
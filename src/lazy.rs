@@ -0,0 +1,131 @@
+use crate::*;
+
+/// A glTF/GLB file mapped into memory; only the JSON is parsed up front.
+///
+/// Buffers are the GLB `BIN` chunk or external files next to the glTF, each mapped rather
+/// than read, so decoding one primitive of a very large file touches only its Draco bytes.
+/// Data URIs aren't supported, and external files must sit at or below the glTF's
+/// directory: absolute URIs and ones climbing out with `..` fail with
+/// [`BadUri`](DracoLoadError::BadUri).
+pub struct LazyDocument {
+    document: gltf::Document,
+    index: DocumentIndex,
+    maps: Vec<memmap2::Mmap>,
+    buffers: Vec<Option<(usize, std::ops::Range<usize>)>>, // buffer -> (map, byte range)
+}
+
+pub struct LazyPrimitive<'a> {
+    doc: &'a LazyDocument,
+    entry: &'a IndexedPrimitive,
+}
+
+impl LazyDocument {
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, DracoLoadError> {
+        let path = path.as_ref();
+        let file_map = map_file(path)?;
+
+        let mut maps = Vec::new();
+        let mut glb_bin = None;
+        let root = if file_map.starts_with(b"glTF") {
+            let glb = gltf::Glb::from_slice(&file_map)?;
+            glb_bin = glb.bin.as_deref().map(|bin| {
+                let start = bin.as_ptr() as usize - file_map.as_ptr() as usize;
+                start..start + bin.len()
+            });
+            gltf::json::deserialize::from_slice(&glb.json).map_err(gltf::Error::from)?
+        } else {
+            gltf::json::deserialize::from_slice(&file_map).map_err(gltf::Error::from)?
+        };
+        let document = gltf::Document::from_json_without_validation(root);
+        maps.push(file_map);
+
+        let resolver =
+            UriResolver::new(path.parent().unwrap_or(std::path::Path::new("."))).sandboxed(true);
+        let mut buffers = Vec::new();
+        for buffer in document.buffers() {
+            let entry = match buffer.source() {
+                gltf::buffer::Source::Bin => glb_bin.clone().map(|range| (0, range)),
+                gltf::buffer::Source::Uri(uri) if !uri.starts_with("data:") => {
+                    let map = map_file(&resolver.resolve(uri)?)?;
+                    let range = 0..map.len();
+                    maps.push(map);
+                    Some((maps.len() - 1, range))
+                }
+                gltf::buffer::Source::Uri(_) => None,
+            };
+            buffers.push(entry);
+        }
+
+        let index = DocumentIndex::build(&document)?;
+        Ok(Self {
+            document,
+            index,
+            maps,
+            buffers,
+        })
+    }
+
+    pub fn document(&self) -> &gltf::Document {
+        &self.document
+    }
+
+    pub fn index(&self) -> &DocumentIndex {
+        &self.index
+    }
+
+    pub fn primitive(
+        &self,
+        mesh: usize,
+        primitive: usize,
+    ) -> Result<LazyPrimitive<'_>, DracoLoadError> {
+        let entry = self
+            .index
+            .get(mesh, primitive)
+            .ok_or(DracoLoadError::NotIndexed(mesh, primitive))?;
+        Ok(LazyPrimitive { doc: self, entry })
+    }
+
+    /// The mapped bytes of buffer `index`.
+    pub fn buffer(&self, index: usize) -> Result<&[u8], DracoLoadError> {
+        let (map, range) = self
+            .buffers
+            .get(index)
+            .cloned()
+            .flatten()
+            .ok_or(DracoLoadError::BadBuffer(index))?;
+        Ok(&self.maps[map][range])
+    }
+}
+
+impl<'a> LazyPrimitive<'a> {
+    pub fn info(&self) -> &'a IndexedPrimitive {
+        self.entry
+    }
+
+    /// The compressed bytes, sliced straight out of the mapping.
+    pub fn draco_bytes(&self) -> Result<&'a [u8], DracoLoadError> {
+        let e = self.entry;
+        view_bytes(
+            self.doc.buffer(e.buffer)?,
+            e.buffer_view,
+            e.byte_offset,
+            e.byte_length,
+        )
+    }
+
+    /// [`IndexedPrimitive::decode`] straight from the mapping.
+    pub async fn decode(
+        &self,
+        options: &DecodeOptions,
+    ) -> Result<DecodedPrimitive, DracoLoadError> {
+        self.entry.decode_bytes(self.draco_bytes()?, options).await
+    }
+}
+
+fn map_file(path: &std::path::Path) -> Result<memmap2::Mmap, DracoLoadError> {
+    let file = std::fs::File::open(path).map_err(gltf::Error::Io)?;
+    // SAFETY: the map is read-only; as with any mmap, truncating the file underneath it is
+    // the caller's responsibility.
+    let map = unsafe { memmap2::Mmap::map(&file) }.map_err(gltf::Error::Io)?;
+    Ok(map)
+}
@@ -4,22 +4,36 @@ pub struct DecodedPrimitive {
     pub positions: Option<Vec<[f32; 3]>>,
     pub normals: Option<Vec<[f32; 3]>>,
     pub tangents: Option<Vec<[f32; 4]>>,
-    pub texcoords: std::collections::HashMap<u32, Vec<[f32; 2]>>,
-    pub colors: std::collections::HashMap<u32, Vec<[f32; 4]>>,
-    pub joints: std::collections::HashMap<u32, Vec<[u16; 4]>>,
-    pub weights: std::collections::HashMap<u32, Vec<[f32; 4]>>,
+    pub texcoords: SetMap<Vec<[f32; 2]>>,
+    pub colors: SetMap<Vec<[f32; 4]>>,
+    pub joints: SetMap<Vec<[u16; 4]>>,
+    pub weights: SetMap<Vec<[f32; 4]>>,
+    /// Application-specific `_NAME` attributes, keyed by attribute name. A
+    /// [`SemanticRegistry`] pins their expected layout and normalization.
+    pub custom: std::collections::HashMap<String, CustomAttribute>,
+    /// Per-triangle areas filled by the decode when [`DecodeOptions::triangle_areas`] asks
+    /// for them. Kept current by [`canonicalize`](Self::canonicalize) and
+    /// [`bake_transform`](Self::bake_transform); primitives built by merging, splitting,
+    /// compacting or from a [`PrimitiveView`] start without.
+    pub areas: Option<TriangleAreas>,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum DracoLoadError {
     #[error("primitive doesn't use KHR_draco_mesh_compression")]
     NotDraco,
-    #[error("missing or malformed KHR_draco_mesh_compression extension")]
+    #[error(
+        "missing or malformed KHR_draco_mesh_compression extension; expected \"bufferView\" index and \"attributes\" mapping of semantic to Draco id"
+    )]
     BadExtension,
     #[error("bufferView {0} not found")]
     BadBufferView(usize),
     #[error("buffer index {0} not found")]
     BadBuffer(usize),
+    #[error("bufferView {0} holds Draco data but defines byteStride {1}; compressed views must be tightly packed")]
+    StridedBufferView(usize, usize),
+    #[error("bufferView {0} range {1}..{2} exceeds its buffer")]
+    BufferViewOutOfRange(usize, usize, usize),
     #[error("attribute mapping missing POSITION accessor (needed for vertex count)")]
     NoPositionAccessor,
     #[error("indices accessor missing for TRIANGLES primitive")]
@@ -28,15 +42,199 @@ pub enum DracoLoadError {
     DracoDecode,
     #[error("attribute id {0} from Draco stream not in glTF extension attributes map")]
     UnknownAttributeId(u32),
-    #[error("unsupported primitive mode (only TRIANGLES supported)")]
-    UnsupportedMode,
+    #[error("unsupported primitive mode {0:?} (only TRIANGLES supported)")]
+    UnsupportedMode(gltf::mesh::Mode),
+    #[error("no accessor for Draco attribute {0}")]
+    MissingAccessor(String),
+    #[error("mesh {0} primitive {1} not found in document index")]
+    NotIndexed(usize, usize),
+    #[error("scene {0} not found")]
+    BadScene(usize),
+    #[error("document has no scenes")]
+    NoScene,
+    #[error("failed to load glTF: {0}")]
+    Gltf(#[from] gltf::Error),
+    #[error("Draco stream decoded to {1} bytes but the accessors describe {0}")]
+    DecodedSizeMismatch(usize, usize),
+    #[error("decoded vertex {0} has no matching vertex in the reference ordering")]
+    ReorderMismatch(usize),
+    #[error("index {0} does not fit in u32")]
+    IndexOverflow(usize),
+    #[error("decode aborted")]
+    Aborted,
+    #[error("primitive has {0} vertices but the vertex deltas cover {1}")]
+    DeltaCountMismatch(usize, usize),
+    #[error("URI {0:?} can't be resolved to a file inside the base directory")]
+    BadUri(String),
+    #[error(
+        "attribute infos disagree with the primitive: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    AttributeMismatch(Vec<AttributeConflict>),
+    #[error(
+        "Draco bitstream version {found} is not supported (decoder reads {}..={})",
+        .supported.start(),
+        .supported.end()
+    )]
+    UnsupportedBitstreamVersion {
+        found: BitstreamVersion,
+        supported: std::ops::RangeInclusive<BitstreamVersion>,
+    },
+    #[error("malformed draco_gltf_rs directives on mesh {0} primitive {1}: {2}")]
+    BadDirectives(usize, usize, String),
+    #[error("Draco buffer view is empty but the accessors describe {0} decoded bytes")]
+    EmptyDracoStream(usize),
+    #[error("JOINTS_{set} holds joint index {value}, which doesn't fit in u16")]
+    JointIndexOverflow { set: u32, value: f32 },
+    #[error("attribute plugin rejected: {0}")]
+    AttributePluginAbi(String),
+    #[error("attribute plugin for {name} failed with code {code}")]
+    AttributePlugin { name: String, code: i32 },
+    #[error("{attribute} is stored as {from} and would be promoted to {to}")]
+    ForbiddenPromotion {
+        attribute: String,
+        from: &'static str,
+        to: &'static str,
+    },
+    #[error(
+        "{semantic} is planned as dim {} type {} but the stream stores {}",
+        .planned.dim,
+        .planned.data_type,
+        .stored.as_ref().map_or("nothing".to_string(), |s| format!("dim {} type {}", s.dim, s.data_type))
+    )]
+    PlanMismatch {
+        semantic: String,
+        planned: AttrInfo,
+        stored: Option<AttrInfo>,
+    },
+    #[error("primitive has {0} vertices, more than 16-bit indices can address")]
+    IndexWidthExceeded(usize),
+    #[error("malformed container at byte {0}: {1}")]
+    BadContainer(usize, &'static str),
+    #[error(
+        "Draco stream has {stream_faces} faces and {stream_vertices} vertices but the fallback accessors have {indices} indices and {vertices} vertices"
+    )]
+    FallbackCountMismatch {
+        stream_vertices: usize,
+        stream_faces: usize,
+        vertices: usize,
+        indices: usize,
+    },
+    #[error("custom attribute {name} is stored as {first} in one part and {other} in another")]
+    CustomAttributeConflict {
+        name: String,
+        first: String,
+        other: String,
+    },
+}
+
+impl DracoLoadError {
+    /// An actionable suggestion for fixing the asset or the call, where one exists.
+    pub fn hint(&self) -> Option<&'static str> {
+        use DracoLoadError::*;
+        Some(match self {
+            NotDraco => "read the primitive's accessors directly instead of decoding it",
+            BadExtension => {
+                "re-export the asset; the extension must look like {\"bufferView\": 0, \"attributes\": {\"POSITION\": 0}}"
+            }
+            BadBufferView(_) | BadBuffer(_) | BufferViewOutOfRange(..) => {
+                "the file is truncated or references missing buffers; make sure all .bin files load"
+            }
+            StridedBufferView(..) => "remove byteStride from the Draco bufferView",
+            NoPositionAccessor => "add a POSITION entry to the primitive's attributes",
+            NoIndicesAccessor => "Draco primitives must reference an indices accessor",
+            DracoDecode | DecodedSizeMismatch(..) => {
+                "the Draco stream is corrupt or disagrees with the accessor counts; try the uncompressed fallback"
+            }
+            UnknownAttributeId(_) | MissingAccessor(_) => {
+                "the extension's attributes map and the primitive's attributes disagree; re-export the asset"
+            }
+            UnsupportedMode(_) => {
+                "only TRIANGLES primitives can be decoded; read the fallback accessors instead"
+            }
+            NotIndexed(..) => "rebuild the DocumentIndex after the document changed",
+            BadScene(_) | NoScene => "pass an existing scene index or add a scene to the document",
+            ReorderMismatch(_) => "disable accessor_order or decode from the fallback accessors",
+            IndexOverflow(_) => "split the merge into fewer primitives",
+            DeltaCountMismatch(..) => "build the deltas against this primitive's decoded vertices",
+            BadUri(_) => "reference external files by relative paths inside the asset's directory",
+            AttributeMismatch(_) => {
+                "take the infos from stored_attr_infos, or set DecodeOptions::salvage_attributes"
+            }
+            UnsupportedBitstreamVersion { .. } => {
+                "re-encode with a Draco encoder whose version is in supported_versions(), or read the fallback accessors"
+            }
+            BadDirectives(..) => "directives look like {\"draco_gltf_rs\": {\"skip\": true}}",
+            EmptyDracoStream(_) => {
+                "the exporter wrote no Draco data for a non-empty primitive; read the fallback accessors or re-export"
+            }
+            JointIndexOverflow { .. } => {
+                "JOINTS must be UNSIGNED_BYTE or UNSIGNED_SHORT; re-export the skinned mesh"
+            }
+            AttributePluginAbi(_) => {
+                "rebuild the plugin against the AttributePlugin layout of this crate version"
+            }
+            AttributePlugin { .. } => {
+                "see the plugin's documentation for the code, or clear_attribute_plugins() to get the stored values"
+            }
+            ForbiddenPromotion { .. } => {
+                "allow the promotion in DecodeOptions::promotions, or re-export with the target type"
+            }
+            PlanMismatch { .. } => {
+                "the stream disagrees with its accessors; decode with decode_draco_raw or read the fallback accessors"
+            }
+            IndexWidthExceeded(_) => {
+                "drop IndexWidthCheck::U16 if 32-bit indices are fine, or split the mesh with split_by_grid"
+            }
+            BadContainer(..) => {
+                "pass a complete bundle of back-to-back GLBs or a ZIP archive; deflated entries need the zip feature"
+            }
+            FallbackCountMismatch { .. } => {
+                "the uncompressed accessors describe a different mesh than the Draco stream; re-export the asset"
+            }
+            CustomAttributeConflict { .. } => {
+                "convert the attribute to one layout in every part, or rename it in the odd ones out"
+            }
+            Gltf(_) | Aborted => return None,
+        })
+    }
+
+    /// Whether a loader can reasonably retry with the uncompressed fallback accessors.
+    ///
+    /// True for failures tied to the Draco payload or its extension object; false for
+    /// document-level problems (bad scene, I/O, index bookkeeping) a fallback wouldn't fix.
+    pub fn is_recoverable(&self) -> bool {
+        use DracoLoadError::*;
+        matches!(
+            self,
+            NotDraco
+                | BadExtension
+                | BadBufferView(_)
+                | BadBuffer(_)
+                | StridedBufferView(..)
+                | BufferViewOutOfRange(..)
+                | NoPositionAccessor
+                | DracoDecode
+                | UnsupportedBitstreamVersion { .. }
+                | JointIndexOverflow { .. }
+                | AttributePlugin { .. }
+                | EmptyDracoStream(_)
+                | UnknownAttributeId(_)
+                | UnsupportedMode(_)
+                | MissingAccessor(_)
+                | DecodedSizeMismatch(..)
+                | ReorderMismatch(_)
+                | PlanMismatch { .. }
+        )
+    }
 }
 
 #[derive(serde::Deserialize)]
 struct DracoExt {
     #[serde(rename = "bufferView")]
     buffer_view: usize,
-    attributes: std::collections::HashMap<String, u32>, // semantic -> draco unique id
+    #[serde(deserialize_with = "interned_attributes")]
+    attributes: Vec<(std::sync::Arc<str>, u32)>, // semantic -> draco unique id, by id
 }
 
 struct AttrSlice<'a> {
@@ -46,59 +244,422 @@ struct AttrSlice<'a> {
     dt: draco_decoder::AttributeDataType,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct AttrInfo {
     pub unique_id: u32, // Draco attribute unique id
     pub dim: u32,       // number of components, e.g. 3 for POSITION
     pub data_type: u8,  // draco::DataType as a small integer
 }
 
+impl AttrInfo {
+    /// The decoder's view of `data_type`.
+    pub fn attribute_data_type(&self) -> AttributeDataType {
+        map_draco_dt(self.data_type)
+    }
+}
+
+pub use draco_decoder::{AttributeDataType, MeshDecodeConfig};
+
 mod mapping;
 use mapping::*;
 
+mod vecmath;
+use vecmath::*;
+
+mod index;
+pub use index::*;
+
+mod scene;
+pub use scene::*;
+
+mod options;
+pub use options::*;
+
+mod fallback;
+use fallback::*;
+
+mod reorder;
+pub use reorder::*;
+
+mod merge;
+pub use merge::*;
+
+mod raw;
+pub use raw::*;
+
+mod upload;
+pub use upload::*;
+
+mod conformance;
+pub use conformance::*;
+
+mod snapshot;
+pub use snapshot::*;
+
+mod split;
+pub use split::*;
+
+mod report;
+pub use report::*;
+
+mod parallel;
+pub use parallel::*;
+
+mod limit;
+pub use limit::*;
+
+mod progressive;
+pub use progressive::*;
+
+mod metrics;
+pub use metrics::*;
+
+mod accessors;
+pub use accessors::*;
+
+mod quirks;
+pub use quirks::*;
+
+mod advisor;
+pub use advisor::*;
+
+mod hash;
+pub use hash::*;
+
+mod sliced;
+pub use sliced::*;
+
+mod abort;
+pub use abort::*;
+
+mod dither;
+pub use dither::*;
+
+mod skinning;
+pub use skinning::*;
+
+mod shadow;
+pub use shadow::*;
+
+mod texel;
+pub use texel::*;
+
+mod strip;
+pub use strip::*;
+
+mod store;
+pub use store::*;
+
+mod warmup;
+pub use warmup::*;
+
+mod bench;
+pub use bench::*;
+
+mod endian;
+pub use endian::*;
+
+mod debug;
+
+mod preview;
+pub use preview::*;
+
+mod pool;
+pub use pool::*;
+
+mod repair;
+pub use repair::*;
+
+mod stl;
+pub use stl::*;
+
+mod batch;
+pub use batch::*;
+
+mod patch;
+pub use patch::*;
+
+mod canonical;
+pub use canonical::*;
+
+mod uri;
+pub use uri::*;
+
+mod semantics;
+pub use semantics::*;
+
+mod compact;
+pub use compact::*;
+
+mod attrcheck;
+pub use attrcheck::*;
+
+mod metadata;
+pub use metadata::*;
+
+mod winding;
+pub use winding::*;
+
+mod uvrebase;
+
+mod bake;
+pub use bake::*;
+
+mod bitstream;
+pub use bitstream::*;
+
+mod voxel;
+pub use voxel::*;
+
+mod navmesh;
+pub use navmesh::*;
+
+mod sample;
+pub use sample::*;
+
+mod areas;
+pub use areas::*;
+
+mod rebase;
+pub use rebase::*;
+
+mod directives;
+pub use directives::*;
+
+mod pack;
+pub use pack::*;
+
+mod meshlet;
+pub use meshlet::*;
+
+mod culling;
+pub use culling::*;
+
+mod setmap;
+pub use setmap::*;
+
+mod recycle;
+pub use recycle::*;
+
+mod plugin;
+pub use plugin::*;
+
+mod observer;
+pub use observer::*;
+mod promotion;
+pub use promotion::*;
+mod visitor;
+pub use visitor::*;
+mod view;
+pub use view::*;
+mod material;
+pub use material::*;
+
+mod transform;
+pub use transform::*;
+
+mod mesh;
+pub use mesh::*;
+
+mod capabilities;
+pub use capabilities::*;
+
+mod plan;
+pub use plan::*;
+
+mod container;
+pub use container::*;
+
+mod smoothing;
+
+#[cfg(feature = "gpu")]
+mod gpu;
+#[cfg(feature = "gpu")]
+pub use gpu::*;
+
+#[cfg(feature = "render-offscreen")]
+mod render;
+#[cfg(feature = "render-offscreen")]
+pub use render::*;
+
+#[cfg(feature = "watch")]
+mod watch;
+#[cfg(feature = "watch")]
+pub use watch::*;
+
+#[cfg(feature = "mmap")]
+mod lazy;
+#[cfg(feature = "mmap")]
+pub use lazy::*;
+#[cfg(feature = "mmap")]
+mod spill;
+#[cfg(feature = "mmap")]
+pub use spill::*;
+
+#[cfg(feature = "arrow")]
+mod columnar;
+
+#[cfg(feature = "alloc-metrics")]
+mod alloc_metrics;
+#[cfg(feature = "alloc-metrics")]
+pub use alloc_metrics::*;
+
+#[cfg(feature = "tokio")]
+mod asyncload;
+#[cfg(feature = "tokio")]
+pub use asyncload::*;
+
+#[cfg(feature = "sync")]
+mod blocking;
+#[cfg(feature = "sync")]
+pub use blocking::*;
+
+/// Draco attribute unique id -> (glTF semantic, accessor component count, accessor normalized).
+type SemanticMap = std::collections::HashMap<u32, (gltf::Semantic, usize, bool)>;
+
+/// Everything `prozes_in` extracts from the document before the Draco decode.
+struct DecodeInput<'a> {
+    draco_bytes: &'a [u8],
+    /// The attribute layout the decoder writes: the stream's own descriptors when its header
+    /// parses, the caller's infos otherwise.
+    infos: Vec<AttrInfo>,
+    cfg: MeshDecodeConfig,
+    index_comp: gltf::accessor::DataType,
+    index_count: usize,
+    vertex_count: usize,
+    draco_ext: DracoExt,
+}
+
+/// Decodes a Draco primitive. Empty `infos` are taken from the primitive's accessors, as
+/// [`attr_infos_from_primitive`] builds them.
+#[allow(clippy::ptr_arg)] // the released signature; the other entry points take slices
 pub async fn decode_draco(
     p: &gltf::mesh::Primitive<'_>,
     document: &gltf::Document,
     buffers: &Vec<gltf::buffer::Data>,
     infos: &Vec<AttrInfo>,
 ) -> Result<DecodedPrimitive, DracoLoadError> {
-    let (draco_bytes, cfg, index_comp, index_count, vertex_count, draco_ext) =
-        prozes_in(p, document, buffers, infos)?;
-    let raw = draco_decoder::decode_mesh(draco_bytes, &cfg).await.ok_or(DracoLoadError::DracoDecode)?;
-    return prozes_out(
-        &raw,
-        index_comp,
-        index_count,
-        vertex_count,
-        infos,
-        p,
-        draco_ext,
+    decode_draco_with_options(p, document, buffers, infos, &DecodeOptions::default()).await
+}
+
+pub async fn decode_draco_with_options(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    infos: &[AttrInfo],
+    options: &DecodeOptions,
+) -> Result<DecodedPrimitive, DracoLoadError> {
+    let mut recycled = PrimitiveBuffers::default();
+    decode_draco_recycled(p, document, buffers, infos, options, &mut recycled).await
+}
+
+/// [`decode_draco_with_options`] filling vectors taken from `recycled` where it has them.
+/// Primitives read from their fallback accessors don't draw on the pool.
+pub async fn decode_draco_recycled(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    infos: &[AttrInfo],
+    options: &DecodeOptions,
+    recycled: &mut PrimitiveBuffers,
+) -> Result<DecodedPrimitive, DracoLoadError> {
+    if options.fallback == FallbackPolicy::PreferFallback && has_fallback(p, document) {
+        // still only accept Draco primitives here
+        let ext = draco_extension_with_quirks(p, &options.quirks)?;
+        if options.promotions != PromotionPolicy::lenient() {
+            let infos = attr_infos_with_quirks(p, &options.quirks)?;
+            let map = options.semantic_map(p, &ext)?;
+            options.promotions.check(p, &infos, &map)?;
+        }
+        check_fallback_counts(p, get_buffer(document, buffers, ext.buffer_view)?)?;
+        return read_plain(p, buffers, options);
+    }
+
+    let derived;
+    let infos = if infos.is_empty() {
+        derived = attr_infos_with_quirks(p, &options.quirks)?;
+        &derived
+    } else {
+        infos
+    };
+    let salvaged;
+    let input = match prozes_in(p, document, buffers, infos, &options.quirks) {
+        Err(DracoLoadError::AttributeMismatch(_)) if options.salvage_attributes => {
+            salvaged = stored_attr_infos(p, document, buffers, &options.quirks)?;
+            prozes_in(p, document, buffers, &salvaged, &options.quirks)?
+        }
+        input => input?,
+    };
+    if has_fallback(p, document) {
+        check_fallback_counts(p, input.draco_bytes)?;
+    }
+    options.check_vertex_count(input.vertex_count)?;
+    let Some(observer) = options.observer.as_deref() else {
+        let decoded = decode_input(p, document, buffers, options, recycled, &input).await;
+        return decoded.map(|(out, _)| out);
+    };
+
+    let started = DecodeStarted {
+        mesh: mesh_index(p, document),
+        primitive: p.index(),
+        buffer_view: input.draco_ext.buffer_view,
+        bytes_in: input.draco_bytes.len(),
+        vertex_count: input.vertex_count,
+        index_count: input.index_count,
+        thread: std::thread::current().id(),
+    };
+    let guard = FinishGuard::start(observer, started);
+    let decoded = decode_input(p, document, buffers, options, recycled, &input).await;
+    guard.finish(
+        decoded.as_ref().map_or(0, |(_, raw_len)| *raw_len),
+        decoded.as_ref().err(),
     );
+    decoded.map(|(out, _)| out)
+}
+
+/// The Draco half of [`decode_draco_recycled`], also returning the decoder's output size.
+async fn decode_input(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    options: &DecodeOptions,
+    recycled: &mut PrimitiveBuffers,
+    input: &DecodeInput<'_>,
+) -> Result<(DecodedPrimitive, usize), DracoLoadError> {
+    let dracoid_to_sem = options.semantic_map(p, &input.draco_ext)?;
+    options.promotions.check(p, &input.infos, &dracoid_to_sem)?;
+    let cap = options.max_concurrent_decodes;
+    // draco_decoder allocates `raw` itself and has no entry point that writes into a given
+    // buffer, so unlike the converted streams it can't come from `recycled`
+    let raw = decode_limited(input.draco_bytes, &input.cfg, cap).await?;
+    let mut out = prozes_out(
+        &raw,
+        input.index_comp,
+        input.index_count,
+        input.vertex_count,
+        &input.infos,
+        &dracoid_to_sem,
+        options,
+        recycled,
+    )?;
+    if options.accessor_order && has_fallback(p, document) {
+        reorder_to_reference(&mut out, &read_fallback(p, buffers)?)?;
+    }
+    options.apply_conventions(&mut out);
+    Ok((out, raw.len()))
 }
 
 fn prozes_in<'a>(
     p: &'a gltf::mesh::Primitive<'_>,
     document: &'a gltf::Document,
-    buffers: &'a Vec<gltf::buffer::Data>,
-    infos: &'a Vec<AttrInfo>,
-) -> Result<
-    (
-        &'a [u8],
-        draco_decoder::MeshDecodeConfig,
-        gltf::accessor::DataType,
-        usize,
-        usize,
-        DracoExt,
-    ),
-    DracoLoadError,
-> {
+    buffers: &'a [gltf::buffer::Data],
+    infos: &'a [AttrInfo],
+    quirks: &VendorQuirks,
+) -> Result<DecodeInput<'a>, DracoLoadError> {
     if p.mode() != gltf::mesh::Mode::Triangles {
-        return Err(DracoLoadError::UnsupportedMode);
+        return Err(DracoLoadError::UnsupportedMode(p.mode()));
     }
-    let value = p
-        .extension_value("KHR_draco_mesh_compression")
-        .ok_or(DracoLoadError::NotDraco)?;
-    let draco_ext: DracoExt =
-        serde_json::from_value(value.clone()).map_err(|_| DracoLoadError::BadExtension)?;
+    let draco_ext = draco_extension_with_quirks(p, quirks)?;
 
     let draco_bytes: &[u8] = get_buffer(document, buffers, draco_ext.buffer_view)?;
 
@@ -109,46 +670,157 @@ fn prozes_in<'a>(
 
     let indices_accessor = p.indices().ok_or(DracoLoadError::NoIndicesAccessor)?;
     let index_count: usize = indices_accessor.count();
-    let mut index_comp: gltf::accessor::DataType = indices_accessor.data_type();
-    if index_comp == gltf::accessor::DataType::U8 {
-        // workaround because draco_decoder has not yet logic for u8
-        index_comp = gltf::accessor::DataType::U16;
-    }
+    let index_comp = index_comp_workaround(indices_accessor.data_type());
 
-    let mut cfg: draco_decoder::MeshDecodeConfig =
-        draco_decoder::MeshDecodeConfig::new(vertex_count as u32, index_count as u32);
-    for info in infos {
-        cfg.add_attribute(info.dim, map_draco_dt(info.data_type));
-    }
-    return Ok((
+    // the stream's own header configures the decoder; the given infos must agree with it
+    let infos = decoder_infos(
+        draco_bytes,
+        infos,
+        || attr_infos_with_quirks(p, quirks),
+        |id| draco_ext.semantic_of(id),
+    )?;
+    let cfg = decode_config(vertex_count, index_count, &infos);
+    Ok(DecodeInput {
         draco_bytes,
+        infos,
         cfg,
         index_comp,
         index_count,
         vertex_count,
         draco_ext,
-    ));
+    })
+}
+
+/// Runs the Draco decoder on `draco_bytes` with a caller-built config and returns its
+/// output buffer: indices first, then each attribute in config order.
+///
+/// The output is checked to be exactly the size the config describes; a stream with fewer
+/// vertices or faces than its accessors claim would otherwise be misread (or panic while
+/// slicing). Waits for a slot if [`set_max_concurrent_decodes`] caps concurrency.
+///
+/// A config describing no output at all (no faces and no vertex data, as procedural
+/// exporters write for empty primitives) returns an empty buffer without running the
+/// decoder, whatever the stream holds; an empty stream for a non-empty config is
+/// [`DracoLoadError::EmptyDracoStream`].
+pub async fn decode_with_config(
+    draco_bytes: &[u8],
+    cfg: &MeshDecodeConfig,
+) -> Result<Vec<u8>, DracoLoadError> {
+    decode_limited(draco_bytes, cfg, max_concurrent_decodes()).await
+}
+
+async fn decode_limited(
+    draco_bytes: &[u8],
+    cfg: &MeshDecodeConfig,
+    cap: Option<usize>,
+) -> Result<Vec<u8>, DracoLoadError> {
+    let expected = cfg.estimate_buffer_size();
+    if expected == 0 {
+        return Ok(Vec::new());
+    }
+    if draco_bytes.is_empty() {
+        return Err(DracoLoadError::EmptyDracoStream(expected));
+    }
+    check_bitstream_version(draco_bytes)?;
+    let _permit = acquire(cap).await;
+    let raw = draco_decoder::decode_mesh(draco_bytes, cfg)
+        .await
+        .ok_or(DracoLoadError::DracoDecode)?;
+    mark_ready();
+    if raw.len() != expected {
+        return Err(DracoLoadError::DecodedSizeMismatch(expected, raw.len()));
+    }
+    Ok(raw)
 }
 
+fn draco_extension(p: &gltf::mesh::Primitive<'_>) -> Result<DracoExt, DracoLoadError> {
+    let value = p
+        .extension_value("KHR_draco_mesh_compression")
+        .ok_or(DracoLoadError::NotDraco)?;
+    serde::Deserialize::deserialize(value).map_err(|_| DracoLoadError::BadExtension)
+}
+
+fn index_comp_workaround(index_comp: gltf::accessor::DataType) -> gltf::accessor::DataType {
+    if index_comp == gltf::accessor::DataType::U8 {
+        // workaround because draco_decoder has not yet logic for u8
+        return gltf::accessor::DataType::U16;
+    }
+    index_comp
+}
+
+/// Builds the decoder config for a primitive from its counts and attribute layout.
+pub fn decode_config(
+    vertex_count: usize,
+    index_count: usize,
+    infos: &[AttrInfo],
+) -> MeshDecodeConfig {
+    let mut cfg = MeshDecodeConfig::new(vertex_count as u32, index_count as u32);
+    for info in infos {
+        cfg.add_attribute(info.dim, info.attribute_data_type());
+    }
+    cfg
+}
+
+fn semantic_map(p: &gltf::mesh::Primitive<'_>, draco_ext: &DracoExt) -> SemanticMap {
+    let mut dracoid_to_sem = SemanticMap::new();
+    for (k, id) in &draco_ext.attributes {
+        if let Some(sem) = dracokey_to_semantic(k)
+            && let Some(acc) = p.get(&sem)
+        {
+            dracoid_to_sem.insert(*id, (sem, dims_count(acc.dimensions()), acc.normalized()));
+        }
+    }
+    dracoid_to_sem
+}
+
+#[allow(clippy::too_many_arguments)]
 fn prozes_out(
     raw: &[u8],
     index_comp: gltf::accessor::DataType,
     index_count: usize,
     vertex_count: usize,
-    infos: &Vec<AttrInfo>,
-    p: &gltf::mesh::Primitive<'_>,
-    draco_ext: DracoExt,
+    infos: &[AttrInfo],
+    dracoid_to_sem: &SemanticMap,
+    options: &DecodeOptions,
+    recycled: &mut PrimitiveBuffers,
 ) -> Result<DecodedPrimitive, DracoLoadError> {
     let index_bytes: usize = index_count * comp_size_bytes(index_comp);
-    let indices = get_indices(&raw, index_bytes, index_comp)?;
+    let indices = get_indices_into(raw, index_bytes, index_comp, recycled.take())?;
+
+    let attr_blocks: Vec<AttrSlice<'_>> = attr_slices(raw, index_bytes, vertex_count, infos)
+        .into_iter()
+        .filter(|b| options.converts_block(dracoid_to_sem, b))
+        .collect();
+
+    let mut out = DecodedPrimitive {
+        indices,
+        ..Default::default()
+    };
+
+    fill_primitive_recycled(
+        &mut out,
+        &attr_blocks,
+        dracoid_to_sem,
+        &options.transforms,
+        recycled,
+    )?;
+    Ok(out)
+}
 
+/// Splits the raw decoder output into one block per attribute, following `infos` order.
+fn attr_slices<'a>(
+    raw: &'a [u8],
+    index_bytes: usize,
+    vertex_count: usize,
+    infos: &[AttrInfo],
+) -> Vec<AttrSlice<'a>> {
     let mut cursor = index_bytes;
-    let mut attr_blocks: Vec<AttrSlice<'_>> = Vec::with_capacity(infos.len());
+    let mut attr_blocks: Vec<AttrSlice<'a>> = Vec::with_capacity(infos.len());
     for info in infos {
         let elem_size = match info.data_type {
             1 | 2 => 1,     // i8/u8
             3 | 4 => 2,     // i16/u16
-            5 | 6 | 7 => 4, // i32/u32/f32
+            5..=7 => 4,     // i32/u32/f32
             _ => 4,
         };
         let byte_len = vertex_count * (info.dim as usize) * elem_size;
@@ -161,173 +833,272 @@ fn prozes_out(
             dt: map_draco_dt(info.data_type),
         });
     }
+    attr_blocks
+}
 
-    let mut dracoid_to_sem: std::collections::HashMap<u32, (gltf::Semantic, gltf::Accessor)> =
-        std::collections::HashMap::new();
-    for (k, id) in &draco_ext.attributes {
-        if let Some(sem) = dracokey_to_semantic(k) {
-            if let Some(acc) = p.get(&sem) {
-                dracoid_to_sem.insert(*id, (sem, acc));
-            }
-        }
+/// Builds the attribute infos for a Draco primitive from its glTF accessors, ordered by
+/// Draco unique id and paired with the semantic key from the extension.
+fn derive_attr_infos<'e>(
+    p: &gltf::mesh::Primitive<'_>,
+    draco_ext: &'e DracoExt,
+) -> Result<Vec<(&'e str, AttrInfo)>, DracoLoadError> {
+    let mut out = Vec::with_capacity(draco_ext.attributes.len());
+    for (key, id) in &draco_ext.attributes {
+        let acc = dracokey_to_semantic(key)
+            .and_then(|sem| p.get(&sem))
+            .ok_or_else(|| DracoLoadError::MissingAccessor(key.to_string()))?;
+        out.push((
+            &**key,
+            AttrInfo {
+                unique_id: *id,
+                dim: dims_count(acc.dimensions()) as u32,
+                data_type: gltf_to_draco_dt(acc.data_type()),
+            },
+        ));
     }
+    Ok(out)
+}
 
-    let mut out = DecodedPrimitive {
-        indices,
-        ..Default::default()
-    };
+/// The attribute infos for a Draco primitive, built from its accessors and the extension's
+/// attributes map, in Draco unique id order. What [`decode_draco`] uses when given none.
+pub fn attr_infos_from_primitive(
+    p: &gltf::mesh::Primitive<'_>,
+) -> Result<Vec<AttrInfo>, DracoLoadError> {
+    attr_infos_with_quirks(p, &VendorQuirks::default())
+}
+
+/// Decodes a Draco primitive with attribute infos taken from its own accessors; see
+/// [`attr_infos_from_primitive`].
+pub async fn decode_draco_derived(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+) -> Result<DecodedPrimitive, DracoLoadError> {
+    decode_draco_derived_with_options(p, document, buffers, &DecodeOptions::default()).await
+}
 
-    fill_primitive(&mut out, &attr_blocks, &dracoid_to_sem)?;
-    return Ok(out);
+pub async fn decode_draco_derived_with_options(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    options: &DecodeOptions,
+) -> Result<DecodedPrimitive, DracoLoadError> {
+    decode_draco_with_options(p, document, buffers, &[], options).await
 }
 
-fn get_buffer<'a>(
+fn buffer_view<'a>(
     document: &'a gltf::Document,
-    buffers: &'a Vec<gltf::buffer::Data>,
     index: usize,
-) -> Result<&'a [u8], DracoLoadError> {
+) -> Result<gltf::buffer::View<'a>, DracoLoadError> {
     let bv = document
         .views()
         .find(|v| v.index() == index)
         .ok_or(DracoLoadError::BadBufferView(index))?;
+    // The spec forbids byteStride on the compressed view, but some exporters set it anyway.
+    // Slicing such a view as if it were packed would hand the decoder the wrong bytes.
+    if let Some(stride) = bv.stride() {
+        return Err(DracoLoadError::StridedBufferView(index, stride));
+    }
+    Ok(bv)
+}
+
+fn view_bytes(
+    buf: &[u8],
+    view: usize,
+    start: usize,
+    length: usize,
+) -> Result<&[u8], DracoLoadError> {
+    let end = start.saturating_add(length);
+    buf.get(start..end)
+        .ok_or(DracoLoadError::BufferViewOutOfRange(view, start, end))
+}
+
+fn get_buffer<'a>(
+    document: &'a gltf::Document,
+    buffers: &'a [gltf::buffer::Data],
+    index: usize,
+) -> Result<&'a [u8], DracoLoadError> {
+    let bv = buffer_view(document, index)?;
 
     let buffer_idx = bv.buffer().index();
     let buf = buffers
         .get(buffer_idx)
         .ok_or(DracoLoadError::BadBuffer(buffer_idx))?;
 
-    let start = bv.offset();
-    let end = start + bv.length();
-    Ok(&buf[start..end])
+    view_bytes(buf, index, bv.offset(), bv.length())
 }
 
 fn get_indices(
     raw: &[u8],
     index_bytes: usize,
     index_comp: gltf::accessor::DataType,
+) -> Result<Vec<u32>, DracoLoadError> {
+    get_indices_into(raw, index_bytes, index_comp, Vec::new())
+}
+
+/// [`get_indices`] reusing `indices`' capacity.
+fn get_indices_into(
+    raw: &[u8],
+    index_bytes: usize,
+    index_comp: gltf::accessor::DataType,
+    mut indices: Vec<u32>,
 ) -> Result<Vec<u32>, DracoLoadError> {
     let indices_bytes = &raw[0..index_bytes];
-    let indices: Vec<u32> = match index_comp {
-        gltf::accessor::DataType::U16 => indices_bytes
-            .chunks_exact(2)
-            .map(|b| u16::from_le_bytes([b[0], b[1]]) as u32)
-            .collect(),
-        gltf::accessor::DataType::U32 => indices_bytes
-            .chunks_exact(4)
-            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
-            .collect(),
-        gltf::accessor::DataType::U8 => indices_bytes.iter().map(|&b| b as u32).collect(),
+    indices.clear();
+    match index_comp {
+        gltf::accessor::DataType::U16 => indices.extend(
+            indices_bytes
+                .chunks_exact(2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]) as u32),
+        ),
+        gltf::accessor::DataType::U32 => indices.extend(
+            indices_bytes
+                .chunks_exact(4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        ),
+        gltf::accessor::DataType::U8 => indices.extend(indices_bytes.iter().map(|&b| b as u32)),
         _ => return Err(DracoLoadError::DracoDecode),
     };
-    return Ok(indices);
+    Ok(indices)
 }
 
 fn fill_primitive(
     p: &mut DecodedPrimitive,
-    attr_blocks: &Vec<AttrSlice<'_>>,
-    dracoid_to_sem: &std::collections::HashMap<u32, (gltf::Semantic, gltf::Accessor)>,
+    attr_blocks: &[AttrSlice<'_>],
+    dracoid_to_sem: &SemanticMap,
+) -> Result<(), DracoLoadError> {
+    fill_primitive_recycled(
+        p,
+        attr_blocks,
+        dracoid_to_sem,
+        &AttributeTransforms::new(),
+        &mut PrimitiveBuffers::default(),
+    )
+}
+
+fn fill_primitive_recycled(
+    p: &mut DecodedPrimitive,
+    attr_blocks: &[AttrSlice<'_>],
+    dracoid_to_sem: &SemanticMap,
+    transforms: &AttributeTransforms,
+    recycled: &mut PrimitiveBuffers,
 ) -> Result<(), DracoLoadError> {
     for blk in attr_blocks {
-        let (sem, acc) = dracoid_to_sem
+        let (sem, acc_dims, normalized) = dracoid_to_sem
             .get(&blk.unique_id)
             .ok_or(DracoLoadError::UnknownAttributeId(blk.unique_id))?;
 
-        let acc_dims = dims_count(acc.dimensions());
-        debug_assert_eq!(acc_dims, blk.dim, "Draco dim != accessor dim");
+        debug_assert_eq!(*acc_dims, blk.dim, "Draco dim != accessor dim");
+        let t = transforms.get(sem);
 
         match *sem {
             gltf::Semantic::Positions => {
-                p.positions = Some(as_f32n::<3>(blk.bytes));
+                p.positions = Some(block_vecs(blk, *normalized, [0.0; 3], t, recycled.take()));
             }
             gltf::Semantic::Normals => {
-                p.normals = Some(as_f32n::<3>(blk.bytes));
+                // integer normals (snorm8/snorm16 under KHR_mesh_quantization) are unit vectors
+                p.normals = Some(block_vecs(blk, true, [0.0; 3], t, recycled.take()));
             }
             gltf::Semantic::Tangents => {
-                p.tangents = Some(as_f32n::<4>(blk.bytes));
+                p.tangents = Some(block_vecs(
+                    blk,
+                    true,
+                    [0.0, 0.0, 0.0, 1.0],
+                    t,
+                    recycled.take(),
+                ));
             }
             gltf::Semantic::TexCoords(set) => {
-                // In practice Draco provides TEXCOORD as f32; if U16/U8 normalized were used
-                // you could map via acc.normalized() to convert to f32 in your renderer.
-                p.texcoords.insert(set, as_f32n::<2>(blk.bytes));
+                // f32, or u8/u16 (normalized or not) under KHR_mesh_quantization
+                p.texcoords.insert(
+                    set,
+                    block_vecs(blk, *normalized, [0.0; 2], t, recycled.take()),
+                );
             }
             gltf::Semantic::Colors(set) => {
-                // Could be f32 or normalized U8. Handle common f32 path here.
-                if matches!(blk.dt, draco_decoder::AttributeDataType::Float32) {
-                    p.colors.insert(set, as_f32n::<4>(blk.bytes));
-                } else {
-                    // fall back: keep as normalized 8-bit expanded to f32 [0..1]
-                    let raw = as_u8x4(blk.bytes);
-                    let conv = raw
-                        .into_iter()
-                        .map(|c| {
-                            [
-                                c[0] as f32 / 255.0,
-                                c[1] as f32 / 255.0,
-                                c[2] as f32 / 255.0,
-                                c[3] as f32 / 255.0,
-                            ]
-                        })
-                        .collect();
-                    p.colors.insert(set, conv);
-                }
+                // integer colors are always normalized; RGB gets alpha 1
+                p.colors.insert(
+                    set,
+                    block_vecs(blk, true, [0.0, 0.0, 0.0, 1.0], t, recycled.take()),
+                );
             }
             gltf::Semantic::Joints(set) => {
-                // Often u8 or u16; we store u16
-                if matches!(blk.dt, draco_decoder::AttributeDataType::UInt16) {
-                    p.joints.insert(set, as_u16x4(blk.bytes));
-                } else {
-                    // widen u8->u16
-                    let v: Vec<[u16; 4]> = blk
-                        .bytes
-                        .chunks_exact(4)
-                        .map(|c| [c[0] as u16, c[1] as u16, c[2] as u16, c[3] as u16])
-                        .collect();
-                    p.joints.insert(set, v);
-                }
+                // u8 or u16 indices, read as stored; we store u16
+                let v = joint_indices(set, blk.bytes, blk.dt, blk.dim)?;
+                p.joints.insert(set, v);
             }
             gltf::Semantic::Weights(set) => {
-                // Usually f32; if normalized u8/u16 were used, convert to f32.
-                if matches!(blk.dt, draco_decoder::AttributeDataType::Float32) {
-                    p.weights.insert(set, as_f32n::<4>(blk.bytes));
-                } else if matches!(blk.dt, draco_decoder::AttributeDataType::UInt16) {
-                    let v: Vec<[f32; 4]> = blk
-                        .bytes
-                        .chunks_exact(8)
-                        .map(|c| {
-                            [
-                                u16::from_le_bytes([c[0], c[1]]) as f32 / 65535.0,
-                                u16::from_le_bytes([c[2], c[3]]) as f32 / 65535.0,
-                                u16::from_le_bytes([c[4], c[5]]) as f32 / 65535.0,
-                                u16::from_le_bytes([c[6], c[7]]) as f32 / 65535.0,
-                            ]
-                        })
-                        .collect();
-                    p.weights.insert(set, v);
-                } else {
-                    let v: Vec<[f32; 4]> = blk
-                        .bytes
-                        .chunks_exact(4)
-                        .map(|c| {
-                            [
-                                c[0] as f32 / 255.0,
-                                c[1] as f32 / 255.0,
-                                c[2] as f32 / 255.0,
-                                c[3] as f32 / 255.0,
-                            ]
-                        })
-                        .collect();
-                    p.weights.insert(set, v);
-                }
+                // f32, or normalized u8/u16
+                p.weights
+                    .insert(set, block_vecs(blk, true, [0.0; 4], t, recycled.take()));
+            }
+            gltf::Semantic::Extras(ref name) => {
+                let key = format!("_{name}");
+                // a registered plugin interprets the stream; normalized or transformed ones
+                // are widened to f32, the rest keep their stored type
+                let attribute = match decode_with_plugin(&key, blk, *normalized) {
+                    Some(decoded) => decoded?,
+                    None if *normalized || t.is_some() => {
+                        let mut values = as_f32_components(blk.bytes, blk.dt, *normalized);
+                        if let Some(t) = t {
+                            for v in values.chunks_mut(blk.dim.max(1)) {
+                                for (i, x) in v.iter_mut().enumerate() {
+                                    *x = t.apply(i, *x);
+                                }
+                            }
+                        }
+                        CustomAttribute {
+                            components: blk.dim,
+                            values: CustomValues::F32(values),
+                        }
+                    }
+                    None => CustomAttribute {
+                        components: blk.dim,
+                        values: CustomValues::from_le_bytes(blk.bytes, blk.dt),
+                    },
+                };
+                p.custom.insert(key, attribute);
             }
         }
     }
-    return Ok(());
+    Ok(())
+}
+
+/// Widens a block to `N`-component `f32` vectors, written into `out`'s storage; components
+/// the block lacks come from `fill`, the ones it has go through `transform`.
+fn block_vecs<const N: usize>(
+    blk: &AttrSlice<'_>,
+    normalized: bool,
+    fill: [f32; N],
+    transform: Option<&AttributeTransform>,
+    mut out: Vec<[f32; N]>,
+) -> Vec<[f32; N]> {
+    let dim = blk.dim.max(1);
+    out.clear();
+    out.reserve(blk.bytes.len() / (blk.dt.size_in_bytes() * dim));
+    // a trailing partial vertex is dropped, as chunks_exact would
+    let (mut v, mut i) = (fill, 0);
+    for_each_f32_component(blk.bytes, blk.dt, normalized, |x| {
+        if let Some(c) = v.get_mut(i) {
+            *c = transform.map_or(x, |t| t.apply(i, x));
+        }
+        i += 1;
+        if i == dim {
+            out.push(std::mem::replace(&mut v, fill));
+            i = 0;
+        }
+    });
+    out
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "alloc-metrics")]
+    #[global_allocator]
+    static ALLOC: TrackingAllocator = TrackingAllocator;
+
      #[tokio::test]
     async  fn test_decode_test_glb() -> Result<(), Box<dyn std::error::Error>> {
         let path = "examples/test.glb";
@@ -350,6 +1121,4230 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_document_index_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffer_data) = load_glb("examples/test.glb")?;
+
+        let index = DocumentIndex::build(&doc)?;
+        let json = serde_json::to_string(&index)?;
+        let index: DocumentIndex = serde_json::from_str(&json)?;
+
+        let entry = index.get(0, 0).ok_or("primitive 0 of mesh 0 not indexed")?;
+        assert_eq!(entry.attributes.len(), 2);
+
+        let decoded = index
+            .decode(0, 0, &buffer_data, &DecodeOptions::new())
+            .await?;
+        let expected = decode_test_glb("examples/test.glb").await?;
+        assert_eq!(decoded.indices, expected.indices);
+        assert_eq!(decoded.positions, expected.positions);
+        assert_eq!(decoded.texcoords, expected.texcoords);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decode_default_scene() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffer_data) = load_glb("examples/test.glb")?;
+
+        let scene = decode_default_scene(&doc, &buffer_data).await?;
+        assert_eq!(scene.index, 0);
+        assert_eq!(scene.primitives.len(), 1);
+
+        let node = doc.nodes().next().ok_or("No nodes found in GLB")?;
+        assert_eq!(scene.primitives[0].world_transform, node.transform().matrix());
+        assert_eq!(scene.roots, vec![0]);
+        let scene_node = scene.node(0).ok_or("node 0 missing from scene")?;
+        assert_eq!(scene_node.parent, None);
+        assert_eq!(scene_node.mesh, Some(0));
+        assert!(matches!(
+            decode_scene_by_index(&doc, &buffer_data, 1).await,
+            Err(DracoLoadError::BadScene(1))
+        ));
+
+        // a second node instancing the same mesh gets its own copy of the one decode
+        let mut root = doc.clone().into_json();
+        root.nodes.push(root.nodes[0].clone());
+        let copy = gltf::json::Index::new(root.nodes.len() as u32 - 1);
+        root.scenes[0].nodes.push(copy);
+        let instanced = gltf::Document::from_json_without_validation(root);
+        let scene = decode_default_scene(&instanced, &buffer_data).await?;
+        assert_eq!(scene.primitives.len(), 2);
+        assert_eq!(scene.primitives[1].node, copy.value());
+        assert_eq!(
+            scene.primitives[0].decoded.indices,
+            scene.primitives[1].decoded.indices
+        );
+        assert_eq!(
+            scene.primitives[0].decoded.positions,
+            scene.primitives[1].decoded.positions
+        );
+
+        let root: gltf::json::Root = serde_json::from_str(
+            r#"{
+                "asset": { "version": "2.0" },
+                "scenes": [{ "nodes": [0] }],
+                "nodes": [{ "children": [1] }, { "children": [0, 1] }]
+            }"#,
+        )?;
+        let cyclic = gltf::Document::from_json_without_validation(root);
+        let scene = decode_default_scene(&cyclic, &[]).await?;
+        let walked: Vec<_> = scene.nodes.iter().map(|n| (n.index, n.parent)).collect();
+        assert_eq!(walked, [(0, None), (1, Some(0))]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_scene_cameras_and_lights() -> Result<(), Box<dyn std::error::Error>> {
+        let root: gltf::json::Root = serde_json::from_str(
+            r#"{
+                "asset": { "version": "2.0" },
+                "extensionsUsed": ["KHR_lights_punctual"],
+                "extensions": { "KHR_lights_punctual": { "lights": [
+                    { "type": "spot", "name": "key", "color": [1, 0.5, 0.25], "intensity": 20,
+                      "range": 8, "spot": { "innerConeAngle": 0.25, "outerConeAngle": 0.5 } },
+                    { "type": "directional" }
+                ] } },
+                "scenes": [{ "nodes": [0, 2] }],
+                "cameras": [
+                    { "type": "perspective", "name": "main",
+                      "perspective": { "yfov": 0.8, "znear": 0.1, "aspectRatio": 1.5 } },
+                    { "type": "orthographic",
+                      "orthographic": { "xmag": 2, "ymag": 1, "znear": 0.5, "zfar": 10 } }
+                ],
+                "nodes": [
+                    { "camera": 0, "translation": [0, 0, 5], "children": [1] },
+                    { "translation": [1, 0, 0],
+                      "extensions": { "KHR_lights_punctual": { "light": 0 } } },
+                    { "camera": 1, "extensions": { "KHR_lights_punctual": { "light": 1 } } }
+                ]
+            }"#,
+        )?;
+        let document = gltf::Document::from_json_without_validation(root);
+        let scene = decode_default_scene(&document, &[]).await?;
+
+        let cameras: Vec<_> = scene
+            .cameras
+            .iter()
+            .map(|c| (c.node, c.projection))
+            .collect();
+        assert_eq!(
+            cameras,
+            [
+                (
+                    0,
+                    Projection::Perspective {
+                        aspect_ratio: Some(1.5),
+                        yfov: 0.8,
+                        znear: 0.1,
+                        zfar: None,
+                    }
+                ),
+                (
+                    2,
+                    Projection::Orthographic {
+                        xmag: 2.0,
+                        ymag: 1.0,
+                        znear: 0.5,
+                        zfar: 10.0,
+                    }
+                ),
+            ]
+        );
+        assert_eq!(scene.cameras[0].name.as_deref(), Some("main"));
+        assert_eq!(scene.cameras[0].world_transform[3], [0.0, 0.0, 5.0, 1.0]);
+
+        let spot = &scene.lights[0];
+        assert_eq!((spot.node, spot.name.as_deref()), (1, Some("key")));
+        // the light inherits its parent camera node's translation
+        assert_eq!(spot.world_transform[3], [1.0, 0.0, 5.0, 1.0]);
+        assert_eq!(
+            spot.kind,
+            LightKind::Spot {
+                inner_cone_angle: 0.25,
+                outer_cone_angle: 0.5,
+            }
+        );
+        assert_eq!(
+            (spot.color, spot.intensity, spot.range),
+            ([1.0, 0.5, 0.25], 20.0, Some(8.0))
+        );
+        let sun = &scene.lights[1];
+        assert_eq!(
+            (sun.node, sun.kind, sun.intensity),
+            (2, LightKind::Directional, 1.0)
+        );
+        assert_eq!(scene.lights.len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prefer_fallback_requires_optional_extension()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffer_data) = load_glb("examples/test.glb")?;
+        let mesh = doc.meshes().next().ok_or("No meshes found in GLB")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+
+        // test.glb lists the extension as required and has no fallback data, so Draco is used
+        let options = DecodeOptions::new().with_fallback(FallbackPolicy::PreferFallback);
+        let infos = DocumentIndex::build(&doc)?.primitives[0].attr_infos();
+        let decoded =
+            decode_draco_with_options(&prim, &doc, &buffer_data, &infos, &options).await?;
+        assert_eq!(decoded.positions.map(|p| p.len()), Some(5849));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prefer_fallback_reads_checked_accessors() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let options = DecodeOptions::new().with_fallback(FallbackPolicy::PreferFallback);
+        // one triangle stored both ways, the fallback shifted so the two can be told apart
+        let stream = [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let shifted = stream.map(|p| p.map(|x| x + 10.0));
+        let with_fallback = |positions: usize, indices: usize| {
+            let mut bytes = old_sequential_stream(2, 1, &stream, 0);
+            let draco_len = bytes.len();
+            bytes.resize(draco_len.next_multiple_of(4), 0);
+            let offset = bytes.len();
+            bytes.extend(shifted.iter().flatten().flat_map(|v| v.to_le_bytes()));
+            bytes.extend([0u16, 1, 2].iter().flat_map(|i| i.to_le_bytes()));
+            let json = serde_json::json!({
+                "asset": { "version": "2.0" },
+                "extensionsUsed": ["KHR_draco_mesh_compression"],
+                "meshes": [{ "primitives": [{
+                    "attributes": { "POSITION": 0 },
+                    "indices": 1,
+                    "extensions": { "KHR_draco_mesh_compression": {
+                        "bufferView": 0, "attributes": { "POSITION": 0 }
+                    } }
+                }] }],
+                "accessors": [
+                    { "bufferView": 1, "componentType": 5126, "count": positions, "type": "VEC3" },
+                    { "bufferView": 2, "componentType": 5123, "count": indices, "type": "SCALAR" }
+                ],
+                "bufferViews": [
+                    { "buffer": 0, "byteLength": draco_len },
+                    { "buffer": 0, "byteOffset": offset, "byteLength": 36 },
+                    { "buffer": 0, "byteOffset": offset + 36, "byteLength": 6 }
+                ],
+                "buffers": [{ "byteLength": bytes.len() }]
+            });
+            let root = serde_json::from_value(json)?;
+            let document = gltf::Document::from_json_without_validation(root);
+            Ok::<_, serde_json::Error>((document, vec![gltf::buffer::Data(bytes)]))
+        };
+        let (doc, buffers) = with_fallback(3, 3)?;
+        let prim = doc
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let draco = decode_primitive(&prim, &doc, &buffers, &DecodeOptions::new()).await?;
+        assert_eq!(draco.positions.as_deref(), Some(&stream[..]));
+        let plain = decode_primitive(&prim, &doc, &buffers, &options).await?;
+        assert_eq!(plain.positions.as_deref(), Some(&shifted[..]));
+        assert_eq!(plain.indices, draco.indices);
+
+        // fallbacks that don't describe the stream's mesh are rejected on either path
+        for (positions, indices) in [(2, 3), (3, 2)] {
+            let (doc, buffers) = with_fallback(positions, indices)?;
+            let prim = doc
+                .meshes()
+                .next()
+                .and_then(|m| m.primitives().next())
+                .ok_or("no primitive")?;
+            for options in [DecodeOptions::new(), options.clone()] {
+                let err = decode_primitive(&prim, &doc, &buffers, &options).await;
+                assert!(
+                    matches!(err, Err(DracoLoadError::FallbackCountMismatch { .. })),
+                    "{err:?}"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reorder_to_reference() -> Result<(), Box<dyn std::error::Error>> {
+        let mut decoded = decode_test_glb("examples/test.glb").await?;
+
+        // a reference with the vertex order reversed
+        let mut reference = decoded.clone();
+        let n = decoded.positions.as_ref().map_or(0, |p| p.len());
+        reference.positions = reference.positions.map(|p| p.into_iter().rev().collect());
+        for v in reference.texcoords.values_mut() {
+            v.reverse();
+        }
+        for idx in reference.indices.iter_mut() {
+            *idx = (n - 1) as u32 - *idx;
+        }
+
+        reorder_to_reference(&mut decoded, &reference)?;
+        assert_eq!(decoded.positions, reference.positions);
+        assert_eq!(decoded.texcoords, reference.texcoords);
+        // exact duplicate vertices may swap places, so compare what each corner resolves to
+        let (Some(pd), Some(pr)) = (&decoded.positions, &reference.positions) else {
+            return Err("Missing positions attribute".into());
+        };
+        for (a, b) in decoded.indices.iter().zip(&reference.indices) {
+            assert_eq!(pd[*a as usize], pr[*b as usize]);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_primitives_material_ids() -> Result<(), Box<dyn std::error::Error>> {
+        let a = decode_test_glb("examples/test.glb").await?;
+        let mut b = a.clone();
+        b.texcoords.clear();
+
+        let merged = merge_primitives(&[(&a, 3), (&b, 7)])?;
+        let n = a.positions.as_ref().map_or(0, |p| p.len());
+        let tris = a.indices.len() / 3;
+        assert_eq!(merged.primitive.positions.map(|p| p.len()), Some(2 * n));
+        assert_eq!(merged.primitive.texcoords[&0].len(), 2 * n);
+        assert_eq!(
+            merged.primitive.indices[a.indices.len()],
+            a.indices[0] + n as u32
+        );
+        assert_eq!(merged.triangle_materials[tris - 1], 3);
+        assert_eq!(merged.triangle_materials[tris], 7);
+        assert_eq!(merged.vertex_materials.len(), 2 * n);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decode_raw_matches_converted() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffer_data) = load_glb("examples/test.glb")?;
+        let mesh = doc.meshes().next().ok_or("No meshes found in GLB")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+        let infos = DocumentIndex::build(&doc)?.primitives[0].attr_infos();
+
+        let raw = decode_draco_raw(&prim, &doc, &buffer_data, &infos).await?;
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        assert_eq!(raw.indices, decoded.indices);
+
+        let pos = raw
+            .attributes
+            .iter()
+            .find(|a| a.semantic == Some(gltf::Semantic::Positions))
+            .ok_or("Missing positions attribute")?;
+        let flat: Vec<f32> = decoded
+            .positions
+            .iter()
+            .flatten()
+            .flatten()
+            .copied()
+            .collect();
+        assert_eq!(pos.dequantize(), flat);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upload_chunks_respect_budget() -> Result<(), Box<dyn std::error::Error>> {
+        let decoded = decode_test_glb("examples/test.glb").await?;
+
+        let mut positions = 0;
+        for chunk in decoded.upload_chunks(1000) {
+            assert!(chunk.bytes.len() <= 1000);
+            assert_eq!(chunk.offset % 4, 0);
+            if chunk.stream == UploadStream::Positions {
+                assert_eq!(chunk.offset % 12, 0);
+                positions += chunk.bytes.len();
+            }
+        }
+        let expected = decoded.positions.as_ref().map_or(0, |p| p.len() * 12);
+        assert_eq!(positions, expected);
+
+        // below the 12-byte vertex, positions split at the copy alignment
+        let split: Vec<_> = decoded
+            .upload_chunks(8)
+            .filter(|c| c.stream == UploadStream::Positions)
+            .collect();
+        assert!(
+            split
+                .iter()
+                .all(|c| c.bytes.len() <= 8 && c.offset % 4 == 0)
+        );
+        assert_eq!(split.iter().map(|c| c.bytes.len()).sum::<usize>(), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_strided_and_oversized_draco_views() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let view = {
+            let prim = document
+                .meshes()
+                .next()
+                .and_then(|m| m.primitives().next())
+                .ok_or("no primitive")?;
+            draco_extension(&prim)?.buffer_view
+        };
+        let decode = |root: gltf::json::Root| {
+            let document = gltf::Document::from_json_without_validation(root);
+            let prim = document.meshes().next().and_then(|m| m.primitives().next());
+            let prim = prim.expect("primitive kept");
+            parallel::block_on(decode_draco_derived(&prim, &document, &buffers)).map(|_| ())
+        };
+
+        let mut strided = document.clone().into_json();
+        strided.buffer_views[view].byte_stride = Some(gltf::json::buffer::Stride(4));
+        let err = decode(strided).expect_err("strided Draco view");
+        assert!(matches!(err, DracoLoadError::StridedBufferView(v, 4) if v == view));
+        assert!(err.is_recoverable());
+
+        let mut oversized = document.clone().into_json();
+        let bv = &mut oversized.buffer_views[view];
+        let start = bv.byte_offset.map_or(0, |o| o.0 as usize);
+        let length = buffers[0].len() + 1;
+        bv.byte_length = gltf::json::validation::USize64(length as u64);
+        let err = decode(oversized).expect_err("view past the buffer");
+        let expected = (view, start, start + length);
+        assert!(matches!(
+            err,
+            DracoLoadError::BufferViewOutOfRange(v, s, e) if (v, s, e) == expected
+        ));
+        assert!(err.is_recoverable());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_conformance_report() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, _) = load_glb("examples/test.glb")?;
+        let mesh = doc.meshes().next().ok_or("No meshes found in GLB")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+        let mut decoded = decode_test_glb("examples/test.glb").await?;
+
+        let report = check_conformance(&decoded, &prim, mesh.index());
+        report.assert_passed();
+        assert!(serde_json::to_string(&report)?.contains("\"passed\":true"));
+
+        decoded.indices.push(u32::MAX);
+        let report = check_conformance(&decoded, &prim, mesh.index());
+        assert!(!report.passed);
+        assert!(report.failures().any(|c| c.check == CheckKind::IndexRange));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fill_primitive_all_data_types() -> Result<(), Box<dyn std::error::Error>> {
+        use draco_decoder::AttributeDataType::*;
+        use gltf::Semantic;
+
+        // two vertices with components 1, 2, 3, ... encoded in each data type
+        fn encode(dt: AttributeDataType, count: usize) -> Vec<u8> {
+            (1..=count as u32)
+                .flat_map(|v| match dt {
+                    Int8 | UInt8 => vec![v as u8],
+                    Int16 | UInt16 => (v as u16).to_le_bytes().to_vec(),
+                    Int32 | UInt32 => v.to_le_bytes().to_vec(),
+                    Float32 => (v as f32).to_le_bytes().to_vec(),
+                })
+                .collect()
+        }
+
+        fn second_x<const N: usize>(v: &[[f32; N]]) -> (usize, f32) {
+            (v.len(), v[1][0])
+        }
+
+        let semantics = [
+            (Semantic::Positions, 3),
+            (Semantic::Normals, 3),
+            (Semantic::Tangents, 4),
+            (Semantic::TexCoords(0), 2),
+            (Semantic::Colors(0), 3),
+            (Semantic::Colors(1), 4),
+            (Semantic::Joints(0), 4),
+            (Semantic::Weights(0), 4),
+        ];
+        for dt in [Int8, UInt8, Int16, UInt16, Int32, UInt32, Float32] {
+            for (sem, dim) in &semantics {
+                let bytes = encode(dt, 2 * dim);
+                let blk = AttrSlice {
+                    unique_id: 0,
+                    bytes: &bytes,
+                    dim: *dim,
+                    dt,
+                };
+                let map = SemanticMap::from([(0, (sem.clone(), *dim, false))]);
+                let mut p = DecodedPrimitive::default();
+                fill_primitive(&mut p, &[blk], &map)?;
+
+                let normalized = matches!(
+                    sem,
+                    Semantic::Normals
+                        | Semantic::Tangents
+                        | Semantic::Colors(_)
+                        | Semantic::Weights(_)
+                );
+                let first = match dt {
+                    Int8 if normalized => 1.0 / 127.0,
+                    UInt8 if normalized => 1.0 / 255.0,
+                    Int16 if normalized => 1.0 / 32767.0,
+                    UInt16 if normalized => 1.0 / 65535.0,
+                    _ => 1.0,
+                };
+                let got = match sem {
+                    Semantic::Positions => p.positions.as_deref().map(second_x),
+                    Semantic::Normals => p.normals.as_deref().map(second_x),
+                    Semantic::Tangents => p.tangents.as_deref().map(second_x),
+                    Semantic::TexCoords(s) => p.texcoords.get(s).map(|v| second_x(v)),
+                    Semantic::Colors(s) => p.colors.get(s).map(|v| second_x(v)),
+                    Semantic::Joints(s) => p.joints.get(s).map(|v| (v.len(), v[1][0] as f32)),
+                    Semantic::Weights(s) => p.weights.get(s).map(|v| second_x(v)),
+                    Semantic::Extras(_) => None,
+                };
+                let (len, x) = got.ok_or("missing output stream")?;
+                assert_eq!(len, 2, "{dt:?} {sem:?}");
+                let expected = (*dim as f32 + 1.0) * first;
+                assert!((x - expected).abs() < 1e-6, "{dt:?} {sem:?}");
+                if let Semantic::Colors(0) = sem {
+                    assert_eq!(p.colors[&0][0][3], 1.0, "RGB colors get opaque alpha");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_hints_and_recoverability() {
+        let err = DracoLoadError::UnsupportedMode(gltf::mesh::Mode::Points);
+        assert!(err.to_string().contains("Points"));
+        assert!(err.is_recoverable());
+        assert!(err.hint().is_some());
+        assert!(!DracoLoadError::NoScene.is_recoverable());
+        assert!(
+            DracoLoadError::BadExtension
+                .to_string()
+                .contains("attributes")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+        let mut decoded = decode_test_glb("examples/test.glb").await?;
+        let dir = std::env::temp_dir().join(format!("draco-snapshot-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("test.txt");
+
+        // goldens are only written on request
+        assert!(matches!(
+            assert_snapshot(&decoded, &path, 1e-4),
+            Err(SnapshotMismatch::Missing(_))
+        ));
+        assert!(!path.exists());
+        std::fs::write(&path, snapshot(&decoded))?;
+        assert_snapshot(&decoded, &path, 1e-4)?;
+
+        decoded.positions.as_mut().ok_or("No positions")?[0][0] += 1e-5;
+        assert_snapshot(&decoded, &path, 1e-4)?;
+        decoded.positions.as_mut().ok_or("No positions")?[0][0] += 1.0;
+        assert!(matches!(
+            assert_snapshot(&decoded, &path, 1e-4),
+            Err(SnapshotMismatch::Line { .. })
+        ));
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_split_by_grid_keeps_every_triangle() -> Result<(), Box<dyn std::error::Error>> {
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        let positions = decoded.positions.as_ref().ok_or("No positions")?;
+        let cells = decoded.split_by_grid(10.0);
+        assert!(cells.len() > 1);
+
+        let mut triangles = 0;
+        for cell in &cells {
+            let p = &cell.primitive;
+            let cell_positions = p.positions.as_ref().ok_or("No positions")?;
+            let count = cell_positions.len() as u32;
+            assert!(p.indices.iter().all(|&i| i < count));
+            assert!(cell_positions.len() <= positions.len());
+            triangles += p.indices.len() / 3;
+        }
+        assert_eq!(triangles, decoded.indices.len() / 3);
+        assert_eq!(decoded.split_by_grid(0.0).len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_asset_report() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let report = asset_report(&doc, &buffers);
+
+        assert!(!report.primitives.is_empty());
+        assert!(report.primitives.iter().all(|p| p.draco));
+        assert!(report.stored_geometry_bytes() < report.uncompressed_geometry_bytes());
+        assert_eq!(report.images.len(), doc.images().count());
+        assert!(serde_json::to_string(&report)?.contains("\"draco\":true"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decode_parallel_is_ordered() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let index = DocumentIndex::build(&doc)?;
+
+        // the caller's options reach every worker, as with the primitive entry points
+        let options = DecodeOptions::new().with_flip_v(true);
+        let parallel = index.decode_parallel(&buffers, 4, &options);
+        assert_eq!(parallel.results.len(), index.primitives.len());
+        assert!(parallel.warnings().is_empty());
+        for (r, e) in parallel.results.iter().zip(&index.primitives) {
+            assert_eq!((r.mesh, r.primitive), (e.mesh, e.primitive));
+            let prim = doc
+                .meshes()
+                .nth(e.mesh)
+                .and_then(|m| m.primitives().nth(e.primitive))
+                .ok_or("no primitive")?;
+            let expected = decode_primitive(&prim, &doc, &buffers, &options).await?;
+            let decoded = r.decoded.as_ref().map_err(|e| e.to_string())?;
+            assert_eq!(decoded.indices, expected.indices);
+            assert_eq!(decoded.texcoords, expected.texcoords);
+        }
+
+        let mut seen = 0;
+        index.decode_parallel_unordered(&buffers, 2, &options, |_, decoded| {
+            assert!(decoded.is_ok());
+            seen += 1;
+        });
+        assert_eq!(seen, index.primitives.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_slots_are_capped() {
+        use std::future::Future;
+
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        let first = parallel::block_on(limit::acquire(Some(1)));
+        assert!(decodes_in_flight() >= 1);
+
+        let mut second = std::pin::pin!(limit::acquire(Some(1)));
+        assert!(second.as_mut().poll(&mut cx).is_pending());
+        drop(first);
+        // other tests may briefly hold slots of their own
+        while second.as_mut().poll(&mut cx).is_pending() {
+            std::thread::yield_now();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_progressive_decode() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let mesh = doc.meshes().next().ok_or("No meshes found in GLB")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+        let infos = attr_infos_from_primitive(&prim)?;
+        let expected = decode_draco(&prim, &doc, &buffers, &infos).await?;
+
+        let progressive = decode_draco_progressive(&prim, &doc, &buffers, &infos).await?;
+        assert_eq!(progressive.indices(), expected.indices.as_slice());
+        assert_eq!(progressive.positions(), expected.positions.as_deref());
+
+        let full = progressive.await_full().await?;
+        assert_eq!(full.normals, expected.normals);
+        assert_eq!(full.texcoords, expected.texcoords);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_quality_metrics() -> Result<(), Box<dyn std::error::Error>> {
+        let reference = decode_test_glb("examples/test.glb").await?;
+        let same = compare_primitives(&reference, &reference);
+        assert_eq!(same.position_psnr, Some(f64::INFINITY));
+        assert_eq!(same.hausdorff, Some(0.0));
+        assert!(same.normal_max_angle.is_none_or(|a| a < 1e-3));
+
+        let mut shifted = reference.clone();
+        for p in shifted.positions.as_mut().ok_or("No positions")? {
+            p[0] += 0.01;
+        }
+        let m = compare_primitives(&reference, &shifted);
+        assert!((m.position_rmse.ok_or("No rmse")? - 0.01).abs() < 1e-4);
+        assert!(m.hausdorff.ok_or("No hausdorff")? <= 0.0101);
+        assert!(m.position_psnr.ok_or("No psnr")?.is_finite());
+
+        // one reference point: no peak for PSNR, and a far test point mustn't stall the grid
+        let point = DecodedPrimitive {
+            positions: Some(vec![[0.0; 3]]),
+            ..Default::default()
+        };
+        let far = DecodedPrimitive {
+            positions: Some(vec![[1.0e6, 0.0, 0.0]]),
+            ..Default::default()
+        };
+        let m = compare_primitives(&point, &far);
+        assert_eq!(m.position_psnr, None);
+        assert_eq!(m.hausdorff, Some(1.0e6));
+        assert_eq!(
+            compare_primitives(&point, &point).position_psnr,
+            Some(f64::INFINITY)
+        );
+        assert_eq!(hausdorff_distance(&point, &point), Some(0.0));
+        let empty = DecodedPrimitive {
+            positions: Some(Vec::new()),
+            ..Default::default()
+        };
+        assert_eq!(hausdorff_distance(&point, &empty), None);
+        let nan = DecodedPrimitive {
+            positions: Some(vec![[f32::NAN; 3]]),
+            ..Default::default()
+        };
+        assert_eq!(compare_primitives(&point, &nan).position_psnr, None);
+        assert_eq!(hausdorff_distance(&point, &nan), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_uv_and_tangent_conventions() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let mesh = doc.meshes().next().ok_or("No meshes found in GLB")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+        let infos = attr_infos_from_primitive(&prim)?;
+
+        let plain = decode_draco(&prim, &doc, &buffers, &infos).await?;
+        let options = DecodeOptions::new().with_flip_v(true);
+        let flipped = decode_draco_with_options(&prim, &doc, &buffers, &infos, &options).await?;
+        assert!(!plain.texcoords.is_empty());
+        for (set, uvs) in &plain.texcoords {
+            for (a, b) in uvs.iter().zip(&flipped.texcoords[set]) {
+                assert_eq!((a[0], 1.0 - a[1]), (b[0], b[1]));
+            }
+        }
+
+        let mut tangents = DecodedPrimitive {
+            tangents: Some(vec![[1.0, 0.0, 0.0, 1.0]]),
+            ..Default::default()
+        };
+        DecodeOptions::new()
+            .with_flip_tangent_handedness(true)
+            .apply_conventions(&mut tangents);
+        assert_eq!(tangents.tangents, Some(vec![[1.0, 0.0, 0.0, -1.0]]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_profiles() {
+        let web = DecodeOptions::from(DecodeProfile::WebProgressive);
+        assert_eq!(web.max_concurrent_decodes, Some(1));
+        assert!(DecodeProfile::WebProgressive.progressive());
+        assert_eq!(DecodeProfile::WebProgressive.threads(), 1);
+
+        let geo = DecodeProfile::GeospatialF64.options();
+        assert_eq!(geo.fallback, FallbackPolicy::PreferFallback);
+        assert!(geo.accessor_order && DecodeProfile::GeospatialF64.validate());
+        assert!(!DecodeProfile::GameRuntime.validate());
+
+        // presets stay overridable field by field
+        let custom = DecodeProfile::GameRuntime.options().with_flip_v(true);
+        assert!(custom.flip_v);
+    }
+
+    #[tokio::test]
+    async fn test_build_accessors() -> Result<(), Box<dyn std::error::Error>> {
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        let generated = build_accessors(&decoded, 0, 3);
+        assert_eq!(generated.views.len(), generated.accessors.len());
+        assert!(generated.views.iter().all(|v| {
+            v.byte_offset.is_some_and(|o| o.0 % 4 == 0)
+                && v.byte_offset.unwrap().0 + v.byte_length.0 <= generated.bytes.len() as u64
+        }));
+
+        let indices = &generated.accessors[generated.indices.ok_or("no indices")?];
+        assert_eq!(indices.count.0, decoded.indices.len() as u64);
+        assert_eq!(indices.buffer_view.map(|v| v.value()), Some(3));
+
+        let (_, pos) = generated
+            .attributes
+            .iter()
+            .find(|(s, _)| *s == gltf::Semantic::Positions)
+            .ok_or("no POSITION accessor")?;
+        let pos = &generated.accessors[*pos];
+        let positions = decoded.positions.as_ref().ok_or("no positions")?;
+        assert_eq!(pos.count.0, positions.len() as u64);
+        let min: Vec<f32> = serde_json::from_value(pos.min.clone().ok_or("no min")?)?;
+        for k in 0..3 {
+            assert_eq!(
+                min[k],
+                positions.iter().map(|p| p[k]).fold(f32::INFINITY, f32::min)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_vendor_quirks() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let expected = decode_test_glb("examples/test.glb").await?;
+
+        // rewrite the extension the way a legacy exporter would
+        let mut root = document.into_json();
+        let ext = root.meshes[0].primitives[0]
+            .extensions
+            .as_mut()
+            .ok_or("no extensions")?;
+        let draco = ext
+            .others
+            .remove("KHR_draco_mesh_compression")
+            .ok_or("not Draco")?;
+        let view = draco["bufferView"].as_u64().ok_or("no bufferView")?;
+        let attributes: serde_json::Map<String, serde_json::Value> = draco["attributes"]
+            .as_object()
+            .ok_or("no attributes")?
+            .iter()
+            .map(|(k, id)| (k.to_lowercase().replace('_', ""), id.to_string().into()))
+            .collect();
+        let legacy = serde_json::json!({
+            "buffer_view": format!("bufferView_{view}"),
+            "attributes": attributes,
+        });
+        ext.others
+            .insert("VENDOR_draco_mesh_compression".into(), legacy);
+        let document = gltf::Document::from_json_without_validation(root);
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+
+        assert!(matches!(
+            draco_extension_with_quirks(&prim, &VendorQuirks::default()),
+            Err(DracoLoadError::NotDraco)
+        ));
+        let quirks = VendorQuirks::tolerant().with_extension_name("VENDOR_draco_mesh_compression");
+        let infos = attr_infos_with_quirks(&prim, &quirks)?;
+        let options = DecodeOptions::new().with_quirks(quirks);
+        let decoded =
+            decode_draco_with_options(&prim, &document, &buffers, &infos, &options).await?;
+        assert_eq!(decoded.positions, expected.positions);
+        assert_eq!(decoded.texcoords, expected.texcoords);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reprojection_hook() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let expected = decode_test_glb("examples/test.glb").await?;
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let infos = attr_infos_from_primitive(&prim)?;
+
+        fn swap_to_z_up(p: &mut [f64; 3]) {
+            *p = [p[0] + 100.0, -p[2], p[1]];
+        }
+        let options = DecodeOptions::new().with_reprojection(swap_to_z_up);
+        let decoded =
+            decode_draco_with_options(&prim, &document, &buffers, &infos, &options).await?;
+        let (got, want) = (
+            decoded.positions.ok_or("no positions")?,
+            expected.positions.ok_or("no positions")?,
+        );
+        for (g, w) in got.iter().zip(&want) {
+            assert_eq!(*g, [(w[0] as f64 + 100.0) as f32, -w[2], w[1]]);
+        }
+        assert_eq!(decoded.texcoords, expected.texcoords);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_quantization_advice() -> Result<(), Box<dyn std::error::Error>> {
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        assert_eq!(bits_for(1023.0, 0.5), 10);
+        assert_eq!(bits_for(1024.0, 0.5), 11);
+
+        let target = ErrorTarget::ScreenSpace {
+            pixels: 1.0,
+            distance: 10.0,
+            fov_y: std::f64::consts::FRAC_PI_2,
+            viewport_height: 1000.0,
+        };
+        assert!((target.model_units() - 0.02).abs() < 1e-12);
+
+        let advice = recommend_quantization(&decoded, &AdvisorTargets::new(target));
+        let bits = advice.bits["POSITION"];
+        assert!((1..=MAX_QUANTIZATION_BITS).contains(&bits));
+        assert!(advice.bits.contains_key("TEXCOORD_0"));
+        assert!(!advice.bits.contains_key("NORMAL"));
+
+        // a looser budget never needs more bits
+        let loose_targets = AdvisorTargets::new(ErrorTarget::ModelUnits(1.0));
+        let loose = recommend_quantization(&decoded, &loose_targets);
+        assert!(loose.bits["POSITION"] <= bits);
+        assert!(advice.current_position_bits.is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_content_hashes() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        let hashes = content_hashes(&decoded);
+        assert!(hashes.streams.contains_key("POSITION"));
+        assert!(hashes.streams.contains_key("TEXCOORD_0"));
+
+        let report = asset_report_with_hashes(&document, &buffers).await;
+        assert_eq!(report.primitives[0].hashes.as_ref(), Some(&hashes));
+        let plain = asset_report(&document, &buffers);
+        assert!(plain.primitives[0].hashes.is_none());
+
+        let mut moved = decoded.clone();
+        moved.positions.as_mut().ok_or("no positions")?[0][0] += 1.0;
+        let moved = content_hashes(&moved);
+        assert_ne!(moved.primitive, hashes.primitive);
+        assert_eq!(moved.streams["TEXCOORD_0"], hashes.streams["TEXCOORD_0"]);
+
+        let zero = DecodedPrimitive {
+            positions: Some(vec![[0.0, 0.0, 0.0]]),
+            ..Default::default()
+        };
+        let negative_zero = DecodedPrimitive {
+            positions: Some(vec![[-0.0, 0.0, 0.0]]),
+            ..Default::default()
+        };
+        assert_eq!(content_hashes(&zero), content_hashes(&negative_zero));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sliced_decode() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let infos = attr_infos_from_primitive(&prim)?;
+        let expected = prozes_in(&prim, &document, &buffers, &infos, &VendorQuirks::default())?;
+        let vertex_count = expected.vertex_count;
+
+        let options = DecodeOptions::new();
+        let mut sliced = decode_draco_sliced(&prim, &document, &buffers, &infos, &options)?
+            .with_chunk_vertices(7);
+        let mut steps = 0;
+        let decoded = loop {
+            steps += 1;
+            if let std::task::Poll::Ready(result) = sliced.poll_step(std::time::Duration::ZERO) {
+                break result?;
+            }
+        };
+        // one step for the decode, then one per chunk
+        assert_eq!(steps, 1 + vertex_count.div_ceil(7));
+
+        let whole = parallel::block_on(decode_draco(&prim, &document, &buffers, &infos))?;
+        assert_eq!(decoded.indices, whole.indices);
+        assert_eq!(decoded.positions, whole.positions);
+        assert_eq!(decoded.texcoords, whole.texcoords);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_abort_handle() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let index = DocumentIndex::build(&doc)?;
+
+        let abort = AbortHandle::new();
+        let live = index.decode_parallel_abortable(&buffers, 2, &DecodeOptions::new(), &abort);
+        assert!(live.results.iter().all(|r| r.decoded.is_ok()));
+
+        abort.clone().abort();
+        let aborted = index.decode_parallel_abortable(&buffers, 2, &DecodeOptions::new(), &abort);
+        assert_eq!(aborted.results.len(), index.primitives.len());
+        assert!(
+            aborted
+                .results
+                .iter()
+                .all(|r| matches!(r.decoded, Err(DracoLoadError::Aborted)))
+        );
+
+        // a future stuck waiting is woken and dropped when another thread aborts
+        let stuck = AbortHandle::new();
+        let remote = stuck.clone();
+        let waiter = std::thread::spawn(move || {
+            parallel::block_on(abortable(&stuck, std::future::pending::<Result<(), _>>()))
+        });
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        remote.abort();
+        let result = waiter.join().map_err(|_| "waiter panicked")?;
+        assert!(matches!(result, Err(DracoLoadError::Aborted)));
+        assert!(!DracoLoadError::Aborted.is_recoverable());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_color_dithering() {
+        let flat = DecodedPrimitive {
+            colors: [(0, vec![[0.3, 0.3, 0.3, 1.0]; 1024])].into(),
+            ..Default::default()
+        };
+        let rounded = flat.colors_u8(0, ColorDither::None).unwrap();
+        assert!(rounded.iter().all(|c| *c == [77, 77, 77, 255]));
+
+        // 0.3 * 255 = 76.5: dithered output mixes 76 and 77 and keeps the mean
+        for dither in [ColorDither::Ordered, ColorDither::BlueNoise] {
+            let out = flat.colors_u8(0, dither).unwrap();
+            let mean = out.iter().map(|c| c[0] as f64).sum::<f64>() / out.len() as f64;
+            assert!((mean - 76.5).abs() < 0.05, "{dither:?}: {mean}");
+            assert!(out.iter().all(|c| (76..=77).contains(&c[0]) && c[3] == 255));
+        }
+        assert!(flat.colors_u8(1, ColorDither::Ordered).is_none());
+    }
+
+    #[test]
+    fn test_validate_skinning() -> Result<(), Box<dyn std::error::Error>> {
+        let root: gltf::json::Root = serde_json::from_str(
+            r#"{
+                "asset": { "version": "2.0" },
+                "nodes": [{ "mesh": 0, "skin": 0 }, {}, {}, { "mesh": 0 }],
+                "skins": [{ "joints": [1, 2] }],
+                "meshes": [{ "primitives": [{ "attributes": {} }] }]
+            }"#,
+        )?;
+        let document = gltf::Document::from_json_without_validation(root);
+        let decoded = DecodedPrimitive {
+            joints: [(0, vec![[0, 1, 0, 0], [0, 2, 0, 0], [1, 1, 1, 1]])].into(),
+            ..Default::default()
+        };
+        let instance = |node| ScenePrimitive {
+            node,
+            mesh: 0,
+            mesh_name: None,
+            primitive: 0,
+            material: None,
+            world_transform: Default::default(),
+            decoded: decoded.clone(),
+        };
+        let scene = DecodedScene {
+            primitives: vec![instance(0), instance(3)],
+            ..Default::default()
+        };
+
+        let violations = validate_skinning(&scene, &document);
+        // one bad vertex on the skinned node, every vertex on the node without a skin
+        assert_eq!(violations.len(), 4);
+        assert_eq!(
+            (violations[0].node, violations[0].vertex, violations[0].skin),
+            (0, 1, Some(0))
+        );
+        assert_eq!(violations[0].joint_count, 2);
+        assert!(
+            violations[1..]
+                .iter()
+                .all(|v| v.node == 3 && v.skin.is_none())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shadow_proxy() -> Result<(), Box<dyn std::error::Error>> {
+        // a quad split along its diagonal with unshared vertices, as after a UV seam
+        let quad = DecodedPrimitive {
+            indices: (0..6).collect(),
+            positions: Some(vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+            ]),
+            texcoords: [(0, vec![[0.0; 2]; 6])].into(),
+            ..Default::default()
+        };
+        let welded = quad.build_shadow_proxy(&ShadowProxyOptions::default());
+        assert_eq!(welded.positions.as_ref().map(Vec::len), Some(4));
+        assert_eq!(welded.indices.len(), 6);
+        assert!(welded.texcoords.is_empty());
+
+        let closed = quad.build_shadow_proxy(&ShadowProxyOptions::default().with_fill_holes(true));
+        assert_eq!(closed.indices.len(), 12);
+        let edges: std::collections::HashSet<(u32, u32)> = closed
+            .indices
+            .chunks_exact(3)
+            .flat_map(|t| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])])
+            .collect();
+        assert!(edges.iter().all(|&(a, b)| edges.contains(&(b, a))));
+
+        let collapsed =
+            quad.build_shadow_proxy(&ShadowProxyOptions::default().with_cluster_size(10.0));
+        assert!(collapsed.indices.is_empty());
+
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        let proxy = decoded.build_shadow_proxy(&ShadowProxyOptions::default());
+        let count = proxy.positions.as_ref().map_or(0, Vec::len);
+        assert!(count <= decoded.positions.as_ref().map_or(0, Vec::len));
+        assert!(proxy.indices.iter().all(|&i| (i as usize) < count));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_texel_density() {
+        // two 2x2 quads side by side; the second squeezes its UVs to half width
+        let d = DecodedPrimitive {
+            indices: vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7],
+            positions: Some(vec![
+                [0.0, 0.0, 0.0],
+                [2.0, 0.0, 0.0],
+                [2.0, 2.0, 0.0],
+                [0.0, 2.0, 0.0],
+                [2.0, 0.0, 0.0],
+                [4.0, 0.0, 0.0],
+                [4.0, 2.0, 0.0],
+                [2.0, 2.0, 0.0],
+            ]),
+            texcoords: [(
+                0,
+                vec![
+                    [0.0, 0.0],
+                    [1.0, 0.0],
+                    [1.0, 1.0],
+                    [0.0, 1.0],
+                    [0.0, 0.0],
+                    [0.5, 0.0],
+                    [0.5, 1.0],
+                    [0.0, 1.0],
+                ],
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let analysis = analyze_texel_density(&d, 0, [1024, 1024]).unwrap();
+        assert_eq!(analysis.triangles.len(), 4);
+        assert!((analysis.triangles[0].density - 512.0).abs() < 1e-9);
+        assert!((analysis.triangles[0].stretch - 1.0).abs() < 1e-9);
+        assert!((analysis.triangles[2].stretch - 2.0).abs() < 1e-9);
+        assert_eq!(analysis.outliers(4.0, 1.5), vec![2, 3]);
+        assert!(analyze_texel_density(&d, 1, [1024, 1024]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_strip_draco() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let expected = decode_test_glb("examples/test.glb").await?;
+
+        let untouched = strip_draco(&document, &buffers, |_, _| false).await?;
+        assert_eq!(untouched.buffers[0].len(), buffers[0].len());
+
+        let stripped =
+            strip_draco(&document, &buffers, |mesh, prim| (mesh, prim) == (0, 0)).await?;
+        let used = &stripped.root.extensions_used;
+        assert!(!used.iter().any(|e| e == "KHR_draco_mesh_compression"));
+        let glb = stripped.to_glb()?;
+
+        let reloaded = gltf::Gltf::from_slice_without_validation(&glb)?;
+        let bufs = gltf::import_buffers(&reloaded.document, None, reloaded.blob)?;
+        let doc = reloaded.document;
+        assert!(doc.accessors().all(|a| a.view().is_some()));
+        let prim = doc
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        assert!(prim.extension_value("KHR_draco_mesh_compression").is_none());
+        let plain = read_fallback(&prim, &bufs)?;
+        assert_eq!(plain.indices, expected.indices);
+        assert_eq!(plain.positions, expected.positions);
+        assert_eq!(plain.texcoords, expected.texcoords);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_output_stores() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let stripped = strip_draco(&document, &buffers, |_, _| true).await?;
+
+        let memory = MemoryStore::new();
+        stripped.write_glb(&memory, "out/test.glb").await?;
+        assert_eq!(memory.keys(), vec!["out/test.glb".to_string()]);
+        assert_eq!(memory.get("out/test.glb"), Some(stripped.to_glb()?));
+
+        let dir = std::env::temp_dir().join(format!("draco-store-{}", std::process::id()));
+        let fs = FsStore::new(&dir);
+        stripped.write_glb(&fs, "nested/test.glb").await?;
+        let written = std::fs::read(dir.join("nested/test.glb"))?;
+        assert_eq!(written, stripped.to_glb()?);
+        assert!(fs.path("../escape.glb").is_err());
+        assert!(fs.put("/abs.glb", Vec::new()).await.is_err());
+        std::fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_init_decoder() -> Result<(), Box<dyn std::error::Error>> {
+        init_decoder().await?;
+        assert!(decoder_ready());
+        init_decoder().await?;
+
+        let health = decoder_health();
+        assert!(health.ready);
+        let json = serde_json::to_value(health)?;
+        assert_eq!(json["ready"], true);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bench_report() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let bench = bench_document("test.glb", &document, &buffers, 2)?;
+        assert_eq!((bench.iterations, bench.primitives), (2, 1));
+        assert!(bench.vertices > 0 && bench.stored_bytes > 0);
+        assert!(bench.min_ms <= bench.mean_ms);
+
+        let report = BenchReport {
+            fixtures: vec![bench.clone()],
+        };
+        let json = serde_json::to_string(&report)?;
+        let parsed: BenchReport = serde_json::from_str(&json)?;
+        assert_eq!(parsed.fixtures[0].name, "test.glb");
+        assert!(report.regressions(&parsed, 0.1).is_empty());
+
+        let faster = BenchReport {
+            fixtures: vec![FixtureBench {
+                mean_ms: bench.mean_ms / 4.0,
+                ..bench
+            }],
+        };
+        assert_eq!(report.regressions(&faster, 0.1).len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_endian_export() -> Result<(), Box<dyn std::error::Error>> {
+        let d = decode_test_glb("examples/test.glb").await?;
+        let little = d.export_endian(Endian::Little);
+        let big = d.export_endian(Endian::Big);
+        let streams = &big.manifest.streams;
+        let names: Vec<&str> = streams.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["INDICES", "POSITION", "TEXCOORD_0"]);
+
+        let positions = d.positions.as_ref().unwrap();
+        let entry = &big.manifest.streams[1];
+        assert_eq!((entry.count, entry.components), (positions.len(), 3));
+        assert_eq!(&big.streams[1][..4], positions[0][0].to_be_bytes());
+        assert_eq!(little.streams[1], bytemuck::cast_slice::<_, u8>(positions));
+
+        let store = MemoryStore::new();
+        big.write(&store, "export").await?;
+        let manifest: ExportManifest =
+            serde_json::from_slice(&store.get("export/manifest.json").unwrap())?;
+        assert_eq!(manifest, big.manifest);
+        let indices = store.get("export/INDICES.bin");
+        assert_eq!(indices.as_ref(), Some(&big.streams[0]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dump_debug() -> Result<(), Box<dyn std::error::Error>> {
+        let d = decode_test_glb("examples/test.glb").await?;
+        let dir = std::env::temp_dir().join(format!("draco-dump-{}", std::process::id()));
+        d.dump_debug(&dir)?;
+
+        let summary = std::fs::read(dir.join("summary.json"))?;
+        let summary: serde_json::Value = serde_json::from_slice(&summary)?;
+        assert_eq!(summary["triangles"], d.indices.len() / 3);
+        assert_eq!(summary["hashes"]["primitive"], content_hashes(&d).primitive);
+        assert!(summary["bounds"]["POSITION"]["min"].is_array());
+        let positions: &[u8] = bytemuck::cast_slice(d.positions.as_ref().unwrap());
+        assert_eq!(std::fs::read(dir.join("POSITION.bin"))?, positions);
+        std::fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_preview_decode() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let mesh = doc.meshes().next().ok_or("No meshes found in GLB")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+        let infos = attr_infos_from_primitive(&prim)?;
+        let full = decode_draco(&prim, &doc, &buffers, &infos).await?;
+        let options = DecodeOptions::default();
+
+        let first = Preview::FirstTriangles(2);
+        let head = decode_draco_preview(&prim, &doc, &buffers, &infos, first, &options).await?;
+        assert_eq!(head.indices.len(), 6);
+        let expected = gather_vertices(&full, &full.indices[..6]);
+        assert_eq!(head.indices, expected.indices);
+        assert_eq!(head.positions, expected.positions);
+        assert_eq!(head.texcoords, expected.texcoords);
+
+        let sample = Preview::Subsample(3);
+        let thumb = decode_draco_preview(&prim, &doc, &buffers, &infos, sample, &options).await?;
+        assert!(thumb.indices.len() <= 9 && !thumb.indices.is_empty());
+        let vertices = thumb.positions.as_ref().map_or(0, Vec::len);
+        assert!(vertices <= thumb.indices.len());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_vertex_pools() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        assert!(detect_shared_vertices(&document).is_empty());
+        let expected = decode_test_glb("examples/test.glb").await?;
+
+        // the same Draco stream referenced by two primitives decodes once
+        let mut root = document.clone().into_json();
+        let split = root.meshes[0].primitives[0].clone();
+        root.meshes[0].primitives.push(split);
+        let doubled = gltf::Document::from_json_without_validation(root);
+        assert_eq!(detect_shared_vertices(&doubled), vec![vec![(0, 0), (0, 1)]]);
+        let pools = decode_vertex_pools(&doubled, &buffers).await?;
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].vertices.positions, expected.positions);
+        assert_eq!(pools[0].members[0].indices, pools[0].members[1].indices);
+
+        // plain primitives sharing attributes with different index ranges
+        let stripped = strip_draco(&doubled, &buffers, |_, _| true).await?;
+        let mut root = stripped.root.clone();
+        let indices = root.meshes[0].primitives[0].indices.ok_or("no indices")?;
+        let mut head = root.accessors[indices.value()].clone();
+        head.count = gltf::json::validation::USize64(3);
+        root.accessors.push(head);
+        let head = gltf::json::Index::new(root.accessors.len() as u32 - 1);
+        root.meshes[0].primitives[1].indices = Some(head);
+        // and a member without indices, which draws the shared vertices in order
+        let mut unindexed = root.meshes[0].primitives[1].clone();
+        unindexed.indices = None;
+        root.meshes[0].primitives.push(unindexed);
+        let plain = gltf::Document::from_json_without_validation(root);
+        let plain_buffers: Vec<gltf::buffer::Data> = stripped
+            .buffers
+            .into_iter()
+            .map(gltf::buffer::Data)
+            .collect();
+        let pools = decode_vertex_pools(&plain, &plain_buffers).await?;
+        let members = &pools[0].members;
+        assert_eq!(members[0].indices, 0..expected.indices.len());
+        let tail = expected.indices.len()..expected.indices.len() + 3;
+        assert_eq!(members[1].indices, tail);
+        let pooled = &pools[0].vertices.indices;
+        assert_eq!(pooled[tail.clone()], expected.indices[..3]);
+        let vertex_count = expected.positions.as_ref().map_or(0, Vec::len);
+        assert_eq!(members[2].indices, tail.end..tail.end + vertex_count);
+        assert!(
+            pooled[tail.end..]
+                .iter()
+                .copied()
+                .eq(0..vertex_count as u32)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_repair_manifold() {
+        // a tetrahedron with a split vertex, one face flipped, one duplicated, one missing
+        let positions = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 0.0],
+        ];
+        let broken = DecodedPrimitive {
+            indices: vec![0, 2, 1, 0, 4, 3, 0, 1, 3, 2, 3, 0, 1, 1, 2],
+            positions: Some(positions),
+            ..Default::default()
+        };
+        assert!(!broken.manifold_stats().is_manifold());
+
+        let (repaired, report) = broken.repair_manifold(&RepairOptions::default());
+        assert_eq!(report.welded_vertices, 1);
+        assert_eq!(report.degenerate_triangles, 1);
+        assert_eq!(report.duplicate_triangles, 1);
+        assert_eq!(report.flipped_triangles, 1);
+        assert_eq!(report.holes_filled, 1);
+        assert!(report.remaining.is_manifold());
+        assert_eq!(repaired.indices.len(), 12);
+        assert_eq!(repaired.positions.as_ref().map(Vec::len), Some(4));
+
+        let open = RepairOptions::default().with_max_hole_edges(0);
+        let (_, report) = broken.repair_manifold(&open);
+        assert_eq!(report.remaining.boundary_edges, 3);
+    }
+
+    #[test]
+    fn test_export_stl() -> Result<(), Box<dyn std::error::Error>> {
+        let tetra = DecodedPrimitive {
+            indices: vec![0, 2, 1, 0, 1, 3, 0, 3, 2, 1, 2, 3],
+            positions: Some(vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0],
+            ]),
+            ..Default::default()
+        };
+        let stl = tetra.to_stl(LengthUnit::Millimeters, false)?;
+        assert_eq!(stl.len(), 84 + 4 * 50);
+        assert_eq!(u32::from_le_bytes(stl[80..84].try_into()?), 4);
+        let facet = |i: usize, k: usize| {
+            let at = 84 + i * 50 + k * 4;
+            f32::from_le_bytes(stl[at..at + 4].try_into().unwrap())
+        };
+        // first facet lies in z = 0 and faces down; vertex 1 is scaled to 1000 mm
+        assert_eq!([facet(0, 0), facet(0, 1), facet(0, 2)], [0.0, 0.0, -1.0]);
+        assert_eq!(facet(0, 9), 1000.0);
+
+        let open = DecodedPrimitive {
+            indices: tetra.indices[..9].to_vec(),
+            ..tetra.clone()
+        };
+        let err = open.to_stl(LengthUnit::Meters, false).unwrap_err();
+        assert!(matches!(err, StlError::NotManifold(s) if s.boundary_edges == 3));
+        assert_eq!(open.to_stl(LengthUnit::Meters, true)?.len(), 84 + 3 * 50);
+
+        let path = std::env::temp_dir().join(format!("draco-{}.stl", std::process::id()));
+        tetra.export_stl_for_printing(&path, LengthUnit::Inches)?;
+        assert_eq!(std::fs::metadata(&path)?.len(), stl.len() as u64);
+        std::fs::remove_file(&path)?;
+
+        let store = MemoryStore::new();
+        block_on(tetra.write_stl_for_printing(&store, "print/tetra.stl", LengthUnit::Millimeters))?;
+        assert_eq!(store.get("print/tetra.stl"), Some(stl));
+        let result = block_on(open.write_stl_for_printing(&store, "open.stl", LengthUnit::Meters));
+        assert!(matches!(result, Err(StlError::NotManifold(_))));
+
+        // no triangles is closed by vacuity, but still nothing to print
+        let empty = DecodedPrimitive {
+            indices: Vec::new(),
+            ..tetra.clone()
+        };
+        for force in [false, true] {
+            let result = empty.to_stl(LengthUnit::Meters, force);
+            assert!(matches!(result, Err(StlError::Empty)));
+        }
+        assert_eq!(store.keys(), ["print/tetra.stl"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decompress_files() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = std::env::temp_dir().join(format!("draco-batch-{}", std::process::id()));
+        let jobs = vec![
+            ("examples/test.glb".into(), "a/test.glb".to_string()),
+            ("examples/missing.glb".into(), "b/missing.glb".to_string()),
+            ("examples/test.glb".into(), "test.glb".to_string()),
+            // a.gltf and a.glb both becoming a.glb
+            ("examples/other.gltf".into(), "test.glb".to_string()),
+        ];
+        let store = MemoryStore::new();
+        let report = decompress_files(&jobs, &store, 2);
+        assert_eq!(report.files.len(), 4);
+        assert_eq!(report.failed().count(), 2);
+        assert!(report.files[1].error.is_some());
+        let collision = report.files[3].error.as_deref().unwrap_or_default();
+        assert!(collision.contains("examples/test.glb"), "{collision}");
+        assert_eq!(report.files[0].primitives, 1);
+        assert_eq!(store.keys(), ["a/test.glb", "test.glb"]);
+        let glb = store.get("a/test.glb").ok_or("not written")?;
+        assert_eq!(report.files[0].output_bytes, glb.len() as u64);
+
+        let gltf = gltf::Gltf::from_slice_without_validation(&glb)?;
+        let prim = gltf.document.meshes().flat_map(|m| m.primitives()).next();
+        let prim = prim.ok_or("no primitive")?;
+        assert!(prim.extension_value("KHR_draco_mesh_compression").is_none());
+
+        // external buffers resolve like everywhere else: backslashes, percent-encoding
+        let (document, buffers) = non_indexed_triangle()?;
+        let mut root = document.into_json();
+        root.buffers[0].uri = Some(r"bin\mesh%20data.bin".into());
+        std::fs::create_dir_all(dir.join("src/bin"))?;
+        std::fs::write(dir.join("src/bin/mesh data.bin"), &*buffers[0])?;
+        std::fs::write(dir.join("src/tri.gltf"), root.to_string_pretty()?)?;
+        let jobs = vec![(dir.join("src/tri.gltf"), "tri.glb".to_string())];
+        let report = decompress_files(&jobs, &FsStore::new(dir.join("out")), 1);
+        assert!(
+            report.files[0].error.is_none(),
+            "{:?}",
+            report.files[0].error
+        );
+        assert!(dir.join("out/tri.glb").is_file());
+        std::fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_patch_primitive() -> Result<(), Box<dyn std::error::Error>> {
+        let glb = std::fs::read("examples/test.glb")?;
+        let mut edited = decode_test_glb("examples/test.glb").await?;
+        for p in edited.positions.iter_mut().flatten() {
+            p[1] += 1.0;
+        }
+        edited.texcoords.clear();
+
+        let patched = patch_primitive(&glb, 0, 0, &edited)?;
+        let gltf = gltf::Gltf::from_slice_without_validation(&patched)?;
+        let buffers = gltf::import_buffers(&gltf.document, None, gltf.blob)?;
+        let prim = gltf.document.meshes().flat_map(|m| m.primitives()).next();
+        let prim = prim.ok_or("no primitive")?;
+        assert!(prim.extension_value("KHR_draco_mesh_compression").is_none());
+        assert!(prim.get(&gltf::Semantic::TexCoords(0)).is_none());
+        let read = read_fallback(&prim, &buffers)?;
+        assert_eq!(read.positions, edited.positions);
+        assert_eq!(read.indices, edited.indices);
+        assert_eq!(gltf.document.images().len(), 1);
+
+        let missing = patch_primitive(&glb, 0, 5, &edited);
+        assert!(matches!(missing, Err(DracoLoadError::NotIndexed(0, 5))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_vertex_deltas() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let mesh = doc.meshes().next().ok_or("No meshes found in GLB")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+        let infos = attr_infos_from_primitive(&prim)?;
+        let base = decode_draco(&prim, &doc, &buffers, &infos).await?;
+        let base_positions = base.positions.as_ref().ok_or("no positions")?;
+
+        let frame = vec![[0.0, 0.5, 0.0]; base_positions.len()];
+        let options = DecodeOptions::new().with_deltas(VertexDeltas::new(frame));
+        let deformed = decode_draco_with_options(&prim, &doc, &buffers, &infos, &options).await?;
+        let deformed_positions = deformed.positions.as_ref().ok_or("no positions")?;
+        for (a, b) in base_positions.iter().zip(deformed_positions) {
+            assert_eq!([a[0], a[1] + 0.5, a[2]], *b);
+        }
+        assert_eq!(deformed.indices, base.indices);
+
+        let short = DecodeOptions::new().with_deltas(VertexDeltas::new(vec![[0.0; 3]; 3]));
+        let err = decode_draco_with_options(&prim, &doc, &buffers, &infos, &short).await;
+        assert!(matches!(err, Err(DracoLoadError::DeltaCountMismatch(_, 3))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stable_ordering() -> Result<(), Box<dyn std::error::Error>> {
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        let mut canonical = decoded.clone();
+        canonical.canonicalize();
+        assert_eq!(content_hashes(&canonical).streams.len(), 3);
+        assert_eq!(canonical.indices.len(), decoded.indices.len());
+
+        // a different vertex and triangle order of the same mesh lands in the same place
+        let count = decoded.positions.as_ref().map_or(0, Vec::len);
+        let perm: Vec<usize> = (0..count).map(|i| (i * 7919) % count).collect();
+        let mut shuffled = decoded.clone();
+        apply_permutation(&mut shuffled, &perm);
+        let mut triangles: Vec<&[u32]> = shuffled.indices.chunks(3).collect();
+        triangles.reverse();
+        shuffled.indices = triangles.concat();
+        shuffled.canonicalize();
+        assert_eq!(shuffled.positions, canonical.positions);
+        assert_eq!(shuffled.texcoords, canonical.texcoords);
+        assert_eq!(shuffled.indices, canonical.indices);
+
+        // pinned so an accidental change to the canonical order fails loudly
+        let hashes = content_hashes(&canonical);
+        assert_eq!(hashes.streams["INDICES"], 0x82e9_1319_f1a6_3da1);
+
+        let options = DecodeOptions::new().with_ordering(StableOrdering::Canonical);
+        let mut again = decoded.clone();
+        options.apply_conventions(&mut again);
+        assert_eq!(again.indices, canonical.indices);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_canonical_order_across_encodings() -> Result<(), Box<dyn std::error::Error>> {
+        // one grid mesh encoded four ways: the decoder emits a different vertex order for
+        // each traversal, the canonical order is the same
+        let mut raw_orders = Vec::new();
+        let mut canonical: Option<DecodedPrimitive> = None;
+        for encoding in ["sequential", "edgebreaker", "valence", "predictive"] {
+            let bytes = std::fs::read(format!("examples/streams/grid_{encoding}.drc"))?;
+            let infos = stream_attr_infos(&bytes).ok_or(encoding)?;
+            let raw = decode_with_config(&bytes, &decode_config(25, 96, &infos)).await?;
+            let floats = |block: &AttrSlice| as_f32_components(block.bytes, map_draco_dt(9), false);
+            let blocks = attr_slices(&raw, 96 * 2, 25, &infos);
+            let mut d = DecodedPrimitive {
+                indices: get_indices(&raw, 96 * 2, gltf::accessor::DataType::U16)?,
+                positions: Some(
+                    floats(&blocks[0])
+                        .chunks(3)
+                        .map(|c| [c[0], c[1], c[2]])
+                        .collect(),
+                ),
+                texcoords: [(
+                    0,
+                    floats(&blocks[1]).chunks(2).map(|c| [c[0], c[1]]).collect(),
+                )]
+                .into(),
+                normals: Some(
+                    floats(&blocks[2])
+                        .chunks(3)
+                        .map(|c| [c[0], c[1], c[2]])
+                        .collect(),
+                ),
+                ..Default::default()
+            };
+            raw_orders.push(d.positions.clone());
+            d.canonicalize();
+            match &canonical {
+                None => canonical = Some(d),
+                Some(first) => {
+                    assert_eq!(d.positions, first.positions, "{encoding}");
+                    assert_eq!(d.normals, first.normals, "{encoding}");
+                    assert_eq!(d.texcoords, first.texcoords, "{encoding}");
+                    assert_eq!(d.indices, first.indices, "{encoding}");
+                }
+            }
+        }
+        assert_ne!(raw_orders[0], raw_orders[1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_uri_resolver() -> Result<(), Box<dyn std::error::Error>> {
+        let open = UriResolver::new("assets");
+        let sandbox = UriResolver::new("assets").sandboxed(true);
+        let base = std::path::Path::new("assets");
+        let cases = [
+            ("mesh%20data.bin", "mesh data.bin"),
+            ("caf%C3%A9/b.bin", "café/b.bin"),
+            ("日本/c.bin", "日本/c.bin"),
+            (r"sub\dir\d.bin", "sub/dir/d.bin"),
+            ("a/../e.bin", "e.bin"),
+            ("file:f.bin", "f.bin"),
+        ];
+        for (uri, path) in cases {
+            assert_eq!(sandbox.resolve(uri)?, base.join(path));
+        }
+
+        let escapes = ["../x.bin", "a/../../x.bin", "%2E%2E/x.bin", "/etc/passwd"];
+        for uri in escapes.into_iter().chain([r"C:\x.bin"]) {
+            let resolved = sandbox.resolve(uri);
+            assert!(matches!(resolved, Err(DracoLoadError::BadUri(_))), "{uri}");
+        }
+        assert!(sandbox.resolve("file:///etc/passwd").is_err());
+        assert!(open.resolve("https://example.com/a.bin").is_err());
+        assert!(open.resolve("bad%ZZ.bin").is_err());
+        assert_eq!(open.resolve("../../a/x.bin")?, base.join("../../a/x.bin"));
+        let drive = open.resolve("file:///C:/Assets/g.bin")?;
+        assert_eq!(drive, std::path::PathBuf::from("C:/Assets/g.bin"));
+
+        let root: gltf::json::Root = serde_json::from_str(
+            r#"{
+                "asset": { "version": "2.0" },
+                "buffers": [{ "byteLength": 5, "uri": "data:application/octet-stream;base64,aGVsbG8=" }]
+            }"#,
+        )?;
+        let document = gltf::Document::from_json_without_validation(root);
+        let buffers = sandbox.load_buffers(&document, None)?;
+        assert_eq!(&buffers[0][..5], b"hello");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compress_in_memory() -> Result<(), Box<dyn std::error::Error>> {
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        let compact = decoded.compress_in_memory();
+        let positions = decoded.positions.as_ref().ok_or("no positions")?;
+        let raw = decoded.indices.len() * 4 + positions.len() * (12 + 8);
+        let size = compact.byte_size();
+        assert!(size * 2 < raw, "{size} vs {raw}");
+
+        let restored = compact.decompress();
+        assert_eq!(restored.indices, decoded.indices);
+        let restored_positions = restored.positions.as_ref().ok_or("no positions")?;
+        let (lo, hi) = float_bounds(positions).ok_or("empty")?;
+        for (a, b) in positions.iter().zip(restored_positions) {
+            for k in 0..3 {
+                let tolerance = (hi[k] - lo[k]) / 65535.0;
+                assert!((a[k] - b[k]).abs() <= tolerance, "{a:?} vs {b:?}");
+            }
+        }
+        assert_eq!(restored.texcoords.len(), 1);
+        assert_eq!(restored.texcoords[&0].len(), positions.len());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_attribute_mismatch_salvage() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let mesh = doc.meshes().next().ok_or("No meshes found in GLB")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+        let mut infos = attr_infos_from_primitive(&prim)?;
+        let expected = decode_draco(&prim, &doc, &buffers, &infos).await?;
+
+        // 7 has always been sent to the decoder as f32, like the header's 9
+        let mut legacy = infos.clone();
+        legacy
+            .iter_mut()
+            .filter(|i| i.data_type == 9)
+            .for_each(|i| i.data_type = 7);
+        assert_ne!(legacy, infos);
+        let decoded = decode_draco(&prim, &doc, &buffers, &legacy).await?;
+        assert_eq!(decoded.positions, expected.positions);
+        assert_eq!(decoded.texcoords, expected.texcoords);
+
+        // index entries are checked against the header too
+        let mut index = DocumentIndex::build(&doc)?;
+        index.primitives[0].attributes[1].info.dim = 3;
+        let err = index
+            .decode(0, 0, &buffers, &DecodeOptions::new())
+            .await
+            .unwrap_err();
+        let DracoLoadError::AttributeMismatch(conflicts) = &err else {
+            panic!("unexpected error {err}");
+        };
+        assert_eq!(conflicts[0].semantic.as_deref(), Some("TEXCOORD_0"));
+
+        let right = infos[1].clone();
+        infos[1].dim = 3;
+        infos[1].data_type = 5;
+        let err = decode_draco(&prim, &doc, &buffers, &infos)
+            .await
+            .unwrap_err();
+        let DracoLoadError::AttributeMismatch(conflicts) = &err else {
+            panic!("unexpected error {err}");
+        };
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].given, infos[1]);
+        assert_eq!(conflicts[0].stored.as_ref(), Some(&right));
+        assert_eq!(conflicts[0].semantic.as_deref(), Some("TEXCOORD_0"));
+        assert!(err.to_string().contains("TEXCOORD_0"));
+
+        let options = DecodeOptions::new().with_salvage_attributes(true);
+        let salvaged = decode_draco_with_options(&prim, &doc, &buffers, &infos, &options).await?;
+        assert_eq!(salvaged.texcoords, expected.texcoords);
+        assert_eq!(salvaged.indices, expected.indices);
+
+        // an accessor claiming normalized u16 UVs for a stream storing floats
+        let uv = prim
+            .get(&gltf::Semantic::TexCoords(0))
+            .ok_or("no UVs")?
+            .index();
+        let mut root = doc.into_json();
+        root.accessors[uv].component_type = gltf::json::validation::Checked::Valid(
+            gltf::json::accessor::GenericComponentType(gltf::accessor::DataType::U16),
+        );
+        root.accessors[uv].normalized = true;
+        let retyped = gltf::Document::from_json_without_validation(root);
+        let prim = retyped
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let accessors = attr_infos_from_primitive(&prim)?;
+        let quirks = VendorQuirks::default();
+        let conflicts = attribute_conflicts(&prim, &retyped, &buffers, &accessors, &quirks)?;
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].given.data_type, 4);
+        assert_eq!(conflicts[0].stored.as_ref(), Some(&right));
+        let salvaged =
+            decode_draco_with_options(&prim, &retyped, &buffers, &accessors, &options).await?;
+        assert_eq!(salvaged.texcoords, expected.texcoords);
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata_values_from_extras() -> Result<(), Box<dyn std::error::Error>> {
+        let json = r#"{
+            "asset": {"version": "2.0"},
+            "meshes": [{
+                "name": "Tür",
+                "extras": {"label": {"en": "door", "de": "Tür", "ja": "ドア"}},
+                "primitives": [{
+                    "attributes": {},
+                    "extras": {"tags": ["wood", 3, 2.5, null, true], "nested": {"id": 7}},
+                    "extensions": {"EXT_structural_metadata": {"propertyTextures": [0]}}
+                }]
+            }]
+        }"#;
+        let gltf = gltf::Gltf::from_slice_without_validation(json.as_bytes())?;
+        let meta = collect_metadata(&gltf.document)?;
+        assert_eq!(meta.len(), 1);
+        assert_eq!(meta[0].mesh_name.as_deref(), Some("Tür"));
+        assert!(!meta[0].draco);
+
+        let label = meta[0].mesh_extras.as_ref().and_then(|e| e.get("label"));
+        let label = label.ok_or("no label")?;
+        assert_eq!(label.localized("de-CH"), Some("Tür"));
+        assert_eq!(label.localized("ja"), Some("ドア"));
+        assert_eq!(label.localized("fr"), None);
+
+        let extras = meta[0].extras.as_ref().ok_or("no extras")?;
+        let tags = vec![
+            MetadataValue::String("wood".into()),
+            MetadataValue::Int(3),
+            MetadataValue::Float(2.5),
+            MetadataValue::Null,
+            MetadataValue::Bool(true),
+        ];
+        assert_eq!(extras.get("tags"), Some(&MetadataValue::Array(tags)));
+        let id = extras.get("nested").and_then(|n| n.get("id"));
+        assert_eq!(id, Some(&MetadataValue::Int(7)));
+        assert!(meta[0].structural.is_some());
+
+        let round: Vec<PrimitiveMetadata> = serde_json::from_str(&serde_json::to_string(&meta)?)?;
+        assert_eq!(round, meta);
+        Ok(())
+    }
+
+    #[cfg(feature = "render-offscreen")]
+    #[test]
+    fn test_offscreen_render_frames_the_primitive() -> Result<(), Box<dyn std::error::Error>> {
+        let (lo, hi) = ([-1.0, 2.0, -3.0], [4.0, 5.0, 0.5]);
+        for aspect in [0.5, 1.0, 2.0] {
+            let (m, _) = RenderCamera::default().view_proj(lo, hi, aspect);
+            for c in 0..8 {
+                let p = [0, 1, 2].map(|k| if c >> k & 1 == 0 { lo[k] } else { hi[k] });
+                let row = |r: usize| (0..3).map(|k| m[k][r] * p[k]).sum::<f32>() + m[3][r];
+                let clip = [0, 1, 2, 3].map(row);
+                let ndc = [0, 1, 2].map(|k| clip[k] / clip[3]);
+                let inside = ndc[0].abs() <= 1.0 && ndc[1].abs() <= 1.0;
+                assert!(inside && (0.0..=1.0).contains(&ndc[2]), "{aspect} {ndc:?}");
+            }
+        }
+
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let options = wgpu::RequestAdapterOptions::default();
+        let Ok(adapter) = parallel::block_on(instance.request_adapter(&options)) else {
+            return Ok(()); // no GPU or software adapter in this environment
+        };
+        let (device, queue) = parallel::block_on(adapter.request_device(&Default::default()))?;
+        let square = vec![[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0]];
+        let prim = DecodedPrimitive {
+            indices: vec![0, 1, 2, 2, 1, 3],
+            positions: Some(square.clone()),
+            ..Default::default()
+        };
+        let camera = RenderCamera::AutoFrame {
+            direction: [0.0, 0.0, -1.0],
+        };
+        let options = RenderOptions::default()
+            .with_size(64, 32)
+            .with_camera(camera);
+        let renderer = OffscreenRenderer::new(&device);
+        let rgba = renderer.render_rgba(&device, &queue, &prim, &options)?;
+        assert_eq!(rgba.len(), 64 * 32 * 4);
+        let center = (16 * 64 + 32) * 4;
+        assert_ne!(&rgba[center..center + 4], &[255, 255, 255, 255]);
+        let png = renderer.render_png(&device, &queue, &prim, &options)?;
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        // the same square without indices draws its vertices as a triangle list
+        let unindexed = DecodedPrimitive {
+            positions: Some(prim.indices.iter().map(|&i| square[i as usize]).collect()),
+            ..Default::default()
+        };
+        assert_eq!(
+            renderer.render_rgba(&device, &queue, &unindexed, &options)?,
+            rgba
+        );
+
+        let max = device.limits().max_texture_dimension_2d;
+        let huge = options.clone().with_size(max + 1, 1);
+        let err = renderer.render_rgba(&device, &queue, &prim, &huge);
+        assert!(matches!(err, Err(RenderError::TooLarge { width, .. }) if width == max + 1));
+        assert!(matches!(
+            renderer.render_rgba(&device, &queue, &DecodedPrimitive::default(), &options),
+            Err(RenderError::NoPositions)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_winding_against_normals() {
+        let mut prim = DecodedPrimitive {
+            indices: vec![0, 1, 2, 2, 3, 1, 0, 0, 1],
+            positions: Some(vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 1.0, 0.0],
+            ]),
+            normals: Some(vec![[0.0, 0.0, 1.0]; 4]),
+            ..Default::default()
+        };
+        let report = prim.winding_report().unwrap();
+        assert_eq!(report.triangles, 3);
+        assert_eq!(report.checked, 2, "the degenerate face can't be judged");
+        assert_eq!(report.flipped, vec![1]);
+        assert_eq!(report.flipped_percent(), 50.0);
+
+        assert_eq!(prim.fix_winding_from_normals(), 1);
+        assert_eq!(&prim.indices[3..6], &[2, 1, 3]);
+        assert!(prim.winding_report().unwrap().flipped.is_empty());
+
+        prim.normals = None;
+        assert!(prim.winding_report().is_none());
+    }
+
+    #[test]
+    fn test_rebase_texcoords_per_chart() {
+        let mut prim = DecodedPrimitive {
+            indices: vec![0, 1, 2, 3, 4, 5],
+            texcoords: [(
+                0,
+                vec![
+                    [1000.25, -3.5],
+                    [1001.5, -2.75],
+                    [1000.75, -3.0],
+                    [0.25, 0.5],
+                    [0.75, 0.5],
+                    [0.5, 0.9],
+                ],
+            )]
+            .into(),
+            ..Default::default()
+        };
+        assert_eq!(prim.rebase_texcoords(), 1);
+        let uv = &prim.texcoords[&0];
+        assert_eq!(uv[0], [0.25, 0.5]);
+        assert_eq!(uv[1], [1.5, 1.25], "tiling span is kept");
+        assert_eq!(&uv[3..], &[[0.25, 0.5], [0.75, 0.5], [0.5, 0.9]]);
+        assert_eq!(prim.rebase_texcoords(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_at_every_await_point() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let mesh = doc.meshes().next().ok_or("No meshes found in GLB")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+        let infos = attr_infos_from_primitive(&prim)?;
+        let options = DecodeOptions::new().with_max_concurrent_decodes(1);
+        let handle = AbortHandle::new();
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+
+        // holding a slot parks the capped decode at its slot wait, polled as often as a
+        // frame-driven caller would before giving up
+        let blocker = limit::acquire(Some(1)).await;
+        for polls in 0..4 {
+            let decode = decode_draco_with_options(&prim, &doc, &buffers, &infos, &options);
+            let mut fut = std::pin::pin!(abortable(&handle, decode));
+            for _ in 0..polls {
+                assert!(fut.as_mut().poll(&mut cx).is_pending());
+            }
+            assert!(handle.pending() <= 1);
+        }
+        assert_eq!(handle.pending(), 0, "cancelled calls unregister");
+
+        let mut sliced = decode_draco_sliced(&prim, &doc, &buffers, &infos, &options)?;
+        let waiting = decodes_waiting();
+        for _ in 0..8 {
+            assert!(sliced.poll_step(std::time::Duration::ZERO).is_pending());
+        }
+        // one registration for the sliced decode, plus one other tests may add meanwhile
+        assert!(decodes_waiting() <= waiting + 2, "polls register once");
+        drop(sliced);
+        drop(blocker);
+
+        // a panic while holding a slot must not poison the limiter
+        let panicked = std::panic::catch_unwind(|| {
+            let _permit = parallel::block_on(limit::acquire(None));
+            panic!("decode failed");
+        });
+        assert!(panicked.is_err());
+
+        let decoded = abortable(&handle, decode_draco(&prim, &doc, &buffers, &infos)).await?;
+        assert_eq!(decoded.indices.len(), 12639);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_vendor_semantics_decode_to_custom() -> Result<(), Box<dyn std::error::Error>> {
+        use gltf::json::validation::Checked::Valid;
+
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let expected = decode_test_glb("examples/test.glb").await?;
+
+        // rename TEXCOORD_0 to a vendor attribute in the primitive and the extension
+        let mut root = document.into_json();
+        let prim = &mut root.meshes[0].primitives[0];
+        let uv = prim
+            .attributes
+            .remove(&Valid(gltf::Semantic::TexCoords(0)))
+            .ok_or("no TEXCOORD_0")?;
+        let vendor = gltf::Semantic::Extras("MYCOMPANY_UV".into());
+        prim.attributes.insert(Valid(vendor), uv);
+        let ext = prim.extensions.as_mut().ok_or("no extensions")?;
+        let draco = ext
+            .others
+            .get_mut("KHR_draco_mesh_compression")
+            .ok_or("not Draco")?;
+        let attributes = draco["attributes"].as_object_mut().ok_or("no attributes")?;
+        let id = attributes.remove("TEXCOORD_0").ok_or("no TEXCOORD_0")?;
+        attributes.insert("_MYCOMPANY_UV".into(), id);
+        let document = gltf::Document::from_json_without_validation(root);
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+
+        let infos = attr_infos_with_quirks(&prim, &VendorQuirks::default())?;
+        let decoded = decode_draco(&prim, &document, &buffers, &infos).await?;
+        assert!(decoded.texcoords.is_empty());
+        let custom = &decoded.custom["_MYCOMPANY_UV"];
+        assert_eq!((custom.components, custom.len()), (2, 5849));
+        let uv = expected.texcoords[&0].as_flattened().to_vec();
+        assert_eq!(custom.values, CustomValues::F32(uv));
+
+        let uv = CustomSemantic::new(2, gltf::accessor::DataType::F32);
+        let registry = SemanticRegistry::new().register_prefix("_MYCOMPANY_", uv);
+        assert_eq!(registry.lookup("_MYCOMPANY_UV"), Some(&uv));
+        assert_eq!(registry.lookup("_OTHER"), None);
+        let options = DecodeOptions::new().with_semantic_registry(registry);
+        let registered =
+            decode_draco_with_options(&prim, &document, &buffers, &infos, &options).await?;
+        assert_eq!(registered.custom, decoded.custom);
+
+        let wrong = CustomSemantic::new(3, gltf::accessor::DataType::F32);
+        let registry = SemanticRegistry::new().register("_MYCOMPANY_UV", wrong);
+        let options = DecodeOptions::new().with_semantic_registry(registry);
+        let result = decode_draco_with_options(&prim, &document, &buffers, &infos, &options).await;
+        assert!(matches!(result, Err(DracoLoadError::AttributeMismatch(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_attributes_keep_stored_type() -> Result<(), Box<dyn std::error::Error>> {
+        // ids past 2^24 have no exact f32
+        let ids = [16_777_217u32, 16_777_219];
+        let bytes: Vec<u8> = ids.iter().flat_map(|i| i.to_le_bytes()).collect();
+        let blk = AttrSlice {
+            unique_id: 0,
+            bytes: &bytes,
+            dim: 1,
+            dt: AttributeDataType::UInt32,
+        };
+        let map = SemanticMap::from([(0, (gltf::Semantic::Extras("ID".into()), 1, false))]);
+        let mut a = DecodedPrimitive {
+            positions: Some(vec![[0.0; 3]; 2]),
+            ..Default::default()
+        };
+        fill_primitive(&mut a, &[blk], &map)?;
+        assert_eq!(a.custom["_ID"].values, CustomValues::U32(ids.to_vec()));
+        assert_eq!(a.custom["_ID"].len(), 2);
+
+        // parts without the attribute are zero-filled in its own type
+        let plain = DecodedPrimitive {
+            positions: Some(vec![[0.0; 3]]),
+            ..Default::default()
+        };
+        let merged = merge_primitives(&[(&plain, 0), (&a, 1)])?;
+        let values = &merged.primitive.custom["_ID"].values;
+        assert_eq!(*values, CustomValues::U32(vec![0, ids[0], ids[1]]));
+
+        // the same name with another layout is rejected instead of misread
+        let mut b = plain.clone();
+        let wider = CustomAttribute {
+            components: 2,
+            values: CustomValues::U32(vec![1, 2]),
+        };
+        b.custom.insert("_ID".into(), wider);
+        let result = merge_primitives(&[(&a, 0), (&b, 1)]);
+        assert!(matches!(
+            result,
+            Err(DracoLoadError::CustomAttributeConflict { ref name, .. }) if name == "_ID"
+        ));
+        b.custom.get_mut("_ID").ok_or("no _ID")?.values = CustomValues::F32(vec![1.0, 2.0]);
+        b.custom.get_mut("_ID").ok_or("no _ID")?.components = 1;
+        assert!(merge_primitives(&[(&a, 0), (&b, 1)]).is_err());
+
+        let registry = SemanticRegistry::new()
+            .register("_ID", CustomSemantic::new(1, gltf::accessor::DataType::U32));
+        let (sem, spec) = registry.resolve("_ID").ok_or("not resolved")?;
+        assert_eq!(sem, gltf::Semantic::Extras("ID".into()));
+        assert_eq!(spec.map(|s| s.components), Some(1));
+        assert_eq!(registry.resolve("TEXCOORD_0").map(|(_, s)| s), Some(None));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bake_transform_normals_and_skins() -> Result<(), Box<dyn std::error::Error>> {
+        let triangle = DecodedPrimitive {
+            indices: vec![0, 1, 2],
+            positions: Some(vec![[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]),
+            normals: Some(vec![[0.5f32.sqrt(), 0.5f32.sqrt(), 0.0]; 3]),
+            tangents: Some(vec![[0.5f32.sqrt(), -(0.5f32.sqrt()), 0.0, 1.0]; 3]),
+            ..Default::default()
+        };
+        let diag = |x: f32, y: f32, z: f32| -> Mat4 {
+            [
+                [x, 0.0, 0.0, 0.0],
+                [0.0, y, 0.0, 0.0],
+                [0.0, 0.0, z, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]
+        };
+        let close = |a: [f32; 3], b: [f32; 3]| (0..3).all(|k| (a[k] - b[k]).abs() < 1e-5);
+
+        let uniform = transform_scale(&diag(3.0, 3.0, 3.0));
+        assert!(uniform.uniform && !uniform.mirrored());
+
+        // stretching X tilts the normal towards Y (inverse-transpose), not towards X
+        let mut stretched = triangle.clone();
+        let ts = stretched.bake_transform(&diag(2.0, 1.0, 1.0));
+        assert!(!ts.uniform);
+        assert_eq!(ts.scale, [2.0, 1.0, 1.0]);
+        let n = stretched.normals.as_ref().ok_or("normals dropped")?[0];
+        let expected = [1.0 / 5f32.sqrt(), 2.0 / 5f32.sqrt(), 0.0];
+        assert!(close(n, expected), "{n:?}");
+        let p = stretched.positions.as_ref().ok_or("positions dropped")?[0];
+        assert_eq!(p, [2.0, 0.0, 0.0]);
+        assert_eq!(stretched.winding_report().map(|r| r.flipped.len()), Some(0));
+
+        // mirroring reverses the winding and the tangent handedness
+        let mut mirrored = triangle.clone();
+        assert!(mirrored.bake_transform(&diag(-1.0, 1.0, 1.0)).mirrored());
+        assert_eq!(mirrored.indices, vec![0, 2, 1]);
+        let n = mirrored.normals.as_ref().ok_or("normals dropped")?[0];
+        assert!(close(n, [-(0.5f32.sqrt()), 0.5f32.sqrt(), 0.0]), "{n:?}");
+        let t = mirrored.tangents.as_ref().ok_or("tangents dropped")?[0];
+        assert_eq!(t[3], -1.0);
+        assert_eq!(mirrored.winding_report().map(|r| r.flipped.len()), Some(0));
+
+        let root: gltf::json::Root = serde_json::from_str(
+            r#"{
+                "asset": { "version": "2.0" },
+                "nodes": [{ "mesh": 0, "skin": 0 }, {}, { "mesh": 0 }, { "mesh": 0 }],
+                "skins": [{ "joints": [1] }],
+                "meshes": [{ "primitives": [{ "attributes": {} }] }]
+            }"#,
+        )?;
+        let document = gltf::Document::from_json_without_validation(root);
+        let instance = |node, world_transform| ScenePrimitive {
+            node,
+            mesh: 0,
+            mesh_name: None,
+            primitive: 0,
+            material: None,
+            world_transform,
+            decoded: triangle.clone(),
+        };
+        let scene = DecodedScene {
+            primitives: vec![
+                instance(0, diag(2.0, 2.0, 2.0)),
+                instance(2, diag(2.0, 1.0, 1.0)),
+                instance(3, diag(1.0, 1.0, 0.0)),
+            ],
+            ..Default::default()
+        };
+        let baked = bake_scene(&scene, &document);
+        assert_eq!(
+            baked.warnings,
+            vec![
+                BakeWarning::SkinnedTransform {
+                    node: 0,
+                    mesh: 0,
+                    primitive: 0
+                },
+                BakeWarning::SingularTransform {
+                    node: 3,
+                    mesh: 0,
+                    primitive: 0
+                },
+            ]
+        );
+        assert_eq!(baked.primitives[0].decoded.positions, triangle.positions);
+        assert_eq!(baked.primitives[0].world_transform, diag(2.0, 2.0, 2.0));
+        assert_eq!(baked.primitives[1].decoded.normals, stretched.normals);
+        assert_eq!(baked.primitives[1].world_transform, diag(1.0, 1.0, 1.0));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bitstream_version_checked_early() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, mut buffers) = load_glb("examples/test.glb")?;
+        let mesh = document.meshes().next().ok_or("No meshes found")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+        let version = primitive_bitstream_version(&prim, &document, &buffers)?;
+        assert_eq!(version, Some(BitstreamVersion::new(2, 2)));
+        assert!(supported_versions().contains(&BitstreamVersion::new(2, 2)));
+        assert_eq!(bitstream_version(b"glTF"), None);
+
+        // bump the header to a major version no decoder knows yet
+        let view = document
+            .views()
+            .nth(draco_extension(&prim)?.buffer_view)
+            .ok_or("Draco view missing")?;
+        let header = view.offset();
+        assert_eq!(&buffers[0].0[header..header + 5], b"DRACO");
+        buffers[0].0[header + 5] = 3;
+        let infos = attr_infos_from_primitive(&prim)?;
+        let err = decode_draco(&prim, &document, &buffers, &infos)
+            .await
+            .expect_err("3.2 stream decoded");
+        assert!(matches!(
+            err,
+            DracoLoadError::UnsupportedBitstreamVersion { found, .. }
+                if found == BitstreamVersion::new(3, 2)
+        ));
+        assert!(err.is_recoverable() && err.hint().is_some());
+        assert!(err.to_string().contains("1.0..=2.2"), "{err}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_joints_follow_type_and_skin_width() -> Result<(), Box<dyn std::error::Error>> {
+        let wide: Vec<u8> = [300u16, 2, 65535, 0]
+            .iter()
+            .flat_map(|j| j.to_le_bytes())
+            .collect();
+        assert_eq!(
+            joint_indices(0, &wide, AttributeDataType::UInt16, 4)?,
+            vec![[300, 2, 65535, 0]]
+        );
+        assert_eq!(
+            joint_indices(0, &[7, 8, 9, 255], AttributeDataType::UInt8, 4)?,
+            vec![[7, 8, 9, 255]]
+        );
+        let too_wide: Vec<u8> = [1u32, 70000, 0, 0]
+            .iter()
+            .flat_map(|j| j.to_le_bytes())
+            .collect();
+        let err = joint_indices(1, &too_wide, AttributeDataType::UInt32, 4)
+            .expect_err("70000 wrapped into u16");
+        assert!(matches!(
+            err,
+            DracoLoadError::JointIndexOverflow { set: 1, value } if value == 70000.0
+        ));
+
+        let document = |component_type: u32| -> Result<gltf::Document, serde_json::Error> {
+            let json = serde_json::json!({
+                "asset": { "version": "2.0" },
+                "nodes": [{ "mesh": 0, "skin": 0 }, {}],
+                "skins": [{ "joints": vec![1; 257] }],
+                "accessors": [{ "componentType": component_type, "count": 1, "type": "VEC4" }],
+                "meshes": [{ "primitives": [{ "attributes": { "JOINTS_0": 0 } }] }]
+            });
+            let root = serde_json::from_value(json)?;
+            Ok(gltf::Document::from_json_without_validation(root))
+        };
+        let decoded = |max: u16| {
+            let mut d = DecodedPrimitive::default();
+            d.joints.insert(0, vec![[0, max, 0, 0]]);
+            d
+        };
+        let narrow = document(5121)?;
+        let node = narrow.nodes().next().ok_or("node 0 missing")?;
+        let mesh = narrow.meshes().next().ok_or("mesh 0 missing")?;
+        let prim = mesh.primitives().next().ok_or("primitive missing")?;
+        // 257 joints behind u8 indices are fine while the primitive stays below the top
+        assert!(check_joint_width(&node, &mesh, &prim, &decoded(200)).is_empty());
+        assert_eq!(
+            check_joint_width(&node, &mesh, &prim, &decoded(255)),
+            [JointWidthWarning {
+                node: 0,
+                mesh: 0,
+                primitive: 0,
+                skin: 0,
+                set: 0,
+                joints: 257,
+                addressable: 256,
+                max_joint: 255,
+            }]
+        );
+        let short = document(5123)?;
+        let node = short.nodes().next().ok_or("node 0 missing")?;
+        let mesh = short.meshes().next().ok_or("mesh 0 missing")?;
+        let prim = mesh.primitives().next().ok_or("primitive missing")?;
+        assert!(check_joint_width(&node, &mesh, &prim, &decoded(255)).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_voxelize_cube_shell_and_fill() {
+        // a closed box from 0.5 to 3.5, so each face sits in the middle of a voxel layer
+        let corners: Vec<[f32; 3]> = (0..8)
+            .map(|i| [i & 1, (i >> 1) & 1, (i >> 2) & 1].map(|b| 0.5 + 3.0 * b as f32))
+            .collect();
+        let cube = DecodedPrimitive {
+            indices: vec![
+                0, 2, 1, 1, 2, 3, 4, 5, 6, 5, 7, 6, 0, 1, 4, 1, 5, 4, 2, 6, 3, 3, 6, 7, 0, 4, 2, 2,
+                4, 6, 1, 3, 5, 3, 7, 5,
+            ],
+            positions: Some(corners),
+            ..Default::default()
+        };
+
+        let mut grid = cube.voxelize(1.0);
+        // a 4x4x4 block minus its 2x2x2 core
+        assert_eq!(grid.len(), 56);
+        assert!(grid.contains([0, 0, 0]) && grid.contains([3, 3, 3]));
+        assert!(!grid.contains([1, 1, 1]));
+        assert_eq!(grid.voxel_bounds(), Some(([0; 3], [3; 3])));
+        assert_eq!(grid.center([1, 2, 3]), [1.5, 2.5, 3.5]);
+
+        assert_eq!(grid.fill_interior(), 8);
+        assert_eq!(grid.len(), 64);
+        assert!(grid.contains([1, 1, 1]));
+
+        assert!(cube.voxelize(0.0).is_empty());
+        assert_eq!(cube.voxelize(0.5).voxel_bounds(), Some(([1; 3], [7; 3])));
+        // 3e6 voxels per axis would be ~3e19 cells; the size doubles until 184³ fit
+        let coarse = cube.voxelize(1e-6);
+        assert_eq!(coarse.voxel_size, 1e-6 * 16384.0);
+        assert!(!coarse.is_empty());
+    }
+
+    #[test]
+    fn test_navmesh_input_marks_walkable_slopes() -> Result<(), Box<dyn std::error::Error>> {
+        let root: gltf::json::Root = serde_json::from_str(
+            r#"{
+                "asset": { "version": "2.0" },
+                "nodes": [{ "mesh": 0 }, { "mesh": 1 }],
+                "meshes": [
+                    { "primitives": [{ "attributes": {} }] },
+                    { "primitives": [{ "attributes": {} }] }
+                ]
+            }"#,
+        )?;
+        let document = gltf::Document::from_json_without_validation(root);
+        let triangle = |positions: Vec<[f32; 3]>| DecodedPrimitive {
+            indices: vec![0, 1, 2],
+            positions: Some(positions),
+            ..Default::default()
+        };
+        let translated = |x: f32| -> Mat4 {
+            [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [x, 0.0, 0.0, 1.0],
+            ]
+        };
+        let floor = ScenePrimitive {
+            node: 0,
+            mesh: 0,
+            mesh_name: None,
+            primitive: 0,
+            material: None,
+            world_transform: translated(10.0),
+            decoded: triangle(vec![[0.0; 3], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]]),
+        };
+        let wall = ScenePrimitive {
+            node: 1,
+            mesh: 1,
+            world_transform: translated(0.0),
+            decoded: triangle(vec![[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]),
+            ..floor.clone()
+        };
+        let scene = DecodedScene {
+            primitives: vec![floor, wall],
+            ..Default::default()
+        };
+
+        let input = navmesh_input(&scene, &document, 45.0);
+        assert_eq!((input.vertex_count(), input.triangle_count()), (6, 2));
+        assert_eq!(input.triangles, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(input.areas, vec![WALKABLE_AREA, NULL_AREA]);
+        // the floor moved to x = 10 before flattening
+        assert_eq!(&input.vertices[..3], &[10.0, 0.0, 0.0]);
+        assert_eq!(input.bmin, [0.0; 3]);
+        assert_eq!(input.bmax, [11.0, 1.0, 1.0]);
+        assert!(input.warnings.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sample_surface_uniform_with_attributes() {
+        // unit square split unevenly, so per-triangle counts must follow area
+        let square = DecodedPrimitive {
+            indices: vec![0, 1, 2, 0, 2, 3],
+            positions: Some(vec![
+                [0.0, 0.0, 0.0],
+                [0.9, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+            ]),
+            texcoords: [(0, vec![[0.0, 0.0], [0.9, 0.0], [1.0, 1.0], [0.0, 1.0]])].into(),
+            ..Default::default()
+        };
+
+        let samples = square.sample_surface(2000.0);
+        // 0.45 + 0.5 square units
+        assert_eq!(samples.len(), 1900);
+        assert_eq!(samples, square.sample_surface(2000.0));
+        assert_ne!(samples, square.sample_surface_seeded(2000.0, 7));
+
+        let on_first = samples.iter().filter(|s| s.triangle == 0).count();
+        assert!((840..960).contains(&on_first), "{on_first}");
+        let mean_y = samples.iter().map(|s| s.position[1]).sum::<f32>() / 1900.0;
+        assert!((mean_y - 0.5).abs() < 0.05, "{mean_y}");
+        for s in &samples {
+            assert_eq!(s.normal, [0.0, 0.0, 1.0]);
+            let uv = s.texcoord.expect("TEXCOORD_0 not interpolated");
+            assert!((uv[0] - s.position[0]).abs() < 1e-5 && (uv[1] - s.position[1]).abs() < 1e-5);
+        }
+
+        assert!(square.sample_surface(0.0).is_empty());
+        assert!(DecodedPrimitive::default().sample_surface(10.0).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_triangle_area_tables() -> Result<(), Box<dyn std::error::Error>> {
+        let quad = DecodedPrimitive {
+            indices: vec![0, 1, 2, 0, 2, 3],
+            positions: Some(vec![
+                [0.0, 0.0, 0.0],
+                [2.0, 0.0, 0.0],
+                [2.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+            ]),
+            texcoords: [(1, vec![[0.0, 0.0], [0.5, 0.0], [0.5, 0.5], [0.0, 0.5]])].into(),
+            ..Default::default()
+        };
+        let areas = quad.triangle_areas(true);
+        assert_eq!(areas.surface, vec![1.0, 1.0]);
+        assert_eq!(areas.uv[&1], vec![0.125, 0.125]);
+        assert_eq!(areas.total_uv(1), Some(0.25));
+        assert_eq!(areas.total_uv(0), None);
+        assert!(quad.triangle_areas(false).uv.is_empty());
+
+        let mut world_transform: Mat4 = Default::default();
+        world_transform[0][0] = 3.0;
+        world_transform[1][1] = 1.0;
+        world_transform[2][2] = 1.0;
+        world_transform[3] = [5.0, 5.0, 5.0, 1.0];
+        let instance = ScenePrimitive {
+            node: 0,
+            mesh: 0,
+            mesh_name: None,
+            primitive: 0,
+            material: None,
+            world_transform,
+            decoded: quad,
+        };
+        let world = instance.triangle_areas(true);
+        assert_eq!(world.surface, vec![3.0, 3.0]);
+        assert_eq!(world.uv, areas.uv);
+
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        let areas = decoded.triangle_areas(true);
+        assert_eq!(areas.surface.len(), decoded.indices.len() / 3);
+        assert_eq!(areas.uv[&0].len(), areas.surface.len());
+        assert!(areas.total_surface() > 0.0);
+        assert_eq!(decoded.areas, None);
+
+        // filled by the decode itself, after the conventions reorder the triangles
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let infos = attr_infos_from_primitive(&prim)?;
+        let options = DecodeOptions::new()
+            .with_ordering(StableOrdering::Canonical)
+            .with_triangle_areas(AreaTables::SurfaceAndUv);
+        let mut canonical =
+            decode_draco_with_options(&prim, &document, &buffers, &infos, &options).await?;
+        assert_eq!(canonical.areas, Some(canonical.triangle_areas(true)));
+        let options = options.with_triangle_areas(AreaTables::Surface);
+        let surface =
+            decode_draco_with_options(&prim, &document, &buffers, &infos, &options).await?;
+        let surface = surface.areas.ok_or("no areas")?;
+        assert!(surface.uv.is_empty());
+
+        let m = [
+            [2.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        canonical.bake_transform(&m);
+        let baked = canonical.areas.as_ref().ok_or("no areas")?;
+        assert!(
+            (baked.total_surface() - canonical.triangle_areas(false).total_surface()).abs() < 1e-9
+        );
+        assert!(baked.total_surface() > surface.total_surface() * 1.5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_estimate_counts_from_header() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let mesh = document.meshes().next().ok_or("No meshes found")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+        let bytes = get_buffer(&document, &buffers, draco_extension(&prim)?.buffer_view)?;
+
+        let (vertices, faces) = estimate_counts(bytes).ok_or("header not readable")?;
+        let indices = prim.indices().ok_or("no indices")?.count();
+        let positions = prim
+            .get(&gltf::Semantic::Positions)
+            .ok_or("no positions")?
+            .count();
+        assert_eq!(faces * 3, indices);
+        // edgebreaker: UV seams add vertices the connectivity doesn't count
+        assert!(vertices <= positions && positions <= faces * 3);
+
+        // sequential 2.2 stream: varint faces, then varint points
+        let sequential = [b"DRACO".as_slice(), &[2, 2, 1, 0, 0, 0, 0x90, 0x03, 0x2c]].concat();
+        assert_eq!(estimate_counts(&sequential), Some((44, 400)));
+        // no attribute metadata, an empty file metadata block, then the same counts
+        let with_metadata = [
+            b"DRACO".as_slice(),
+            &[2, 2, 1, 0, 0, 0x80, 0, 0, 0, 0x90, 0x03, 0x2c],
+        ]
+        .concat();
+        assert_eq!(estimate_counts(&with_metadata), Some((44, 400)));
+        assert_eq!(estimate_counts(&bytes[..9]), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_empty_primitives_and_streams() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let view = {
+            let mesh = document.meshes().next().ok_or("No meshes found")?;
+            let prim = mesh
+                .primitives()
+                .next()
+                .ok_or("No primitives found in mesh")?;
+            draco_extension(&prim)?.buffer_view
+        };
+        let root = document.into_json();
+        let rewrite = |edit: &dyn Fn(&mut gltf::json::Root)| {
+            let mut root = root.clone();
+            edit(&mut root);
+            gltf::Document::from_json_without_validation(root)
+        };
+
+        // a procedural exporter's empty primitive: zero counts and an empty view
+        let empty = rewrite(&|root| {
+            for accessor in &mut root.accessors {
+                accessor.count = 0u64.into();
+            }
+            root.buffer_views[view].byte_length = 0u64.into();
+        });
+        let mesh = empty.meshes().next().ok_or("No meshes found")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+        let decoded = decode_draco_derived(&prim, &empty, &buffers).await?;
+        assert!(decoded.indices.is_empty());
+        assert_eq!(decoded.positions.as_ref().map(Vec::len), Some(0));
+        assert_eq!(decoded.texcoords.get(&0).map(Vec::len), Some(0));
+
+        // counts without data are an error, not a slice panic
+        let truncated = rewrite(&|root| root.buffer_views[view].byte_length = 0u64.into());
+        let mesh = truncated.meshes().next().ok_or("No meshes found")?;
+        let prim = mesh
+            .primitives()
+            .next()
+            .ok_or("No primitives found in mesh")?;
+        let err = decode_draco_derived(&prim, &truncated, &buffers)
+            .await
+            .expect_err("decoded an empty stream");
+        assert!(matches!(err, DracoLoadError::EmptyDracoStream(n) if n > 0));
+        assert!(err.is_recoverable());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_rebasing_promotes_and_checks() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            pack_indices(&[0, 1, 65534]),
+            PackedIndices::U16(vec![0, 1, 65534])
+        );
+        assert!(pack_indices(&[0, 65535]).is_wide());
+
+        let mut packed = PackedIndices::default();
+        packed.push_rebased(&[0, 1, 2], 0)?;
+        assert!(!packed.is_wide());
+        packed.push_rebased(&[0, 1, 2], 65533)?;
+        assert_eq!(
+            packed,
+            PackedIndices::U32(vec![0, 1, 2, 65533, 65534, 65535])
+        );
+        assert_eq!(packed.as_bytes().len(), 24);
+        let err = packed.push_rebased(&[1], u32::MAX as usize);
+        assert!(matches!(err, Err(DracoLoadError::IndexOverflow(_))));
+        assert_eq!(packed.len(), 6);
+
+        let mut indices = vec![0, 1, 2];
+        append_rebased(&mut indices, &[0, 2, 1], 3)?;
+        assert_eq!(indices, vec![0, 1, 2, 3, 5, 4]);
+        assert!(rebase_indices(&mut indices, u32::MAX as usize - 4).is_err());
+        assert_eq!(indices, vec![0, 1, 2, 3, 5, 4]);
+        rebase_indices(&mut indices, 10)?;
+        assert_eq!(indices[0], 10);
+
+        assert_eq!(next_vertex_base(10, 5)?, 15);
+        assert_eq!(next_vertex_base(0, u32::MAX as usize + 1)?, 1 << 32);
+        assert!(next_vertex_base(1, u32::MAX as usize + 1).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_extras_directives_skip() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let base = serde_json::to_value(document.into_json())?;
+        let annotate = |mesh: serde_json::Value, prim: serde_json::Value| {
+            let mut json = base.clone();
+            json["meshes"][0]["extras"] = mesh;
+            json["meshes"][0]["primitives"][0]["extras"] = prim;
+            let root = serde_json::from_str(&json.to_string())?;
+            Ok::<_, serde_json::Error>(gltf::Document::from_json_without_validation(root))
+        };
+        let directives = |document: &gltf::Document| {
+            let mesh = document.meshes().next().ok_or("No meshes found")?;
+            let prim = mesh.primitives().next().ok_or("No primitives found")?;
+            Ok::<_, Box<dyn std::error::Error>>(decode_directives(&mesh, &prim)?)
+        };
+
+        let skipped = annotate(
+            serde_json::json!({ "draco_gltf_rs": { "skip": true } }),
+            serde_json::json!({ "author": "someone" }),
+        )?;
+        assert!(directives(&skipped)?.skip);
+        let scene = decode_default_scene(&skipped, &buffers).await?;
+        assert!(scene.primitives.is_empty());
+        assert!(DocumentIndex::build(&skipped)?.primitives.is_empty());
+
+        // the primitive overrides the mesh field by field
+        let kept = annotate(
+            serde_json::json!({ "draco_gltf_rs": { "skip": true } }),
+            serde_json::json!({ "draco_gltf_rs": { "skip": false } }),
+        )?;
+        assert!(!directives(&kept)?.skip);
+        let scene = decode_default_scene(&kept, &buffers).await?;
+        assert_eq!(scene.primitives.len(), 1);
+
+        // directives this version doesn't know don't stop the load
+        let future = annotate(
+            serde_json::Value::Null,
+            serde_json::json!({ "draco_gltf_rs": { "skipp": true, "precision": "f64" } }),
+        )?;
+        assert!(!directives(&future)?.skip);
+        assert_eq!(DocumentIndex::build(&future)?.primitives.len(), 1);
+
+        let mistyped = annotate(
+            serde_json::Value::Null,
+            serde_json::json!({ "draco_gltf_rs": { "skip": "yes" } }),
+        )?;
+        let err = directives(&mistyped).expect_err("mistyped directive accepted");
+        assert!(err.to_string().contains("yes"), "{err}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pack_primitives_into_aligned_buffers() -> Result<(), Box<dyn std::error::Error>> {
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        let triangle = DecodedPrimitive {
+            indices: vec![0, 1, 2],
+            positions: Some(vec![[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]),
+            ..Default::default()
+        };
+
+        let options = PackOptions::default();
+        let packed = pack_primitives(&[(0, 0, &decoded), (1, 0, &triangle)], &options);
+        assert_eq!(packed.buffers.len(), 1);
+        assert_eq!(packed.primitives.len(), 2);
+        let first = &packed.primitives[0];
+        assert_eq!((first.vertex_count, first.index_count), (5849, 12639));
+        let positions = first
+            .stream(UploadStream::Positions)
+            .ok_or("positions not packed")?;
+        assert_eq!(positions.stride, 12);
+        let bytes = &packed.buffers[0][positions.offset as usize..][..positions.size as usize];
+        let expected = decoded.positions.as_ref().ok_or("no positions")?;
+        assert_eq!(bytes, bytemuck::cast_slice::<_, u8>(expected));
+        let ranges = packed
+            .primitives
+            .iter()
+            .flat_map(|p| std::iter::once(p.indices).chain(p.streams.iter().map(|(_, r)| *r)));
+        assert!(ranges.clone().all(|r| r.offset % 256 == 0));
+
+        // a budget below the first primitive's size starts a new buffer for the second
+        let tight = PackOptions {
+            max_buffer_size: 1 << 16,
+            ..options
+        };
+        let packed = pack_primitives(&[(0, 0, &decoded), (1, 0, &triangle)], &tight);
+        assert_eq!(packed.buffers.len(), 2);
+        assert_eq!(packed.primitives[1].buffer, 1);
+        assert_eq!(packed.primitives[1].indices.offset, 0);
+
+        let instance = |node| ScenePrimitive {
+            node,
+            mesh: 0,
+            mesh_name: None,
+            primitive: 0,
+            material: Some(2),
+            world_transform: Default::default(),
+            decoded: triangle.clone(),
+        };
+        let scene = DecodedScene {
+            primitives: vec![instance(0), instance(1)],
+            ..Default::default()
+        };
+        let packed = pack_scene(&scene, &options);
+        assert_eq!(packed.primitives.len(), 1);
+        assert_eq!(packed.instances.len(), 2);
+        assert_eq!(packed.instances[1].node, 1);
+        assert_eq!(packed.instances[1].primitive, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_build_meshlets_within_limits() -> Result<(), Box<dyn std::error::Error>> {
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        let meshlets = decoded.build_meshlets(64, 124);
+        assert!(meshlets.meshlets.len() >= decoded.indices.len() / 3 / 124);
+
+        let mut rebuilt = Vec::new();
+        for m in &meshlets.meshlets {
+            assert!(m.vertex_count <= 64 && m.triangle_count <= 124);
+            let vertices = meshlets.meshlet_vertices(m);
+            rebuilt.extend(
+                meshlets
+                    .meshlet_triangles(m)
+                    .iter()
+                    .map(|&l| vertices[l as usize]),
+            );
+            let pos = decoded.positions.as_ref().ok_or("no positions")?;
+            let b = &m.bounds;
+            assert!(vertices.iter().all(|&v| {
+                let d = [0, 1, 2].map(|k| pos[v as usize][k] - b.center[k]);
+                (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt() <= b.radius * 1.0001
+            }));
+        }
+        assert_eq!(rebuilt, decoded.indices);
+
+        // a flat quad facing +z gets a tight cone along +z
+        let quad = DecodedPrimitive {
+            indices: vec![0, 1, 2, 0, 2, 3],
+            positions: Some(vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+            ]),
+            ..Default::default()
+        };
+        let meshlets = quad.build_meshlets(3, 124);
+        assert_eq!(meshlets.meshlets.len(), 2);
+        let meshlets = quad.build_meshlets(64, 124);
+        assert_eq!(meshlets.meshlets.len(), 1);
+        assert_eq!(meshlets.vertices, [0, 1, 2, 3]);
+        let b = meshlets.meshlets[0].bounds;
+        assert_eq!(b.center, [0.5, 0.5, 0.0]);
+        assert_eq!(b.cone_axis, [0.0, 0.0, 1.0]);
+        assert!(b.cone_cutoff.abs() < 1e-6);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_culling_data_in_world_space() -> Result<(), Box<dyn std::error::Error>> {
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        let instance = |node, world_transform| ScenePrimitive {
+            node,
+            mesh: 0,
+            mesh_name: None,
+            primitive: 0,
+            material: None,
+            world_transform,
+            decoded: decoded.clone(),
+        };
+        let identity = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        let scaled = [
+            [2.0, 0.0, 0.0, 0.0],
+            [0.0, 2.0, 0.0, 0.0],
+            [0.0, 0.0, 2.0, 0.0],
+            [10.0, 0.0, 0.0, 1.0],
+        ];
+        let scene = DecodedScene {
+            primitives: vec![instance(3, identity), instance(4, scaled)],
+            ..Default::default()
+        };
+
+        let flat = build_culling_data(&scene, None);
+        assert_eq!(flat.records.len(), 2);
+        let culling = build_culling_data(&scene, Some((64, 124)));
+        let meshlets = decoded.build_meshlets(64, 124).meshlets.len();
+        assert_eq!(culling.records.len(), 2 * (1 + meshlets));
+
+        let [a, b] = [0, 1 + meshlets].map(|i| culling.records[i]);
+        assert_eq!((a.meshlet, b.meshlet), (NO_MESHLET, NO_MESHLET));
+        assert_eq!((b.node, b.primitive), (4, 1));
+        assert_eq!(
+            (b.first_child, b.child_count),
+            (2 + meshlets as u32, meshlets as u32)
+        );
+        assert_eq!(
+            (a.aabb_min, a.aabb_max),
+            (flat.records[0].aabb_min, flat.records[0].aabb_max)
+        );
+        for k in 0..3 {
+            let shift = if k == 0 { 10.0 } else { 0.0 };
+            assert_eq!(b.aabb_min[k], a.aabb_min[k] * 2.0 + shift);
+            assert_eq!(b.aabb_max[k], a.aabb_max[k] * 2.0 + shift);
+        }
+        for i in 0..meshlets {
+            let (m, n) = (culling.records[1 + i], culling.records[2 + meshlets + i]);
+            assert_eq!((m.meshlet, n.meshlet), (i as u32, i as u32));
+            assert_eq!(m.cone_cutoff, n.cone_cutoff);
+            assert!((0..3).all(|k| (m.cone_axis[k] - n.cone_axis[k]).abs() < 1e-5));
+        }
+
+        let bytes = culling.to_bytes();
+        assert_eq!(bytes.len(), culling.records.len() * CullRecord::SIZE);
+        let record = &bytes[(1 + meshlets) * CullRecord::SIZE..][..CullRecord::SIZE];
+        assert_eq!(record[12..16], 4u32.to_le_bytes());
+        assert_eq!(record[60..64], NO_MESHLET.to_le_bytes());
+        assert_eq!(record[16..20], b.aabb_max[0].to_le_bytes());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interned_keys_and_set_maps() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, _) = load_glb("examples/test.glb")?;
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let (a, b) = (draco_extension(&prim)?, draco_extension(&prim)?);
+        let keys: Vec<(&str, u32)> = a.attributes.iter().map(|(k, id)| (&**k, *id)).collect();
+        assert_eq!(keys, [("POSITION", 0), ("TEXCOORD_0", 1)]);
+        assert!(
+            a.attributes
+                .iter()
+                .zip(&b.attributes)
+                .all(|((x, _), (y, _))| std::sync::Arc::ptr_eq(x, y))
+        );
+        // other keys aren't pooled, so untrusted documents can't grow memory for good
+        let custom = intern_semantic("_BATCHID");
+        assert_eq!(&*custom, "_BATCHID");
+        assert_eq!(std::sync::Arc::strong_count(&custom), 1);
+
+        let mut sets = SetMap::new();
+        sets.insert(2, "b");
+        sets.insert(0, "a");
+        assert_eq!(sets.insert(2, "c"), Some("b"));
+        assert_eq!(sets.keys().copied().collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(sets.get(&2), Some(&"c"));
+        assert_eq!(sets.remove(&0), Some("a"));
+        assert_eq!(sets.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decode_into_recycled_buffers() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let infos = attr_infos_from_primitive(&prim)?;
+        let options = DecodeOptions::default();
+
+        let first = decode_draco(&prim, &document, &buffers, &infos).await?;
+        let (indices, positions) = (first.indices.clone(), first.positions.clone());
+        let index_storage = first.indices.as_ptr();
+        let mut pool = first.recycle();
+        assert_eq!(pool.len(), 3);
+        assert!(pool.capacity_bytes() >= 12639 * 4 + 5849 * (12 + 8));
+
+        let again =
+            decode_draco_recycled(&prim, &document, &buffers, &infos, &options, &mut pool).await?;
+        assert!(pool.is_empty());
+        assert_eq!(again.indices.as_ptr(), index_storage);
+        assert_eq!(again.indices, indices);
+        assert_eq!(again.positions, positions);
+
+        // an empty pool decodes like decode_draco
+        let mut empty = PrimitiveBuffers::new();
+        let fresh =
+            decode_draco_recycled(&prim, &document, &buffers, &infos, &options, &mut empty).await?;
+        assert_eq!(fresh.texcoords, again.texcoords);
+        again.recycle_into(&mut pool);
+        pool.absorb(fresh.recycle());
+        assert_eq!(pool.len(), 6);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decode_observer_events() -> Result<(), Box<dyn std::error::Error>> {
+        #[derive(Default)]
+        struct Recorder(std::sync::Mutex<Vec<(usize, usize, bool)>>);
+
+        impl DecodeObserver for Recorder {
+            fn primitive_started(&self, event: &DecodeStarted) {
+                assert_eq!((event.vertex_count, event.index_count), (5849, 12639));
+                self.0.lock().unwrap().push((event.bytes_in, 0, false));
+            }
+
+            fn primitive_finished(&self, event: &DecodeFinished<'_>) {
+                let mut events = self.0.lock().unwrap();
+                events.push((event.bytes_in, event.bytes_out, event.succeeded()));
+            }
+        }
+
+        let (document, mut buffers) = load_glb("examples/test.glb")?;
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let ext = draco_extension(&prim)?;
+        let infos = attr_infos_from_primitive(&prim)?;
+        let recorder = std::sync::Arc::new(Recorder::default());
+        let options = DecodeOptions::default().with_observer(recorder.clone());
+
+        decode_draco_with_options(&prim, &document, &buffers, &infos, &options).await?;
+        let view = document
+            .views()
+            .nth(ext.buffer_view)
+            .ok_or("no Draco view")?;
+        let stored = view.length();
+        let decoded = 12639 * 2 + 5849 * (12 + 8);
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [(stored, 0, false), (stored, decoded, true)]
+        );
+
+        // a corrupted stream still reports its end, as a failure
+        let range = view.offset()..view.offset() + stored;
+        buffers[view.buffer().index()].0[range].fill(0);
+        let err = decode_draco_with_options(&prim, &document, &buffers, &infos, &options).await;
+        assert!(err.is_err());
+        assert_eq!(recorder.0.lock().unwrap()[3], (stored, 0, false));
+
+        // a decode dropped while waiting for a slot still reports, as aborted
+        #[derive(Default)]
+        struct Finished(std::sync::Mutex<Vec<(Option<usize>, bool)>>);
+
+        impl DecodeObserver for Finished {
+            fn primitive_finished(&self, event: &DecodeFinished<'_>) {
+                let aborted = matches!(event.error, Some(DracoLoadError::Aborted));
+                self.0.lock().unwrap().push((event.mesh, aborted));
+            }
+        }
+
+        let finished = std::sync::Arc::new(Finished::default());
+        let options = DecodeOptions::default()
+            .with_observer(finished.clone())
+            .with_max_concurrent_decodes(1);
+        let (_, buffers) = load_glb("examples/test.glb")?;
+        let held = acquire(Some(1)).await;
+        let mut decode = Box::pin(decode_draco_with_options(
+            &prim, &document, &buffers, &infos, &options,
+        ));
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        assert!(decode.as_mut().poll(&mut cx).is_pending());
+        drop(decode);
+        drop(held);
+        assert_eq!(*finished.0.lock().unwrap(), [(Some(0), true)]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_promotion_policy_forbids_widening() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let infos = attr_infos_from_primitive(&prim)?;
+
+        // u16 indices; f32 positions and texcoords
+        let strict = DecodeOptions::default().with_promotions(PromotionPolicy::strict());
+        let err = decode_draco_with_options(&prim, &document, &buffers, &infos, &strict).await;
+        let Err(err @ DracoLoadError::ForbiddenPromotion { .. }) = err else {
+            panic!("expected a forbidden promotion, got {err:?}");
+        };
+        assert_eq!(
+            err.to_string(),
+            "indices is stored as u16 and would be promoted to u32"
+        );
+        assert!(err.hint().is_some());
+
+        let policy = PromotionPolicy {
+            indices: true,
+            ..PromotionPolicy::strict()
+        };
+        let options = DecodeOptions::default().with_promotions(policy);
+        let out = decode_draco_with_options(&prim, &document, &buffers, &infos, &options).await?;
+        assert_eq!(out.indices.len(), 12639);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_scene_visitor_in_hierarchy_order() -> Result<(), Box<dyn std::error::Error>> {
+        #[derive(Default)]
+        struct Recorder(Vec<String>);
+
+        impl SceneVisitor for Recorder {
+            fn node(&mut self, node: &SceneNode) {
+                let event = format!("node {} <- {:?}", node.index, node.parent);
+                self.0.push(event);
+            }
+
+            fn mesh(&mut self, node: &SceneNode, h: MeshHandle, _: &ScenePrimitive, first: bool) {
+                let event = format!("mesh {} {}/{} {first}", node.index, h.mesh, h.primitive);
+                self.0.push(event);
+            }
+
+            fn skin(&mut self, node: &SceneNode, skin: &SceneSkin) {
+                let event = format!("skin {} {:?}", node.index, skin.joints);
+                self.0.push(event);
+            }
+
+            fn camera(&mut self, node: &SceneNode, _: &SceneCamera) {
+                self.0.push(format!("camera {}", node.index));
+            }
+        }
+
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let mut scene = decode_default_scene(&doc, &buffers).await?;
+        assert_eq!(
+            scene.nodes[0].world_transform,
+            scene.primitives[0].world_transform
+        );
+
+        // a skinned child instancing the same mesh, and a camera on the root
+        let mut child = scene.nodes[0].clone();
+        (child.index, child.parent, child.skin) = (1, Some(0), Some(0));
+        scene.nodes[0].children = vec![1];
+        scene.nodes.push(child);
+        let mut instance = scene.primitives[0].clone();
+        instance.node = 1;
+        scene.primitives.push(instance);
+        scene.skins.push(SceneSkin {
+            index: 0,
+            name: None,
+            joints: vec![0],
+            skeleton: None,
+        });
+        scene.cameras.push(SceneCamera {
+            node: 0,
+            name: None,
+            world_transform: scene.nodes[0].world_transform,
+            projection: Projection::Orthographic {
+                xmag: 1.0,
+                ymag: 1.0,
+                znear: 0.1,
+                zfar: 10.0,
+            },
+        });
+
+        let mut recorder = Recorder::default();
+        scene.visit(&mut recorder);
+        assert_eq!(
+            recorder.0,
+            [
+                "node 0 <- None",
+                "mesh 0 0/0 true",
+                "camera 0",
+                "node 1 <- Some(0)",
+                "mesh 1 0/0 false",
+                "skin 1 [0]",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_derived_infos_match_hand_built() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let prim = doc
+            .meshes()
+            .last()
+            .and_then(|m| m.primitives().last())
+            .ok_or("no primitive")?;
+
+        let infos = attr_infos_from_primitive(&prim)?;
+        let hand_built = [(0, 3), (1, 2)].map(|(unique_id, dim)| AttrInfo {
+            unique_id,
+            dim,
+            data_type: 9,
+        });
+        assert_eq!(infos, hand_built);
+
+        let derived = decode_draco_derived(&prim, &doc, &buffers).await?;
+        let without_infos = decode_draco(&prim, &doc, &buffers, &Vec::new()).await?;
+        assert_eq!(without_infos.positions, derived.positions);
+        let expected = decode_test_glb("examples/test.glb").await?;
+        assert_eq!(derived.indices, expected.indices);
+        assert_eq!(derived.positions, expected.positions);
+        assert_eq!(derived.texcoords, expected.texcoords);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_material_summaries_read_extensions() -> Result<(), Box<dyn std::error::Error>> {
+        let root: gltf::json::Root = serde_json::from_str(
+            r#"{
+                "asset": { "version": "2.0" },
+                "materials": [
+                    { "name": "glass", "alphaMode": "BLEND", "extensions": {
+                        "KHR_materials_transmission": { "transmissionFactor": 0.9 },
+                        "KHR_materials_volume": { "thicknessFactor": 0.1 },
+                        "KHR_materials_ior": {}
+                    } },
+                    { "emissiveFactor": [1, 0.5, 0], "extensions": {
+                        "KHR_materials_emissive_strength": { "emissiveStrength": 5 },
+                        "KHR_materials_clearcoat": {
+                            "clearcoatFactor": 1,
+                            "clearcoatNormalTexture": { "index": 2 }
+                        },
+                        "KHR_materials_unlit": {}
+                    } }
+                ]
+            }"#,
+        )?;
+        let document = gltf::Document::from_json_without_validation(root);
+        let [glass, paint] = <[MaterialSummary; 2]>::try_from(material_summaries(&document))
+            .map_err(|_| "expected two materials")?;
+
+        assert_eq!(glass.name.as_deref(), Some("glass"));
+        assert!(glass.is_transparent() && glass.volume && !glass.has_clearcoat());
+        assert_eq!((glass.transmission, glass.ior), (Some(0.9), Some(1.5)));
+        assert_eq!(glass.emissive_strength, 1.0);
+        assert_eq!(
+            glass.extensions,
+            [
+                "KHR_materials_ior",
+                "KHR_materials_transmission",
+                "KHR_materials_volume"
+            ]
+        );
+
+        assert!(!paint.is_transparent() && paint.unlit && paint.has_clearcoat());
+        assert_eq!(paint.emissive_factor, [1.0, 0.5, 0.0]);
+        assert_eq!(paint.emissive_strength, 5.0);
+        let clearcoat = paint.clearcoat.ok_or("no clearcoat")?;
+        assert_eq!(
+            (clearcoat.roughness_factor, clearcoat.normal_texture),
+            (0.0, Some(2))
+        );
+
+        // the scene carries a summary for every material its primitives use
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let scene = decode_default_scene(&doc, &buffers).await?;
+        let material = scene.primitives[0].material;
+        assert_eq!(scene.materials.len(), 1);
+        assert_eq!(scene.material(material), scene.materials.first());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stream_attributes_from_header() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let bytes = get_buffer(&document, &buffers, draco_extension(&prim)?.buffer_view)?;
+
+        let attributes = stream_attributes(bytes).ok_or("header not readable")?;
+        let layout: Vec<(u32, u8, u8, u8)> = attributes
+            .iter()
+            .map(|a| (a.unique_id, a.attribute_type, a.components, a.data_type))
+            .collect();
+        assert_eq!(layout, [(0, 0, 3, 9), (1, 3, 2, 9)]);
+        let infos: Vec<AttrInfo> = attributes.iter().map(StreamAttribute::info).collect();
+        assert_eq!(infos, attr_infos_from_primitive(&prim)?);
+        // cut inside the connectivity
+        assert_eq!(stream_attributes(&bytes[..1000]), None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stream_attributes_across_encodings() -> Result<(), Box<dyn std::error::Error>> {
+        // a 4x4 grid with positions, texcoords and normals, written by the Draco encoder
+        for encoding in [
+            "sequential",
+            "sequential_compressed",
+            "edgebreaker",
+            "predictive",
+            "valence",
+        ] {
+            let bytes = std::fs::read(format!("examples/streams/grid_{encoding}.drc"))?;
+            let attributes = stream_attributes(&bytes).ok_or(encoding)?;
+            let layout: Vec<(u32, u8, u8, u8)> = attributes
+                .iter()
+                .map(|a| (a.unique_id, a.attribute_type, a.components, a.data_type))
+                .collect();
+            assert_eq!(
+                layout,
+                [(0, 0, 3, 9), (1, 3, 2, 9), (2, 1, 3, 9)],
+                "{encoding}"
+            );
+            assert_eq!(estimate_counts(&bytes).map(|(_, faces)| faces), Some(32));
+
+            // the decoder writes exactly the parsed layout
+            let infos: Vec<AttrInfo> = attributes.iter().map(StreamAttribute::info).collect();
+            let raw = decode_with_config(&bytes, &decode_config(25, 96, &infos)).await?;
+            assert_eq!(raw.len(), 96 * 2 + 25 * 8 * 4, "{encoding}");
+        }
+
+        // older streams: u32 counts before 2.2, u32 attribute counts before 2.0, u16 ids
+        // before 1.3
+        let positions = [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        for (major, minor) in [(1, 2), (2, 0), (2, 1)] {
+            let bytes = old_sequential_stream(major, minor, &positions, 7);
+            let attributes = stream_attributes(&bytes).ok_or("old header")?;
+            assert_eq!(attributes.len(), 1);
+            assert_eq!(
+                attributes[0].info(),
+                AttrInfo {
+                    unique_id: 7,
+                    dim: 3,
+                    data_type: 9
+                }
+            );
+            assert_eq!(estimate_counts(&bytes), Some((3, 1)));
+            let raw =
+                decode_with_config(&bytes, &decode_config(3, 3, &[attributes[0].info()])).await?;
+            let decoded: Vec<f32> = raw[6..]
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect();
+            assert_eq!(decoded, positions.as_flattened(), "{major}.{minor}");
+        }
+        Ok(())
+    }
+
+    /// One triangle, sequentially encoded with raw indices and raw float positions, as
+    /// encoders before bitstream 2.2 wrote it.
+    fn old_sequential_stream(major: u8, minor: u8, positions: &[[f32; 3]], id: u16) -> Vec<u8> {
+        let mut out = b"DRACO".to_vec();
+        out.extend([major, minor, 1, 0, 0, 0]); // mesh, sequential, no flags
+        out.extend(1u32.to_le_bytes()); // faces
+        out.extend((positions.len() as u32).to_le_bytes());
+        out.extend([1, 0, 1, 2]); // raw u8 indices
+        out.push(1); // attribute decoders
+        if (major, minor) < (2, 0) {
+            out.extend(1u32.to_le_bytes());
+        } else {
+            out.push(1);
+        }
+        out.extend([0, 9, 3, 0]); // position, f32, 3 components
+        if (major, minor) < (1, 3) {
+            out.extend(id.to_le_bytes());
+        } else {
+            out.push(id as u8);
+        }
+        out.push(0); // generic sequential attribute decoder
+        out.extend(positions.iter().flatten().flat_map(|v| v.to_le_bytes()));
+        out
+    }
+
+    #[test]
+    fn test_hostile_headers_rejected_quickly() {
+        // sub-metadata nested three bytes per level, far deeper than any encoder writes
+        let mut nested = b"DRACO\x02\x02\x01\x01\x00\x80\x00".to_vec();
+        for _ in 0..400_000 {
+            nested.extend([0, 1, 0]); // no entries, one sub-metadata with an empty name
+        }
+        assert_eq!(stream_attributes(&nested), None);
+
+        // a billion faces whose tagged symbols the twenty bytes can't hold
+        let mut faces = b"DRACO\x02\x02\x01\x00\x00\x00".to_vec();
+        faces.extend([0xff, 0xff, 0xff, 0xff, 0x04, 0x03, 0x00, 0x00]);
+        faces.extend([0x01, 0x00, 0x01, 0x40]);
+        assert_eq!(stream_attributes(&faces), None);
+    }
+
+    #[tokio::test]
+    async fn test_given_infos_checked_against_header() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let wrong = [AttrInfo {
+            unique_id: 0,
+            dim: 4,
+            data_type: 2,
+        }];
+        let err = decode_draco_raw(&prim, &document, &buffers, &wrong)
+            .await
+            .unwrap_err();
+        let DracoLoadError::AttributeMismatch(conflicts) = &err else {
+            panic!("unexpected error {err}");
+        };
+        let stored = AttrInfo {
+            unique_id: 0,
+            dim: 3,
+            data_type: 9,
+        };
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].stored, Some(stored));
+        assert_eq!(conflicts[0].semantic.as_deref(), Some("POSITION"));
+
+        // a subset of the stored attributes is fine; the decoder still gets all of them
+        let subset = &stream_attr_infos(get_buffer(&document, &buffers, 0)?).ok_or("header")?[..1];
+        let raw = decode_draco_raw(&prim, &document, &buffers, subset).await?;
+        assert_eq!(raw.attributes.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_attribute_transforms_in_conversion() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let prim = doc
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let infos = attr_infos_from_primitive(&prim)?;
+
+        let plain = decode_draco(&prim, &doc, &buffers, &infos).await?;
+        let options = DecodeOptions::new()
+            .with_transform(
+                gltf::Semantic::Positions,
+                AttributeTransform::uniform_scale(0.01),
+            )
+            .with_transform(
+                gltf::Semantic::TexCoords(0),
+                AttributeTransform::IDENTITY.with_offset([0.5, 0.25, 0.0, 0.0]),
+            );
+        let moved = decode_draco_with_options(&prim, &doc, &buffers, &infos, &options).await?;
+        let (before, after) = (plain.positions.ok_or("no positions")?, moved.positions);
+        for (a, b) in before.iter().zip(after.iter().flatten()) {
+            assert_eq!(a.map(|x| x * 0.01), *b);
+        }
+        for (a, b) in plain.texcoords[&0].iter().zip(&moved.texcoords[&0]) {
+            assert_eq!([a[0] + 0.5, a[1] + 0.25], *b);
+        }
+        assert_eq!(plain.indices, moved.indices);
+        Ok(())
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn test_blocking_decode_matches_async() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let prim = doc
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let infos = attr_infos_from_primitive(&prim)?;
+
+        let blocking = decode_draco_blocking(&prim, &doc, &buffers, &infos)?;
+        // awaited on a real runtime, not through the executor the blocking path uses
+        let runtime = tokio::runtime::Builder::new_multi_thread().build()?;
+        let awaited = runtime.block_on(decode_draco(&prim, &doc, &buffers, &infos))?;
+        assert_eq!(blocking.indices, awaited.indices);
+        assert_eq!(blocking.positions, awaited.positions);
+        assert_eq!(blocking.texcoords, awaited.texcoords);
+        let options = DecodeOptions::new().with_flip_v(true);
+        let flipped = decode_draco_blocking_with_options(&prim, &doc, &buffers, &infos, &options)?;
+        assert_eq!(flipped.indices, blocking.indices);
+        let (uv, flipped_uv) = (&blocking.texcoords[&0], &flipped.texcoords[&0]);
+        assert!(!uv.is_empty() && uv.len() == flipped_uv.len());
+        for (a, b) in uv.iter().zip(flipped_uv) {
+            assert_eq!(*b, [a[0], 1.0 - a[1]]);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decode_mesh_draco_and_plain() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let expected = decode_test_glb("examples/test.glb").await?;
+        let options = DecodeOptions::new();
+
+        let mesh = document.meshes().next().ok_or("no mesh")?;
+        let draco = decode_mesh(&mesh, &document, &buffers, &options).await?;
+        assert_eq!(draco.index, 0);
+        assert_eq!(draco.name.as_deref(), mesh.name());
+        assert_eq!(draco.primitives.len(), mesh.primitives().len());
+        assert_eq!(draco.primitives[0].indices, expected.indices);
+
+        let stripped = strip_draco(&document, &buffers, |_, _| true).await?;
+        let reloaded = gltf::Gltf::from_slice_without_validation(&stripped.to_glb()?)?;
+        let bufs = gltf::import_buffers(&reloaded.document, None, reloaded.blob)?;
+        let mesh = reloaded.document.meshes().next().ok_or("no mesh")?;
+        let plain = decode_mesh(&mesh, &reloaded.document, &bufs, &options).await?;
+        assert_eq!(plain.primitives[0].indices, expected.indices);
+        assert_eq!(plain.primitives[0].positions, expected.positions);
+        assert_eq!(plain.primitives[0].texcoords, expected.texcoords);
+        Ok(())
+    }
+
+    #[test]
+    fn test_capability_report() -> Result<(), Box<dyn std::error::Error>> {
+        let caps = features();
+        assert_eq!(caps.modes, [gltf::mesh::Mode::Triangles]);
+        assert!(supports(Extension::DracoMeshCompression));
+        assert!(!supports(Extension::MeshoptCompression));
+        assert_eq!(caps.bitstream_versions, supported_versions());
+        assert_eq!(
+            caps.cargo_features.contains(&"mmap"),
+            cfg!(feature = "mmap")
+        );
+        for e in Extension::ALL {
+            assert_eq!(Extension::from_name(e.name()), Some(e));
+        }
+
+        let (document, _) = load_glb("examples/test.glb")?;
+        assert!(caps.unsupported_required(&document).is_empty());
+        let mut root = document.into_json();
+        root.extensions_required = vec![
+            "KHR_draco_mesh_compression".to_string(),
+            "EXT_meshopt_compression".to_string(),
+            "VENDOR_unknown".to_string(),
+        ];
+        let document = gltf::Document::from_json_without_validation(root);
+        assert_eq!(
+            caps.unsupported_required(&document),
+            ["EXT_meshopt_compression", "VENDOR_unknown"]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decode_document_reads_plain() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let expected = decode_test_glb("examples/test.glb").await?;
+        let scene = decode_document(&document, &buffers, &DecodeOptions::default()).await?;
+        assert_eq!(scene.primitives.len(), 1);
+        assert_eq!(scene.primitives[0].decoded.indices, expected.indices);
+
+        let stripped = strip_draco(&document, &buffers, |_, _| true).await?;
+        let reloaded = gltf::Gltf::from_slice_without_validation(&stripped.to_glb()?)?;
+        let bufs = gltf::import_buffers(&reloaded.document, None, reloaded.blob)?;
+        let draco_only = decode_default_scene(&reloaded.document, &bufs).await?;
+        assert!(draco_only.primitives.is_empty());
+        let whole = decode_document(&reloaded.document, &bufs, &DecodeOptions::default()).await?;
+        assert_eq!(whole.primitives.len(), 1);
+        let node = reloaded.document.nodes().next().ok_or("no nodes")?;
+        assert_eq!(
+            whole.primitives[0].world_transform,
+            node.transform().matrix()
+        );
+        assert_eq!(whole.nodes.len(), draco_only.nodes.len());
+        assert_eq!(whole.primitives[0].decoded.positions, expected.positions);
+        assert_eq!(whole.primitives[0].decoded.texcoords, expected.texcoords);
+        assert!(whole.skipped.is_empty());
+
+        let options = DecodeOptions::new().with_semantics([gltf::Semantic::Positions]);
+        let positions = decode_document(&reloaded.document, &bufs, &options).await?;
+        assert_eq!(
+            positions.primitives[0].decoded.positions,
+            expected.positions
+        );
+        assert!(positions.primitives[0].decoded.texcoords.is_empty());
+
+        let (document, buffers) = non_indexed_triangle()?;
+        let mut root = document.into_json();
+        root.meshes[0].primitives[0].mode =
+            gltf::json::validation::Checked::Valid(gltf::mesh::Mode::Lines);
+        let lines = gltf::Document::from_json_without_validation(root);
+        let scene = decode_document(&lines, &buffers, &DecodeOptions::default()).await?;
+        assert!(scene.primitives.is_empty());
+        let skipped = SkippedPrimitive {
+            node: 0,
+            mesh: 0,
+            primitive: 0,
+            mode: gltf::mesh::Mode::Lines,
+        };
+        assert_eq!(scene.skipped, [skipped]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_plain_primitive_without_indices() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = non_indexed_triangle()?;
+        let mesh = document.meshes().next().ok_or("no mesh")?;
+        let prim = mesh.primitives().next().ok_or("no primitive")?;
+        let options = DecodeOptions::new();
+        let decoded = decode_primitive(&prim, &document, &buffers, &options).await?;
+        assert_eq!(decoded.indices, [0, 1, 2]);
+        assert_eq!(decoded.positions.as_ref().map(Vec::len), Some(3));
+        let decoded_mesh = decode_mesh(&mesh, &document, &buffers, &options).await?;
+        assert_eq!(decoded_mesh.primitives[0].indices, [0, 1, 2]);
+        let scene = decode_document(&document, &buffers, &DecodeOptions::default()).await?;
+        assert_eq!(scene.primitives.len(), 1);
+        assert_eq!(scene.primitives[0].decoded.indices, [0, 1, 2]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_plan_then_execute() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let expected = decode_test_glb("examples/test.glb").await?;
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let plan = plan_decode(&prim, &document)?;
+        let positions = expected.positions.as_ref().ok_or("no positions")?;
+        assert_eq!(plan.vertex_count, positions.len());
+        assert_eq!(plan.index_count, expected.indices.len());
+        let pos = plan
+            .attribute(&gltf::Semantic::Positions)
+            .ok_or("POSITION not planned")?;
+        assert_eq!(
+            (pos.data_type, pos.components),
+            (gltf::accessor::DataType::F32, 3)
+        );
+        assert_eq!(pos.byte_size, positions.len() * 12);
+        assert_eq!(plan.attributes.len(), 2);
+
+        let out = execute(&plan, &buffers).await?;
+        assert_eq!(out.indices.len(), plan.index_byte_size());
+        let indices: Vec<u32> = match plan.index_type {
+            gltf::accessor::DataType::U16 => bytemuck::cast_slice::<u8, u16>(&out.indices)
+                .iter()
+                .map(|&i| i as u32)
+                .collect(),
+            _ => bytemuck::cast_slice::<u8, u32>(&out.indices).to_vec(),
+        };
+        assert_eq!(indices, expected.indices);
+        assert_eq!(
+            out.attributes[0],
+            bytemuck::cast_slice::<[f32; 3], u8>(positions)
+        );
+
+        let mut indices = vec![0u8; plan.index_byte_size()];
+        let mut attrs: Vec<Vec<u8>> = plan
+            .attributes
+            .iter()
+            .map(|a| vec![0; a.byte_size])
+            .collect();
+        let mut targets: Vec<&mut [u8]> = attrs.iter_mut().map(Vec::as_mut_slice).collect();
+        execute_into(&plan, &buffers, &mut indices, &mut targets).await?;
+        assert_eq!((indices, attrs), (out.indices, out.attributes));
+
+        let mut wrong = plan.clone();
+        wrong.attributes[0].data_type = gltf::accessor::DataType::U16;
+        assert!(matches!(
+            execute(&wrong, &buffers).await,
+            Err(DracoLoadError::PlanMismatch {
+                stored: Some(_),
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_glb_containers() -> Result<(), Box<dyn std::error::Error>> {
+        let glb = std::fs::read("examples/test.glb")?;
+        let expected = decode_test_glb("examples/test.glb").await?;
+
+        let mut stream = glb.clone();
+        stream.extend([0; 4]);
+        stream.extend(&glb);
+        let container = GlbContainer::parse(&stream)?;
+        assert_eq!(container.kind(), ContainerKind::Concatenated);
+        let offsets: Vec<usize> = container.glbs().iter().map(|g| g.offset).collect();
+        assert_eq!(offsets, [0, glb.len() + 4]);
+        for entry in decode_container(&stream).await? {
+            assert_eq!(entry.scene?.primitives[0].decoded.indices, expected.indices);
+        }
+        assert!(matches!(
+            GlbContainer::parse(&stream[..stream.len() - 1]),
+            Err(DracoLoadError::BadContainer(at, _)) if at == glb.len() + 4
+        ));
+        assert!(matches!(
+            GlbContainer::parse(b"not a bundle"),
+            Err(DracoLoadError::BadContainer(0, _))
+        ));
+
+        // a GLB whose buffer is another entry of the archive
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let mut root = document.into_json();
+        root.buffers[0].uri = Some("../bin/test.bin".into());
+        let external = gltf::binary::Glb {
+            header: gltf::binary::Header {
+                magic: *b"glTF",
+                version: 2,
+                length: 0,
+            },
+            json: serde_json::to_vec(&root)?.into(),
+            bin: None,
+        }
+        .to_vec()?;
+        let zip = zip_archive(&[
+            ("models/a.glb", &glb),
+            ("readme.txt", b"two models"),
+            ("models/b.glb", &external),
+            ("bin/test.bin", &buffers[0].0),
+        ]);
+        let container = GlbContainer::parse(&zip)?;
+        assert_eq!(container.kind(), ContainerKind::Zip);
+        let names: Vec<_> = container
+            .glbs()
+            .into_iter()
+            .filter_map(|g| g.name)
+            .collect();
+        assert_eq!(names, ["models/a.glb", "models/b.glb"]);
+        for entry in container.decode_all().await {
+            let scene = entry.scene?;
+            assert_eq!(scene.primitives[0].decoded.positions, expected.positions);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn test_deflated_zip_entries() -> Result<(), Box<dyn std::error::Error>> {
+        let glb = std::fs::read("examples/test.glb")?;
+        let deflated = miniz_oxide::deflate::compress_to_vec(&glb, 6);
+        let mut zip = zip_archive(&[("a.glb", &deflated)]);
+        // turn the stored entry into a deflated one
+        let central = 30 + "a.glb".len() + deflated.len();
+        for (method, size) in [(8, 22), (central + 10, central + 24)] {
+            zip[method] = 8;
+            zip[size..size + 4].copy_from_slice(&(glb.len() as u32).to_le_bytes());
+        }
+        let container = GlbContainer::parse(&zip)?;
+        let glbs = container.glbs();
+        assert_eq!(container.bytes(&glbs[0])?, &glb[..]);
+        let (document, _) = container.load(&glbs[0])?;
+        assert_eq!(document.meshes().len(), 1);
+        let capped = container.with_max_inflated_size(glb.len() - 1);
+        assert!(matches!(
+            capped.bytes(&glbs[0]),
+            Err(DracoLoadError::BadContainer(
+                _,
+                "deflated entry inflates past the container's limit"
+            ))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decode_primitive_either_way() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let options = DecodeOptions::new();
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let draco = decode_primitive(&prim, &document, &buffers, &options).await?;
+
+        let stripped = strip_draco(&document, &buffers, |_, _| true).await?;
+        let reloaded = gltf::Gltf::from_slice_without_validation(&stripped.to_glb()?)?;
+        let bufs = gltf::import_buffers(&reloaded.document, None, reloaded.blob)?;
+        let prim = reloaded
+            .document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let plain = decode_primitive(&prim, &reloaded.document, &bufs, &options).await?;
+        assert_eq!(plain.indices, draco.indices);
+        assert_eq!(plain.positions, draco.positions);
+        assert_eq!(plain.texcoords, draco.texcoords);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_width_and_value_policies() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let narrow = DecodeOptions::new().with_index_width_check(IndexWidthCheck::U16);
+        decode_draco_derived_with_options(&prim, &document, &buffers, &narrow).await?;
+        assert!(matches!(
+            narrow.check_vertex_count(70_000),
+            Err(DracoLoadError::IndexWidthExceeded(70_000))
+        ));
+        assert!(narrow.check_vertex_count(65_535).is_ok());
+
+        let mut p = DecodedPrimitive {
+            colors: [(0, vec![[1.5, 0.5, -0.25, 0.5]])].into(),
+            weights: [
+                (0, vec![[0.5, 0.25, 0.0, 0.0]]),
+                (1, vec![[0.25, 0.0, 0.0, 0.0]]),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        DecodeOptions::new()
+            .with_color_policy(ColorPolicy::PremultiplyAlpha)
+            .with_weight_policy(WeightPolicy::Normalize)
+            .apply_conventions(&mut p);
+        assert_eq!(p.colors[&0], [[0.5, 0.25, 0.0, 0.5]]);
+        assert_eq!(p.weights[&0], [[0.5, 0.25, 0.0, 0.0]]);
+        assert_eq!(p.weights[&1], [[0.25, 0.0, 0.0, 0.0]]);
+        p.weights = [(0, vec![[0.5, 0.25, 0.0, 0.0]])].into();
+        DecodeOptions::new()
+            .with_weight_policy(WeightPolicy::Normalize)
+            .apply_conventions(&mut p);
+        let sum: f32 = p.weights[&0][0].iter().sum();
+        assert!((sum - 1.0).abs() < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_normals_from_angle_threshold() {
+        // two triangles folded 90 degrees along the shared edge 0-1
+        let mut fold = DecodedPrimitive {
+            indices: vec![0, 1, 2, 1, 0, 3],
+            positions: Some(vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0],
+            ]),
+            ..Default::default()
+        };
+        let mut smooth = fold.clone();
+        assert_eq!(fold.recompute_normals_with_angle(60.0), 2);
+        let normals = fold.normals.as_ref().unwrap();
+        assert_eq!(normals.len(), 6);
+        for (t, face) in [[0.0, 0.0, 1.0], [0.0, 1.0, 0.0]].into_iter().enumerate() {
+            for &i in &fold.indices[t * 3..t * 3 + 3] {
+                assert_eq!(normals[i as usize], face);
+            }
+        }
+        assert_eq!(
+            fold.positions.as_ref().unwrap()[4..],
+            [[1.0, 0.0, 0.0], [0.0; 3]]
+        );
+
+        assert_eq!(smooth.recompute_normals_with_angle(120.0), 0);
+        let n = smooth.normals.as_ref().unwrap()[0];
+        assert!((n[1] - n[2]).abs() < 1e-6 && n[0] == 0.0 && n[1] > 0.0);
+
+        // a UV seam: coplanar triangles that don't share vertices still smooth together
+        let mut seam = DecodedPrimitive {
+            indices: vec![0, 1, 2, 3, 4, 5],
+            positions: Some(vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(seam.recompute_normals_with_angle(30.0), 0);
+        assert!(seam.normals.unwrap().iter().all(|n| *n == [0.0, 0.0, 1.0]));
+
+        // a flat fan whose every triangle has its own copy of the hub: one position group of
+        // 20000 corners sharing a single face normal
+        let spokes = 20000u32;
+        let mut fan = DecodedPrimitive {
+            positions: Some(
+                (0..spokes)
+                    .flat_map(|i| {
+                        let a = i as f32 / spokes as f32 * std::f32::consts::TAU;
+                        let b = (i + 1) as f32 / spokes as f32 * std::f32::consts::TAU;
+                        [
+                            [0.0, 0.0, 0.0],
+                            [a.cos(), a.sin(), 0.0],
+                            [b.cos(), b.sin(), 0.0],
+                        ]
+                    })
+                    .collect(),
+            ),
+            indices: (0..spokes * 3).collect(),
+            ..Default::default()
+        };
+        assert_eq!(fan.recompute_normals_with_angle(30.0), 0);
+        assert!(
+            fan.normals
+                .unwrap()
+                .iter()
+                .all(|n| (n[2] - 1.0).abs() < 1e-5)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decode_only_requested_semantics() -> Result<(), Box<dyn std::error::Error>> {
+        let (document, buffers) = load_glb("examples/test.glb")?;
+        let expected = decode_test_glb("examples/test.glb").await?;
+        let options = DecodeOptions::new().with_semantics([gltf::Semantic::Positions]);
+        assert!(!options.converts(&gltf::Semantic::TexCoords(0)));
+        let prim = document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let decoded = decode_primitive(&prim, &document, &buffers, &options).await?;
+        assert_eq!(decoded.indices, expected.indices);
+        assert_eq!(decoded.positions, expected.positions);
+        assert!(decoded.texcoords.is_empty());
+
+        let stripped = strip_draco(&document, &buffers, |_, _| true).await?;
+        let reloaded = gltf::Gltf::from_slice_without_validation(&stripped.to_glb()?)?;
+        let bufs = gltf::import_buffers(&reloaded.document, None, reloaded.blob)?;
+        let mesh = reloaded.document.meshes().next().ok_or("no mesh")?;
+        let plain = decode_mesh(&mesh, &reloaded.document, &bufs, &options).await?;
+        assert_eq!(plain.primitives[0].positions, expected.positions);
+        assert!(plain.primitives[0].texcoords.is_empty());
+
+        // the vertex count checks still see positions that weren't asked for
+        let prim = mesh.primitives().next().ok_or("no primitive")?;
+        let uvs = DecodeOptions::new().with_semantics([gltf::Semantic::TexCoords(0)]);
+        let short = uvs
+            .clone()
+            .with_deltas(VertexDeltas::new(vec![[0.0; 3]; 3]));
+        let err = decode_primitive(&prim, &reloaded.document, &bufs, &short).await;
+        assert!(matches!(err, Err(DracoLoadError::DeltaCountMismatch(n, 3)) if n > 3));
+        let only_uvs = decode_primitive(&prim, &reloaded.document, &bufs, &uvs).await?;
+        assert!(only_uvs.positions.is_none());
+        assert_eq!(only_uvs.texcoords.get(&0), expected.texcoords.get(&0));
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_load_glb_without_blocking() -> Result<(), Box<dyn std::error::Error>> {
+        let loaded = load_glb_async("examples/test.glb").await?;
+        let prim = loaded
+            .document
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let decoded = decode_draco_derived(&prim, &loaded.document, &loaded.buffers).await?;
+        let expected = decode_test_glb("examples/test.glb").await?;
+        assert_eq!(decoded.indices, expected.indices);
+        assert_eq!(decoded.positions, expected.positions);
+
+        let missing = load_glb_async("examples/missing.glb").await;
+        assert!(matches!(
+            missing,
+            Err(DracoLoadError::Gltf(gltf::Error::Io(_)))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_attribute_plugin_dispatch() -> Result<(), Box<dyn std::error::Error>> {
+        use std::ffi::c_void;
+
+        // doubles u8 values, rejects anything else with code 7
+        unsafe extern "C" fn double(
+            _: *mut c_void,
+            input: *const AttributePluginInput,
+            out: *mut f32,
+            len: usize,
+        ) -> i32 {
+            let input = unsafe { &*input };
+            if input.component_type != 5121 {
+                return 7;
+            }
+            let bytes = unsafe { std::slice::from_raw_parts(input.bytes, input.byte_len) };
+            let out = unsafe { std::slice::from_raw_parts_mut(out, len) };
+            for (o, b) in out.iter_mut().zip(bytes) {
+                *o = *b as f32 * 2.0;
+            }
+            0
+        }
+        let plugin = AttributePlugin {
+            abi_version: ATTRIBUTE_PLUGIN_ABI,
+            prefix: c"_PLUGTEST_".as_ptr(),
+            components: 1,
+            user_data: std::ptr::null_mut(),
+            decode: Some(double),
+            destroy: None,
+        };
+        unsafe { register_attribute_plugin(&plugin)? };
+        let stale = AttributePlugin {
+            abi_version: ATTRIBUTE_PLUGIN_ABI + 1,
+            ..plugin
+        };
+        assert!(matches!(
+            unsafe { register_attribute_plugin(&stale) },
+            Err(DracoLoadError::AttributePluginAbi(_))
+        ));
+
+        let fill = |name: &str, dt| {
+            let bytes = [1u8, 2, 3];
+            let blk = AttrSlice {
+                unique_id: 0,
+                bytes: &bytes,
+                dim: 1,
+                dt,
+            };
+            let map = SemanticMap::from([(0, (gltf::Semantic::Extras(name.into()), 1, false))]);
+            let mut p = DecodedPrimitive::default();
+            fill_primitive(&mut p, &[blk], &map).map(|_| p)
+        };
+        let p = fill("PLUGTEST_A", AttributeDataType::UInt8)?;
+        assert_eq!(
+            p.custom["_PLUGTEST_A"].values,
+            CustomValues::F32(vec![2.0, 4.0, 6.0])
+        );
+        let p = fill("OTHER", AttributeDataType::UInt8)?;
+        assert_eq!(p.custom["_OTHER"].values, CustomValues::U8(vec![1, 2, 3]));
+        let err = fill("PLUGTEST_A", AttributeDataType::Int8).err();
+        assert!(matches!(
+            err,
+            Some(DracoLoadError::AttributePlugin { code: 7, .. })
+        ));
+        assert!(unregister_attribute_plugin("_PLUGTEST_"));
+        assert!(!unregister_attribute_plugin("_PLUGTEST_"));
+        let p = fill("PLUGTEST_A", AttributeDataType::UInt8)?;
+        assert_eq!(
+            p.custom["_PLUGTEST_A"].values,
+            CustomValues::U8(vec![1, 2, 3])
+        );
+
+        // one table registered twice and under a second prefix is destroyed once, last
+        static DESTROYED: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        unsafe extern "C" fn destroy(_: *mut c_void) {
+            DESTROYED.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+        let owned = AttributePlugin {
+            prefix: c"_PLUGOWN_".as_ptr(),
+            user_data: std::ptr::NonNull::<u8>::dangling().as_ptr().cast(),
+            destroy: Some(destroy),
+            ..plugin
+        };
+        let destroyed = || DESTROYED.load(std::sync::atomic::Ordering::SeqCst);
+        unsafe { register_attribute_plugin(&owned)? };
+        unsafe { register_attribute_plugin(&owned)? };
+        let other = AttributePlugin {
+            prefix: c"_PLUGOWN_B_".as_ptr(),
+            ..owned
+        };
+        unsafe { register_attribute_plugin(&other)? };
+        assert_eq!(destroyed(), 0);
+        assert!(unregister_attribute_plugin("_PLUGOWN_"));
+        assert_eq!(destroyed(), 0);
+        assert!(unregister_attribute_plugin("_PLUGOWN_B_"));
+        assert_eq!(destroyed(), 1);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "mmap")]
+    #[tokio::test]
+    async fn test_out_of_core_spills_past_budget() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let index = DocumentIndex::build(&doc)?;
+        let expected = index.decode(0, 0, &buffers, &DecodeOptions::new()).await?;
+
+        let dir = std::env::temp_dir();
+        let roomy = index
+            .decode_out_of_core(&buffers, &SpillOptions::new(usize::MAX))
+            .await?;
+        assert!(!roomy[0].is_spilled());
+
+        let stored = index
+            .decode_out_of_core(&buffers, &SpillOptions::new(0).with_dir(&dir))
+            .await?;
+        let StoredPrimitive::Spilled(spilled) = &stored[0] else {
+            panic!("expected a spilled primitive");
+        };
+        let path = spilled.path().to_path_buf();
+        assert_eq!(std::fs::metadata(&path)?.len() as usize, spilled.len());
+
+        let view = stored[0].view();
+        assert_eq!(view.vertex_count(), 5849);
+        assert_eq!(view.indices, expected.indices);
+        assert_eq!(view.texcoords[&0], expected.texcoords[&0].as_slice());
+        let reloaded = view.to_decoded();
+        assert_eq!(reloaded.positions, expected.positions);
+        assert_eq!(reloaded.texcoords, expected.texcoords);
+
+        drop(stored);
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[cfg(feature = "mmap")]
+    #[tokio::test]
+    async fn test_lazy_document_matches_eager_decode() -> Result<(), Box<dyn std::error::Error>> {
+        let lazy = LazyDocument::open("examples/test.glb")?;
+        let mesh = lazy
+            .document()
+            .meshes()
+            .next()
+            .ok_or("No meshes found in GLB")?;
+        let prim = lazy.primitive(mesh.index(), 0)?;
+        assert_eq!(prim.draco_bytes()?.len(), prim.info().byte_length);
+
+        let decoded = prim.decode(&DecodeOptions::new()).await?;
+        let expected = decode_test_glb("examples/test.glb").await?;
+        assert_eq!(decoded.indices, expected.indices);
+        assert_eq!(decoded.positions, expected.positions);
+        assert!(matches!(
+            lazy.primitive(mesh.index(), 99),
+            Err(DracoLoadError::NotIndexed(_, 99))
+        ));
+
+        // buffer names can't reach outside the document's directory
+        let dir = std::env::temp_dir().join(format!("draco-lazy-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        for uri in ["../outside.bin", "/etc/outside.bin"] {
+            let json = serde_json::json!({
+                "asset": { "version": "2.0" },
+                "buffers": [{ "uri": uri, "byteLength": 4 }]
+            });
+            let path = dir.join("escape.gltf");
+            std::fs::write(&path, serde_json::to_vec(&json)?)?;
+            assert!(matches!(
+                LazyDocument::open(&path),
+                Err(DracoLoadError::BadUri(u)) if u == uri
+            ));
+        }
+        std::fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "arrow")]
+    #[tokio::test]
+    async fn test_arrow_columns_and_parquet() -> Result<(), Box<dyn std::error::Error>> {
+        let decoded = decode_test_glb("examples/test.glb").await?;
+        let batch = decoded.to_arrow()?;
+        let positions = decoded.positions.as_ref().ok_or("No positions")?;
+        assert_eq!(batch.num_rows(), positions.len());
+        assert!(batch.schema().field_with_name("POSITION").is_ok());
+        assert_eq!(
+            decoded.triangles_to_arrow()?.num_rows(),
+            decoded.indices.len() / 3
+        );
+
+        let mut parquet = Vec::new();
+        decoded.write_parquet(&mut parquet)?;
+        assert!(parquet.starts_with(b"PAR1"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc-metrics")]
+    #[tokio::test]
+    async fn test_alloc_scope_reports_peak() -> Result<(), Box<dyn std::error::Error>> {
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let index = DocumentIndex::build(&doc)?;
+        let entry = index.primitives.first().ok_or("No Draco primitives")?;
+
+        let scope = AllocScope::begin();
+        let decoded = entry.decode(&buffers, &DecodeOptions::new()).await?;
+        let stats = scope.finish();
+
+        let positions = decoded.positions.as_ref().ok_or("No positions")?;
+        assert!(stats.allocations > 0);
+        assert!(stats.peak_bytes >= std::mem::size_of_val(positions.as_slice()));
+        assert!(stats.peak_bytes <= stats.allocated_bytes);
+
+        // an inner scope must not reset the outer one's high-water mark
+        let outer = AllocScope::begin();
+        drop(std::hint::black_box(vec![0u8; 1 << 20]));
+        let inner = AllocScope::begin().finish();
+        let outer = outer.finish();
+        assert!(outer.peak_bytes >= 1 << 20);
+        assert!(inner.peak_bytes <= outer.peak_bytes);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "watch")]
+    #[tokio::test]
+    async fn test_hot_reloader_reports_changes_once() -> Result<(), Box<dyn std::error::Error>> {
+        let path = std::env::temp_dir().join(format!("draco-reload-{}.glb", std::process::id()));
+        std::fs::copy("examples/test.glb", &path)?;
+
+        let options = DecodeOptions::new().with_flip_v(true);
+        let mut reloader = HotReloader::new(&path).with_options(options.clone());
+        let events = reloader.poll().await?;
+        assert_eq!(events.len(), 1);
+        let ReloadEvent::Changed {
+            mesh: 0,
+            primitive: 0,
+            decoded,
+        } = &events[0]
+        else {
+            panic!("unexpected event {:?}", events[0]);
+        };
+        let (doc, buffers) = load_glb("examples/test.glb")?;
+        let prim = doc
+            .meshes()
+            .next()
+            .and_then(|m| m.primitives().next())
+            .ok_or("no primitive")?;
+        let expected = decode_primitive(&prim, &doc, &buffers, &options).await?;
+        assert_eq!(decoded.texcoords, expected.texcoords);
+        assert!(reloader.poll().await?.is_empty());
+
+        // rewriting identical bytes bumps the mtime but must not re-decode anything
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        std::fs::write(&path, std::fs::read("examples/test.glb")?)?;
+        assert!(reloader.poll().await?.is_empty());
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    /// A ZIP archive of stored entries, without CRCs (the reader doesn't check them).
+    fn zip_archive(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let (mut out, mut central) = (Vec::new(), Vec::new());
+        for (name, data) in entries {
+            let size = (data.len() as u32).to_le_bytes();
+            let name_len = (name.len() as u16).to_le_bytes();
+            let local = (out.len() as u32).to_le_bytes();
+            out.extend(0x0403_4b50u32.to_le_bytes());
+            out.extend([20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            out.extend(size.iter().chain(&size).chain(&name_len).chain(&[0, 0]));
+            out.extend(name.as_bytes());
+            out.extend(*data);
+            central.extend(0x0201_4b50u32.to_le_bytes());
+            central.extend([20, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            central.extend(size.iter().chain(&size).chain(&name_len));
+            central.extend([0; 12]);
+            central.extend(local);
+            central.extend(name.as_bytes());
+        }
+        let dir = (out.len() as u32).to_le_bytes();
+        let count = (entries.len() as u16).to_le_bytes();
+        out.extend(&central);
+        out.extend(0x0605_4b50u32.to_le_bytes());
+        out.extend([0, 0, 0, 0]);
+        out.extend(count.iter().chain(&count));
+        out.extend((central.len() as u32).to_le_bytes());
+        out.extend(dir);
+        out.extend([0, 0]);
+        out
+    }
+
+    /// One plain, non-indexed triangle instanced by one node, buffers embedded.
+    fn non_indexed_triangle()
+    -> Result<(gltf::Document, Vec<gltf::buffer::Data>), Box<dyn std::error::Error>> {
+        let root: gltf::json::Root = serde_json::from_str(
+            r#"{
+                "asset": { "version": "2.0" },
+                "scene": 0,
+                "scenes": [{ "nodes": [0] }],
+                "nodes": [{ "mesh": 0 }],
+                "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+                "accessors": [{
+                    "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                    "min": [0, 0, 0], "max": [1, 1, 0]
+                }],
+                "bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+                "buffers": [{
+                    "byteLength": 36,
+                    "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA"
+                }]
+            }"#,
+        )?;
+        let document = gltf::Document::from_json_without_validation(root);
+        let buffers = gltf::import_buffers(&document, None, None)?;
+        Ok((document, buffers))
+    }
+
+    fn load_glb(
+        path: &str,
+    ) -> Result<(gltf::Document, Vec<gltf::buffer::Data>), Box<dyn std::error::Error>> {
+        let mut file = std::fs::File::open(path)?;
+        let glb = gltf::Gltf::from_reader_without_validation(&mut file)?;
+        let buffer_data = gltf::import_buffers(&glb.document, None, glb.blob)?;
+        Ok((glb.document, buffer_data))
+    }
+
     pub async  fn decode_test_glb(path: &str) -> Result<DecodedPrimitive, Box<dyn std::error::Error>> {
         // Open the file safely
         let mut file = std::fs::File::open(path)?;
@@ -0,0 +1,108 @@
+use std::sync::Mutex;
+use std::task::{Poll, Waker};
+
+/// Crate-wide count of running Draco decodes, shared by every entry point.
+///
+/// Every update is a single step under the lock and no waker runs while it is held, so a
+/// future dropped at any await point, or a panicking waker, leaves the state consistent.
+struct State {
+    in_flight: usize,
+    max: Option<usize>,
+    /// One entry per waiting [`acquire`], keyed by its id and removed when it is dropped.
+    waiters: Vec<(u64, Waker)>,
+    next_id: u64,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    in_flight: 0,
+    max: None,
+    waiters: Vec::new(),
+    next_id: 0,
+});
+
+/// Caps how many Draco decodes may run at once across all crate entry points; `None` lifts
+/// the cap. Callers over the limit wait asynchronously for a slot.
+pub fn set_max_concurrent_decodes(max: Option<usize>) {
+    let mut s = lock();
+    s.max = max.map(|m| m.max(1));
+    wake_all(s);
+}
+
+/// The cap set by [`set_max_concurrent_decodes`].
+pub fn max_concurrent_decodes() -> Option<usize> {
+    lock().max
+}
+
+/// Number of decodes currently holding a slot.
+pub fn decodes_in_flight() -> usize {
+    lock().in_flight
+}
+
+pub(crate) struct Permit;
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut s = lock();
+        s.in_flight -= 1;
+        wake_all(s);
+    }
+}
+
+/// Number of decodes currently waiting for a slot.
+pub fn decodes_waiting() -> usize {
+    lock().waiters.len()
+}
+
+/// Waits until fewer than `min(cap, global max)` decodes are running, then takes a slot.
+///
+/// Cancel safe: dropping the future while it waits unregisters it, and the slot is only
+/// taken in the poll that returns it.
+pub(crate) async fn acquire(cap: Option<usize>) -> Permit {
+    let waiter = Waiter(lock().take_id());
+    std::future::poll_fn(|cx| {
+        let mut s = lock();
+        let limit = match (cap.map(|c| c.max(1)), s.max) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        s.waiters.retain(|(id, _)| *id != waiter.0);
+        if limit.is_none_or(|l| s.in_flight < l) {
+            s.in_flight += 1;
+            Poll::Ready(Permit)
+        } else {
+            s.waiters.push((waiter.0, cx.waker().clone()));
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+/// Removes a dropped [`acquire`]'s waker, so cancelled waits don't pile up.
+struct Waiter(u64);
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        lock().waiters.retain(|(id, _)| *id != self.0);
+    }
+}
+
+impl State {
+    fn take_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+fn lock() -> std::sync::MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Wakes every waiter after releasing the lock; a woken task that was dropped meanwhile
+/// just never re-polls, and the others all get a chance at the freed slot.
+fn wake_all(mut s: std::sync::MutexGuard<'static, State>) {
+    let wakers = std::mem::take(&mut s.waiters);
+    drop(s);
+    for (_, w) in wakers {
+        w.wake();
+    }
+}
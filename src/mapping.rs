@@ -1,4 +1,10 @@
+/// The glTF semantic for an attribute key. Application-specific `_NAME` keys map to
+/// [`gltf::Semantic::Extras`], which decode into
+/// [`DecodedPrimitive::custom`](crate::DecodedPrimitive::custom).
 pub fn dracokey_to_semantic(key: &str) -> Option<gltf::Semantic> {
+    if let Some(name) = key.strip_prefix('_') {
+        return (!name.is_empty()).then(|| gltf::Semantic::Extras(name.to_string()));
+    }
     if key == "POSITION" {
         return Some(gltf::Semantic::Positions);
     }
@@ -36,6 +42,19 @@ pub fn map_draco_dt(dt_u8: u8) -> draco_decoder::AttributeDataType {
     }
 }
 
+pub fn gltf_to_draco_dt(ct: gltf::accessor::DataType) -> u8 {
+    use gltf::accessor::DataType::*;
+    match ct {
+        // draco::DataType discriminants; DT_FLOAT32 is 9
+        I8 => 1,
+        U8 => 2,
+        I16 => 3,
+        U16 => 4,
+        U32 => 6,
+        F32 => 9,
+    }
+}
+
 pub fn comp_size_bytes(ct: gltf::accessor::DataType) -> usize {
     use gltf::accessor::DataType::*;
     match ct {
@@ -43,7 +62,6 @@ pub fn comp_size_bytes(ct: gltf::accessor::DataType) -> usize {
         I16 | U16 => 2,
         U32 | F32 => 4,
         // I32 isn't allowed in glTF 2.0 accessors; F64 not used here.
-        _ => 4,
     }
 }
 
@@ -59,35 +77,47 @@ pub fn dims_count(d: gltf::accessor::Dimensions) -> usize {
     }
 }
 
-pub fn as_f32n<const N: usize>(bytes: &[u8]) -> Vec<[f32; N]> {
-    bytes
-        .chunks_exact(4 * N)
-        .map(|c| {
-            let mut v = [0f32; N];
-            for i in 0..N {
-                let base = i * 4;
-                v[i] = f32::from_le_bytes([c[base], c[base + 1], c[base + 2], c[base + 3]]);
-            }
-            v
-        })
-        .collect()
+/// Widens a block of `dt` components to `f32`. Integers convert by value, or when `normalized`
+/// is set to [0, 1] (unsigned) or [-1, 1] (signed, `max(x / MAX, -1)` as the glTF spec requires).
+/// 32-bit integers are never normalized.
+pub fn as_f32_components(
+    bytes: &[u8],
+    dt: draco_decoder::AttributeDataType,
+    normalized: bool,
+) -> Vec<f32> {
+    let mut out = Vec::with_capacity(bytes.len() / dt.size_in_bytes());
+    for_each_f32_component(bytes, dt, normalized, |x| out.push(x));
+    out
 }
-pub fn as_u16x4(bytes: &[u8]) -> Vec<[u16; 4]> {
-    bytes
-        .chunks_exact(8)
-        .map(|c| {
-            [
-                u16::from_le_bytes([c[0], c[1]]),
-                u16::from_le_bytes([c[2], c[3]]),
-                u16::from_le_bytes([c[4], c[5]]),
-                u16::from_le_bytes([c[6], c[7]]),
-            ]
-        })
-        .collect()
+
+/// [`as_f32_components`] handing each component to `f` instead of collecting them, for
+/// callers that write into storage of their own.
+pub(crate) fn for_each_f32_component(
+    bytes: &[u8],
+    dt: draco_decoder::AttributeDataType,
+    normalized: bool,
+    mut f: impl FnMut(f32),
+) {
+    use draco_decoder::AttributeDataType::*;
+    let unorm = |v: f32, max: f32| if normalized { v / max } else { v };
+    let snorm = |v: f32, max: f32| if normalized { (v / max).max(-1.0) } else { v };
+    match dt {
+        Int8 => bytes.iter().for_each(|&x| f(snorm(x as i8 as f32, 127.0))),
+        UInt8 => bytes.iter().for_each(|&x| f(unorm(x as f32, 255.0))),
+        Int16 => bytes
+            .chunks_exact(2)
+            .for_each(|c| f(snorm(i16::from_le_bytes([c[0], c[1]]) as f32, 32767.0))),
+        UInt16 => bytes
+            .chunks_exact(2)
+            .for_each(|c| f(unorm(u16::from_le_bytes([c[0], c[1]]) as f32, 65535.0))),
+        Int32 => bytes
+            .chunks_exact(4)
+            .for_each(|c| f(i32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f32)),
+        UInt32 => bytes
+            .chunks_exact(4)
+            .for_each(|c| f(u32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f32)),
+        Float32 => bytes
+            .chunks_exact(4)
+            .for_each(|c| f(f32::from_le_bytes([c[0], c[1], c[2], c[3]]))),
+    }
 }
-pub fn as_u8x4(bytes: &[u8]) -> Vec<[u8; 4]> {
-    bytes
-        .chunks_exact(4)
-        .map(|c| [c[0], c[1], c[2], c[3]])
-        .collect()
-}
\ No newline at end of file
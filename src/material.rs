@@ -0,0 +1,118 @@
+use serde_json::Value;
+
+/// The parts of a glTF material a renderer needs to pick a shader variant, with the
+/// common `KHR_materials_*` extensions already read, so scene consumers don't re-parse the
+/// JSON. Texture fields are texture indices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaterialSummary {
+    /// `None` for the glTF default material.
+    pub index: Option<usize>,
+    pub name: Option<String>,
+    pub alpha_mode: gltf::material::AlphaMode,
+    pub alpha_cutoff: Option<f32>,
+    pub double_sided: bool,
+    pub base_color_factor: [f32; 4],
+    pub base_color_texture: Option<usize>,
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+    pub metallic_roughness_texture: Option<usize>,
+    pub normal_texture: Option<usize>,
+    pub occlusion_texture: Option<usize>,
+    pub emissive_factor: [f32; 3],
+    pub emissive_texture: Option<usize>,
+    /// `KHR_materials_emissive_strength`; 1 without the extension.
+    pub emissive_strength: f32,
+    /// `KHR_materials_unlit`.
+    pub unlit: bool,
+    /// `KHR_materials_clearcoat`.
+    pub clearcoat: Option<Clearcoat>,
+    /// `KHR_materials_transmission` factor.
+    pub transmission: Option<f32>,
+    /// `KHR_materials_volume` is present, so transmission refracts through a thick medium.
+    pub volume: bool,
+    /// `KHR_materials_ior`; glTF's default is 1.5.
+    pub ior: Option<f32>,
+    /// Every extension the material uses, sorted, including ones summarized above.
+    pub extensions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clearcoat {
+    pub factor: f32,
+    pub roughness_factor: f32,
+    pub texture: Option<usize>,
+    pub roughness_texture: Option<usize>,
+    pub normal_texture: Option<usize>,
+}
+
+impl MaterialSummary {
+    pub fn new(material: &gltf::Material<'_>) -> Self {
+        let ext = |name: &str| material.extension_value(name);
+        let pbr = material.pbr_metallic_roughness();
+        let mut extensions: Vec<String> = material
+            .extensions()
+            .map(|m| m.keys().cloned().collect())
+            .unwrap_or_default();
+        extensions.sort();
+        Self {
+            index: material.index(),
+            name: material.name().map(str::to_string),
+            alpha_mode: material.alpha_mode(),
+            alpha_cutoff: material.alpha_cutoff(),
+            double_sided: material.double_sided(),
+            base_color_factor: pbr.base_color_factor(),
+            base_color_texture: pbr.base_color_texture().map(|t| t.texture().index()),
+            metallic_factor: pbr.metallic_factor(),
+            roughness_factor: pbr.roughness_factor(),
+            metallic_roughness_texture: pbr
+                .metallic_roughness_texture()
+                .map(|t| t.texture().index()),
+            normal_texture: material.normal_texture().map(|t| t.texture().index()),
+            occlusion_texture: material.occlusion_texture().map(|t| t.texture().index()),
+            emissive_factor: material.emissive_factor(),
+            emissive_texture: material.emissive_texture().map(|t| t.texture().index()),
+            emissive_strength: ext("KHR_materials_emissive_strength")
+                .and_then(|e| number(e, "emissiveStrength"))
+                .unwrap_or(1.0),
+            unlit: ext("KHR_materials_unlit").is_some(),
+            clearcoat: ext("KHR_materials_clearcoat").map(|e| Clearcoat {
+                factor: number(e, "clearcoatFactor").unwrap_or(0.0),
+                roughness_factor: number(e, "clearcoatRoughnessFactor").unwrap_or(0.0),
+                texture: texture(e, "clearcoatTexture"),
+                roughness_texture: texture(e, "clearcoatRoughnessTexture"),
+                normal_texture: texture(e, "clearcoatNormalTexture"),
+            }),
+            transmission: ext("KHR_materials_transmission")
+                .map(|e| number(e, "transmissionFactor").unwrap_or(0.0)),
+            volume: ext("KHR_materials_volume").is_some(),
+            ior: ext("KHR_materials_ior").map(|e| number(e, "ior").unwrap_or(1.5)),
+            extensions,
+        }
+    }
+
+    /// Whether the material needs blending or alpha testing rather than an opaque pass.
+    pub fn is_transparent(&self) -> bool {
+        self.alpha_mode != gltf::material::AlphaMode::Opaque || self.transmission.is_some()
+    }
+
+    /// Whether the clearcoat layer contributes anything.
+    pub fn has_clearcoat(&self) -> bool {
+        self.clearcoat.is_some_and(|c| c.factor > 0.0)
+    }
+}
+
+/// Summaries of every material in `document`, in document order.
+pub fn material_summaries(document: &gltf::Document) -> Vec<MaterialSummary> {
+    document
+        .materials()
+        .map(|m| MaterialSummary::new(&m))
+        .collect()
+}
+
+fn number(ext: &Value, key: &str) -> Option<f32> {
+    ext.get(key)?.as_f64().map(|v| v as f32)
+}
+
+fn texture(ext: &Value, key: &str) -> Option<usize> {
+    ext.get(key)?.get("index")?.as_u64().map(|i| i as usize)
+}
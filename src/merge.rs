@@ -0,0 +1,126 @@
+use crate::*;
+
+/// Result of [`merge_primitives`]: one combined primitive plus material id streams.
+#[derive(Debug, Clone, Default)]
+pub struct MergedPrimitive {
+    pub primitive: DecodedPrimitive,
+    pub triangle_materials: Vec<u32>, // one id per triangle (indices.len() / 3)
+    pub vertex_materials: Vec<u32>,   // one id per vertex, for per-vertex branching
+}
+
+/// Concatenates primitives into one, tagging every triangle and vertex with the caller's
+/// material id so a single draw can branch on material.
+///
+/// Attribute streams present in only some parts are zero-filled for the others, keeping
+/// all streams aligned with the merged vertex count. Custom attributes must have the same
+/// components and component type in every part that has them.
+pub fn merge_primitives(
+    parts: &[(&DecodedPrimitive, u32)],
+) -> Result<MergedPrimitive, DracoLoadError> {
+    let mut out = MergedPrimitive::default();
+    let m = &mut out.primitive;
+
+    let mut base = 0usize;
+    for (part, material) in parts {
+        let count = vertex_count(part);
+
+        append_rebased(&mut m.indices, &part.indices, base)?;
+        out.triangle_materials
+            .extend(std::iter::repeat_n(*material, part.indices.len() / 3));
+        out.vertex_materials
+            .extend(std::iter::repeat_n(*material, count));
+
+        append_vertices(m, part, base, count)?;
+        base += count;
+    }
+    Ok(out)
+}
+
+/// Appends the vertex streams of `part` (`count` vertices) to `dst`, which holds `base`.
+///
+/// Fails if a custom attribute already in `dst` has different components or component
+/// type than `part`'s.
+pub(crate) fn append_vertices(
+    dst: &mut DecodedPrimitive,
+    part: &DecodedPrimitive,
+    base: usize,
+    count: usize,
+) -> Result<(), DracoLoadError> {
+    for (name, s) in &part.custom {
+        if let Some(d) = dst.custom.get(name)
+            && (d.components != s.components || !d.values.same_type(&s.values))
+        {
+            return Err(DracoLoadError::CustomAttributeConflict {
+                name: name.clone(),
+                first: d.layout(),
+                other: s.layout(),
+            });
+        }
+    }
+    merge_opt(&mut dst.positions, &part.positions, base, count);
+    merge_opt(&mut dst.normals, &part.normals, base, count);
+    merge_opt(&mut dst.tangents, &part.tangents, base, count);
+    merge_sets(&mut dst.texcoords, &part.texcoords, base, count);
+    merge_sets(&mut dst.colors, &part.colors, base, count);
+    merge_sets(&mut dst.joints, &part.joints, base, count);
+    merge_sets(&mut dst.weights, &part.weights, base, count);
+    for (name, s) in &part.custom {
+        let d = dst
+            .custom
+            .entry(name.clone())
+            .or_insert_with(|| CustomAttribute {
+                components: s.components,
+                values: s.values.empty_like(),
+            });
+        d.values.resize(base * d.components);
+        d.values.extend_from(&s.values);
+    }
+    for d in dst.custom.values_mut() {
+        d.values.resize((base + count) * d.components);
+    }
+    Ok(())
+}
+
+/// Vertex count of a primitive, taken from whichever stream is present.
+pub(crate) fn vertex_count(p: &DecodedPrimitive) -> usize {
+    p.positions
+        .as_ref()
+        .map(|v| v.len())
+        .or_else(|| p.normals.as_ref().map(|v| v.len()))
+        .or_else(|| p.texcoords.values().next().map(|v| v.len()))
+        .or_else(|| p.colors.values().next().map(|v| v.len()))
+        .unwrap_or(0)
+}
+
+fn merge_opt<T: Copy + Default>(
+    dst: &mut Option<Vec<T>>,
+    src: &Option<Vec<T>>,
+    base: usize,
+    count: usize,
+) {
+    match (dst.as_mut(), src) {
+        (Some(d), Some(s)) => d.extend_from_slice(s),
+        (Some(d), None) => d.resize(base + count, T::default()),
+        (None, Some(s)) => {
+            let mut d = vec![T::default(); base];
+            d.extend_from_slice(s);
+            *dst = Some(d);
+        }
+        (None, None) => {}
+    }
+}
+
+fn merge_sets<T: Copy + Default>(
+    dst: &mut SetMap<Vec<T>>,
+    src: &SetMap<Vec<T>>,
+    base: usize,
+    count: usize,
+) {
+    for (set, s) in src {
+        dst.get_or_insert_with(*set, || vec![T::default(); base])
+            .extend_from_slice(s);
+    }
+    for d in dst.values_mut() {
+        d.resize(base + count, T::default());
+    }
+}
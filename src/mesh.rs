@@ -0,0 +1,50 @@
+use crate::*;
+
+/// Every primitive of a glTF mesh, decoded by [`decode_mesh`].
+#[derive(Debug, Clone)]
+pub struct DecodedMesh {
+    pub index: usize,
+    pub name: Option<String>,
+    /// In the mesh's primitive order.
+    pub primitives: Vec<DecodedPrimitive>,
+}
+
+/// Decodes primitive `p` whether it's Draco-compressed or not: Draco primitives with
+/// attribute infos derived from their accessors, plain ones read from their accessors into
+/// the same layout (indexed `0..count` when they have no indices accessor). Transforms and conventions in `options` apply to both; the fallback
+/// policy only to Draco ones.
+///
+/// Primitives that aren't triangle lists are [`DracoLoadError::UnsupportedMode`] either way.
+pub async fn decode_primitive(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    options: &DecodeOptions,
+) -> Result<DecodedPrimitive, DracoLoadError> {
+    if p.extension_value("KHR_draco_mesh_compression").is_some() {
+        decode_draco_derived_with_options(p, document, buffers, options).await
+    } else if p.mode() != gltf::mesh::Mode::Triangles {
+        Err(DracoLoadError::UnsupportedMode(p.mode()))
+    } else {
+        read_plain(p, buffers, options)
+    }
+}
+
+/// Decodes every primitive of `mesh` with [`decode_primitive`]; the first primitive that
+/// fails fails the mesh.
+pub async fn decode_mesh(
+    mesh: &gltf::Mesh<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    options: &DecodeOptions,
+) -> Result<DecodedMesh, DracoLoadError> {
+    let mut primitives = Vec::with_capacity(mesh.primitives().len());
+    for p in mesh.primitives() {
+        primitives.push(decode_primitive(&p, document, buffers, options).await?);
+    }
+    Ok(DecodedMesh {
+        index: mesh.index(),
+        name: mesh.name().map(str::to_string),
+        primitives,
+    })
+}
@@ -0,0 +1,202 @@
+use crate::*;
+
+/// Clusters from [`DecodedPrimitive::build_meshlets`], flattened the way mesh shaders read
+/// them: each [`Meshlet`] addresses a run of `vertices` and of `triangles`.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct Meshlets {
+    pub meshlets: Vec<Meshlet>,
+    /// Primitive vertex indices, `vertex_count` of them per meshlet.
+    pub vertices: Vec<u32>,
+    /// Three meshlet-local vertex indices per triangle, `triangle_count * 3` per meshlet.
+    pub triangles: Vec<u8>,
+}
+
+impl Meshlets {
+    /// The primitive vertex indices of meshlet `m`.
+    pub fn meshlet_vertices(&self, m: &Meshlet) -> &[u32] {
+        let start = m.vertex_offset as usize;
+        &self.vertices[start..start + m.vertex_count as usize]
+    }
+
+    /// The local triangle list of meshlet `m`.
+    pub fn meshlet_triangles(&self, m: &Meshlet) -> &[u8] {
+        let start = m.triangle_offset as usize;
+        &self.triangles[start..start + m.triangle_count as usize * 3]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct Meshlet {
+    pub vertex_offset: u32,
+    pub vertex_count: u32,
+    /// Offset into [`Meshlets::triangles`], in bytes.
+    pub triangle_offset: u32,
+    pub triangle_count: u32,
+    pub bounds: MeshletBounds,
+}
+
+/// Culling data for one meshlet, in mesh units.
+///
+/// The meshlet is entirely back-facing, and can be skipped, when
+/// `dot(normalize(cone_apex - camera), cone_axis) >= cone_cutoff`. A cutoff of 1 means the
+/// normals spread too far for cone culling.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+pub struct MeshletBounds {
+    pub center: [f32; 3],
+    pub radius: f32,
+    pub cone_apex: [f32; 3],
+    pub cone_axis: [f32; 3],
+    pub cone_cutoff: f32,
+}
+
+/// Meshlet-local indices are `u8`.
+const MAX_MESHLET_VERTICES: usize = 256;
+
+/// Marks a vertex not yet in the meshlet being built.
+const UNSET: u16 = MAX_MESHLET_VERTICES as u16;
+
+impl DecodedPrimitive {
+    /// Splits the triangles into meshlets of at most `max_verts` vertices and `max_tris`
+    /// triangles, e.g. 64 and 124 for most mesh-shader hardware, with bounding spheres and
+    /// normal cones for per-meshlet culling.
+    ///
+    /// Triangles are taken in index order, so clusters are only as coherent as the index
+    /// buffer; Draco's edgebreaker output already keeps neighbours close. `max_verts` is
+    /// clamped to 3..=256.
+    pub fn build_meshlets(&self, max_verts: usize, max_tris: usize) -> Meshlets {
+        let max_verts = max_verts.clamp(3, MAX_MESHLET_VERTICES);
+        let max_tris = max_tris.max(1);
+        let mut out = Meshlets::default();
+        // primitive vertex -> local index in the meshlet being built
+        let mut local = vec![UNSET; vertex_count(self)];
+        let mut start = (0, 0);
+
+        for tri in self.indices.chunks_exact(3) {
+            // out-of-range indices can't be shared, so each one takes a slot of its own
+            let added = tri
+                .iter()
+                .enumerate()
+                .filter(|&(k, &i)| match local.get(i as usize) {
+                    Some(&l) => l == UNSET && !tri[..k].contains(&i),
+                    None => true,
+                })
+                .count();
+            let verts = out.vertices.len() - start.0;
+            let tris = (out.triangles.len() - start.1) / 3;
+            if verts + added > max_verts || tris + 1 > max_tris {
+                self.finish_meshlet(&mut out, &mut local, start);
+                start = (out.vertices.len(), out.triangles.len());
+            }
+            for &i in tri {
+                let next = (out.vertices.len() - start.0) as u16;
+                let l = match local.get_mut(i as usize) {
+                    Some(l) if *l != UNSET => *l,
+                    Some(l) => {
+                        *l = next;
+                        out.vertices.push(i);
+                        next
+                    }
+                    None => {
+                        out.vertices.push(i);
+                        next
+                    }
+                };
+                out.triangles.push(l as u8);
+            }
+        }
+        if out.triangles.len() > start.1 {
+            self.finish_meshlet(&mut out, &mut local, start);
+        }
+        out
+    }
+
+    fn finish_meshlet(&self, out: &mut Meshlets, local: &mut [u16], start: (usize, usize)) {
+        for &v in &out.vertices[start.0..] {
+            if let Some(l) = local.get_mut(v as usize) {
+                *l = UNSET;
+            }
+        }
+        let meshlet = Meshlet {
+            vertex_offset: start.0 as u32,
+            vertex_count: (out.vertices.len() - start.0) as u32,
+            triangle_offset: start.1 as u32,
+            triangle_count: ((out.triangles.len() - start.1) / 3) as u32,
+            bounds: MeshletBounds::default(),
+        };
+        let bounds = self.meshlet_bounds(
+            out.meshlet_vertices(&meshlet),
+            out.meshlet_triangles(&meshlet),
+        );
+        out.meshlets.push(Meshlet { bounds, ..meshlet });
+    }
+
+    fn meshlet_bounds(&self, vertices: &[u32], triangles: &[u8]) -> MeshletBounds {
+        let pos = self.positions.as_deref().unwrap_or_default();
+        let points: Vec<[f32; 3]> = vertices
+            .iter()
+            .filter_map(|&v| pos.get(v as usize).copied())
+            .collect();
+        if points.is_empty() {
+            return MeshletBounds {
+                cone_cutoff: 1.0,
+                ..Default::default()
+            };
+        }
+
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for p in &points {
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+        }
+        let center = [0, 1, 2].map(|k| (min[k] + max[k]) * 0.5);
+        let radius = points
+            .iter()
+            .map(|p| length(sub(*p, center)))
+            .fold(0.0, f32::max);
+
+        // unit face normals with one corner each; degenerate triangles don't constrain the cone
+        let faces: Vec<([f32; 3], [f32; 3])> = triangles
+            .chunks_exact(3)
+            .filter_map(|t| {
+                let [a, b, c] = [0, 1, 2].map(|k| pos.get(vertices[t[k] as usize] as usize));
+                let (a, b, c) = (*a?, *b?, *c?);
+                let n = cross(sub(b, a), sub(c, a));
+                let l = length(n);
+                (l > 0.0).then(|| (n.map(|x| x / l), a))
+            })
+            .collect();
+        let mut bounds = MeshletBounds {
+            center,
+            radius,
+            cone_apex: center,
+            cone_axis: [0.0; 3],
+            cone_cutoff: 1.0,
+        };
+
+        let sum = faces
+            .iter()
+            .fold([0.0f32; 3], |s, (n, _)| [0, 1, 2].map(|k| s[k] + n[k]));
+        let l = length(sum);
+        if l == 0.0 {
+            return bounds;
+        }
+        let axis = sum.map(|x| x / l);
+        let min_dot = faces.iter().map(|(n, _)| dot(*n, axis)).fold(1.0, f32::min);
+        // past ~84 degrees the cone is too wide to cull anything
+        if min_dot <= 0.1 {
+            return bounds;
+        }
+        // move the apex back along the axis until it's behind every triangle's plane
+        let t = faces
+            .iter()
+            .map(|(n, p)| dot(sub(center, *p), *n) / dot(axis, *n))
+            .fold(0.0, f32::max);
+        bounds.cone_apex = [0, 1, 2].map(|k| center[k] - axis[k] * t);
+        bounds.cone_axis = axis;
+        bounds.cone_cutoff = (1.0 - min_dot * min_dot).sqrt();
+        bounds
+    }
+}
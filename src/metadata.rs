@@ -0,0 +1,121 @@
+use crate::*;
+
+use std::collections::BTreeMap;
+
+/// A JSON metadata value from `extras` or `EXT_structural_metadata`, with integers kept
+/// apart from floats so ids and counts survive indexing unchanged.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum MetadataValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<MetadataValue>),
+    Object(BTreeMap<String, MetadataValue>),
+}
+
+impl MetadataValue {
+    /// Parses an object's `extras`; `None` when it has none.
+    pub fn from_extras(
+        extras: &gltf::json::Extras,
+    ) -> Result<Option<MetadataValue>, DracoLoadError> {
+        extras
+            .as_deref()
+            .map(|raw| serde_json::from_str(raw.get()))
+            .transpose()
+            .map_err(|e| DracoLoadError::Gltf(gltf::Error::Deserialize(e)))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            MetadataValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Member `key` of an object.
+    pub fn get(&self, key: &str) -> Option<&MetadataValue> {
+        match self {
+            MetadataValue::Object(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    /// A label in language `lang` (a BCP 47 tag such as `de-CH`). Plain strings match any
+    /// language; objects keyed by tag are looked up by the full tag, then by its primary
+    /// subtag (`de`).
+    pub fn localized(&self, lang: &str) -> Option<&str> {
+        if let MetadataValue::String(s) = self {
+            return Some(s);
+        }
+        let primary = lang.split(['-', '_']).next().unwrap_or(lang);
+        self.get(lang)
+            .or_else(|| self.get(primary))
+            .and_then(MetadataValue::as_str)
+    }
+}
+
+impl From<serde_json::Value> for MetadataValue {
+    fn from(v: serde_json::Value) -> Self {
+        match v {
+            serde_json::Value::Null => MetadataValue::Null,
+            serde_json::Value::Bool(b) => MetadataValue::Bool(b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => MetadataValue::Int(i),
+                None => MetadataValue::Float(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_json::Value::String(s) => MetadataValue::String(s),
+            serde_json::Value::Array(a) => {
+                MetadataValue::Array(a.into_iter().map(MetadataValue::from).collect())
+            }
+            serde_json::Value::Object(o) => MetadataValue::Object(
+                o.into_iter()
+                    .map(|(k, v)| (k, MetadataValue::from(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Metadata attached to one primitive and its mesh, from [`collect_metadata`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PrimitiveMetadata {
+    pub mesh: usize,
+    pub primitive: usize,
+    pub mesh_name: Option<String>,
+    /// Whether the primitive is Draco compressed.
+    pub draco: bool,
+    pub mesh_extras: Option<MetadataValue>,
+    pub extras: Option<MetadataValue>,
+    /// The primitive's `EXT_structural_metadata` object. Property tables it references
+    /// stay in their buffers; only the JSON is decoded.
+    pub structural: Option<MetadataValue>,
+}
+
+/// Decodes the `extras` and structural metadata of every primitive in the document, for
+/// indexing labels without decoding any geometry.
+pub fn collect_metadata(
+    document: &gltf::Document,
+) -> Result<Vec<PrimitiveMetadata>, DracoLoadError> {
+    let mut out = Vec::new();
+    for mesh in document.meshes() {
+        let mesh_extras = MetadataValue::from_extras(mesh.extras())?;
+        for p in mesh.primitives() {
+            out.push(PrimitiveMetadata {
+                mesh: mesh.index(),
+                primitive: p.index(),
+                mesh_name: mesh.name().map(str::to_string),
+                draco: p.extension_value("KHR_draco_mesh_compression").is_some(),
+                mesh_extras: mesh_extras.clone(),
+                extras: MetadataValue::from_extras(p.extras())?,
+                structural: p
+                    .extension_value("EXT_structural_metadata")
+                    .cloned()
+                    .map(MetadataValue::from),
+            });
+        }
+    }
+    Ok(out)
+}
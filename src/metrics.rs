@@ -0,0 +1,180 @@
+use crate::*;
+
+/// Error of a decoded primitive against a reference, e.g. the uncompressed source.
+///
+/// Per-vertex metrics need both primitives to have the same vertex count and order (see
+/// [`reorder_to_reference`]) and are `None` otherwise; the Hausdorff distance compares the
+/// position sets and works for any pair.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct QualityMetrics {
+    /// dB, with the reference bounding-box diagonal as peak; infinite when identical, `None`
+    /// when the reference collapses to a point and so has no peak.
+    pub position_psnr: Option<f64>,
+    pub position_rmse: Option<f64>,
+    /// Symmetric vertex-to-nearest-vertex distance.
+    pub hausdorff: Option<f64>,
+    /// Degrees.
+    pub normal_mean_angle: Option<f64>,
+    pub normal_max_angle: Option<f64>,
+    /// Largest UV distance per texcoord set present in both.
+    pub uv_max_drift: std::collections::BTreeMap<u32, f64>,
+}
+
+pub fn compare_primitives(reference: &DecodedPrimitive, test: &DecodedPrimitive) -> QualityMetrics {
+    let normals = normal_angular_error(reference, test);
+    QualityMetrics {
+        position_psnr: position_psnr(reference, test),
+        position_rmse: position_rmse(reference, test),
+        hausdorff: hausdorff_distance(reference, test),
+        normal_mean_angle: normals.map(|n| n.0),
+        normal_max_angle: normals.map(|n| n.1),
+        uv_max_drift: reference
+            .texcoords
+            .keys()
+            .filter_map(|&set| Some((set, uv_drift(reference, test, set)?)))
+            .collect(),
+    }
+}
+
+pub fn position_rmse(reference: &DecodedPrimitive, test: &DecodedPrimitive) -> Option<f64> {
+    let (a, b) = paired(reference.positions.as_deref(), test.positions.as_deref())?;
+    let sum: f64 = a.iter().zip(b).map(|(x, y)| dist2(x, y)).sum();
+    Some((sum / a.len() as f64).sqrt())
+}
+
+pub fn position_psnr(reference: &DecodedPrimitive, test: &DecodedPrimitive) -> Option<f64> {
+    let rmse = position_rmse(reference, test).filter(|r| r.is_finite())?;
+    if rmse == 0.0 {
+        return Some(f64::INFINITY);
+    }
+    let (min, max) = bounds(reference.positions.as_deref()?)?;
+    let peak = dist2(&min, &max).sqrt();
+    (peak > 0.0).then(|| 20.0 * (peak / rmse).log10())
+}
+
+/// Mean and max angle between corresponding normals, in degrees.
+pub fn normal_angular_error(
+    reference: &DecodedPrimitive,
+    test: &DecodedPrimitive,
+) -> Option<(f64, f64)> {
+    let (a, b) = paired(reference.normals.as_deref(), test.normals.as_deref())?;
+    let (mut sum, mut max) = (0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        let (lx, ly) = (dot(x, x).sqrt(), dot(y, y).sqrt());
+        let angle = if lx == 0.0 || ly == 0.0 {
+            0.0
+        } else {
+            (dot(x, y) / (lx * ly)).clamp(-1.0, 1.0).acos().to_degrees()
+        };
+        sum += angle;
+        max = max.max(angle);
+    }
+    Some((sum / a.len() as f64, max))
+}
+
+/// Largest distance between corresponding UVs of `set`.
+pub fn uv_drift(reference: &DecodedPrimitive, test: &DecodedPrimitive, set: u32) -> Option<f64> {
+    let (a, b) = paired(
+        reference.texcoords.get(&set).map(Vec::as_slice),
+        test.texcoords.get(&set).map(Vec::as_slice),
+    )?;
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| dist2(x, y).sqrt())
+        .reduce(f64::max)
+}
+
+/// Symmetric Hausdorff distance between the two position sets; `None` when either is empty
+/// or holds a non-finite position.
+pub fn hausdorff_distance(a: &DecodedPrimitive, b: &DecodedPrimitive) -> Option<f64> {
+    let (pa, pb) = (a.positions.as_deref()?, b.positions.as_deref()?);
+    let (lo_a, hi_a) = bounds(pa)?;
+    let (lo_b, hi_b) = bounds(pb)?;
+    if ![lo_a, hi_a, lo_b, hi_b]
+        .iter()
+        .flatten()
+        .all(|v| v.is_finite())
+    {
+        return None;
+    }
+    let min: [f64; 3] = std::array::from_fn(|k| lo_a[k].min(lo_b[k]));
+    let extent = (0..3)
+        .map(|k| hi_a[k].max(hi_b[k]) - min[k])
+        .fold(0.0f64, f64::max);
+    if extent == 0.0 {
+        return Some(0.0); // every point of both sets coincides
+    }
+    Some(one_sided(pa, pb, min, extent).max(one_sided(pb, pa, min, extent)))
+}
+
+/// Max over `from` of the distance to the nearest point of `to`, using a uniform grid over
+/// both sets, whose corner is `min` and largest side `extent`. Sizing the cells from the
+/// union keeps them finite when `to` alone is a single point, so the search ends within
+/// `cbrt(to.len()) + 1` shells.
+fn one_sided(from: &[[f32; 3]], to: &[[f32; 3]], min: [f64; 3], extent: f64) -> f64 {
+    let cell = extent / (to.len() as f64).cbrt().max(1.0);
+    let key = |p: &[f32; 3]| -> [i64; 3] {
+        std::array::from_fn(|k| ((p[k] as f64 - min[k]) / cell).floor() as i64)
+    };
+    let mut grid: std::collections::HashMap<[i64; 3], Vec<usize>> = Default::default();
+    for (i, p) in to.iter().enumerate() {
+        grid.entry(key(p)).or_default().push(i);
+    }
+
+    let mut worst = 0.0f64;
+    for p in from {
+        let c = key(p);
+        let mut best = f64::INFINITY;
+        // grow the searched shell until no unvisited cell can hold anything closer
+        for r in 0i64.. {
+            for dx in -r..=r {
+                for dy in -r..=r {
+                    for dz in -r..=r {
+                        if dx.abs().max(dy.abs()).max(dz.abs()) != r {
+                            continue;
+                        }
+                        for &i in grid
+                            .get(&[c[0] + dx, c[1] + dy, c[2] + dz])
+                            .into_iter()
+                            .flatten()
+                        {
+                            best = best.min(dist2(p, &to[i]));
+                        }
+                    }
+                }
+            }
+            if best.sqrt() <= r as f64 * cell {
+                break;
+            }
+        }
+        worst = worst.max(best.sqrt());
+    }
+    worst
+}
+
+fn paired<'a, T>(a: Option<&'a [T]>, b: Option<&'a [T]>) -> Option<(&'a [T], &'a [T])> {
+    let (a, b) = (a?, b?);
+    (a.len() == b.len() && !a.is_empty()).then_some((a, b))
+}
+
+fn bounds(p: &[[f32; 3]]) -> Option<([f64; 3], [f64; 3])> {
+    let first = p.first()?.map(f64::from);
+    Some(p.iter().fold((first, first), |(mut lo, mut hi), v| {
+        for k in 0..3 {
+            lo[k] = lo[k].min(v[k] as f64);
+            hi[k] = hi[k].max(v[k] as f64);
+        }
+        (lo, hi)
+    }))
+}
+
+fn dist2<T: Copy + Into<f64>, const N: usize>(a: &[T; N], b: &[T; N]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| ((*x).into() - (*y).into()).powi(2))
+        .sum()
+}
+
+fn dot(a: &[f32; 3], b: &[f32; 3]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| *x as f64 * *y as f64).sum()
+}
@@ -0,0 +1,90 @@
+use crate::*;
+
+/// Recast's `RC_WALKABLE_AREA`: the area id of a triangle the agent can stand on.
+pub const WALKABLE_AREA: u8 = 63;
+/// Recast's `RC_NULL_AREA`: the area id of a triangle too steep to walk on.
+pub const NULL_AREA: u8 = 0;
+
+/// Scene geometry in the layout `rcRasterizeTriangles` (and the `recast-rs` bindings) take,
+/// from [`navmesh_input`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NavmeshInput {
+    /// `x, y, z` per vertex, world space, Y up (glTF and Recast agree on the axes).
+    pub vertices: Vec<f32>,
+    /// Three vertex indices per triangle, counter-clockwise seen from above the surface.
+    pub triangles: Vec<i32>,
+    /// One area id per triangle, [`WALKABLE_AREA`] or [`NULL_AREA`], as
+    /// `rcMarkWalkableTriangles` would set them.
+    pub areas: Vec<u8>,
+    /// World bounds of `vertices`, for `rcCreateHeightfield`.
+    pub bmin: [f32; 3],
+    pub bmax: [f32; 3],
+    /// Primitives that were placed without (or despite) their node transform.
+    pub warnings: Vec<BakeWarning>,
+}
+
+impl NavmeshInput {
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len() / 3
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.areas.len()
+    }
+}
+
+/// Flattens every primitive of `scene` into one world-space triangle soup for navmesh
+/// baking, with transforms applied by [`bake_scene`].
+///
+/// Triangles whose normal is within `walkable_slope_deg` of straight up are marked
+/// [`WALKABLE_AREA`], the rest [`NULL_AREA`], using Recast's own test. Primitives without
+/// positions are skipped, as are triangles referencing missing vertices.
+pub fn navmesh_input(
+    scene: &DecodedScene,
+    document: &gltf::Document,
+    walkable_slope_deg: f32,
+) -> NavmeshInput {
+    let baked = bake_scene(scene, document);
+    let walkable_y = walkable_slope_deg.to_radians().cos();
+    let mut out = NavmeshInput {
+        bmin: [f32::INFINITY; 3],
+        bmax: [f32::NEG_INFINITY; 3],
+        warnings: baked.warnings,
+        ..Default::default()
+    };
+
+    for sp in &baked.primitives {
+        let Some(pos) = sp.decoded.positions.as_deref() else {
+            continue;
+        };
+        let base = out.vertex_count() as i32;
+        for p in pos {
+            out.vertices.extend_from_slice(p);
+            out.bmin = [0, 1, 2].map(|k| out.bmin[k].min(p[k]));
+            out.bmax = [0, 1, 2].map(|k| out.bmax[k].max(p[k]));
+        }
+        for tri in sp.decoded.indices.chunks_exact(3) {
+            if tri.iter().any(|&i| i as usize >= pos.len()) {
+                continue;
+            }
+            let [a, b, c] = [0, 1, 2].map(|k| pos[tri[k] as usize]);
+            let e0 = [0, 1, 2].map(|k| b[k] - a[k]);
+            let e1 = [0, 1, 2].map(|k| c[k] - a[k]);
+            let n = [
+                e0[1] * e1[2] - e0[2] * e1[1],
+                e0[2] * e1[0] - e0[0] * e1[2],
+                e0[0] * e1[1] - e0[1] * e1[0],
+            ];
+            let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+            let walkable = len > 0.0 && n[1] / len > walkable_y;
+            out.triangles.extend(tri.iter().map(|&i| base + i as i32));
+            out.areas
+                .push(if walkable { WALKABLE_AREA } else { NULL_AREA });
+        }
+    }
+    if out.vertices.is_empty() {
+        out.bmin = [0.0; 3];
+        out.bmax = [0.0; 3];
+    }
+    out
+}
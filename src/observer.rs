@@ -0,0 +1,126 @@
+use crate::*;
+
+use std::thread::ThreadId;
+use std::time::{Duration, Instant};
+
+/// Receives structured events from every Draco decode made with
+/// [`DecodeOptions::observer`], for forwarding to Prometheus, OpenTelemetry and the like.
+///
+/// Called inline on the decoding task, so implementations should only record and return.
+/// Primitives read from their uncompressed fallback produce no events.
+pub trait DecodeObserver: Send + Sync {
+    fn primitive_started(&self, _event: &DecodeStarted) {}
+
+    /// Sent for every started decode, including ones that failed and ones whose future was
+    /// dropped before completing, which report [`DracoLoadError::Aborted`].
+    fn primitive_finished(&self, _event: &DecodeFinished<'_>) {}
+}
+
+impl std::fmt::Debug for dyn DecodeObserver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DecodeObserver")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeStarted {
+    /// The mesh holding the primitive; `None` if it isn't in the document passed to the
+    /// decode.
+    pub mesh: Option<usize>,
+    /// Index of the primitive within its mesh.
+    pub primitive: usize,
+    /// The compressed bufferView, which identifies the stream across meshes.
+    pub buffer_view: usize,
+    /// Compressed Draco bytes.
+    pub bytes_in: usize,
+    pub vertex_count: usize,
+    pub index_count: usize,
+    pub thread: ThreadId,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecodeFinished<'a> {
+    pub mesh: Option<usize>,
+    pub primitive: usize,
+    pub buffer_view: usize,
+    pub bytes_in: usize,
+    /// Bytes the Draco decoder produced, before widening to the output streams; 0 when
+    /// the decode failed.
+    pub bytes_out: usize,
+    /// From the start event, including time spent waiting for a decode slot.
+    pub duration: Duration,
+    /// The thread the decode finished on, which for async callers may differ from the
+    /// one it started on.
+    pub thread: ThreadId,
+    pub error: Option<&'a DracoLoadError>,
+}
+
+impl DecodeFinished<'_> {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// The index of the mesh `p` belongs to in `document`.
+pub(crate) fn mesh_index(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+) -> Option<usize> {
+    // primitives don't expose their mesh; match on the address of their JSON
+    document
+        .meshes()
+        .find(|m| {
+            m.primitives()
+                .nth(p.index())
+                .is_some_and(|q| std::ptr::eq(q.extras(), p.extras()))
+        })
+        .map(|m| m.index())
+}
+
+/// Sends the finished event for a started decode, from [`finish`](Self::finish) or, if the
+/// decode is cancelled first, on drop.
+pub(crate) struct FinishGuard<'a> {
+    observer: &'a dyn DecodeObserver,
+    started: DecodeStarted,
+    clock: Instant,
+    finished: bool,
+}
+
+impl<'a> FinishGuard<'a> {
+    /// Sends the started event.
+    pub(crate) fn start(observer: &'a dyn DecodeObserver, started: DecodeStarted) -> Self {
+        observer.primitive_started(&started);
+        Self {
+            observer,
+            started,
+            clock: Instant::now(),
+            finished: false,
+        }
+    }
+
+    pub(crate) fn finish(mut self, bytes_out: usize, error: Option<&DracoLoadError>) {
+        self.send(bytes_out, error);
+    }
+
+    fn send(&mut self, bytes_out: usize, error: Option<&DracoLoadError>) {
+        self.finished = true;
+        self.observer.primitive_finished(&DecodeFinished {
+            mesh: self.started.mesh,
+            primitive: self.started.primitive,
+            buffer_view: self.started.buffer_view,
+            bytes_in: self.started.bytes_in,
+            bytes_out,
+            duration: self.clock.elapsed(),
+            thread: std::thread::current().id(),
+            error,
+        });
+    }
+}
+
+impl Drop for FinishGuard<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.send(0, Some(&DracoLoadError::Aborted));
+        }
+    }
+}
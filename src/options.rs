@@ -0,0 +1,440 @@
+/// Knobs for [`decode_draco_with_options`](crate::decode_draco_with_options).
+#[derive(Debug, Clone, Default)]
+pub struct DecodeOptions {
+    pub fallback: FallbackPolicy,
+    /// Reorder Draco-decoded vertices to the uncompressed fallback's order, when present.
+    pub accessor_order: bool,
+    /// Wait until fewer than this many decodes are running crate-wide before starting;
+    /// combined with [`set_max_concurrent_decodes`](crate::set_max_concurrent_decodes).
+    pub max_concurrent_decodes: Option<usize>,
+    /// Write `1 - v` to every texcoord set (glTF/DirectX top-left origin to OpenGL bottom-left).
+    pub flip_v: bool,
+    /// Negate tangent `w` for engines whose bitangent sign convention is the opposite of glTF's.
+    pub flip_tangent_handedness: bool,
+    /// Tolerance for non-standard Draco extension layouts from legacy exporters.
+    pub quirks: crate::VendorQuirks,
+    /// Per-vertex position transform, e.g. ECEF to a local ENU frame. Each position is
+    /// widened to `f64` for the call and narrowed back afterwards, in the same pass as the
+    /// other conventions. Normals and tangents are left alone.
+    ///
+    /// This keeps the transform's own arithmetic in `f64` but doesn't add precision: the
+    /// positions it gets were already rounded to `f32` when decoded, so coordinates too large
+    /// for `f32` stay as coarse as they were.
+    pub reproject: Option<fn(&mut [f64; 3])>,
+    /// Offsets added to the decoded vertices before any other convention, e.g. one frame of
+    /// an animation stored as deltas against the Draco base mesh.
+    pub deltas: Option<VertexDeltas>,
+    /// Vertex and index order of the output; applied before the other conventions, so
+    /// deltas and sidecar data index into the chosen order.
+    pub ordering: crate::StableOrdering,
+    /// Decode with the stored layout, the stream header's when it parses (see
+    /// [`stored_attr_infos`](crate::stored_attr_infos)), instead of failing with
+    /// [`AttributeMismatch`](crate::DracoLoadError::AttributeMismatch) when the given infos
+    /// disagree with it.
+    pub salvage_attributes: bool,
+    /// Shift UV charts by whole repeats into `[0, 1)` after the other conventions; see
+    /// [`DecodedPrimitive::rebase_texcoords`](crate::DecodedPrimitive::rebase_texcoords).
+    pub rebase_uvs: bool,
+    /// Expected layouts of the `_NAME` attributes decoded into
+    /// [`DecodedPrimitive::custom`](crate::DecodedPrimitive::custom).
+    pub semantic_registry: Option<std::sync::Arc<crate::SemanticRegistry>>,
+    /// Told when each Draco decode starts and finishes, for telemetry.
+    pub observer: Option<std::sync::Arc<dyn crate::DecodeObserver>>,
+    /// Implicit type widenings allowed while decoding; all of them by default.
+    pub promotions: crate::PromotionPolicy,
+    /// Affine transforms applied per semantic as streams are converted to `f32`, before
+    /// the other conventions; see [`AttributeTransform`](crate::AttributeTransform).
+    pub transforms: crate::AttributeTransforms,
+    /// Index width the vertex count must fit, checked before decoding. Output indices are
+    /// `u32` either way; this only rejects meshes a narrower consumer can't take.
+    pub index_width_check: IndexWidthCheck,
+    pub color_policy: ColorPolicy,
+    pub weight_policy: WeightPolicy,
+    /// Attributes to convert, `None` for all. The decoder still decodes every attribute in
+    /// the stream; the others are dropped before they're converted or allocated.
+    pub semantics: Option<Vec<gltf::Semantic>>,
+    /// Fill [`DecodedPrimitive::areas`](crate::DecodedPrimitive::areas) as the last
+    /// convention, so the tables match the returned positions, texcoords and triangle order.
+    pub triangle_areas: AreaTables,
+}
+
+/// Per-vertex offsets for [`DecodeOptions::deltas`], indexed in decoded vertex order (the
+/// accessor order when [`DecodeOptions::accessor_order`] is set). Shared through `Arc` so
+/// options can be cloned per frame cheaply.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexDeltas {
+    pub positions: std::sync::Arc<[[f32; 3]]>,
+    /// Added to the normals, which are then renormalized.
+    pub normals: Option<std::sync::Arc<[[f32; 3]]>>,
+}
+
+impl VertexDeltas {
+    pub fn new(positions: impl Into<std::sync::Arc<[[f32; 3]]>>) -> Self {
+        Self {
+            positions: positions.into(),
+            normals: None,
+        }
+    }
+
+    pub fn with_normals(mut self, normals: impl Into<std::sync::Arc<[[f32; 3]]>>) -> Self {
+        self.normals = Some(normals.into());
+        self
+    }
+}
+
+/// What to do when a primitive also ships uncompressed fallback accessors.
+///
+/// Fallbacks are only considered when `KHR_draco_mesh_compression` is listed in
+/// `extensionsUsed` but not in `extensionsRequired`, as described by the extension spec.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FallbackPolicy {
+    /// Always decode the Draco stream.
+    #[default]
+    Draco,
+    /// Read the uncompressed accessors when they are available, Draco otherwise.
+    PreferFallback,
+}
+
+/// Index buffer width a consumer can take, for [`DecodeOptions::index_width_check`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IndexWidthCheck {
+    /// Any vertex count is accepted.
+    #[default]
+    None,
+    /// Fail with [`IndexWidthExceeded`](crate::DracoLoadError::IndexWidthExceeded) when a
+    /// primitive has more vertices than `u16` indices below the 65535 restart value reach,
+    /// for targets without 32-bit index buffers.
+    U16,
+}
+
+/// How decoded `COLOR_n` values are passed on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorPolicy {
+    #[default]
+    AsStored,
+    /// Clamp every channel into `[0, 1]`; float colors from some exporters overshoot.
+    Clamp,
+    /// Clamp, then multiply RGB by alpha for premultiplied blending.
+    PremultiplyAlpha,
+}
+
+/// How decoded `WEIGHTS_n` values are passed on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WeightPolicy {
+    #[default]
+    AsStored,
+    /// Rescale each vertex's weights, across all sets, to sum to 1; quantized weights
+    /// usually miss by a few ULPs of their storage type.
+    Normalize,
+}
+
+/// Which [`TriangleAreas`](crate::TriangleAreas) tables a decode fills, for
+/// [`DecodeOptions::triangle_areas`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AreaTables {
+    #[default]
+    None,
+    Surface,
+    /// Surface areas plus UV areas for every texcoord set.
+    SurfaceAndUv,
+}
+
+impl DecodeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_fallback(mut self, fallback: FallbackPolicy) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    pub fn with_accessor_order(mut self, accessor_order: bool) -> Self {
+        self.accessor_order = accessor_order;
+        self
+    }
+
+    pub fn with_max_concurrent_decodes(mut self, max: usize) -> Self {
+        self.max_concurrent_decodes = Some(max);
+        self
+    }
+
+    pub fn with_flip_v(mut self, flip_v: bool) -> Self {
+        self.flip_v = flip_v;
+        self
+    }
+
+    pub fn with_flip_tangent_handedness(mut self, flip: bool) -> Self {
+        self.flip_tangent_handedness = flip;
+        self
+    }
+
+    pub fn with_quirks(mut self, quirks: crate::VendorQuirks) -> Self {
+        self.quirks = quirks;
+        self
+    }
+
+    pub fn with_reprojection(mut self, reproject: fn(&mut [f64; 3])) -> Self {
+        self.reproject = Some(reproject);
+        self
+    }
+
+    pub fn with_ordering(mut self, ordering: crate::StableOrdering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    pub fn with_semantic_registry(mut self, registry: crate::SemanticRegistry) -> Self {
+        self.semantic_registry = Some(std::sync::Arc::new(registry));
+        self
+    }
+
+    pub fn with_rebase_uvs(mut self, rebase: bool) -> Self {
+        self.rebase_uvs = rebase;
+        self
+    }
+
+    pub fn with_salvage_attributes(mut self, salvage: bool) -> Self {
+        self.salvage_attributes = salvage;
+        self
+    }
+
+    pub fn with_deltas(mut self, deltas: VertexDeltas) -> Self {
+        self.deltas = Some(deltas);
+        self
+    }
+
+    pub fn with_observer(mut self, observer: std::sync::Arc<dyn crate::DecodeObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    pub fn with_promotions(mut self, promotions: crate::PromotionPolicy) -> Self {
+        self.promotions = promotions;
+        self
+    }
+
+    pub fn with_transform(
+        mut self,
+        semantic: gltf::Semantic,
+        transform: crate::AttributeTransform,
+    ) -> Self {
+        self.transforms.insert(semantic, transform);
+        self
+    }
+
+    pub fn with_index_width_check(mut self, check: IndexWidthCheck) -> Self {
+        self.index_width_check = check;
+        self
+    }
+
+    pub fn with_color_policy(mut self, policy: ColorPolicy) -> Self {
+        self.color_policy = policy;
+        self
+    }
+
+    pub fn with_weight_policy(mut self, policy: WeightPolicy) -> Self {
+        self.weight_policy = policy;
+        self
+    }
+
+    /// Converts only `semantics`, e.g. `[Positions]` for a collision mesh; indices always are.
+    pub fn with_semantics(mut self, semantics: impl IntoIterator<Item = gltf::Semantic>) -> Self {
+        self.semantics = Some(semantics.into_iter().collect());
+        self
+    }
+
+    pub fn with_triangle_areas(mut self, tables: AreaTables) -> Self {
+        self.triangle_areas = tables;
+        self
+    }
+
+    /// Whether [`semantics`](Self::semantics) asks for `semantic`.
+    pub fn converts(&self, semantic: &gltf::Semantic) -> bool {
+        self.semantics.as_ref().is_none_or(|s| s.contains(semantic))
+    }
+
+    /// Whether to convert `blk`; blocks the map doesn't know are kept so they still fail.
+    pub(crate) fn converts_block(
+        &self,
+        map: &crate::SemanticMap,
+        blk: &crate::AttrSlice<'_>,
+    ) -> bool {
+        map.get(&blk.unique_id)
+            .is_none_or(|(sem, ..)| self.converts(sem))
+    }
+
+    /// Draco id to semantic map for `p`, with registered vendor attributes checked
+    /// against their accessors and given the registry's normalization.
+    pub(crate) fn semantic_map(
+        &self,
+        p: &gltf::mesh::Primitive<'_>,
+        ext: &crate::DracoExt,
+    ) -> Result<crate::SemanticMap, crate::DracoLoadError> {
+        match &self.semantic_registry {
+            Some(registry) => registry.semantic_map(p, ext),
+            None => Ok(crate::semantic_map(p, ext)),
+        }
+    }
+
+    /// Fails if `vertex_count` vertices don't pass
+    /// [`index_width_check`](Self::index_width_check) or
+    /// [`deltas`](Self::deltas) don't cover exactly that many.
+    pub(crate) fn check_vertex_count(
+        &self,
+        vertex_count: usize,
+    ) -> Result<(), crate::DracoLoadError> {
+        if self.index_width_check == IndexWidthCheck::U16 && vertex_count > u16::MAX as usize {
+            return Err(crate::DracoLoadError::IndexWidthExceeded(vertex_count));
+        }
+        let Some(deltas) = &self.deltas else {
+            return Ok(());
+        };
+        let normals = deltas.normals.as_ref().map_or(vertex_count, |n| n.len());
+        for len in [deltas.positions.len(), normals] {
+            if len != vertex_count {
+                return Err(crate::DracoLoadError::DeltaCountMismatch(vertex_count, len));
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the coordinate-convention options to an already decoded primitive.
+    pub fn apply_conventions(&self, d: &mut crate::DecodedPrimitive) {
+        if self.ordering == crate::StableOrdering::Canonical {
+            d.canonicalize();
+        }
+        if let Some(deltas) = &self.deltas {
+            add_deltas(d.positions.as_deref_mut(), &deltas.positions);
+            if let Some(normals) = &deltas.normals
+                && add_deltas(d.normals.as_deref_mut(), normals)
+            {
+                for n in d.normals.iter_mut().flatten() {
+                    let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+                    if len > 0.0 {
+                        *n = n.map(|x| x / len);
+                    }
+                }
+            }
+        }
+        if let Some(reproject) = self.reproject {
+            for p in d.positions.iter_mut().flatten() {
+                let mut wide = p.map(f64::from);
+                reproject(&mut wide);
+                *p = wide.map(|x| x as f32);
+            }
+        }
+        if self.flip_v {
+            for uv in d.texcoords.values_mut().flatten() {
+                uv[1] = 1.0 - uv[1];
+            }
+        }
+        if self.rebase_uvs {
+            d.rebase_texcoords();
+        }
+        if self.flip_tangent_handedness {
+            for t in d.tangents.iter_mut().flatten() {
+                t[3] = -t[3];
+            }
+        }
+        if self.color_policy != ColorPolicy::AsStored {
+            for c in d.colors.values_mut().flatten() {
+                *c = c.map(|x| x.clamp(0.0, 1.0));
+                if self.color_policy == ColorPolicy::PremultiplyAlpha {
+                    *c = [c[0] * c[3], c[1] * c[3], c[2] * c[3], c[3]];
+                }
+            }
+        }
+        if self.weight_policy == WeightPolicy::Normalize {
+            normalize_weights(d);
+        }
+        if self.triangle_areas != AreaTables::None {
+            d.areas = Some(d.triangle_areas(self.triangle_areas == AreaTables::SurfaceAndUv));
+        }
+    }
+}
+
+fn normalize_weights(d: &mut crate::DecodedPrimitive) {
+    let vertices = d.weights.values().map(Vec::len).max().unwrap_or(0);
+    for v in 0..vertices {
+        let sum: f32 = d.weights.values().filter_map(|w| w.get(v)).flatten().sum();
+        if sum > 0.0 {
+            for w in d.weights.values_mut().filter_map(|w| w.get_mut(v)) {
+                *w = w.map(|x| x / sum);
+            }
+        }
+    }
+}
+
+/// Adds `deltas` lane by lane over the flattened components, a loop the compiler
+/// vectorizes. Skipped (returning false) unless the counts match.
+fn add_deltas(v: Option<&mut [[f32; 3]]>, deltas: &[[f32; 3]]) -> bool {
+    let Some(v) = v.filter(|v| v.len() == deltas.len()) else {
+        return false;
+    };
+    for (x, dx) in v.as_flattened_mut().iter_mut().zip(deltas.as_flattened()) {
+        *x += dx;
+    }
+    true
+}
+
+/// Presets bundling decode options, parallelism and validation for common uses. Start
+/// from [`DecodeProfile::options`] and override individual fields as needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeProfile {
+    /// Fast loading in a game: Draco output as-is, decodes capped to the core count.
+    GameRuntime,
+    /// Asset pipelines: accessor ordering and conformance checks, all cores.
+    OfflineTooling,
+    /// Large-coordinate data: prefer lossless fallbacks, keep accessor order, validate.
+    /// Output positions are still `f32`.
+    GeospatialF64,
+    /// Browser viewers: one decode at a time, positions first via
+    /// [`decode_draco_progressive`](crate::decode_draco_progressive).
+    WebProgressive,
+}
+
+impl DecodeProfile {
+    pub fn options(self) -> DecodeOptions {
+        let cores = available_cores();
+        match self {
+            DecodeProfile::GameRuntime => DecodeOptions::new().with_max_concurrent_decodes(cores),
+            DecodeProfile::OfflineTooling => DecodeOptions::new().with_accessor_order(true),
+            DecodeProfile::GeospatialF64 => DecodeOptions::new()
+                .with_fallback(FallbackPolicy::PreferFallback)
+                .with_accessor_order(true),
+            DecodeProfile::WebProgressive => DecodeOptions::new().with_max_concurrent_decodes(1),
+        }
+    }
+
+    /// Worker threads for [`DocumentIndex::decode_parallel`](crate::DocumentIndex::decode_parallel).
+    pub fn threads(self) -> usize {
+        match self {
+            DecodeProfile::WebProgressive => 1,
+            _ => available_cores(),
+        }
+    }
+
+    /// Whether results should go through [`check_conformance`](crate::check_conformance).
+    pub fn validate(self) -> bool {
+        matches!(
+            self,
+            DecodeProfile::OfflineTooling | DecodeProfile::GeospatialF64
+        )
+    }
+
+    /// Whether to decode with [`decode_draco_progressive`](crate::decode_draco_progressive).
+    pub fn progressive(self) -> bool {
+        self == DecodeProfile::WebProgressive
+    }
+}
+
+impl From<DecodeProfile> for DecodeOptions {
+    fn from(profile: DecodeProfile) -> Self {
+        profile.options()
+    }
+}
+
+fn available_cores() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
@@ -0,0 +1,155 @@
+use crate::*;
+
+/// Buffer limits for [`pack_primitives`] and [`pack_scene`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackOptions {
+    /// Every stream starts at a multiple of this many bytes. The default, 256, satisfies
+    /// `minStorageBufferOffsetAlignment` on every WebGPU, Vulkan and D3D12 device.
+    pub alignment: usize,
+    /// A new buffer is started once the current one would grow past this; the default is
+    /// WebGPU's default `maxBufferSize` of 256 MiB. A primitive larger than this gets a
+    /// buffer of its own.
+    pub max_buffer_size: usize,
+}
+
+impl Default for PackOptions {
+    fn default() -> Self {
+        Self {
+            alignment: 256,
+            max_buffer_size: 256 << 20,
+        }
+    }
+}
+
+/// Byte range of one stream inside a [`PackedScene`] buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct PackedRange {
+    pub offset: u64,
+    pub size: u64,
+    /// Bytes per element: 4 for indices, 12 for positions and so on.
+    pub stride: u32,
+}
+
+/// Where one primitive's data lives, for a bindless renderer's geometry table.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct PackedPrimitive {
+    pub mesh: usize,
+    pub primitive: usize,
+    /// Index into [`PackedScene::buffers`]; all of the primitive's streams are in it.
+    pub buffer: usize,
+    pub vertex_count: u32,
+    pub index_count: u32,
+    /// `u32` indices, local to the primitive's own vertices.
+    pub indices: PackedRange,
+    /// Non-interleaved vertex streams, in [`DecodedPrimitive::upload_chunks`] order.
+    /// Custom `_NAME` attributes aren't packed.
+    pub streams: Vec<(UploadStream, PackedRange)>,
+}
+
+impl PackedPrimitive {
+    pub fn stream(&self, stream: UploadStream) -> Option<PackedRange> {
+        self.streams
+            .iter()
+            .find(|(s, _)| *s == stream)
+            .map(|(_, r)| *r)
+    }
+}
+
+/// One node's use of a packed primitive, from [`pack_scene`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct PackedInstance {
+    pub node: usize,
+    /// Index into [`PackedScene::primitives`].
+    pub primitive: usize,
+    pub material: Option<usize>,
+    pub world_transform: Mat4,
+}
+
+/// Decoded geometry laid out in a few large buffers, plus the manifest to find each
+/// primitive in them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackedScene {
+    pub buffers: Vec<Vec<u8>>,
+    pub primitives: Vec<PackedPrimitive>,
+    /// Empty from [`pack_primitives`].
+    pub instances: Vec<PackedInstance>,
+}
+
+/// Packs `(mesh, primitive, decoded)` triples in the given order, each primitive's
+/// streams contiguous and aligned, so a renderer uploads `buffers` once and addresses
+/// geometry by the manifest's offsets.
+pub fn pack_primitives(
+    parts: &[(usize, usize, &DecodedPrimitive)],
+    options: &PackOptions,
+) -> PackedScene {
+    let align = options.alignment.max(1);
+    let mut out = PackedScene::default();
+    for &(mesh, primitive, d) in parts {
+        let streams = d.upload_streams();
+        let size = streams.iter().fold(0usize, |end, (_, bytes, _)| {
+            end.next_multiple_of(align) + bytes.len()
+        });
+
+        let start_new = match out.buffers.last() {
+            None => true,
+            Some(b) => {
+                !b.is_empty() && b.len().next_multiple_of(align) + size > options.max_buffer_size
+            }
+        };
+        if start_new {
+            out.buffers.push(Vec::new());
+        }
+        let buffer = out.buffers.len() - 1;
+        let b = &mut out.buffers[buffer];
+
+        let mut ranges = Vec::with_capacity(streams.len());
+        for (stream, bytes, stride) in streams {
+            b.resize(b.len().next_multiple_of(align), 0);
+            ranges.push((
+                stream,
+                PackedRange {
+                    offset: b.len() as u64,
+                    size: bytes.len() as u64,
+                    stride: stride as u32,
+                },
+            ));
+            b.extend_from_slice(bytes);
+        }
+        let indices = ranges.remove(0).1;
+        out.primitives.push(PackedPrimitive {
+            mesh,
+            primitive,
+            buffer,
+            vertex_count: vertex_count(d) as u32,
+            index_count: d.indices.len() as u32,
+            indices,
+            streams: ranges,
+        });
+    }
+    out
+}
+
+/// Packs each distinct mesh primitive of `scene` once and lists every node using it as
+/// an instance.
+pub fn pack_scene(scene: &DecodedScene, options: &PackOptions) -> PackedScene {
+    let mut slots = std::collections::HashMap::new();
+    let mut parts = Vec::new();
+    let mut instances = Vec::new();
+    for sp in &scene.primitives {
+        let key = (sp.mesh, sp.primitive);
+        let slot = *slots.entry(key).or_insert_with(|| {
+            parts.push((sp.mesh, sp.primitive, &sp.decoded));
+            parts.len() - 1
+        });
+        instances.push(PackedInstance {
+            node: sp.node,
+            primitive: slot,
+            material: sp.material,
+            world_transform: sp.world_transform,
+        });
+    }
+    PackedScene {
+        instances,
+        ..pack_primitives(&parts, options)
+    }
+}
@@ -0,0 +1,160 @@
+use crate::*;
+
+/// Results of [`DocumentIndex::decode_parallel`], in index order regardless of which worker
+/// finished first.
+#[derive(Debug)]
+pub struct ParallelDecode {
+    pub results: Vec<PrimitiveResult>,
+}
+
+#[derive(Debug)]
+pub struct PrimitiveResult {
+    pub mesh: usize,
+    pub primitive: usize,
+    pub decoded: Result<DecodedPrimitive, DracoLoadError>,
+}
+
+impl ParallelDecode {
+    /// One line per failed primitive, in index order, so logs are reproducible across runs
+    /// and thread counts.
+    pub fn warnings(&self) -> Vec<String> {
+        self.results
+            .iter()
+            .filter_map(|r| {
+                let err = r.decoded.as_ref().err()?;
+                let mut line = format!("mesh {} primitive {}: {err}", r.mesh, r.primitive);
+                if let Some(hint) = err.hint() {
+                    line.push_str(&format!(" ({hint})"));
+                }
+                Some(line)
+            })
+            .collect()
+    }
+}
+
+impl DocumentIndex {
+    /// Decodes every indexed primitive with [`IndexedPrimitive::decode`] on up to `threads`
+    /// OS threads and returns the results in index order.
+    pub fn decode_parallel(
+        &self,
+        buffers: &[gltf::buffer::Data],
+        threads: usize,
+        options: &DecodeOptions,
+    ) -> ParallelDecode {
+        self.decode_parallel_abortable(buffers, threads, options, &AbortHandle::new())
+    }
+
+    /// Like [`decode_parallel`](Self::decode_parallel), but stops starting new decodes once
+    /// `abort` is triggered; primitives not decoded by then report
+    /// [`DracoLoadError::Aborted`].
+    pub fn decode_parallel_abortable(
+        &self,
+        buffers: &[gltf::buffer::Data],
+        threads: usize,
+        options: &DecodeOptions,
+        abort: &AbortHandle,
+    ) -> ParallelDecode {
+        let mut done = Vec::with_capacity(self.primitives.len());
+        self.run_parallel(buffers, threads, options, abort, |i, decoded| {
+            done.push((i, decoded))
+        });
+        done.sort_unstable_by_key(|(i, _)| *i);
+
+        let results = done
+            .into_iter()
+            .map(|(i, decoded)| PrimitiveResult {
+                mesh: self.primitives[i].mesh,
+                primitive: self.primitives[i].primitive,
+                decoded,
+            })
+            .collect();
+        ParallelDecode { results }
+    }
+
+    /// Like [`decode_parallel`](Self::decode_parallel), but hands each result to `on_done` on
+    /// the calling thread as soon as it is ready. Completion order is not deterministic.
+    pub fn decode_parallel_unordered(
+        &self,
+        buffers: &[gltf::buffer::Data],
+        threads: usize,
+        options: &DecodeOptions,
+        mut on_done: impl FnMut(&IndexedPrimitive, Result<DecodedPrimitive, DracoLoadError>),
+    ) {
+        self.run_parallel(
+            buffers,
+            threads,
+            options,
+            &AbortHandle::new(),
+            |i, decoded| on_done(&self.primitives[i], decoded),
+        );
+    }
+
+    fn run_parallel(
+        &self,
+        buffers: &[gltf::buffer::Data],
+        threads: usize,
+        options: &DecodeOptions,
+        abort: &AbortHandle,
+        on_done: impl FnMut(usize, Result<DecodedPrimitive, DracoLoadError>),
+    ) {
+        let decode = |_, entry: &IndexedPrimitive| {
+            block_on(abortable(abort, entry.decode(buffers, options)))
+        };
+        run_parallel(&self.primitives, threads, decode, on_done);
+    }
+}
+
+/// Runs `work` over `items` on up to `threads` OS threads, handing each result to `on_done`
+/// on the calling thread as soon as it is ready. `on_done` sees every index exactly once: the
+/// receiver outlives the workers, and a panicking worker panics the whole call.
+pub(crate) fn run_parallel<T: Sync, R: Send>(
+    items: &[T],
+    threads: usize,
+    work: impl Fn(usize, &T) -> R + Sync,
+    mut on_done: impl FnMut(usize, R),
+) {
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::scope(|s| {
+        for _ in 0..threads.clamp(1, items.len().max(1)) {
+            let tx = tx.clone();
+            let (next, work) = (&next, &work);
+            s.spawn(move || {
+                loop {
+                    let i = next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    let Some(item) = items.get(i) else {
+                        break;
+                    };
+                    if tx.send((i, work(i, item))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+        for (i, result) in rx {
+            on_done(i, result);
+        }
+    });
+}
+
+/// Minimal executor for running the decoder's futures on worker threads; natively they finish
+/// on the first poll.
+pub(crate) fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    struct Unpark(std::thread::Thread);
+    impl std::task::Wake for Unpark {
+        fn wake(self: std::sync::Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = std::task::Waker::from(std::sync::Arc::new(Unpark(std::thread::current())));
+    let mut cx = std::task::Context::from_waker(&waker);
+    let mut fut = std::pin::pin!(fut);
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            std::task::Poll::Ready(out) => return out,
+            std::task::Poll::Pending => std::thread::park(),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::*;
+
+use gltf::json::validation::USize64;
+
+/// Replaces the geometry of one primitive in a GLB and returns the new GLB, leaving every
+/// other mesh, material, texture and buffer view untouched.
+///
+/// The new data is appended to the BIN chunk and the primitive's accessors are redefined
+/// in place (unless another primitive shares them); attributes `new_geometry` lacks are
+/// removed from the primitive. The crate has no Draco encoder, so a Draco primitive is
+/// written uncompressed and loses the extension; its old compressed bufferView stays in
+/// the file, unused.
+pub fn patch_primitive(
+    glb_bytes: &[u8],
+    mesh: usize,
+    primitive: usize,
+    new_geometry: &DecodedPrimitive,
+) -> Result<Vec<u8>, DracoLoadError> {
+    let gltf = gltf::Gltf::from_slice_without_validation(glb_bytes)?;
+    let buffers = gltf::import_buffers(&gltf.document, None, gltf.blob)?;
+    let document = &gltf.document;
+    document
+        .meshes()
+        .nth(mesh)
+        .and_then(|m| m.primitives().nth(primitive))
+        .ok_or(DracoLoadError::NotIndexed(mesh, primitive))?;
+
+    let mut shared = std::collections::HashSet::new();
+    for m in document.meshes() {
+        for p in m.primitives() {
+            if (m.index(), p.index()) != (mesh, primitive) {
+                shared.extend(p.attributes().map(|(_, a)| a.index()));
+                shared.extend(p.indices().map(|a| a.index()));
+            }
+        }
+    }
+
+    let (mut root, mut out) = editable(document, &buffers);
+    let written = splice_primitive(
+        &mut root,
+        &mut out[0],
+        &shared,
+        mesh,
+        primitive,
+        new_geometry,
+    );
+    root.meshes[mesh].primitives[primitive]
+        .attributes
+        .retain(|semantic, _| written.contains(semantic));
+    root.buffers[0].byte_length = USize64(out[0].len() as u64);
+    drop_unused_extension(&mut root);
+
+    StrippedDocument { root, buffers: out }.to_glb()
+}
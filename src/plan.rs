@@ -0,0 +1,180 @@
+use crate::*;
+
+use gltf::accessor::DataType;
+
+/// What [`execute`] will write for a Draco primitive, from [`plan_decode`]: taken from the
+/// document alone, so destination buffers (GPU ones, typically) can be allocated before
+/// any buffer is read or decoded.
+///
+/// Values stay in their stored component types, as in [`RawPrimitive`]; nothing is
+/// dequantized or widened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodePlan {
+    /// `U16` for `U8` index accessors, which the decoder can't write.
+    pub index_type: DataType,
+    pub index_count: usize,
+    pub vertex_count: usize,
+    /// In Draco unique id order.
+    pub attributes: Vec<PlannedAttribute>,
+    view: usize,
+    buffer: usize,
+    offset: usize,
+    length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedAttribute {
+    pub unique_id: u32,
+    pub semantic: gltf::Semantic,
+    pub data_type: DataType,
+    pub components: usize,
+    pub normalized: bool, // from the glTF accessor
+    /// `vertex_count * components` values, tightly packed, little-endian.
+    pub byte_size: usize,
+}
+
+impl PlannedAttribute {
+    fn info(&self) -> AttrInfo {
+        AttrInfo {
+            unique_id: self.unique_id,
+            dim: self.components as u32,
+            data_type: gltf_to_draco_dt(self.data_type),
+        }
+    }
+}
+
+impl DecodePlan {
+    pub fn index_byte_size(&self) -> usize {
+        self.index_count * comp_size_bytes(self.index_type)
+    }
+
+    /// Indices and every attribute together.
+    pub fn byte_size(&self) -> usize {
+        self.index_byte_size() + self.attributes.iter().map(|a| a.byte_size).sum::<usize>()
+    }
+
+    pub fn attribute(&self, semantic: &gltf::Semantic) -> Option<&PlannedAttribute> {
+        self.attributes.iter().find(|a| &a.semantic == semantic)
+    }
+}
+
+/// Buffers sized exactly as a [`DecodePlan`] describes, filled by [`execute`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlannedBuffers {
+    pub indices: Vec<u8>,
+    /// In [`DecodePlan::attributes`] order.
+    pub attributes: Vec<Vec<u8>>,
+}
+
+/// Plans the decode of Draco primitive `p` from its extension and accessors.
+pub fn plan_decode(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+) -> Result<DecodePlan, DracoLoadError> {
+    if p.mode() != gltf::mesh::Mode::Triangles {
+        return Err(DracoLoadError::UnsupportedMode(p.mode()));
+    }
+    let draco_ext = draco_extension(p)?;
+    let bv = buffer_view(document, draco_ext.buffer_view)?;
+    let vertex_count = p
+        .get(&gltf::Semantic::Positions)
+        .ok_or(DracoLoadError::NoPositionAccessor)?
+        .count();
+    let indices = p.indices().ok_or(DracoLoadError::NoIndicesAccessor)?;
+
+    let mut attributes = Vec::with_capacity(draco_ext.attributes.len());
+    for (key, info) in derive_attr_infos(p, &draco_ext)? {
+        let semantic =
+            dracokey_to_semantic(key).ok_or_else(|| DracoLoadError::MissingAccessor(key.into()))?;
+        let accessor = p
+            .get(&semantic)
+            .ok_or_else(|| DracoLoadError::MissingAccessor(key.into()))?;
+        let components = info.dim as usize;
+        attributes.push(PlannedAttribute {
+            unique_id: info.unique_id,
+            semantic,
+            data_type: accessor.data_type(),
+            components,
+            normalized: accessor.normalized(),
+            byte_size: vertex_count * components * comp_size_bytes(accessor.data_type()),
+        });
+    }
+
+    Ok(DecodePlan {
+        index_type: index_comp_workaround(indices.data_type()),
+        index_count: indices.count(),
+        vertex_count,
+        attributes,
+        view: bv.index(),
+        buffer: bv.buffer().index(),
+        offset: bv.offset(),
+        length: bv.length(),
+    })
+}
+
+/// Decodes `plan` into newly allocated buffers of exactly the planned sizes.
+pub async fn execute(
+    plan: &DecodePlan,
+    buffers: &[gltf::buffer::Data],
+) -> Result<PlannedBuffers, DracoLoadError> {
+    let mut out = PlannedBuffers {
+        indices: vec![0; plan.index_byte_size()],
+        attributes: plan
+            .attributes
+            .iter()
+            .map(|a| vec![0; a.byte_size])
+            .collect(),
+    };
+    let mut targets: Vec<&mut [u8]> = out.attributes.iter_mut().map(Vec::as_mut_slice).collect();
+    execute_into(plan, buffers, &mut out.indices, &mut targets).await?;
+    Ok(out)
+}
+
+/// Decodes `plan` into caller-owned destinations, one per [`DecodePlan::attributes`] entry.
+///
+/// The stream's own attributes are checked against the plan; one stored with another type
+/// or component count than its accessor declares is [`DracoLoadError::PlanMismatch`].
+/// Waits for a slot under the cap set with [`set_max_concurrent_decodes`].
+///
+/// # Panics
+///
+/// If a destination's length differs from the planned byte size.
+pub async fn execute_into(
+    plan: &DecodePlan,
+    buffers: &[gltf::buffer::Data],
+    indices: &mut [u8],
+    attributes: &mut [&mut [u8]],
+) -> Result<(), DracoLoadError> {
+    assert_eq!(attributes.len(), plan.attributes.len());
+    let buf = buffers
+        .get(plan.buffer)
+        .ok_or(DracoLoadError::BadBuffer(plan.buffer))?;
+    let draco_bytes = view_bytes(buf, plan.view, plan.offset, plan.length)?;
+    let planned: Vec<AttrInfo> = plan.attributes.iter().map(PlannedAttribute::info).collect();
+    let infos = stream_attr_infos(draco_bytes).unwrap_or_else(|| planned.clone());
+    // where each planned attribute sits among the ones the decoder writes
+    let mut at = Vec::with_capacity(planned.len());
+    for (info, attr) in planned.iter().zip(&plan.attributes) {
+        let stored = infos.iter().position(|i| i.unique_id == info.unique_id);
+        match stored {
+            Some(i) if infos[i] == *info => at.push(i),
+            _ => {
+                return Err(DracoLoadError::PlanMismatch {
+                    semantic: attr.semantic.to_string(),
+                    planned: info.clone(),
+                    stored: stored.map(|i| infos[i].clone()),
+                });
+            }
+        }
+    }
+    let cfg = decode_config(plan.vertex_count, plan.index_count, &infos);
+    let raw = decode_with_config(draco_bytes, &cfg).await?;
+
+    let index_bytes = plan.index_byte_size();
+    indices.copy_from_slice(&raw[..index_bytes]);
+    let blocks = attr_slices(&raw, index_bytes, plan.vertex_count, &infos);
+    for (dest, i) in attributes.iter_mut().zip(at) {
+        dest.copy_from_slice(blocks[i].bytes);
+    }
+    Ok(())
+}
@@ -0,0 +1,246 @@
+use crate::*;
+
+use std::ffi::{CStr, c_char, c_void};
+use std::sync::{Arc, RwLock};
+
+/// ABI version a plugin must report in [`AttributePlugin::abi_version`].
+pub const ATTRIBUTE_PLUGIN_ABI: u32 = 1;
+
+/// Symbol `load_attribute_plugin` looks up, declared in C as
+/// `const AttributePlugin *draco_gltf_attribute_plugin(void)`.
+pub const ATTRIBUTE_PLUGIN_SYMBOL: &str = "draco_gltf_attribute_plugin";
+
+/// A custom attribute decoder behind a C ABI, so vendors can ship interpreters for their
+/// `_NAME` streams (say, a proprietary packing inside `_VENDOR_DATA`) as a closed-source
+/// shared library. In C:
+///
+/// ```c
+/// typedef struct {
+///     uint32_t abi_version;      /* ATTRIBUTE_PLUGIN_ABI */
+///     const char *prefix;        /* "_VENDOR_"; matches every attribute starting with it */
+///     uint32_t components;       /* floats written per vertex */
+///     void *user_data;
+///     int32_t (*decode)(void *user_data, const AttributePluginInput *in, float *out, size_t out_len);
+///     void (*destroy)(void *user_data); /* may be NULL */
+/// } AttributePlugin;
+/// ```
+///
+/// `decode` gets the attribute's decoded Draco block and fills `out`, which holds
+/// `vertex_count * components` floats, returning 0 on success or a nonzero code that
+/// surfaces as [`DracoLoadError::AttributePlugin`]. It may be called from several threads
+/// at once.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct AttributePlugin {
+    pub abi_version: u32,
+    pub prefix: *const c_char,
+    pub components: u32,
+    pub user_data: *mut c_void,
+    pub decode: Option<
+        unsafe extern "C" fn(*mut c_void, *const AttributePluginInput, *mut f32, usize) -> i32,
+    >,
+    pub destroy: Option<unsafe extern "C" fn(*mut c_void)>,
+}
+
+/// One attribute block handed to [`AttributePlugin::decode`]. Pointers are valid only for
+/// the duration of the call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct AttributePluginInput {
+    /// The full attribute name, e.g. `_VENDOR_DATA`; not NUL-terminated.
+    pub name: *const u8,
+    pub name_len: usize,
+    /// Values as Draco decoded them, little-endian, `components` per vertex.
+    pub bytes: *const u8,
+    pub byte_len: usize,
+    pub vertex_count: usize,
+    pub components: u32,
+    /// GL component type: 5120 (i8), 5121 (u8), 5122 (i16), 5123 (u16), 5124 (i32),
+    /// 5125 (u32) or 5126 (f32).
+    pub component_type: u32,
+    /// The accessor's (or registry's) `normalized` flag, 0 or 1.
+    pub normalized: u8,
+}
+
+struct Registered {
+    prefix: String,
+    plugin: AttributePlugin,
+    data: Arc<PluginData>,
+}
+
+/// A plugin's `user_data`, destroyed once no registration or running decode uses it; a
+/// table registered again (or under another prefix) shares it instead of destroying it.
+struct PluginData {
+    user_data: *mut c_void,
+    destroy: Option<unsafe extern "C" fn(*mut c_void)>,
+}
+
+// Plugins promise thread-safe `decode`; the pointers are only handed back to them.
+unsafe impl Send for Registered {}
+unsafe impl Sync for Registered {}
+unsafe impl Send for PluginData {}
+unsafe impl Sync for PluginData {}
+
+impl Drop for PluginData {
+    fn drop(&mut self) {
+        if let Some(destroy) = self.destroy {
+            // SAFETY: `destroy` and `user_data` come from the same plugin table
+            unsafe { destroy(self.user_data) };
+        }
+    }
+}
+
+static PLUGINS: RwLock<Vec<Arc<Registered>>> = RwLock::new(Vec::new());
+
+/// Registers `plugin` for every decode in the process. Longer prefixes win over shorter
+/// ones, and a later plugin replaces an earlier one with the same prefix. Registering a
+/// table again is fine: its `user_data` is destroyed once, after its last registration
+/// is gone.
+///
+/// # Safety
+///
+/// `plugin` must point to a valid [`AttributePlugin`] whose `prefix` is a NUL-terminated
+/// string and whose functions stay callable, from any thread, until it is unregistered
+/// (see [`unregister_attribute_plugin`] and [`clear_attribute_plugins`]).
+pub unsafe fn register_attribute_plugin(
+    plugin: *const AttributePlugin,
+) -> Result<(), DracoLoadError> {
+    let bad = |why: &str| DracoLoadError::AttributePluginAbi(why.to_string());
+    // SAFETY: the caller guarantees `plugin` is null or valid
+    let plugin = unsafe { plugin.as_ref() }.ok_or_else(|| bad("null plugin table"))?;
+    if plugin.abi_version != ATTRIBUTE_PLUGIN_ABI {
+        return Err(bad(&format!(
+            "ABI version {} (expected {ATTRIBUTE_PLUGIN_ABI})",
+            plugin.abi_version
+        )));
+    }
+    if plugin.prefix.is_null() || plugin.decode.is_none() || plugin.components == 0 {
+        return Err(bad("missing prefix, decode function or component count"));
+    }
+    // SAFETY: checked non-null; the caller guarantees NUL termination
+    let prefix = unsafe { CStr::from_ptr(plugin.prefix) }
+        .to_str()
+        .map_err(|_| bad("prefix isn't UTF-8"))?;
+    if !prefix.starts_with('_') {
+        return Err(bad("prefix must start with '_'"));
+    }
+
+    let mut plugins = PLUGINS.write().unwrap_or_else(|e| e.into_inner());
+    let data = plugins
+        .iter()
+        .find(|p| {
+            p.plugin.user_data == plugin.user_data
+                && p.plugin.destroy.map(|f| f as usize) == plugin.destroy.map(|f| f as usize)
+        })
+        .map(|p| p.data.clone())
+        .unwrap_or_else(|| {
+            Arc::new(PluginData {
+                user_data: plugin.user_data,
+                destroy: plugin.destroy,
+            })
+        });
+    plugins.retain(|p| p.prefix != prefix);
+    plugins.push(Arc::new(Registered {
+        prefix: prefix.to_string(),
+        plugin: *plugin,
+        data,
+    }));
+    plugins.sort_by_key(|p| std::cmp::Reverse(p.prefix.len()));
+    Ok(())
+}
+
+/// Drops the plugin registered for exactly `prefix`, returning whether there was one. Its
+/// `destroy` runs once no decode or other registration still uses its `user_data`.
+pub fn unregister_attribute_plugin(prefix: &str) -> bool {
+    let mut plugins = PLUGINS.write().unwrap_or_else(|e| e.into_inner());
+    let before = plugins.len();
+    plugins.retain(|p| p.prefix != prefix);
+    plugins.len() != before
+}
+
+/// Drops every registered plugin, calling their `destroy` once no decode still uses them.
+pub fn clear_attribute_plugins() {
+    PLUGINS.write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// Loads a shared library exporting [`ATTRIBUTE_PLUGIN_SYMBOL`] and registers its plugin.
+/// The library stays loaded for the life of the process.
+///
+/// # Safety
+///
+/// Loading a library runs its initializers, and the plugin table it returns must meet the
+/// requirements of [`register_attribute_plugin`].
+#[cfg(feature = "plugins")]
+pub unsafe fn load_attribute_plugin(
+    path: impl AsRef<std::ffi::OsStr>,
+) -> Result<(), DracoLoadError> {
+    let load = |e: libloading::Error| DracoLoadError::AttributePluginAbi(e.to_string());
+    // SAFETY: the caller vouches for the library
+    let library = unsafe { libloading::Library::new(path) }.map_err(load)?;
+    let table = unsafe {
+        let entry: libloading::Symbol<unsafe extern "C" fn() -> *const AttributePlugin> = library
+            .get(ATTRIBUTE_PLUGIN_SYMBOL.as_bytes())
+            .map_err(load)?;
+        entry()
+    };
+    // unloading would leave the registered function pointers dangling
+    std::mem::forget(library);
+    // SAFETY: as promised by the caller
+    unsafe { register_attribute_plugin(table) }
+}
+
+/// The plugin registered for attribute `key`, if any.
+fn plugin_for(key: &str) -> Option<Arc<Registered>> {
+    let plugins = PLUGINS.read().unwrap_or_else(|e| e.into_inner());
+    plugins
+        .iter()
+        .find(|p| key.starts_with(p.prefix.as_str()))
+        .cloned()
+}
+
+/// Runs the plugin registered for `key` over `blk`, or `None` when there is none.
+pub(crate) fn decode_with_plugin(
+    key: &str,
+    blk: &AttrSlice<'_>,
+    normalized: bool,
+) -> Option<Result<CustomAttribute, DracoLoadError>> {
+    use draco_decoder::AttributeDataType::*;
+    let registered = plugin_for(key)?;
+    let plugin = &registered.plugin;
+    let decode = plugin.decode?;
+    let (component_type, size) = match blk.dt {
+        Int8 => (5120, 1),
+        UInt8 => (5121, 1),
+        Int16 => (5122, 2),
+        UInt16 => (5123, 2),
+        Int32 => (5124, 4),
+        UInt32 => (5125, 4),
+        Float32 => (5126, 4),
+    };
+    let vertex_count = blk.bytes.len() / (size * blk.dim.max(1));
+    let components = plugin.components as usize;
+    let mut values = vec![0.0f32; vertex_count * components];
+    let input = AttributePluginInput {
+        name: key.as_ptr(),
+        name_len: key.len(),
+        bytes: blk.bytes.as_ptr(),
+        byte_len: blk.bytes.len(),
+        vertex_count,
+        components: blk.dim as u32,
+        component_type,
+        normalized: normalized as u8,
+    };
+    // SAFETY: registration checked the table; `input` and `values` outlive the call
+    let code = unsafe { decode(plugin.user_data, &input, values.as_mut_ptr(), values.len()) };
+    Some(if code == 0 {
+        Ok(CustomAttribute {
+            components,
+            values: CustomValues::F32(values),
+        })
+    } else {
+        Err(DracoLoadError::AttributePlugin {
+            name: key.to_string(),
+            code,
+        })
+    })
+}
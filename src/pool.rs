@@ -0,0 +1,121 @@
+use crate::*;
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Vertex data shared by several primitives, from [`decode_vertex_pools`].
+#[derive(Debug, Clone)]
+pub struct VertexPool {
+    /// The shared vertices; `indices` holds every member's indices back to back.
+    pub vertices: DecodedPrimitive,
+    pub members: Vec<PoolMember>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolMember {
+    pub mesh: usize,
+    pub primitive: usize,
+    /// This primitive's slice of [`VertexPool::vertices`]`.indices`. Members decoded from
+    /// the same Draco stream share one range.
+    pub indices: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum PoolKey {
+    /// Same compressed stream and attribute ids; the stream carries the indices too.
+    Draco(usize, Vec<(std::sync::Arc<str>, u32)>),
+    /// Same plain attribute accessors, with any indices.
+    Plain(Vec<(String, usize)>),
+}
+
+fn pool_key(p: &gltf::mesh::Primitive<'_>) -> Option<PoolKey> {
+    if p.mode() != gltf::mesh::Mode::Triangles {
+        return None;
+    }
+    if p.extension_value("KHR_draco_mesh_compression").is_some() {
+        let ext = draco_extension(p).ok()?;
+        return Some(PoolKey::Draco(ext.buffer_view, ext.attributes));
+    }
+    let mut attributes: Vec<_> = p
+        .attributes()
+        .map(|(s, a)| (format!("{s:?}"), a.index()))
+        .collect();
+    attributes.sort();
+    Some(PoolKey::Plain(attributes))
+}
+
+/// Groups of primitives, as `(mesh, primitive)`, that use the same vertex data: exporters
+/// often split one logical mesh by material this way. Only groups of two or more are
+/// returned, each in document order.
+pub fn detect_shared_vertices(document: &gltf::Document) -> Vec<Vec<(usize, usize)>> {
+    let mut groups: Vec<Vec<(usize, usize)>> = Vec::new();
+    let mut by_key: HashMap<PoolKey, usize> = HashMap::new();
+    for mesh in document.meshes() {
+        for p in mesh.primitives() {
+            let Some(key) = pool_key(&p) else { continue };
+            let slot = *by_key.entry(key).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[slot].push((mesh.index(), p.index()));
+        }
+    }
+    groups.retain(|g| g.len() > 1);
+    groups
+}
+
+/// Decodes each group from [`detect_shared_vertices`] once, instead of duplicating the
+/// vertices per primitive.
+pub async fn decode_vertex_pools(
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+) -> Result<Vec<VertexPool>, DracoLoadError> {
+    let mut pools = Vec::new();
+    for group in detect_shared_vertices(document) {
+        let lookup = |(mesh, primitive): (usize, usize)| {
+            document
+                .meshes()
+                .nth(mesh)
+                .and_then(|m| m.primitives().nth(primitive))
+                .ok_or(DracoLoadError::NotIndexed(mesh, primitive))
+        };
+        let first = lookup(group[0])?;
+        let draco = first
+            .extension_value("KHR_draco_mesh_compression")
+            .is_some();
+        let mut vertices = if draco {
+            decode_draco_derived(&first, document, buffers).await?
+        } else {
+            read_fallback(&first, buffers)?
+        };
+
+        let mut members = Vec::new();
+        if draco {
+            let indices = 0..vertices.indices.len();
+            for &(mesh, primitive) in &group {
+                let indices = indices.clone();
+                members.push(PoolMember {
+                    mesh,
+                    primitive,
+                    indices,
+                });
+            }
+        } else {
+            vertices.indices.clear();
+            for &(mesh, primitive) in &group {
+                let p = lookup((mesh, primitive))?;
+                let start = vertices.indices.len();
+                // a member without indices draws the shared vertices in order
+                vertices.indices.extend(plain_indices(&p, buffers)?);
+                let indices = start..vertices.indices.len();
+                members.push(PoolMember {
+                    mesh,
+                    primitive,
+                    indices,
+                });
+            }
+        }
+        pools.push(VertexPool { vertices, members });
+    }
+    Ok(pools)
+}
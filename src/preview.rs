@@ -0,0 +1,104 @@
+use crate::*;
+
+/// Which triangles [`decode_draco_preview`] keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preview {
+    /// The first `n` triangles in stream order.
+    FirstTriangles(usize),
+    /// About `n` triangles spread evenly over the whole mesh, for thumbnails that should
+    /// show the full shape.
+    Subsample(usize),
+}
+
+/// Decodes `p` but converts only the triangles `preview` selects and the vertices they
+/// reference, compacted in first-use order.
+///
+/// The Draco decode itself still runs in full; what's saved is the conversion and memory
+/// of everything else, which dominates listings over large repositories. Quirks, the
+/// decode cap and the coordinate conventions of `options` are applied; fallback,
+/// accessor-order and vertex-delta options are not. Triangles referencing vertices past the
+/// end of the stream are dropped.
+pub async fn decode_draco_preview(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    infos: &[AttrInfo],
+    preview: Preview,
+    options: &DecodeOptions,
+) -> Result<DecodedPrimitive, DracoLoadError> {
+    let input = prozes_in(p, document, buffers, infos, &options.quirks)?;
+    let raw = decode_limited(
+        input.draco_bytes,
+        &input.cfg,
+        options.max_concurrent_decodes,
+    )
+    .await?;
+    let index_bytes = input.index_count * comp_size_bytes(input.index_comp);
+    let indices = get_indices(&raw, index_bytes, input.index_comp)?;
+
+    let triangles = indices.len() / 3;
+    let (limit, step) = match preview {
+        Preview::FirstTriangles(n) => (n, 1),
+        Preview::Subsample(n) => (n, triangles.div_ceil(n.max(1)).max(1)),
+    };
+    let count = input.vertex_count;
+    let picked: Vec<u32> = indices
+        .chunks_exact(3)
+        .step_by(step)
+        .filter(|t| t.iter().all(|&i| (i as usize) < count))
+        .take(limit)
+        .flatten()
+        .copied()
+        .collect();
+
+    let mut remap = std::collections::HashMap::new();
+    let mut keep: Vec<usize> = Vec::new();
+    let indices = picked
+        .iter()
+        .map(|&i| {
+            *remap.entry(i).or_insert_with(|| {
+                keep.push(i as usize);
+                (keep.len() - 1) as u32
+            })
+        })
+        .collect();
+
+    let map = options.semantic_map(p, &input.draco_ext)?;
+    let blocks: Vec<AttrSlice<'_>> = attr_slices(&raw, index_bytes, count, &input.infos)
+        .into_iter()
+        .filter(|b| options.converts_block(&map, b))
+        .collect();
+    let compacted: Vec<Vec<u8>> = blocks
+        .iter()
+        .map(|blk| {
+            let stride = blk.bytes.len() / count.max(1);
+            keep.iter()
+                .flat_map(|&i| &blk.bytes[i * stride..(i + 1) * stride])
+                .copied()
+                .collect()
+        })
+        .collect();
+    let slices: Vec<AttrSlice<'_>> = blocks
+        .into_iter()
+        .zip(&compacted)
+        .map(|(blk, bytes)| AttrSlice { bytes, ..blk })
+        .collect();
+
+    let mut out = DecodedPrimitive {
+        indices,
+        ..Default::default()
+    };
+    fill_primitive_recycled(
+        &mut out,
+        &slices,
+        &map,
+        &options.transforms,
+        &mut PrimitiveBuffers::default(),
+    )?;
+    let conventions = DecodeOptions {
+        deltas: None,
+        ..options.clone()
+    };
+    conventions.apply_conventions(&mut out);
+    Ok(out)
+}
@@ -0,0 +1,92 @@
+use crate::*;
+
+/// A primitive whose indices and positions are ready for silhouette rendering while the
+/// remaining attributes are still raw decoder output.
+///
+/// Draco decodes all attributes in one pass, so the saving is in conversion: phase one
+/// converts only indices and positions, [`await_full`](Self::await_full) converts the rest.
+pub struct ProgressivePrimitive {
+    partial: DecodedPrimitive,
+    raw: Vec<u8>,
+    index_bytes: usize,
+    vertex_count: usize,
+    infos: Vec<AttrInfo>,
+    dracoid_to_sem: SemanticMap,
+}
+
+/// Decodes `p` and converts just indices and positions before returning.
+pub async fn decode_draco_progressive(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    infos: &[AttrInfo],
+) -> Result<ProgressivePrimitive, DracoLoadError> {
+    let input = prozes_in(p, document, buffers, infos, &VendorQuirks::default())?;
+    let raw = decode_with_config(input.draco_bytes, &input.cfg).await?;
+    let dracoid_to_sem = semantic_map(p, &input.draco_ext);
+
+    let index_bytes = input.index_count * comp_size_bytes(input.index_comp);
+    let mut partial = DecodedPrimitive {
+        indices: get_indices(&raw, index_bytes, input.index_comp)?,
+        ..Default::default()
+    };
+    let blocks = attr_slices(&raw, index_bytes, input.vertex_count, &input.infos);
+    let positions: Vec<AttrSlice<'_>> = blocks
+        .into_iter()
+        .filter(|b| is_position(&dracoid_to_sem, b))
+        .collect();
+    fill_primitive(&mut partial, &positions, &dracoid_to_sem)?;
+
+    Ok(ProgressivePrimitive {
+        partial,
+        raw,
+        index_bytes,
+        vertex_count: input.vertex_count,
+        infos: input.infos,
+        dracoid_to_sem,
+    })
+}
+
+impl ProgressivePrimitive {
+    pub fn indices(&self) -> &[u32] {
+        &self.partial.indices
+    }
+
+    pub fn positions(&self) -> Option<&[[f32; 3]]> {
+        self.partial.positions.as_deref()
+    }
+
+    /// Converts the remaining attributes, yielding to the executor once first so a render
+    /// loop can present the silhouette in between.
+    pub async fn await_full(self) -> Result<DecodedPrimitive, DracoLoadError> {
+        yield_now().await;
+        let mut out = self.partial;
+        let rest: Vec<AttrSlice<'_>> =
+            attr_slices(&self.raw, self.index_bytes, self.vertex_count, &self.infos)
+                .into_iter()
+                .filter(|b| !is_position(&self.dracoid_to_sem, b))
+                .collect();
+        fill_primitive(&mut out, &rest, &self.dracoid_to_sem)?;
+        Ok(out)
+    }
+}
+
+fn is_position(map: &SemanticMap, blk: &AttrSlice<'_>) -> bool {
+    matches!(
+        map.get(&blk.unique_id),
+        Some((gltf::Semantic::Positions, ..))
+    )
+}
+
+async fn yield_now() {
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if yielded {
+            return std::task::Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        std::task::Poll::Pending
+    })
+    .await
+}
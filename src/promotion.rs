@@ -0,0 +1,111 @@
+use crate::*;
+
+use draco_decoder::AttributeDataType;
+
+/// Which implicit widenings the decoder may make to fit a stream into
+/// [`DecodedPrimitive`]'s types, for [`DecodeOptions::promotions`].
+///
+/// Everything is allowed by default. Pipelines that must not lose track of the stored
+/// types (say, a re-exporter that would otherwise write quantized data back as `f32`)
+/// can forbid some or all of them and get [`DracoLoadError::ForbiddenPromotion`] instead,
+/// before the stream is decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromotionPolicy {
+    /// `UNSIGNED_BYTE` and `UNSIGNED_SHORT` indices widened to `u32`.
+    pub indices: bool,
+    /// `UNSIGNED_BYTE` joints widened to `u16`, or non-`u16` ones converted by value.
+    pub joints: bool,
+    /// Integer (normalized) weights converted to `f32`.
+    pub weights: bool,
+    /// Every other integer attribute converted to `f32`: quantized positions, normals,
+    /// tangents, texcoords and colors (`KHR_mesh_quantization`) and custom `_NAME` streams.
+    pub attributes: bool,
+}
+
+impl Default for PromotionPolicy {
+    fn default() -> Self {
+        Self::lenient()
+    }
+}
+
+impl PromotionPolicy {
+    /// Allows every promotion; the decoder's historical behavior.
+    pub fn lenient() -> Self {
+        Self {
+            indices: true,
+            joints: true,
+            weights: true,
+            attributes: true,
+        }
+    }
+
+    /// Allows none: only `u32` indices, `u16` joints and `f32` attributes decode.
+    pub fn strict() -> Self {
+        Self {
+            indices: false,
+            joints: false,
+            weights: false,
+            attributes: false,
+        }
+    }
+
+    /// Fails on the first promotion decoding `p` with `infos` would need that this policy
+    /// forbids, in indices-then-`infos` order.
+    pub(crate) fn check(
+        &self,
+        p: &gltf::mesh::Primitive<'_>,
+        infos: &[AttrInfo],
+        map: &SemanticMap,
+    ) -> Result<(), DracoLoadError> {
+        if !self.indices
+            && let Some(acc) = p.indices()
+            && acc.data_type() != gltf::accessor::DataType::U32
+        {
+            return Err(forbidden("indices", type_name(acc.data_type()), "u32"));
+        }
+        for info in infos {
+            let Some((sem, ..)) = map.get(&info.unique_id) else {
+                continue;
+            };
+            let dt = info.attribute_data_type();
+            let (allowed, to) = match sem {
+                gltf::Semantic::Joints(_) => (self.joints, "u16"),
+                gltf::Semantic::Weights(_) => (self.weights, "f32"),
+                _ => (self.attributes, "f32"),
+            };
+            let stored = match dt {
+                AttributeDataType::Int8 => "i8",
+                AttributeDataType::UInt8 => "u8",
+                AttributeDataType::Int16 => "i16",
+                AttributeDataType::UInt16 => "u16",
+                AttributeDataType::Int32 => "i32",
+                AttributeDataType::UInt32 => "u32",
+                AttributeDataType::Float32 => "f32",
+            };
+            if !allowed && stored != to {
+                return Err(forbidden(&sem.to_string(), stored, to));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn forbidden(attribute: &str, from: &'static str, to: &'static str) -> DracoLoadError {
+    DracoLoadError::ForbiddenPromotion {
+        attribute: attribute.to_string(),
+        from,
+        to,
+    }
+}
+
+fn type_name(dt: gltf::accessor::DataType) -> &'static str {
+    use gltf::accessor::DataType::*;
+    match dt {
+        I8 => "i8",
+        U8 => "u8",
+        I16 => "i16",
+        U16 => "u16",
+        U32 => "u32",
+        F32 => "f32",
+    }
+}
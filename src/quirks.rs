@@ -0,0 +1,123 @@
+use crate::*;
+
+/// Switches for non-standard `KHR_draco_mesh_compression` layouts written by legacy
+/// exporters. All off by default, which is the strict spec parser.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VendorQuirks {
+    /// Extra extension names to read the Draco object from when the KHR one is absent.
+    pub extension_names: Vec<String>,
+    /// Accept `buffer_view`, `bufferview` and `BufferView` for `bufferView`.
+    pub buffer_view_aliases: bool,
+    /// Accept ids written as strings, glTF 1.0 style (`"3"`, `"bufferView_3"`).
+    pub string_ids: bool,
+    /// Accept lowercase attribute keys and missing or unseparated set indices
+    /// (`texcoord`, `TEXCOORD0`), normalized to `TEXCOORD_0`.
+    pub loose_attribute_keys: bool,
+}
+
+impl VendorQuirks {
+    /// Every switch on, with no extra extension names.
+    pub fn tolerant() -> Self {
+        Self {
+            extension_names: Vec::new(),
+            buffer_view_aliases: true,
+            string_ids: true,
+            loose_attribute_keys: true,
+        }
+    }
+
+    pub fn with_extension_name(mut self, name: impl Into<String>) -> Self {
+        self.extension_names.push(name.into());
+        self
+    }
+}
+
+const BUFFER_VIEW_ALIASES: [&str; 3] = ["buffer_view", "bufferview", "BufferView"];
+
+pub(crate) fn draco_extension_with_quirks(
+    p: &gltf::mesh::Primitive<'_>,
+    quirks: &VendorQuirks,
+) -> Result<DracoExt, DracoLoadError> {
+    if *quirks == VendorQuirks::default() {
+        return draco_extension(p);
+    }
+    let value = std::iter::once("KHR_draco_mesh_compression")
+        .chain(quirks.extension_names.iter().map(String::as_str))
+        .find_map(|name| p.extension_value(name))
+        .ok_or(DracoLoadError::NotDraco)?;
+    let obj = value.as_object().ok_or(DracoLoadError::BadExtension)?;
+
+    let view = obj.get("bufferView").or_else(|| {
+        BUFFER_VIEW_ALIASES
+            .iter()
+            .filter(|_| quirks.buffer_view_aliases)
+            .find_map(|k| obj.get(*k))
+    });
+    let buffer_view = view
+        .and_then(|v| parse_id(v, quirks.string_ids))
+        .ok_or(DracoLoadError::BadExtension)? as usize;
+
+    let mut attributes: Vec<(std::sync::Arc<str>, u32)> = Vec::new();
+    let attrs = obj
+        .get("attributes")
+        .and_then(|a| a.as_object())
+        .ok_or(DracoLoadError::BadExtension)?;
+    for (key, id) in attrs {
+        let id = parse_id(id, quirks.string_ids).ok_or(DracoLoadError::BadExtension)?;
+        let key = if quirks.loose_attribute_keys {
+            normalize_key(key)
+        } else {
+            key.clone()
+        };
+        let key = intern_semantic(&key);
+        // normalized keys can collide; the last one wins, as in a map
+        attributes.retain(|(k, _)| *k != key);
+        attributes.push((
+            key,
+            u32::try_from(id).map_err(|_| DracoLoadError::BadExtension)?,
+        ));
+    }
+    attributes.sort_by_key(|&(_, id)| id);
+    Ok(DracoExt {
+        buffer_view,
+        attributes,
+    })
+}
+
+fn parse_id(v: &serde_json::Value, string_ids: bool) -> Option<u64> {
+    if let Some(n) = v.as_u64() {
+        return Some(n);
+    }
+    let s = v.as_str().filter(|_| string_ids)?;
+    let digits = s.trim_start_matches(|c: char| !c.is_ascii_digit());
+    digits.parse().ok()
+}
+
+/// `texcoord` -> `TEXCOORD_0`, `COLOR1` -> `COLOR_1`; standard keys pass through unchanged.
+pub(crate) fn normalize_key(key: &str) -> String {
+    let upper = key.to_ascii_uppercase();
+    for kind in ["TEXCOORD", "COLOR", "JOINTS", "WEIGHTS"] {
+        if let Some(rest) = upper.strip_prefix(kind) {
+            let set = rest.trim_start_matches('_');
+            if set.is_empty() {
+                return format!("{kind}_0");
+            }
+            if set.bytes().all(|b| b.is_ascii_digit()) {
+                return format!("{kind}_{set}");
+            }
+        }
+    }
+    upper
+}
+
+/// Attribute infos for `p` taken from its own accessors, reading the extension with `quirks`.
+pub fn attr_infos_with_quirks(
+    p: &gltf::mesh::Primitive<'_>,
+    quirks: &VendorQuirks,
+) -> Result<Vec<AttrInfo>, DracoLoadError> {
+    let ext = draco_extension_with_quirks(p, quirks)?;
+    Ok(derive_attr_infos(p, &ext)?
+        .into_iter()
+        .map(|(_, info)| info)
+        .collect())
+}
@@ -0,0 +1,74 @@
+use crate::*;
+
+/// Draco output kept in its stored (possibly quantized) component types, for callers that
+/// convert on their own terms, e.g. on the GPU with the `gpu` feature.
+#[derive(Debug, Clone, Default)]
+pub struct RawPrimitive {
+    pub indices: Vec<u32>,
+    pub vertex_count: usize,
+    pub attributes: Vec<RawAttribute>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RawAttribute {
+    pub unique_id: u32,
+    pub semantic: Option<gltf::Semantic>,
+    pub dim: usize,
+    pub data_type: u8,    // draco::DataType as a small integer, see `AttrInfo`
+    pub normalized: bool, // from the glTF accessor
+    pub bytes: Vec<u8>,   // little-endian, `dim` components per vertex
+}
+
+pub async fn decode_draco_raw(
+    p: &gltf::mesh::Primitive<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    infos: &[AttrInfo],
+) -> Result<RawPrimitive, DracoLoadError> {
+    let input = prozes_in(p, document, buffers, infos, &VendorQuirks::default())?;
+    let raw = decode_with_config(input.draco_bytes, &input.cfg).await?;
+
+    let index_bytes = input.index_count * comp_size_bytes(input.index_comp);
+    let indices = get_indices(&raw, index_bytes, input.index_comp)?;
+
+    let mut sem_by_id = std::collections::HashMap::new();
+    for (k, id) in &input.draco_ext.attributes {
+        sem_by_id.insert(*id, &**k);
+    }
+
+    let blocks = attr_slices(&raw, index_bytes, input.vertex_count, &input.infos);
+    let attributes = blocks
+        .iter()
+        .zip(&input.infos)
+        .map(|(blk, info)| {
+            let semantic = sem_by_id
+                .get(&blk.unique_id)
+                .and_then(|k| dracokey_to_semantic(k));
+            let normalized = semantic
+                .as_ref()
+                .and_then(|s| p.get(s))
+                .is_some_and(|a| a.normalized());
+            RawAttribute {
+                unique_id: blk.unique_id,
+                semantic,
+                dim: blk.dim,
+                data_type: info.data_type,
+                normalized,
+                bytes: blk.bytes.to_vec(),
+            }
+        })
+        .collect();
+
+    Ok(RawPrimitive {
+        indices,
+        vertex_count: input.vertex_count,
+        attributes,
+    })
+}
+
+impl RawAttribute {
+    /// CPU dequantization to one `f32` per component; the GPU path computes the same values.
+    pub fn dequantize(&self) -> Vec<f32> {
+        as_f32_components(&self.bytes, map_draco_dt(self.data_type), self.normalized)
+    }
+}
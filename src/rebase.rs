@@ -0,0 +1,121 @@
+use crate::*;
+
+/// An index buffer in the narrowest width that holds it, from [`pack_indices`] or built up
+/// with [`push_rebased`](Self::push_rebased).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackedIndices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Default for PackedIndices {
+    fn default() -> Self {
+        PackedIndices::U16(Vec::new())
+    }
+}
+
+impl PackedIndices {
+    pub fn len(&self) -> usize {
+        match self {
+            PackedIndices::U16(v) => v.len(),
+            PackedIndices::U32(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_wide(&self) -> bool {
+        matches!(self, PackedIndices::U32(_))
+    }
+
+    /// Little-endian bytes, ready for an index buffer.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            PackedIndices::U16(v) => bytemuck::cast_slice(v),
+            PackedIndices::U32(v) => bytemuck::cast_slice(v),
+        }
+    }
+
+    pub fn to_u32(&self) -> Vec<u32> {
+        match self {
+            PackedIndices::U16(v) => v.iter().map(|&i| i as u32).collect(),
+            PackedIndices::U32(v) => v.clone(),
+        }
+    }
+
+    /// Appends `indices` offset by `base`, widening the whole buffer to `u32` once a
+    /// rebased index no longer fits `u16`. Fails with [`DracoLoadError::IndexOverflow`],
+    /// appending nothing, if one doesn't fit `u32` either.
+    pub fn push_rebased(&mut self, indices: &[u32], base: usize) -> Result<(), DracoLoadError> {
+        let max = checked_max_rebased(indices, base)?;
+        if let PackedIndices::U16(v) = self
+            && max.is_some_and(|m| m >= U16_LIMIT)
+        {
+            *self = PackedIndices::U32(v.iter().map(|&i| i as u32).collect());
+        }
+        match self {
+            PackedIndices::U16(v) => v.extend(indices.iter().map(|&i| (base + i as usize) as u16)),
+            PackedIndices::U32(v) => v.extend(indices.iter().map(|&i| (base + i as usize) as u32)),
+        }
+        Ok(())
+    }
+}
+
+/// `u16` indices stay below this: 65535 is the strip-restart value in WebGPU and Vulkan.
+const U16_LIMIT: u32 = u16::MAX as u32;
+
+/// Packs `indices` as `u16` when every index is below 65535, `u32` otherwise.
+pub fn pack_indices(indices: &[u32]) -> PackedIndices {
+    if indices.iter().all(|&i| i < U16_LIMIT) {
+        PackedIndices::U16(indices.iter().map(|&i| i as u16).collect())
+    } else {
+        PackedIndices::U32(indices.to_vec())
+    }
+}
+
+/// Appends `indices` offset by `base` (the number of vertices already in front of them) to
+/// `dst`, as when concatenating primitives. Fails with [`DracoLoadError::IndexOverflow`],
+/// leaving `dst` untouched, if a rebased index doesn't fit `u32`.
+pub fn append_rebased(
+    dst: &mut Vec<u32>,
+    indices: &[u32],
+    base: usize,
+) -> Result<(), DracoLoadError> {
+    checked_max_rebased(indices, base)?;
+    dst.extend(indices.iter().map(|&i| (base + i as usize) as u32));
+    Ok(())
+}
+
+/// Offsets every index by `base` in place, all or nothing.
+pub fn rebase_indices(indices: &mut [u32], base: usize) -> Result<(), DracoLoadError> {
+    checked_max_rebased(indices, base)?;
+    for i in indices {
+        *i = (base + *i as usize) as u32;
+    }
+    Ok(())
+}
+
+/// The vertex base after appending `count` vertices at `base`, failing if the last of them
+/// couldn't be addressed by a `u32` index.
+pub fn next_vertex_base(base: usize, count: usize) -> Result<usize, DracoLoadError> {
+    let next = base
+        .checked_add(count)
+        .ok_or(DracoLoadError::IndexOverflow(usize::MAX))?;
+    if next > u32::MAX as usize + 1 {
+        return Err(DracoLoadError::IndexOverflow(next - 1));
+    }
+    Ok(next)
+}
+
+/// Largest rebased index, `None` for no indices.
+fn checked_max_rebased(indices: &[u32], base: usize) -> Result<Option<u32>, DracoLoadError> {
+    let Some(max) = indices.iter().max() else {
+        return Ok(None);
+    };
+    let rebased = base.saturating_add(*max as usize);
+    u32::try_from(rebased)
+        .map(Some)
+        .map_err(|_| DracoLoadError::IndexOverflow(rebased))
+}
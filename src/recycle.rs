@@ -0,0 +1,114 @@
+use crate::*;
+
+/// Emptied index and vertex vectors kept for their capacity, from
+/// [`DecodedPrimitive::recycle`]. [`decode_draco_recycled`] decodes into them instead of
+/// allocating, so a viewer streaming tiles in and out stops churning the allocator.
+///
+/// Joint and custom attribute vectors are built by conversion rather than filled in
+/// place, so they aren't kept, and neither is the decoder's raw output, which
+/// `draco_decoder` allocates per call.
+#[derive(Debug, Clone, Default)]
+pub struct PrimitiveBuffers {
+    indices: Vec<Vec<u32>>,
+    vec2: Vec<Vec<[f32; 2]>>,
+    vec3: Vec<Vec<[f32; 3]>>,
+    vec4: Vec<Vec<[f32; 4]>>,
+}
+
+impl PrimitiveBuffers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves `other`'s vectors into this pool, e.g. when unloading a whole tile.
+    pub fn absorb(&mut self, other: PrimitiveBuffers) {
+        self.indices.extend(other.indices);
+        self.vec2.extend(other.vec2);
+        self.vec3.extend(other.vec3);
+        self.vec4.extend(other.vec4);
+    }
+
+    /// Number of vectors held.
+    pub fn len(&self) -> usize {
+        self.indices.len() + self.vec2.len() + self.vec3.len() + self.vec4.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Heap bytes held, for capping the pool.
+    pub fn capacity_bytes(&self) -> usize {
+        fn bytes<T>(pool: &[Vec<T>]) -> usize {
+            pool.iter().map(|v| v.capacity() * size_of::<T>()).sum()
+        }
+        bytes(&self.indices) + bytes(&self.vec2) + bytes(&self.vec3) + bytes(&self.vec4)
+    }
+
+    /// An empty vector, with capacity left over from a recycled primitive if there is one.
+    pub(crate) fn take<T: Pooled>(&mut self) -> Vec<T> {
+        T::pool(self).pop().unwrap_or_default()
+    }
+
+    fn put<T: Pooled>(&mut self, mut v: Vec<T>) {
+        if v.capacity() > 0 {
+            v.clear();
+            T::pool(self).push(v);
+        }
+    }
+}
+
+/// Element types [`PrimitiveBuffers`] keeps vectors of.
+pub(crate) trait Pooled: Sized {
+    fn pool(buffers: &mut PrimitiveBuffers) -> &mut Vec<Vec<Self>>;
+}
+
+impl Pooled for u32 {
+    fn pool(buffers: &mut PrimitiveBuffers) -> &mut Vec<Vec<Self>> {
+        &mut buffers.indices
+    }
+}
+
+impl Pooled for [f32; 2] {
+    fn pool(buffers: &mut PrimitiveBuffers) -> &mut Vec<Vec<Self>> {
+        &mut buffers.vec2
+    }
+}
+
+impl Pooled for [f32; 3] {
+    fn pool(buffers: &mut PrimitiveBuffers) -> &mut Vec<Vec<Self>> {
+        &mut buffers.vec3
+    }
+}
+
+impl Pooled for [f32; 4] {
+    fn pool(buffers: &mut PrimitiveBuffers) -> &mut Vec<Vec<Self>> {
+        &mut buffers.vec4
+    }
+}
+
+impl DecodedPrimitive {
+    /// Gives up the primitive's vectors for reuse by [`decode_draco_recycled`].
+    pub fn recycle(self) -> PrimitiveBuffers {
+        let mut buffers = PrimitiveBuffers::default();
+        self.recycle_into(&mut buffers);
+        buffers
+    }
+
+    /// [`recycle`](Self::recycle) into an existing pool.
+    pub fn recycle_into(self, buffers: &mut PrimitiveBuffers) {
+        buffers.put(self.indices);
+        for v in [self.positions, self.normals].into_iter().flatten() {
+            buffers.put(v);
+        }
+        if let Some(v) = self.tangents {
+            buffers.put(v);
+        }
+        for (_, v) in self.texcoords {
+            buffers.put(v);
+        }
+        for (_, v) in self.colors.into_iter().chain(self.weights) {
+            buffers.put(v);
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use crate::*;
+use wgpu::util::DeviceExt;
+
+const COLOR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// Where [`OffscreenRenderer`] looks from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderCamera {
+    /// Looks along `direction` at the center of the positions' bounding box, far enough
+    /// back that the whole box fits the frame.
+    AutoFrame { direction: [f32; 3] },
+    Fixed {
+        eye: [f32; 3],
+        target: [f32; 3],
+        /// Vertical field of view in radians.
+        fov_y: f32,
+    },
+}
+
+impl Default for RenderCamera {
+    fn default() -> Self {
+        RenderCamera::AutoFrame {
+            direction: [-1.0, -0.7, -1.0],
+        }
+    }
+}
+
+const AUTO_FOV_Y: f32 = std::f32::consts::FRAC_PI_4;
+
+impl RenderCamera {
+    /// Column-major view-projection matrix (wgpu clip space, depth 0..1) for positions
+    /// bounded by `lo`/`hi`, plus the unit view direction.
+    pub fn view_proj(&self, lo: [f32; 3], hi: [f32; 3], aspect: f32) -> (Mat4, [f32; 3]) {
+        let center = [0, 1, 2].map(|k| (lo[k] + hi[k]) * 0.5);
+        let radius = (0..3)
+            .map(|k| (hi[k] - lo[k]) * 0.5)
+            .map(|h| h * h)
+            .sum::<f32>()
+            .sqrt()
+            .max(1e-6);
+        let (eye, target, fov_y) = match *self {
+            RenderCamera::AutoFrame { direction } => {
+                let dir = view_direction(direction);
+                // the bounding sphere must fit the narrower of the two fields of view
+                let half = (AUTO_FOV_Y * 0.5).tan() * aspect.min(1.0);
+                let distance = radius / half.atan().sin() * 1.05;
+                let eye = [0, 1, 2].map(|k| center[k] - dir[k] * distance);
+                (eye, center, AUTO_FOV_Y)
+            }
+            RenderCamera::Fixed { eye, target, fov_y } => (eye, target, fov_y),
+        };
+        let dir = view_direction(sub(target, eye));
+        let distance = (0..3).map(|k| (center[k] - eye[k]) * dir[k]).sum::<f32>();
+        let near = (distance - radius).max(radius * 1e-3);
+        let far = distance + radius * 1.01;
+        let proj = perspective(fov_y, aspect, near, far);
+        (mat4_mul(&proj, &look_at(eye, dir)), dir)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    pub width: u32,
+    pub height: u32,
+    pub camera: RenderCamera,
+    /// Linear RGBA clear color.
+    pub background: [f64; 4],
+    /// Linear RGB surface color.
+    pub color: [f32; 3],
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            width: 256,
+            height: 256,
+            camera: RenderCamera::default(),
+            background: [1.0, 1.0, 1.0, 1.0],
+            color: [0.6, 0.6, 0.65],
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_camera(mut self, camera: RenderCamera) -> Self {
+        self.camera = camera;
+        self
+    }
+}
+
+/// Why [`OffscreenRenderer`] or [`render_thumbnail`] produced no image.
+#[derive(Debug, thiserror::Error)]
+pub enum RenderError {
+    #[error("primitive has no positions")]
+    NoPositions,
+    #[error("{width}x{height} image exceeds the device's {max}px texture limit")]
+    TooLarge { width: u32, height: u32, max: u32 },
+    /// No adapter or device, or an error the device reported while rendering.
+    #[error("GPU error: {0}")]
+    Gpu(String),
+    #[error("PNG encoding failed: {0}")]
+    Png(#[from] png::EncodingError),
+}
+
+/// Render pipeline that rasterizes a [`DecodedPrimitive`] into an RGBA8 image, for
+/// catalog thumbnails. Surfaces are flat shaded by a headlight; only positions and
+/// indices are used, and primitives without indices draw their vertices as a triangle list.
+///
+/// Create it once per device, like [`GpuDequantizer`](crate::GpuDequantizer).
+pub struct OffscreenRenderer {
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl OffscreenRenderer {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("draco-gltf-rs render"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("render.wgsl").into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("draco-gltf-rs render"),
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: 12,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x3],
+                }],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: Default::default(),
+                bias: Default::default(),
+            }),
+            multisample: Default::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(COLOR_FORMAT.into())],
+            }),
+            multiview: None,
+            cache: None,
+        });
+        Self { pipeline }
+    }
+
+    /// Renders `prim` and reads the image back as tightly packed sRGB RGBA8 rows.
+    /// Blocks until the GPU is done.
+    ///
+    /// Sizes past the device's texture limit are [`RenderError::TooLarge`], and anything else
+    /// the device rejects is [`RenderError::Gpu`] rather than wgpu's default panic.
+    pub fn render_rgba(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        prim: &DecodedPrimitive,
+        options: &RenderOptions,
+    ) -> Result<Vec<u8>, RenderError> {
+        let positions = prim
+            .positions
+            .as_deref()
+            .filter(|p| !p.is_empty())
+            .ok_or(RenderError::NoPositions)?;
+        let (width, height) = (options.width.max(1), options.height.max(1));
+        let max = device.limits().max_texture_dimension_2d;
+        if width > max || height > max {
+            return Err(RenderError::TooLarge { width, height, max });
+        }
+
+        device.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let rendered = self.draw(device, queue, prim, positions, options);
+        let validation = parallel::block_on(device.pop_error_scope());
+        let out_of_memory = parallel::block_on(device.pop_error_scope());
+        match validation.or(out_of_memory) {
+            Some(e) => Err(RenderError::Gpu(e.to_string())),
+            None => rendered,
+        }
+    }
+
+    /// The GPU work of [`render_rgba`](Self::render_rgba), inside its error scopes.
+    fn draw(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        prim: &DecodedPrimitive,
+        positions: &[[f32; 3]],
+        options: &RenderOptions,
+    ) -> Result<Vec<u8>, RenderError> {
+        let (width, height) = (options.width.max(1), options.height.max(1));
+        let (lo, hi) = float_bounds(positions).unwrap_or_default();
+        let (lo, hi) = ([lo[0], lo[1], lo[2]], [hi[0], hi[1], hi[2]]);
+        let aspect = width as f32 / height as f32;
+        let (view_proj, view_dir) = options.camera.view_proj(lo, hi, aspect);
+
+        let mut uniforms: Vec<f32> = view_proj.as_flattened().to_vec();
+        uniforms.extend([view_dir[0], view_dir[1], view_dir[2], 0.0]);
+        uniforms.extend([options.color[0], options.color[1], options.color[2], 1.0]);
+        let uniform = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("draco-gltf-rs render uniforms"),
+            contents: bytemuck::cast_slice(&uniforms),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let vertices = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("draco-gltf-rs render positions"),
+            contents: bytemuck::cast_slice(positions),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        // an empty index buffer is invalid, so non-indexed primitives get none
+        let index_buffer = (!prim.indices.is_empty()).then(|| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("draco-gltf-rs render indices"),
+                contents: bytemuck::cast_slice(&prim.indices),
+                usage: wgpu::BufferUsages::INDEX,
+            })
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("draco-gltf-rs render"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform.as_entire_binding(),
+            }],
+        });
+
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let target = |format, usage| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("draco-gltf-rs render target"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage,
+                view_formats: &[],
+            })
+        };
+        let color = target(
+            COLOR_FORMAT,
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        );
+        let depth = target(DEPTH_FORMAT, wgpu::TextureUsages::RENDER_ATTACHMENT);
+        let color_view = color.create_view(&Default::default());
+        let depth_view = depth.create_view(&Default::default());
+
+        // texture-to-buffer copies need rows padded to 256 bytes
+        let row = width as usize * 4;
+        let padded_row = row.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize);
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("draco-gltf-rs render readback"),
+            size: (padded_row * height as usize) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("draco-gltf-rs render"),
+        });
+        {
+            let [r, g, b, a] = options.background;
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("draco-gltf-rs render"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &color_view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color { r, g, b, a }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.set_vertex_buffer(0, vertices.slice(..));
+            match &index_buffer {
+                Some(index_buffer) => {
+                    pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                    pass.draw_indexed(0..prim.indices.len() as u32 / 3 * 3, 0, 0..1);
+                }
+                None => pass.draw(0..positions.len() as u32 / 3 * 3, 0..1),
+            }
+        }
+        encoder.copy_texture_to_buffer(
+            color.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row as u32),
+                    rows_per_image: None,
+                },
+            },
+            size,
+        );
+        queue.submit(Some(encoder.finish()));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        readback.map_async(wgpu::MapMode::Read, .., move |r| {
+            let _ = tx.send(r);
+        });
+        let gpu_error = |e: &dyn std::fmt::Display| RenderError::Gpu(e.to_string());
+        device
+            .poll(wgpu::PollType::Wait)
+            .map_err(|e| gpu_error(&e))?;
+        rx.recv()
+            .map_err(|e| gpu_error(&e))?
+            .map_err(|e| gpu_error(&e))?;
+
+        let mapped = readback.slice(..).get_mapped_range();
+        let mut rgba = Vec::with_capacity(row * height as usize);
+        for line in mapped.chunks(padded_row) {
+            rgba.extend_from_slice(&line[..row]);
+        }
+        drop(mapped);
+        readback.unmap();
+        Ok(rgba)
+    }
+
+    /// [`render_rgba`](Self::render_rgba), encoded as a PNG file.
+    pub fn render_png(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        prim: &DecodedPrimitive,
+        options: &RenderOptions,
+    ) -> Result<Vec<u8>, RenderError> {
+        let rgba = self.render_rgba(device, queue, prim, options)?;
+        encode_png(options.width.max(1), options.height.max(1), &rgba)
+    }
+}
+
+/// Renders a PNG thumbnail on a headless device of its own. Prefer
+/// [`OffscreenRenderer`] when rendering many primitives, to reuse the device and pipeline.
+pub fn render_thumbnail(
+    prim: &DecodedPrimitive,
+    options: &RenderOptions,
+) -> Result<Vec<u8>, RenderError> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+    let adapter =
+        parallel::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+            .map_err(|e| RenderError::Gpu(e.to_string()))?;
+    let (device, queue) = parallel::block_on(adapter.request_device(&Default::default()))
+        .map_err(|e| RenderError::Gpu(e.to_string()))?;
+    OffscreenRenderer::new(&device).render_png(&device, &queue, prim, options)
+}
+
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>, RenderError> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(rgba)?;
+    writer.finish()?;
+    Ok(out)
+}
+
+/// Unit `v`, or -Z for a zero vector.
+fn view_direction(v: [f32; 3]) -> [f32; 3] {
+    let n = normalize(v);
+    if n == [0.0; 3] { [0.0, 0.0, -1.0] } else { n }
+}
+
+/// Right-handed view matrix looking along unit `dir`, with +Y up unless `dir` is vertical.
+fn look_at(eye: [f32; 3], dir: [f32; 3]) -> Mat4 {
+    let up = if dir[1].abs() > 0.999 {
+        [0.0, 0.0, 1.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    let s = normalize(cross(dir, up));
+    let u = cross(s, dir);
+    let dot = |a: [f32; 3]| -(a[0] * eye[0] + a[1] * eye[1] + a[2] * eye[2]);
+    [
+        [s[0], u[0], -dir[0], 0.0],
+        [s[1], u[1], -dir[1], 0.0],
+        [s[2], u[2], -dir[2], 0.0],
+        [dot(s), dot(u), -dot(dir), 1.0],
+    ]
+}
+
+/// Right-handed perspective projection to wgpu's 0..1 depth range.
+fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Mat4 {
+    let f = 1.0 / (fov_y * 0.5).tan();
+    let range = near - far;
+    [
+        [f / aspect, 0.0, 0.0, 0.0],
+        [0.0, f, 0.0, 0.0],
+        [0.0, 0.0, far / range, -1.0],
+        [0.0, 0.0, near * far / range, 0.0],
+    ]
+}
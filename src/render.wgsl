@@ -0,0 +1,30 @@
+struct Uniforms {
+    view_proj: mat4x4<f32>,
+    // xyz: direction the camera looks along
+    view_dir: vec4<f32>,
+    color: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+
+struct VsOut {
+    @builtin(position) clip: vec4<f32>,
+    @location(0) world: vec3<f32>,
+};
+
+@vertex
+fn vs_main(@location(0) position: vec3<f32>) -> VsOut {
+    var out: VsOut;
+    out.clip = u.view_proj * vec4<f32>(position, 1.0);
+    out.world = position;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
+    // faceted normal from screen-space derivatives, so no NORMAL stream is needed;
+    // two-sided headlight shading
+    let n = normalize(cross(dpdx(in.world), dpdy(in.world)));
+    let lambert = abs(dot(n, u.view_dir.xyz));
+    return vec4<f32>(u.color.rgb * (0.2 + 0.8 * lambert), 1.0);
+}
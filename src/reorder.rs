@@ -0,0 +1,133 @@
+use crate::*;
+
+/// Reorders `decoded` so vertex `i` matches vertex `i` of `reference` (typically the
+/// uncompressed fallback of the same primitive), remapping the indices accordingly.
+///
+/// Draco is free to reorder vertices, which breaks sidecar data keyed by vertex index.
+/// Vertices are matched by nearest position (within quantization tolerance), with
+/// texcoords and normals breaking ties between coincident vertices.
+pub fn reorder_to_reference(
+    decoded: &mut DecodedPrimitive,
+    reference: &DecodedPrimitive,
+) -> Result<(), DracoLoadError> {
+    let (Some(src), Some(dst)) = (&decoded.positions, &reference.positions) else {
+        return Err(DracoLoadError::NoPositionAccessor);
+    };
+    if src.len() != dst.len() {
+        return Err(DracoLoadError::ReorderMismatch(src.len().min(dst.len())));
+    }
+
+    let cell = cell_size(dst);
+    let mut grid: std::collections::HashMap<[i64; 3], Vec<usize>> =
+        std::collections::HashMap::new();
+    for (i, p) in dst.iter().enumerate() {
+        grid.entry(cell_of(p, cell)).or_default().push(i);
+    }
+
+    let mut taken = vec![false; dst.len()];
+    let mut perm = vec![0usize; src.len()];
+    for (i, p) in src.iter().enumerate() {
+        let c = cell_of(p, cell);
+        let mut best: Option<(f32, usize)> = None;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let Some(cands) = grid.get(&[c[0] + dx, c[1] + dy, c[2] + dz]) else {
+                        continue;
+                    };
+                    for &j in cands {
+                        if taken[j] {
+                            continue;
+                        }
+                        let d = vertex_distance(decoded, i, reference, j);
+                        if best.is_none_or(|(bd, _)| d < bd) {
+                            best = Some((d, j));
+                        }
+                    }
+                }
+            }
+        }
+        let (_, j) = best.ok_or(DracoLoadError::ReorderMismatch(i))?;
+        taken[j] = true;
+        perm[i] = j;
+    }
+
+    apply_permutation(decoded, &perm);
+    Ok(())
+}
+
+/// Moves vertex `i` to `perm[i]` in every attribute stream and rewrites the indices.
+pub(crate) fn apply_permutation(d: &mut DecodedPrimitive, perm: &[usize]) {
+    fn permute<T: Copy + Default>(v: &[T], perm: &[usize]) -> Vec<T> {
+        let mut out = vec![T::default(); v.len()];
+        for (i, x) in v.iter().enumerate() {
+            out[perm[i]] = *x;
+        }
+        out
+    }
+
+    for idx in d.indices.iter_mut() {
+        *idx = perm[*idx as usize] as u32;
+    }
+    d.positions = d.positions.as_deref().map(|v| permute(v, perm));
+    d.normals = d.normals.as_deref().map(|v| permute(v, perm));
+    d.tangents = d.tangents.as_deref().map(|v| permute(v, perm));
+    for v in d.texcoords.values_mut() {
+        *v = permute(v, perm);
+    }
+    for v in d.colors.values_mut() {
+        *v = permute(v, perm);
+    }
+    for v in d.joints.values_mut() {
+        *v = permute(v, perm);
+    }
+    for v in d.weights.values_mut() {
+        *v = permute(v, perm);
+    }
+    for c in d.custom.values_mut() {
+        c.values = c.values.permute(c.components.max(1), perm);
+    }
+}
+
+fn vertex_distance(a: &DecodedPrimitive, i: usize, b: &DecodedPrimitive, j: usize) -> f32 {
+    fn sq<const N: usize>(x: &[f32; N], y: &[f32; N]) -> f32 {
+        x.iter().zip(y).map(|(p, q)| (p - q) * (p - q)).sum()
+    }
+
+    let mut d = 0.0;
+    if let (Some(pa), Some(pb)) = (&a.positions, &b.positions) {
+        d += sq(&pa[i], &pb[j]);
+    }
+    if let (Some(na), Some(nb)) = (&a.normals, &b.normals) {
+        d += sq(&na[i], &nb[j]);
+    }
+    if let (Some(ta), Some(tb)) = (a.texcoords.get(&0), b.texcoords.get(&0)) {
+        d += sq(&ta[i], &tb[j]);
+    }
+    d
+}
+
+/// Grid cell edge: generous compared to Draco's position quantization error.
+fn cell_size(positions: &[[f32; 3]]) -> f32 {
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for p in positions {
+        for k in 0..3 {
+            min[k] = min[k].min(p[k]);
+            max[k] = max[k].max(p[k]);
+        }
+    }
+    let diag = (0..3)
+        .map(|k| (max[k] - min[k]).max(0.0).powi(2))
+        .sum::<f32>()
+        .sqrt();
+    (diag / 1024.0).max(f32::EPSILON)
+}
+
+fn cell_of(p: &[f32; 3], cell: f32) -> [i64; 3] {
+    [
+        (p[0] / cell).floor() as i64,
+        (p[1] / cell).floor() as i64,
+        (p[2] / cell).floor() as i64,
+    ]
+}
@@ -0,0 +1,222 @@
+use crate::*;
+
+use std::collections::{HashMap, HashSet};
+
+/// Settings for [`DecodedPrimitive::repair_manifold`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RepairOptions {
+    /// Edge of the grid cells used for welding: positions that round to the same cell merge
+    /// into one vertex. Close positions straddling a cell border stay separate.
+    pub weld_epsilon: f32,
+    /// Make neighbouring triangles agree on winding, then turn closed parts outward.
+    pub fix_winding: bool,
+    /// Fan-fill boundary loops of up to this many edges; 0 leaves every hole open.
+    pub max_hole_edges: usize,
+}
+
+impl Default for RepairOptions {
+    fn default() -> Self {
+        Self {
+            weld_epsilon: 1e-5,
+            fix_winding: true,
+            max_hole_edges: 8,
+        }
+    }
+}
+
+impl RepairOptions {
+    pub fn with_fix_winding(mut self, fix: bool) -> Self {
+        self.fix_winding = fix;
+        self
+    }
+
+    pub fn with_max_hole_edges(mut self, max: usize) -> Self {
+        self.max_hole_edges = max;
+        self
+    }
+}
+
+/// What [`DecodedPrimitive::repair_manifold`] changed, and what it couldn't fix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct RepairReport {
+    pub welded_vertices: usize,
+    pub degenerate_triangles: usize,
+    pub duplicate_triangles: usize,
+    pub flipped_triangles: usize,
+    pub holes_filled: usize,
+    /// Topology of the output.
+    pub remaining: ManifoldStats,
+}
+
+/// Edge topology of a triangle mesh; see [`DecodedPrimitive::manifold_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ManifoldStats {
+    /// Edges with a single triangle: holes or open borders.
+    pub boundary_edges: usize,
+    /// Edges shared by more than two triangles.
+    pub non_manifold_edges: usize,
+    /// Edges whose two triangles traverse it in the same direction.
+    pub inconsistent_edges: usize,
+}
+
+impl ManifoldStats {
+    /// Closed, two-manifold and consistently wound: what CSG and slicers expect.
+    pub fn is_manifold(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl DecodedPrimitive {
+    /// Edge topology of the index buffer as is. Vertices split for normals or UVs count as
+    /// separate, so weld first (or use [`repair_manifold`](Self::repair_manifold)) to judge
+    /// the shape rather than its seams.
+    pub fn manifold_stats(&self) -> ManifoldStats {
+        let mut edges: HashMap<(u32, u32), (usize, isize)> = HashMap::new();
+        for tri in self.indices.chunks_exact(3) {
+            for k in 0..3 {
+                let (a, b) = (tri[k], tri[(k + 1) % 3]);
+                let (key, dir) = if a < b { ((a, b), 1) } else { ((b, a), -1) };
+                let e = edges.entry(key).or_default();
+                e.0 += 1;
+                e.1 += dir;
+            }
+        }
+        let mut stats = ManifoldStats::default();
+        for (count, balance) in edges.into_values() {
+            match count {
+                1 => stats.boundary_edges += 1,
+                2 if balance != 0 => stats.inconsistent_edges += 1,
+                2 => {}
+                _ => stats.non_manifold_edges += 1,
+            }
+        }
+        stats
+    }
+
+    /// A position-only copy prepared for CSG or 3D printing: vertices welded, degenerate and
+    /// duplicate triangles (in either winding) removed, winding made consistent and
+    /// outward, and small holes closed. Attributes other than positions are dropped since
+    /// welding merges vertices that disagree on them.
+    pub fn repair_manifold(&self, options: &RepairOptions) -> (DecodedPrimitive, RepairReport) {
+        let positions = self.positions.as_deref().unwrap_or_default();
+        let (welded, remap) =
+            weld_positions(positions, options.weld_epsilon.max(f32::MIN_POSITIVE));
+        let mut report = RepairReport {
+            welded_vertices: positions.len() - welded.len(),
+            ..Default::default()
+        };
+
+        let mut seen = HashSet::new();
+        let mut tris: Vec<[u32; 3]> = Vec::with_capacity(self.indices.len() / 3);
+        for tri in self.indices.chunks_exact(3) {
+            let Some(t) = tri
+                .iter()
+                .map(|&i| remap.get(i as usize).copied())
+                .collect::<Option<Vec<u32>>>()
+            else {
+                report.degenerate_triangles += 1;
+                continue;
+            };
+            if t[0] == t[1] || t[1] == t[2] || t[0] == t[2] {
+                report.degenerate_triangles += 1;
+                continue;
+            }
+            let mut key = [t[0], t[1], t[2]];
+            key.sort_unstable();
+            if seen.insert(key) {
+                tris.push([t[0], t[1], t[2]]);
+            } else {
+                report.duplicate_triangles += 1;
+            }
+        }
+
+        if options.fix_winding {
+            report.flipped_triangles = orient(&mut tris, &welded);
+        }
+        let mut indices: Vec<u32> = tris.into_iter().flatten().collect();
+        if options.max_hole_edges >= 3 {
+            report.holes_filled = fill_holes(&mut indices, options.max_hole_edges);
+        }
+
+        let out = gather_vertices(
+            &DecodedPrimitive {
+                indices: Vec::new(),
+                positions: Some(welded),
+                ..Default::default()
+            },
+            &indices,
+        );
+        report.remaining = out.manifold_stats();
+        (out, report)
+    }
+}
+
+/// Flips triangles so neighbours across two-triangle edges agree, then flips closed
+/// components with negative volume. Returns how many triangles end up reversed.
+fn orient(tris: &mut [[u32; 3]], positions: &[[f32; 3]]) -> usize {
+    let mut by_edge: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (t, tri) in tris.iter().enumerate() {
+        for k in 0..3 {
+            let (a, b) = (tri[k], tri[(k + 1) % 3]);
+            by_edge.entry((a.min(b), a.max(b))).or_default().push(t);
+        }
+    }
+    let has_edge =
+        |tri: &[u32; 3], a: u32, b: u32| (0..3).any(|k| tri[k] == a && tri[(k + 1) % 3] == b);
+
+    let mut flipped = vec![false; tris.len()];
+    let mut visited = vec![false; tris.len()];
+    for seed in 0..tris.len() {
+        if visited[seed] {
+            continue;
+        }
+        visited[seed] = true;
+        let mut component = vec![seed];
+        let mut closed = true;
+        let mut next = 0;
+        while next < component.len() {
+            let t = component[next];
+            next += 1;
+            for k in 0..3 {
+                let (a, b) = (tris[t][k], tris[t][(k + 1) % 3]);
+                let shared = &by_edge[&(a.min(b), a.max(b))];
+                if shared.len() != 2 {
+                    closed = false;
+                    continue;
+                }
+                let u = if shared[0] == t { shared[1] } else { shared[0] };
+                if visited[u] {
+                    continue;
+                }
+                visited[u] = true;
+                if has_edge(&tris[u], a, b) {
+                    tris[u].swap(1, 2);
+                    flipped[u] = !flipped[u];
+                }
+                component.push(u);
+            }
+        }
+
+        if closed {
+            let volume: f64 = component
+                .iter()
+                .map(|&t| {
+                    let [a, b, c] = tris[t].map(|i| positions[i as usize].map(f64::from));
+                    let cross = [
+                        b[1] * c[2] - b[2] * c[1],
+                        b[2] * c[0] - b[0] * c[2],
+                        b[0] * c[1] - b[1] * c[0],
+                    ];
+                    a[0] * cross[0] + a[1] * cross[1] + a[2] * cross[2]
+                })
+                .sum();
+            if volume < 0.0 {
+                for &t in &component {
+                    tris[t].swap(1, 2);
+                    flipped[t] = !flipped[t];
+                }
+            }
+        }
+    }
+    flipped.into_iter().filter(|&f| f).count()
+}
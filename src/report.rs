@@ -0,0 +1,191 @@
+use crate::*;
+
+/// Geometry and texture delivery stats for a whole document, serializable for asset
+/// pipelines ("is this asset ready for web delivery?").
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AssetReport {
+    pub primitives: Vec<PrimitiveStats>,
+    pub images: Vec<ImageStats>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PrimitiveStats {
+    pub mesh: usize,
+    pub primitive: usize,
+    pub draco: bool,
+    pub vertex_count: usize,
+    pub index_count: usize,
+    /// The Draco bufferView, or the packed accessor data when uncompressed.
+    pub stored_bytes: usize,
+    /// Tightly packed accessor data.
+    pub uncompressed_bytes: usize,
+    /// Decoded content hashes; only filled by [`asset_report_with_hashes`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hashes: Option<ContentHashes>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ImageStats {
+    pub image: usize,
+    pub name: Option<String>,
+    pub format: ImageFormat,
+    pub bytes: Option<usize>, // unknown for external files
+    pub basisu: bool,         // referenced through KHR_texture_basisu
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageFormat {
+    Ktx2,
+    Png,
+    Jpeg,
+    Webp,
+    Unknown,
+}
+
+impl AssetReport {
+    pub fn stored_geometry_bytes(&self) -> usize {
+        self.primitives.iter().map(|p| p.stored_bytes).sum()
+    }
+
+    pub fn uncompressed_geometry_bytes(&self) -> usize {
+        self.primitives.iter().map(|p| p.uncompressed_bytes).sum()
+    }
+
+    /// Sum of known image sizes; external images are not counted.
+    pub fn texture_bytes(&self) -> usize {
+        self.images.iter().filter_map(|i| i.bytes).sum()
+    }
+
+    /// Images still shipped as PNG/JPEG/WebP rather than KTX2.
+    pub fn uncompressed_images(&self) -> impl Iterator<Item = &ImageStats> {
+        self.images.iter().filter(|i| {
+            matches!(
+                i.format,
+                ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Webp
+            )
+        })
+    }
+}
+
+pub fn asset_report(document: &gltf::Document, buffers: &[gltf::buffer::Data]) -> AssetReport {
+    let mut primitives = Vec::new();
+    for mesh in document.meshes() {
+        for p in mesh.primitives() {
+            primitives.push(primitive_stats(document, &mesh, &p));
+        }
+    }
+
+    let basisu: std::collections::HashSet<u64> = document
+        .textures()
+        .filter_map(|t| {
+            t.extension_value("KHR_texture_basisu")?
+                .get("source")?
+                .as_u64()
+        })
+        .collect();
+
+    let images = document
+        .images()
+        .map(|image| {
+            let (format, bytes) = match image.source() {
+                gltf::image::Source::View { view, mime_type } => {
+                    let data = buffers
+                        .get(view.buffer().index())
+                        .and_then(|b| b.get(view.offset()..view.offset() + view.length()));
+                    let format = match from_mime(mime_type) {
+                        ImageFormat::Unknown => data.map_or(ImageFormat::Unknown, from_magic),
+                        f => f,
+                    };
+                    (format, Some(view.length()))
+                }
+                gltf::image::Source::Uri { uri, mime_type } => {
+                    let format = match mime_type.map(from_mime) {
+                        Some(f) if f != ImageFormat::Unknown => f,
+                        _ => from_uri(uri),
+                    };
+                    // data URIs are base64: 4 characters per 3 bytes
+                    let bytes = uri
+                        .strip_prefix("data:")
+                        .and_then(|rest| rest.split_once(";base64,"))
+                        .map(|(_, b64)| b64.trim_end_matches('=').len() * 3 / 4);
+                    (format, bytes)
+                }
+            };
+            ImageStats {
+                image: image.index(),
+                name: image.name().map(str::to_owned),
+                format,
+                bytes,
+                basisu: basisu.contains(&(image.index() as u64)),
+            }
+        })
+        .collect();
+
+    AssetReport { primitives, images }
+}
+
+fn primitive_stats(
+    document: &gltf::Document,
+    mesh: &gltf::Mesh<'_>,
+    p: &gltf::mesh::Primitive<'_>,
+) -> PrimitiveStats {
+    fn packed(a: &gltf::Accessor<'_>) -> usize {
+        a.count() * dims_count(a.dimensions()) * comp_size_bytes(a.data_type())
+    }
+
+    let uncompressed_bytes = p.attributes().map(|(_, a)| packed(&a)).sum::<usize>()
+        + p.indices().map_or(0, |a| packed(&a));
+    let draco_view = draco_extension(p)
+        .ok()
+        .and_then(|ext| buffer_view(document, ext.buffer_view).ok());
+
+    PrimitiveStats {
+        mesh: mesh.index(),
+        primitive: p.index(),
+        draco: draco_view.is_some(),
+        vertex_count: p.get(&gltf::Semantic::Positions).map_or(0, |a| a.count()),
+        index_count: p.indices().map_or(0, |a| a.count()),
+        stored_bytes: draco_view.map_or(uncompressed_bytes, |v| v.length()),
+        uncompressed_bytes,
+        hashes: None,
+    }
+}
+
+fn from_mime(mime: &str) -> ImageFormat {
+    match mime {
+        "image/ktx2" => ImageFormat::Ktx2,
+        "image/png" => ImageFormat::Png,
+        "image/jpeg" => ImageFormat::Jpeg,
+        "image/webp" => ImageFormat::Webp,
+        _ => ImageFormat::Unknown,
+    }
+}
+
+fn from_uri(uri: &str) -> ImageFormat {
+    if let Some(mime) = uri.strip_prefix("data:").and_then(|r| r.split(';').next()) {
+        return from_mime(mime);
+    }
+    let ext = uri.rsplit('.').next().unwrap_or("").to_ascii_lowercase();
+    match ext.as_str() {
+        "ktx2" => ImageFormat::Ktx2,
+        "png" => ImageFormat::Png,
+        "jpg" | "jpeg" => ImageFormat::Jpeg,
+        "webp" => ImageFormat::Webp,
+        _ => ImageFormat::Unknown,
+    }
+}
+
+fn from_magic(data: &[u8]) -> ImageFormat {
+    if data.starts_with(b"\xABKTX 20\xBB") {
+        ImageFormat::Ktx2
+    } else if data.starts_with(b"\x89PNG") {
+        ImageFormat::Png
+    } else if data.starts_with(b"\xFF\xD8\xFF") {
+        ImageFormat::Jpeg
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        ImageFormat::Webp
+    } else {
+        ImageFormat::Unknown
+    }
+}
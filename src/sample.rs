@@ -0,0 +1,107 @@
+use crate::*;
+
+/// A point on the surface from [`DecodedPrimitive::sample_surface`].
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct SurfaceSample {
+    pub position: [f32; 3],
+    /// Vertex normals interpolated and renormalized; the face normal when the primitive
+    /// has none.
+    pub normal: [f32; 3],
+    /// `TEXCOORD_0` interpolated at the point.
+    pub texcoord: Option<[f32; 2]>,
+    /// Index of the triangle the point lies on.
+    pub triangle: usize,
+}
+
+impl DecodedPrimitive {
+    /// Scatters `density` points per unit of surface area (in mesh units) uniformly over
+    /// the triangles, with a fixed seed so the same mesh always gives the same points.
+    pub fn sample_surface(&self, density: f32) -> Vec<SurfaceSample> {
+        self.sample_surface_seeded(density, 0)
+    }
+
+    /// [`sample_surface`](Self::sample_surface) with a caller-chosen seed, e.g. one per
+    /// epoch when generating training data.
+    ///
+    /// Triangles are picked in proportion to their area and points placed uniformly inside
+    /// them, so the result doesn't depend on how the mesh is tessellated. Degenerate
+    /// triangles and ones referencing missing vertices get no samples.
+    pub fn sample_surface_seeded(&self, density: f32, seed: u64) -> Vec<SurfaceSample> {
+        let Some(pos) = self.positions.as_deref() else {
+            return Vec::new();
+        };
+        let uvs = self.texcoords.get(&0).map(Vec::as_slice);
+
+        // running area total per usable triangle
+        let mut cumulative: Vec<(f64, usize)> = Vec::new();
+        let mut total = 0.0f64;
+        for (t, tri) in self.indices.chunks_exact(3).enumerate() {
+            if tri.iter().any(|&i| i as usize >= pos.len()) {
+                continue;
+            }
+            let [a, b, c] = [0, 1, 2].map(|k| pos[tri[k] as usize]);
+            let area = 0.5 * length(face_normal(a, b, c)) as f64;
+            if area > 0.0 && area.is_finite() {
+                total += area;
+                cumulative.push((total, t));
+            }
+        }
+        let count = (total * density as f64).round();
+        if count.is_nan() || count < 1.0 {
+            return Vec::new();
+        }
+
+        let mut rng = SplitMix64(seed);
+        (0..count as usize)
+            .map(|_| {
+                let target = rng.next_f64() * total;
+                let slot = cumulative
+                    .partition_point(|&(c, _)| c <= target)
+                    .min(cumulative.len() - 1);
+                let t = cumulative[slot].1;
+                let tri = &self.indices[t * 3..t * 3 + 3];
+                let (r1, r2) = (rng.next_f64().sqrt(), rng.next_f64());
+                let w = [1.0 - r1, r1 * (1.0 - r2), r1 * r2].map(|x| x as f32);
+                let lerp3 = |v: &[[f32; 3]]| {
+                    [0, 1, 2].map(|k| (0..3).map(|i| w[i] * v[tri[i] as usize][k]).sum())
+                };
+                let [a, b, c] = [0, 1, 2].map(|k| pos[tri[k] as usize]);
+                let normal = match self.normals.as_deref() {
+                    Some(n) if tri.iter().all(|&i| (i as usize) < n.len()) => lerp3(n),
+                    _ => face_normal(a, b, c),
+                };
+                let texcoord = uvs
+                    .filter(|uv| tri.iter().all(|&i| (i as usize) < uv.len()))
+                    .map(|uv| [0, 1].map(|k| (0..3).map(|i| w[i] * uv[tri[i] as usize][k]).sum()));
+                SurfaceSample {
+                    position: lerp3(pos),
+                    normal: normalize(normal),
+                    texcoord,
+                    triangle: t,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Small, seedable generator so sampling needs no RNG dependency.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn face_normal(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> [f32; 3] {
+    cross(sub(b, a), sub(c, a))
+}
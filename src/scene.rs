@@ -0,0 +1,377 @@
+use crate::*;
+
+pub type Mat4 = [[f32; 4]; 4];
+
+#[derive(Debug, Clone, Default)]
+pub struct DecodedScene {
+    pub index: usize,
+    pub name: Option<String>,
+    pub roots: Vec<usize>,     // node indices listed by the scene itself
+    pub nodes: Vec<SceneNode>, // every node reachable from `roots`, depth-first
+    pub primitives: Vec<ScenePrimitive>,
+    pub cameras: Vec<SceneCamera>,
+    pub lights: Vec<SceneLight>,
+    pub skins: Vec<SceneSkin>, // skins used by `nodes`, by first use
+    pub materials: Vec<MaterialSummary>, // materials used by `primitives`, by first use
+    pub skipped: Vec<SkippedPrimitive>, // left out by `decode_document`
+    pub joint_width: Vec<JointWidthWarning>, // see `check_joint_width`
+}
+
+/// A primitive [`decode_document`] left out of the scene because it isn't a triangle list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkippedPrimitive {
+    pub node: usize,
+    pub mesh: usize,
+    pub primitive: usize,
+    pub mode: gltf::mesh::Mode,
+}
+
+#[derive(Debug, Clone)]
+pub struct SceneNode {
+    pub index: usize,
+    pub name: Option<String>,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    pub mesh: Option<usize>,
+    pub mesh_name: Option<String>,
+    pub transform: Mat4,       // local, relative to `parent`
+    pub world_transform: Mat4, // column-major, as in glTF
+    pub skin: Option<usize>,
+}
+
+/// A skin bound by a node of the scene.
+#[derive(Debug, Clone)]
+pub struct SceneSkin {
+    pub index: usize,
+    pub name: Option<String>,
+    pub joints: Vec<usize>, // node indices, in JOINTS_n order
+    pub skeleton: Option<usize>,
+}
+
+impl DecodedScene {
+    pub fn node(&self, index: usize) -> Option<&SceneNode> {
+        self.nodes.iter().find(|n| n.index == index)
+    }
+
+    /// The summary of [`ScenePrimitive::material`]; `None` is the default material.
+    pub fn material(&self, index: Option<usize>) -> Option<&MaterialSummary> {
+        self.materials.iter().find(|m| m.index == index)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SceneCamera {
+    pub node: usize,
+    pub name: Option<String>,
+    pub world_transform: Mat4,
+    pub projection: Projection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    Perspective {
+        aspect_ratio: Option<f32>,
+        yfov: f32,
+        znear: f32,
+        zfar: Option<f32>, // None means an infinite projection
+    },
+    Orthographic {
+        xmag: f32,
+        ymag: f32,
+        znear: f32,
+        zfar: f32,
+    },
+}
+
+/// A `KHR_lights_punctual` light attached to a node.
+#[derive(Debug, Clone)]
+pub struct SceneLight {
+    pub node: usize,
+    pub name: Option<String>,
+    pub world_transform: Mat4, // lights shine along the local -Z axis
+    pub kind: LightKind,
+    pub color: [f32; 3],
+    pub intensity: f32,
+    pub range: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightKind {
+    Directional,
+    Point,
+    Spot {
+        inner_cone_angle: f32,
+        outer_cone_angle: f32,
+    },
+}
+
+/// One primitive instanced by a node of the scene: Draco only, except from
+/// [`decode_document`].
+#[derive(Debug, Clone)]
+pub struct ScenePrimitive {
+    pub node: usize,
+    pub mesh: usize,
+    pub mesh_name: Option<String>, // glTF primitives carry no name of their own
+    pub primitive: usize,
+    pub material: Option<usize>,
+    pub world_transform: Mat4, // column-major, as in glTF
+    pub decoded: DecodedPrimitive,
+}
+
+/// Decodes the document's default scene, falling back to the first scene when `scene` is unset.
+pub async fn decode_default_scene(
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+) -> Result<DecodedScene, DracoLoadError> {
+    let scene = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+        .ok_or(DracoLoadError::NoScene)?;
+    decode_scene(&scene, document, buffers, None).await
+}
+
+/// Decodes the document's default scene like [`decode_default_scene`], but with every
+/// primitive its nodes instance, Draco or not, so a renderer gets the whole asset from one
+/// call. Plain primitives are read from their accessors, and every primitive is decoded
+/// with `options`. Primitives that aren't triangle lists are listed in
+/// [`DecodedScene::skipped`] instead of failing the scene.
+pub async fn decode_document(
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    options: &DecodeOptions,
+) -> Result<DecodedScene, DracoLoadError> {
+    let scene = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+        .ok_or(DracoLoadError::NoScene)?;
+    decode_scene(&scene, document, buffers, Some(options)).await
+}
+
+pub async fn decode_scene_by_index(
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    index: usize,
+) -> Result<DecodedScene, DracoLoadError> {
+    let scene = document
+        .scenes()
+        .nth(index)
+        .ok_or(DracoLoadError::BadScene(index))?;
+    decode_scene(&scene, document, buffers, None).await
+}
+
+/// Decodes every scene of the document, in document order.
+pub async fn decode_all_scenes(
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+) -> Result<Vec<DecodedScene>, DracoLoadError> {
+    let mut out = Vec::new();
+    for scene in document.scenes() {
+        out.push(decode_scene(&scene, document, buffers, None).await?);
+    }
+    Ok(out)
+}
+
+async fn decode_scene(
+    scene: &gltf::Scene<'_>,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    // also read primitives that aren't Draco-compressed, decoding with these options
+    plain: Option<&DecodeOptions>,
+) -> Result<DecodedScene, DracoLoadError> {
+    let default_options = DecodeOptions::default();
+    let options = plain.unwrap_or(&default_options);
+    let walked = walk_nodes(scene);
+    // meshes instanced by several nodes decode once; their primitives are kept only until
+    // the last node using them, and meshes used once are never copied
+    let mut uses_left: std::collections::HashMap<usize, usize> = std::collections::HashMap::new();
+    for mesh in walked.iter().filter_map(|(node, ..)| node.mesh()) {
+        *uses_left.entry(mesh.index()).or_default() += 1;
+    }
+    let mut cache: std::collections::HashMap<(usize, usize), DecodedPrimitive> =
+        std::collections::HashMap::new();
+    let mut primitives = Vec::new();
+    let mut nodes = Vec::new();
+    let mut cameras = Vec::new();
+    let mut lights = Vec::new();
+    let mut skins: Vec<SceneSkin> = Vec::new();
+    let mut materials: Vec<MaterialSummary> = Vec::new();
+    let mut skipped = Vec::new();
+    let mut joint_width = Vec::new();
+
+    for (node, parent, world_transform) in walked {
+        nodes.push(SceneNode {
+            index: node.index(),
+            name: node.name().map(str::to_string),
+            parent,
+            children: node.children().map(|c| c.index()).collect(),
+            mesh: node.mesh().map(|m| m.index()),
+            mesh_name: node.mesh().and_then(|m| m.name().map(str::to_string)),
+            transform: node.transform().matrix(),
+            world_transform,
+            skin: node.skin().map(|s| s.index()),
+        });
+        if let Some(skin) = node.skin()
+            && !skins.iter().any(|s| s.index == skin.index())
+        {
+            skins.push(SceneSkin {
+                index: skin.index(),
+                name: skin.name().map(str::to_string),
+                joints: skin.joints().map(|j| j.index()).collect(),
+                skeleton: skin.skeleton().map(|n| n.index()),
+            });
+        }
+        if let Some(camera) = node.camera() {
+            cameras.push(SceneCamera {
+                node: node.index(),
+                name: camera.name().map(str::to_string),
+                world_transform,
+                projection: camera_projection(&camera),
+            });
+        }
+        if let Some(light) = node.light() {
+            lights.push(SceneLight {
+                node: node.index(),
+                name: light.name().map(str::to_string),
+                world_transform,
+                kind: light_kind(light.kind()),
+                color: light.color(),
+                intensity: light.intensity(),
+                range: light.range(),
+            });
+        }
+        let Some(mesh) = node.mesh() else {
+            continue;
+        };
+        let later_uses = uses_left.get_mut(&mesh.index()).map_or(0, |n| {
+            *n -= 1;
+            *n
+        });
+        for p in mesh.primitives() {
+            if plain.is_none() && p.extension_value("KHR_draco_mesh_compression").is_none() {
+                continue;
+            }
+            if plain.is_some() && p.mode() != gltf::mesh::Mode::Triangles {
+                skipped.push(SkippedPrimitive {
+                    node: node.index(),
+                    mesh: mesh.index(),
+                    primitive: p.index(),
+                    mode: p.mode(),
+                });
+                continue;
+            }
+            if decode_directives(&mesh, &p)?.skip {
+                continue;
+            }
+            let key = (mesh.index(), p.index());
+            let decoded = match cache.remove(&key) {
+                Some(d) => d,
+                None => decode_primitive(&p, document, buffers, options).await?,
+            };
+            if later_uses > 0 {
+                cache.insert(key, decoded.clone());
+            }
+            joint_width.extend(check_joint_width(&node, &mesh, &p, &decoded));
+            let material = p.material();
+            if !materials.iter().any(|m| m.index == material.index()) {
+                materials.push(MaterialSummary::new(&material));
+            }
+            primitives.push(ScenePrimitive {
+                node: node.index(),
+                mesh: mesh.index(),
+                mesh_name: mesh.name().map(str::to_string),
+                primitive: p.index(),
+                material: p.material().index(),
+                world_transform,
+                decoded,
+            });
+        }
+    }
+
+    Ok(DecodedScene {
+        index: scene.index(),
+        name: scene.name().map(str::to_string),
+        roots: scene.nodes().map(|n| n.index()).collect(),
+        nodes,
+        primitives,
+        cameras,
+        lights,
+        skins,
+        materials,
+        skipped,
+        joint_width,
+    })
+}
+
+fn camera_projection(camera: &gltf::Camera<'_>) -> Projection {
+    match camera.projection() {
+        gltf::camera::Projection::Perspective(p) => Projection::Perspective {
+            aspect_ratio: p.aspect_ratio(),
+            yfov: p.yfov(),
+            znear: p.znear(),
+            zfar: p.zfar(),
+        },
+        gltf::camera::Projection::Orthographic(o) => Projection::Orthographic {
+            xmag: o.xmag(),
+            ymag: o.ymag(),
+            znear: o.znear(),
+            zfar: o.zfar(),
+        },
+    }
+}
+
+fn light_kind(kind: gltf::khr_lights_punctual::Kind) -> LightKind {
+    match kind {
+        gltf::khr_lights_punctual::Kind::Directional => LightKind::Directional,
+        gltf::khr_lights_punctual::Kind::Point => LightKind::Point,
+        gltf::khr_lights_punctual::Kind::Spot {
+            inner_cone_angle,
+            outer_cone_angle,
+        } => LightKind::Spot {
+            inner_cone_angle,
+            outer_cone_angle,
+        },
+    }
+}
+
+/// Depth-first walk of the scene's node tree, yielding each node with its parent index and
+/// world matrix. A node is visited once, so a cyclic or shared hierarchy (invalid glTF, but
+/// loadable without validation) can't loop forever.
+fn walk_nodes<'a>(scene: &gltf::Scene<'a>) -> Vec<(gltf::Node<'a>, Option<usize>, Mat4)> {
+    let mut out = Vec::new();
+    let mut visited = std::collections::HashSet::new();
+    let mut stack: Vec<(gltf::Node<'a>, Option<usize>, Mat4)> =
+        scene.nodes().map(|n| (n, None, IDENTITY)).collect();
+    stack.reverse();
+    while let Some((node, parent, parent_world)) = stack.pop() {
+        if !visited.insert(node.index()) {
+            continue;
+        }
+        let world = mat4_mul(&parent_world, &node.transform().matrix());
+        let children: Vec<_> = node.children().collect();
+        stack.extend(
+            children
+                .into_iter()
+                .rev()
+                .map(|c| (c, Some(node.index()), world)),
+        );
+        out.push((node, parent, world));
+    }
+    out
+}
+
+pub(crate) const IDENTITY: Mat4 = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+pub fn mat4_mul(a: &Mat4, b: &Mat4) -> Mat4 {
+    let mut out = [[0f32; 4]; 4];
+    for (c, col) in out.iter_mut().enumerate() {
+        for (r, v) in col.iter_mut().enumerate() {
+            *v = (0..4).map(|k| a[k][r] * b[c][k]).sum();
+        }
+    }
+    out
+}
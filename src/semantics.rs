@@ -0,0 +1,307 @@
+use crate::*;
+
+use std::collections::HashMap;
+
+/// Expected layout of a vendor attribute registered in a [`SemanticRegistry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomSemantic {
+    pub components: u32,
+    pub component_type: gltf::accessor::DataType,
+    /// Widen integers to `[0, 1]` / `[-1, 1]`; overrides the accessor's flag, which
+    /// vendor exporters often leave out.
+    pub normalized: bool,
+}
+
+impl CustomSemantic {
+    pub fn new(components: u32, component_type: gltf::accessor::DataType) -> Self {
+        Self {
+            components,
+            component_type,
+            normalized: false,
+        }
+    }
+
+    pub fn normalized(mut self, normalized: bool) -> Self {
+        self.normalized = normalized;
+        self
+    }
+}
+
+/// A decoded vendor attribute.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CustomAttribute {
+    pub components: usize,
+    /// `components` values per vertex.
+    pub values: CustomValues,
+}
+
+impl CustomAttribute {
+    /// Number of vertices.
+    pub fn len(&self) -> usize {
+        self.values.len() / self.components.max(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Components and component type, e.g. `2 x u32`, for error messages.
+    pub(crate) fn layout(&self) -> String {
+        format!("{} x {}", self.components, self.values.type_name())
+    }
+}
+
+/// The values of a [`CustomAttribute`] in their stored component type, so ids and other
+/// integer payloads survive exactly. Normalized attributes, ones with an
+/// [`AttributeTransform`] and plugin output are `F32`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CustomValues {
+    I8(Vec<i8>),
+    U8(Vec<u8>),
+    I16(Vec<i16>),
+    U16(Vec<u16>),
+    I32(Vec<i32>),
+    U32(Vec<u32>),
+    F32(Vec<f32>),
+}
+
+impl Default for CustomValues {
+    fn default() -> Self {
+        Self::F32(Vec::new())
+    }
+}
+
+macro_rules! each_values {
+    ($values:expr, $v:ident => $e:expr) => {
+        match $values {
+            CustomValues::I8($v) => $e,
+            CustomValues::U8($v) => $e,
+            CustomValues::I16($v) => $e,
+            CustomValues::U16($v) => $e,
+            CustomValues::I32($v) => $e,
+            CustomValues::U32($v) => $e,
+            CustomValues::F32($v) => $e,
+        }
+    };
+}
+
+macro_rules! map_values {
+    ($values:expr, $v:ident => $e:expr) => {
+        match $values {
+            CustomValues::I8($v) => CustomValues::I8($e),
+            CustomValues::U8($v) => CustomValues::U8($e),
+            CustomValues::I16($v) => CustomValues::I16($e),
+            CustomValues::U16($v) => CustomValues::U16($e),
+            CustomValues::I32($v) => CustomValues::I32($e),
+            CustomValues::U32($v) => CustomValues::U32($e),
+            CustomValues::F32($v) => CustomValues::F32($e),
+        }
+    };
+}
+
+impl CustomValues {
+    /// Reads little-endian `bytes` of type `dt` as stored.
+    pub(crate) fn from_le_bytes(bytes: &[u8], dt: draco_decoder::AttributeDataType) -> Self {
+        use draco_decoder::AttributeDataType::*;
+        fn read<T: bytemuck::Pod>(bytes: &[u8]) -> Vec<T> {
+            bytes
+                .chunks_exact(std::mem::size_of::<T>())
+                .map(bytemuck::pod_read_unaligned)
+                .collect()
+        }
+        match dt {
+            Int8 => Self::I8(read(bytes)),
+            UInt8 => Self::U8(bytes.to_vec()),
+            Int16 => Self::I16(read(bytes)),
+            UInt16 => Self::U16(read(bytes)),
+            Int32 => Self::I32(read(bytes)),
+            UInt32 => Self::U32(read(bytes)),
+            Float32 => Self::F32(read(bytes)),
+        }
+    }
+
+    /// The component type's Rust name.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::I8(_) => "i8",
+            Self::U8(_) => "u8",
+            Self::I16(_) => "i16",
+            Self::U16(_) => "u16",
+            Self::I32(_) => "i32",
+            Self::U32(_) => "u32",
+            Self::F32(_) => "f32",
+        }
+    }
+
+    /// Total number of components.
+    pub fn len(&self) -> usize {
+        each_values!(self, v => v.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The values widened to `f32` as stored, without normalization. Integers past 2^24
+    /// lose precision here, so read the typed variant when they matter.
+    pub fn to_f32(&self) -> Vec<f32> {
+        match self {
+            Self::I8(v) => v.iter().map(|&x| x as f32).collect(),
+            Self::U8(v) => v.iter().map(|&x| x as f32).collect(),
+            Self::I16(v) => v.iter().map(|&x| x as f32).collect(),
+            Self::U16(v) => v.iter().map(|&x| x as f32).collect(),
+            Self::I32(v) => v.iter().map(|&x| x as f32).collect(),
+            Self::U32(v) => v.iter().map(|&x| x as f32).collect(),
+            Self::F32(v) => v.clone(),
+        }
+    }
+
+    /// Whether both hold the same component type.
+    pub(crate) fn same_type(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// An empty stream of the same component type.
+    pub(crate) fn empty_like(&self) -> Self {
+        map_values!(self, _v => Vec::new())
+    }
+
+    /// Appends `other`, which must hold the same component type.
+    pub(crate) fn extend_from(&mut self, other: &Self) {
+        match (self, other) {
+            (Self::I8(a), Self::I8(b)) => a.extend_from_slice(b),
+            (Self::U8(a), Self::U8(b)) => a.extend_from_slice(b),
+            (Self::I16(a), Self::I16(b)) => a.extend_from_slice(b),
+            (Self::U16(a), Self::U16(b)) => a.extend_from_slice(b),
+            (Self::I32(a), Self::I32(b)) => a.extend_from_slice(b),
+            (Self::U32(a), Self::U32(b)) => a.extend_from_slice(b),
+            (Self::F32(a), Self::F32(b)) => a.extend_from_slice(b),
+            _ => debug_assert!(false, "custom attribute component types differ"),
+        }
+    }
+
+    /// Zero-fills or truncates to `len` components.
+    pub(crate) fn resize(&mut self, len: usize) {
+        each_values!(self, v => v.resize(len, Default::default()))
+    }
+
+    /// The `n`-component vertices at `keep`, in that order.
+    pub(crate) fn pick(&self, n: usize, keep: &[usize]) -> Self {
+        map_values!(self, v => keep.iter().flat_map(|&i| &v[i * n..][..n]).copied().collect())
+    }
+
+    /// Moves `n`-component vertex `i` to `perm[i]`.
+    pub(crate) fn permute(&self, n: usize, perm: &[usize]) -> Self {
+        map_values!(self, v => {
+            let mut out = v.clone();
+            for (i, x) in v.chunks_exact(n).enumerate() {
+                out[perm[i] * n..][..n].copy_from_slice(x);
+            }
+            out
+        })
+    }
+}
+
+/// Expected layouts of application-specific attributes (`_CESIUM_*`, `_MYCOMPANY_*`),
+/// set as [`DecodeOptions::semantic_registry`].
+///
+/// Every `_NAME` attribute decodes into [`DecodedPrimitive::custom`] with its accessor's
+/// layout. Registered ones are checked against the expected components and type, so a
+/// mismatched export fails with [`DracoLoadError::AttributeMismatch`] instead of feeding
+/// misread values downstream, and take their normalization from the registry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SemanticRegistry {
+    names: HashMap<String, CustomSemantic>,
+    prefixes: Vec<(String, CustomSemantic)>,
+}
+
+impl SemanticRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers one attribute by its full name, e.g. `_CESIUM_OUTLINE`.
+    pub fn register(mut self, name: impl Into<String>, semantic: CustomSemantic) -> Self {
+        self.names.insert(name.into(), semantic);
+        self
+    }
+
+    /// Registers every attribute starting with `prefix`, e.g. `_MYCOMPANY_`. Exact names
+    /// win over prefixes, and longer prefixes over shorter ones.
+    pub fn register_prefix(mut self, prefix: impl Into<String>, semantic: CustomSemantic) -> Self {
+        self.prefixes.push((prefix.into(), semantic));
+        self.prefixes
+            .sort_by_key(|(p, _)| std::cmp::Reverse(p.len()));
+        self
+    }
+
+    /// The registered layout for attribute `key`. Only `_`-prefixed names can be vendor
+    /// attributes in glTF.
+    pub fn lookup(&self, key: &str) -> Option<&CustomSemantic> {
+        if !key.starts_with('_') {
+            return None;
+        }
+        self.names.get(key).or_else(|| {
+            self.prefixes
+                .iter()
+                .find(|(p, _)| key.starts_with(p.as_str()))
+                .map(|(_, s)| s)
+        })
+    }
+
+    /// Maps a Draco attribute key to its glTF semantic like [`dracokey_to_semantic`], along
+    /// with the layout registered for it, if any.
+    pub fn resolve(&self, key: &str) -> Option<(gltf::Semantic, Option<&CustomSemantic>)> {
+        dracokey_to_semantic(key).map(|sem| (sem, self.lookup(key)))
+    }
+
+    /// The primitive's Draco id to semantic map, resolved through [`resolve`](Self::resolve):
+    /// registered attributes are checked against their accessors and take the registered
+    /// normalization.
+    pub(crate) fn semantic_map(
+        &self,
+        p: &gltf::mesh::Primitive<'_>,
+        ext: &DracoExt,
+    ) -> Result<SemanticMap, DracoLoadError> {
+        let mut map = SemanticMap::new();
+        let mut conflicts = Vec::new();
+        for (key, id) in &ext.attributes {
+            let Some((sem, spec)) = self.resolve(key) else {
+                continue;
+            };
+            let Some(acc) = p.get(&sem) else {
+                continue;
+            };
+            let dim = dims_count(acc.dimensions());
+            let Some(spec) = spec else {
+                map.insert(*id, (sem, dim, acc.normalized()));
+                continue;
+            };
+            let expected = AttrInfo {
+                unique_id: *id,
+                dim: spec.components,
+                data_type: gltf_to_draco_dt(spec.component_type),
+            };
+            let found = AttrInfo {
+                unique_id: *id,
+                dim: dim as u32,
+                data_type: gltf_to_draco_dt(acc.data_type()),
+            };
+            if found != expected {
+                conflicts.push(AttributeConflict {
+                    unique_id: *id,
+                    semantic: Some(key.to_string()),
+                    given: expected,
+                    stored: Some(found),
+                });
+                continue;
+            }
+            map.insert(*id, (sem, dim, spec.normalized));
+        }
+        if conflicts.is_empty() {
+            Ok(map)
+        } else {
+            Err(DracoLoadError::AttributeMismatch(conflicts))
+        }
+    }
+}
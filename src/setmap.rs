@@ -0,0 +1,238 @@
+use std::sync::{Arc, OnceLock};
+
+/// Per-set attribute storage (`TEXCOORD_n`, `COLOR_n`, ...) as a small vector sorted by
+/// set, with one allocation for all sets instead of a hash table's, iterating in set
+/// order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetMap<T> {
+    entries: Vec<(u32, T)>,
+}
+
+impl<T> Default for SetMap<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T> SetMap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn position(&self, set: u32) -> Result<usize, usize> {
+        self.entries.binary_search_by_key(&set, |(s, _)| *s)
+    }
+
+    pub fn get(&self, set: &u32) -> Option<&T> {
+        let i = self.position(*set).ok()?;
+        Some(&self.entries[i].1)
+    }
+
+    pub fn get_mut(&mut self, set: &u32) -> Option<&mut T> {
+        let i = self.position(*set).ok()?;
+        Some(&mut self.entries[i].1)
+    }
+
+    pub fn contains_key(&self, set: &u32) -> bool {
+        self.position(*set).is_ok()
+    }
+
+    /// Stores `value` for `set`, returning the value it replaces.
+    pub fn insert(&mut self, set: u32, value: T) -> Option<T> {
+        match self.position(set) {
+            Ok(i) => Some(std::mem::replace(&mut self.entries[i].1, value)),
+            Err(i) => {
+                self.entries.insert(i, (set, value));
+                None
+            }
+        }
+    }
+
+    /// The value for `set`, inserting `make()` first if there is none.
+    pub fn get_or_insert_with(&mut self, set: u32, make: impl FnOnce() -> T) -> &mut T {
+        let i = match self.position(set) {
+            Ok(i) => i,
+            Err(i) => {
+                self.entries.insert(i, (set, make()));
+                i
+            }
+        };
+        &mut self.entries[i].1
+    }
+
+    pub fn remove(&mut self, set: &u32) -> Option<T> {
+        let i = self.position(*set).ok()?;
+        Some(self.entries.remove(i).1)
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&u32, &mut T) -> bool) {
+        self.entries.retain_mut(|(s, v)| keep(s, v));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Entries in ascending set order.
+    pub fn iter(&self) -> impl Iterator<Item = (&u32, &T)> {
+        self.entries.iter().map(|(s, v)| (s, v))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&u32, &mut T)> {
+        self.entries.iter_mut().map(|(s, v)| (&*s, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &u32> {
+        self.entries.iter().map(|(s, _)| s)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.entries.iter_mut().map(|(_, v)| v)
+    }
+}
+
+impl<T> FromIterator<(u32, T)> for SetMap<T> {
+    fn from_iter<I: IntoIterator<Item = (u32, T)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<T> Extend<(u32, T)> for SetMap<T> {
+    fn extend<I: IntoIterator<Item = (u32, T)>>(&mut self, iter: I) {
+        for (set, value) in iter {
+            self.insert(set, value);
+        }
+    }
+}
+
+impl<T, const N: usize> From<[(u32, T); N]> for SetMap<T> {
+    fn from(entries: [(u32, T); N]) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+impl<T> IntoIterator for SetMap<T> {
+    type Item = (u32, T);
+    type IntoIter = std::vec::IntoIter<(u32, T)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SetMap<T> {
+    type Item = (&'a u32, &'a T);
+    type IntoIter =
+        std::iter::Map<std::slice::Iter<'a, (u32, T)>, fn(&'a (u32, T)) -> (&'a u32, &'a T)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter().map(|(s, v)| (s, v))
+    }
+}
+
+impl<T> std::ops::Index<&u32> for SetMap<T> {
+    type Output = T;
+
+    fn index(&self, set: &u32) -> &T {
+        self.get(set).expect("no entry for set")
+    }
+}
+
+/// Keys shared by every extension that names them.
+const STANDARD_KEYS: &[&str] = &[
+    "POSITION",
+    "NORMAL",
+    "TANGENT",
+    "TEXCOORD_0",
+    "TEXCOORD_1",
+    "TEXCOORD_2",
+    "TEXCOORD_3",
+    "COLOR_0",
+    "COLOR_1",
+    "JOINTS_0",
+    "JOINTS_1",
+    "WEIGHTS_0",
+    "WEIGHTS_1",
+];
+
+/// An attribute key as the Draco extension stores it. The standard keys are allocated once
+/// and shared, so parsing the extension of thousands of primitives allocates nothing for
+/// them; any other key gets its own copy, freed with the last extension holding it.
+pub fn intern_semantic(key: &str) -> Arc<str> {
+    static STANDARD: OnceLock<Vec<Arc<str>>> = OnceLock::new();
+    let standard = STANDARD.get_or_init(|| STANDARD_KEYS.iter().map(|&k| k.into()).collect());
+    match standard.iter().find(|k| ***k == *key) {
+        Some(k) => k.clone(),
+        None => key.into(),
+    }
+}
+
+/// Reads the extension's `attributes` object as `(key, unique id)` pairs, sorted by id,
+/// with keys from [`intern_semantic`].
+pub(crate) fn interned_attributes<'de, D: serde::Deserializer<'de>>(
+    d: D,
+) -> Result<Vec<(Arc<str>, u32)>, D::Error> {
+    struct Key(Arc<str>);
+
+    impl<'de> serde::Deserialize<'de> for Key {
+        fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            d.deserialize_str(KeyVisitor)
+        }
+    }
+
+    struct KeyVisitor;
+
+    impl serde::de::Visitor<'_> for KeyVisitor {
+        type Value = Key;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("an attribute key")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Key, E> {
+            Ok(Key(intern_semantic(v)))
+        }
+    }
+
+    struct MapVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for MapVisitor {
+        type Value = Vec<(Arc<str>, u32)>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a map of attribute keys to Draco unique ids")
+        }
+
+        fn visit_map<A: serde::de::MapAccess<'de>>(
+            self,
+            mut map: A,
+        ) -> Result<Self::Value, A::Error> {
+            let mut out: Vec<(Arc<str>, u32)> = Vec::with_capacity(map.size_hint().unwrap_or(0));
+            while let Some((Key(key), id)) = map.next_entry::<Key, u32>()? {
+                // a repeated key overrides, as it would in a map
+                out.retain(|(k, _)| *k != key);
+                out.push((key, id));
+            }
+            out.sort_by_key(|&(_, id)| id);
+            Ok(out)
+        }
+    }
+
+    d.deserialize_map(MapVisitor)
+}
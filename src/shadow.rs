@@ -0,0 +1,151 @@
+use crate::*;
+
+use std::collections::HashMap;
+
+/// Settings for [`DecodedPrimitive::build_shadow_proxy`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowProxyOptions {
+    /// Edge of the grid cells used for welding: positions that round to the same cell merge
+    /// into one vertex at their mean. Merged positions are always less than this apart per
+    /// axis, but two close positions straddling a cell border stay separate.
+    pub weld_epsilon: f32,
+    /// Simplify by welding with cells of this size instead of `weld_epsilon`, collapsing
+    /// each cell to the mean of its vertices; `None` keeps full detail.
+    pub cluster_size: Option<f32>,
+    /// Close boundary loops with triangle fans so the proxy casts solid shadows.
+    pub fill_holes: bool,
+}
+
+impl Default for ShadowProxyOptions {
+    fn default() -> Self {
+        Self {
+            weld_epsilon: 1e-5,
+            cluster_size: None,
+            fill_holes: false,
+        }
+    }
+}
+
+impl ShadowProxyOptions {
+    pub fn with_cluster_size(mut self, size: f32) -> Self {
+        self.cluster_size = Some(size);
+        self
+    }
+
+    pub fn with_fill_holes(mut self, fill: bool) -> Self {
+        self.fill_holes = fill;
+        self
+    }
+}
+
+impl DecodedPrimitive {
+    /// A position-only proxy for shadow maps and occlusion culling: seams from split normals
+    /// and UVs are welded, degenerate and duplicate triangles dropped, and optionally the
+    /// mesh is simplified and its holes filled.
+    pub fn build_shadow_proxy(&self, options: &ShadowProxyOptions) -> DecodedPrimitive {
+        let positions = self.positions.as_deref().unwrap_or_default();
+        let cell = options
+            .cluster_size
+            .unwrap_or(options.weld_epsilon)
+            .max(f32::MIN_POSITIVE);
+
+        let (welded, remap) = weld_positions(positions, cell);
+
+        let mut seen = std::collections::HashSet::new();
+        let mut indices = Vec::with_capacity(self.indices.len());
+        for tri in self.indices.chunks_exact(3) {
+            let Some(t) = tri
+                .iter()
+                .map(|&i| remap.get(i as usize).copied())
+                .collect::<Option<Vec<u32>>>()
+            else {
+                continue;
+            };
+            if t[0] == t[1] || t[1] == t[2] || t[0] == t[2] {
+                continue;
+            }
+            let mut key = [t[0], t[1], t[2]];
+            key.sort_unstable();
+            if seen.insert(key) {
+                indices.extend_from_slice(&t);
+            }
+        }
+        if options.fill_holes {
+            fill_holes(&mut indices, usize::MAX);
+        }
+
+        gather_vertices(
+            &DecodedPrimitive {
+                indices: Vec::new(),
+                positions: Some(welded),
+                ..Default::default()
+            },
+            &indices,
+        )
+    }
+}
+
+/// Rounds each position to the nearest multiple of `cell` per axis and merges the positions
+/// sharing a grid point into one vertex at their mean. Returns the merged positions and, per
+/// input vertex, its merged index.
+pub(crate) fn weld_positions(positions: &[[f32; 3]], cell: f32) -> (Vec<[f32; 3]>, Vec<u32>) {
+    let mut cells: HashMap<[i64; 3], u32> = HashMap::new();
+    let mut sums: Vec<([f64; 3], u32)> = Vec::new();
+    let remap: Vec<u32> = positions
+        .iter()
+        .map(|p| {
+            let key = p.map(|x| (x / cell).round() as i64);
+            let id = *cells.entry(key).or_insert_with(|| {
+                sums.push(([0.0; 3], 0));
+                sums.len() as u32 - 1
+            });
+            let (sum, n) = &mut sums[id as usize];
+            for k in 0..3 {
+                sum[k] += p[k] as f64;
+            }
+            *n += 1;
+            id
+        })
+        .collect();
+    let welded = sums
+        .iter()
+        .map(|(sum, n)| sum.map(|s| (s / *n as f64) as f32))
+        .collect();
+    (welded, remap)
+}
+
+/// Fans every boundary loop of at most `max_edges` edges shut, oriented so each new edge
+/// pairs with the boundary edge. Returns the number of loops closed.
+pub(crate) fn fill_holes(indices: &mut Vec<u32>, max_edges: usize) -> usize {
+    let mut edges: HashMap<(u32, u32), usize> = HashMap::new();
+    for tri in indices.chunks_exact(3) {
+        for k in 0..3 {
+            *edges.entry((tri[k], tri[(k + 1) % 3])).or_default() += 1;
+        }
+    }
+    let mut next: HashMap<u32, u32> = edges
+        .keys()
+        .filter(|(a, b)| !edges.contains_key(&(*b, *a)))
+        .map(|&(a, b)| (a, b))
+        .collect();
+
+    let mut starts: Vec<u32> = next.keys().copied().collect();
+    starts.sort_unstable();
+    let mut filled = 0;
+    for start in starts {
+        let mut path = Vec::new();
+        let mut v = start;
+        while let Some(n) = next.remove(&v) {
+            path.push(v);
+            v = n;
+        }
+        if v != start || path.len() < 3 || path.len() > max_edges {
+            continue; // not a simple loop, or too large; leave it open
+        }
+        for i in 1..path.len() - 1 {
+            indices.extend_from_slice(&[path[0], path[i + 1], path[i]]);
+        }
+        filled += 1;
+    }
+    filled
+}
@@ -0,0 +1,145 @@
+use crate::*;
+
+/// A vertex whose `JOINTS_n` value points past the joint array of its node's skin.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct JointViolation {
+    pub node: usize,
+    pub mesh: usize,
+    pub primitive: usize,
+    /// `None` when the node has no skin at all, so every joint is out of range.
+    pub skin: Option<usize>,
+    pub set: u32,
+    pub vertex: usize,
+    pub joints: [u16; 4],
+    pub joint_count: usize,
+}
+
+/// Checks every decoded `JOINTS_n` value in `scene` against the joint count of the skin on
+/// the instancing node, reporting each offending vertex.
+///
+/// Out-of-range joints are reported even when their weight is zero: GPU skinning still
+/// indexes the joint matrix array with them.
+pub fn validate_skinning(scene: &DecodedScene, document: &gltf::Document) -> Vec<JointViolation> {
+    let mut out = Vec::new();
+    for sp in &scene.primitives {
+        let skin = document.nodes().nth(sp.node).and_then(|n| n.skin());
+        let joint_count = skin.as_ref().map_or(0, |s| s.joints().count());
+        for (set, joints) in sorted(&sp.decoded.joints) {
+            for (vertex, j) in joints.iter().enumerate() {
+                if j.iter().any(|&j| j as usize >= joint_count) {
+                    out.push(JointViolation {
+                        node: sp.node,
+                        mesh: sp.mesh,
+                        primitive: sp.primitive,
+                        skin: skin.as_ref().map(|s| s.index()),
+                        set,
+                        vertex,
+                        joints: *j,
+                        joint_count,
+                    });
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Joint indices of a `JOINTS_n` block, read in the stored component type.
+///
+/// `u8` and `u16` (the types glTF allows) convert losslessly. Anything else an exporter
+/// slipped through must still hold whole indices that fit in `u16`; a value that doesn't
+/// fails instead of wrapping into a different joint.
+pub(crate) fn joint_indices(
+    set: u32,
+    bytes: &[u8],
+    dt: AttributeDataType,
+    dim: usize,
+) -> Result<Vec<[u16; 4]>, DracoLoadError> {
+    let values: Vec<u16> = match dt {
+        AttributeDataType::UInt8 => bytes.iter().map(|&b| b as u16).collect(),
+        AttributeDataType::UInt16 => bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect(),
+        _ => as_f32_components(bytes, dt, false)
+            .into_iter()
+            .map(|x| {
+                if x.fract() == 0.0 && (0.0..=u16::MAX as f32).contains(&x) {
+                    Ok(x as u16)
+                } else {
+                    Err(DracoLoadError::JointIndexOverflow { set, value: x })
+                }
+            })
+            .collect::<Result<_, _>>()?,
+    };
+    Ok(values
+        .chunks_exact(dim.max(1))
+        .map(|c| {
+            let mut j = [0u16; 4];
+            for (out, x) in j.iter_mut().zip(c) {
+                *out = *x;
+            }
+            j
+        })
+        .collect())
+}
+
+/// A `JOINTS_n` set whose largest index is the last one its component type can address,
+/// on a node whose skin has more joints than that: 256 for `UNSIGNED_BYTE`, 65536 otherwise.
+/// Indices past the range would wrap onto it, which usually means the exporter truncated them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct JointWidthWarning {
+    pub node: usize,
+    pub mesh: usize,
+    pub primitive: usize,
+    pub skin: usize,
+    pub set: u32,
+    pub joints: usize,
+    pub addressable: usize,
+    pub max_joint: u16,
+}
+
+/// Checks the joint indices `decoded` actually uses against the width of each `JOINTS_n`
+/// accessor of `p`. A skin with more joints than the width can address is legal as long as
+/// the primitive stays within the range, so only sets reaching its top are reported.
+pub fn check_joint_width(
+    node: &gltf::Node<'_>,
+    mesh: &gltf::Mesh<'_>,
+    p: &gltf::mesh::Primitive<'_>,
+    decoded: &DecodedPrimitive,
+) -> Vec<JointWidthWarning> {
+    let Some(skin) = node.skin() else {
+        return Vec::new();
+    };
+    let joints = skin.joints().count();
+    let mut out = Vec::new();
+    for (semantic, accessor) in p.attributes() {
+        let gltf::Semantic::Joints(set) = semantic else {
+            continue;
+        };
+        let addressable = match accessor.data_type() {
+            gltf::accessor::DataType::U8 => 1 << 8,
+            _ => 1 << 16,
+        };
+        let max_joint = decoded
+            .joints
+            .get(&set)
+            .and_then(|j| j.iter().flatten().copied().max());
+        if let Some(max_joint) = max_joint
+            && joints > addressable
+            && max_joint as usize + 1 >= addressable
+        {
+            out.push(JointWidthWarning {
+                node: node.index(),
+                mesh: mesh.index(),
+                primitive: p.index(),
+                skin: skin.index(),
+                set,
+                joints,
+                addressable,
+                max_joint,
+            });
+        }
+    }
+    out
+}
@@ -0,0 +1,174 @@
+use crate::*;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+/// Vertices converted per unit of work unless overridden with
+/// [`SlicedDecode::with_chunk_vertices`].
+pub const DEFAULT_CHUNK_VERTICES: usize = 16 * 1024;
+
+type DecodeFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<u8>, DracoLoadError>> + 'a>>;
+
+/// An incremental decode driven by [`poll_step`](Self::poll_step), for single-threaded
+/// environments (WASM without threads) where a long decode would stall the UI.
+///
+/// The Draco call itself can't be split and runs inside one step; the conversion to
+/// [`DecodedPrimitive`] afterwards is done in chunks of vertices. Fallback and
+/// accessor-order options are not applied; quirks, the decode cap and the coordinate
+/// conventions are.
+pub struct SlicedDecode<'a> {
+    stage: Stage<'a>,
+    plan: Plan,
+    out: DecodedPrimitive,
+    chunk_vertices: usize,
+    clock: fn() -> Duration,
+}
+
+enum Stage<'a> {
+    Decoding(DecodeFuture<'a>),
+    Converting { raw: Vec<u8>, next_vertex: usize },
+    Done,
+}
+
+struct Plan {
+    index_comp: gltf::accessor::DataType,
+    index_count: usize,
+    vertex_count: usize,
+    infos: Vec<AttrInfo>,
+    dracoid_to_sem: SemanticMap,
+    options: DecodeOptions,
+}
+
+/// Prepares a time-sliced decode of `p`; no decoding happens until the first step.
+pub fn decode_draco_sliced<'a>(
+    p: &'a gltf::mesh::Primitive<'_>,
+    document: &'a gltf::Document,
+    buffers: &'a [gltf::buffer::Data],
+    infos: &'a [AttrInfo],
+    options: &DecodeOptions,
+) -> Result<SlicedDecode<'a>, DracoLoadError> {
+    let input = prozes_in(p, document, buffers, infos, &options.quirks)?;
+    options.check_vertex_count(input.vertex_count)?;
+    let plan = Plan {
+        index_comp: input.index_comp,
+        index_count: input.index_count,
+        vertex_count: input.vertex_count,
+        infos: input.infos,
+        dracoid_to_sem: options.semantic_map(p, &input.draco_ext)?,
+        options: options.clone(),
+    };
+    let (bytes, cfg, cap) = (input.draco_bytes, input.cfg, options.max_concurrent_decodes);
+    Ok(SlicedDecode {
+        stage: Stage::Decoding(Box::pin(
+            async move { decode_limited(bytes, &cfg, cap).await },
+        )),
+        plan,
+        out: DecodedPrimitive::default(),
+        chunk_vertices: DEFAULT_CHUNK_VERTICES,
+        clock: monotonic,
+    })
+}
+
+impl SlicedDecode<'_> {
+    pub fn with_chunk_vertices(mut self, chunk_vertices: usize) -> Self {
+        self.chunk_vertices = chunk_vertices.max(1);
+        self
+    }
+
+    /// Replaces the time source used to measure the budget. `std::time::Instant` panics on
+    /// `wasm32-unknown-unknown`, so pass e.g. a `performance.now()` wrapper there.
+    pub fn with_clock(mut self, clock: fn() -> Duration) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Does at least one unit of work and keeps going until `budget` is used up. Returns
+    /// `Pending` while work remains; call again on the next frame.
+    ///
+    /// # Panics
+    ///
+    /// If called again after returning `Ready`.
+    pub fn poll_step(
+        &mut self,
+        budget: Duration,
+    ) -> Poll<Result<DecodedPrimitive, DracoLoadError>> {
+        let start = (self.clock)();
+        loop {
+            match self.step() {
+                Ok(Some(done)) => return Poll::Ready(Ok(done)),
+                Ok(None) => {}
+                Err(e) => {
+                    self.stage = Stage::Done;
+                    return Poll::Ready(Err(e));
+                }
+            }
+            // a decode waiting for a slot makes no progress within this step
+            if matches!(self.stage, Stage::Decoding(_))
+                || (self.clock)().saturating_sub(start) >= budget
+            {
+                return Poll::Pending;
+            }
+        }
+    }
+
+    fn step(&mut self) -> Result<Option<DecodedPrimitive>, DracoLoadError> {
+        let (plan, out) = (&self.plan, &mut self.out);
+        match &mut self.stage {
+            Stage::Decoding(decode) => {
+                let mut cx = Context::from_waker(Waker::noop());
+                if let Poll::Ready(raw) = decode.as_mut().poll(&mut cx) {
+                    let raw = raw?;
+                    let index_bytes = plan.index_count * comp_size_bytes(plan.index_comp);
+                    out.indices = get_indices(&raw, index_bytes, plan.index_comp)?;
+                    self.stage = Stage::Converting {
+                        raw,
+                        next_vertex: 0,
+                    };
+                }
+                Ok(None)
+            }
+            Stage::Converting { raw, next_vertex } => {
+                let (start, count) = (*next_vertex, plan.vertex_count);
+                let end = (start + self.chunk_vertices).min(count);
+                let index_bytes = plan.index_count * comp_size_bytes(plan.index_comp);
+                let chunk: Vec<AttrSlice<'_>> = attr_slices(raw, index_bytes, count, &plan.infos)
+                    .into_iter()
+                    .filter(|b| plan.options.converts_block(&plan.dracoid_to_sem, b))
+                    .map(|blk| {
+                        let stride = blk.bytes.len() / count.max(1);
+                        AttrSlice {
+                            bytes: &blk.bytes[start * stride..end * stride],
+                            ..blk
+                        }
+                    })
+                    .collect();
+                let mut part = DecodedPrimitive::default();
+                fill_primitive_recycled(
+                    &mut part,
+                    &chunk,
+                    &plan.dracoid_to_sem,
+                    &plan.options.transforms,
+                    &mut PrimitiveBuffers::default(),
+                )?;
+                append_vertices(out, &part, start, end - start)?;
+                *next_vertex = end;
+
+                if end < count {
+                    return Ok(None);
+                }
+                let mut done = std::mem::take(out);
+                self.stage = Stage::Done;
+                plan.options.apply_conventions(&mut done);
+                Ok(Some(done))
+            }
+            Stage::Done => panic!("SlicedDecode polled after completion"),
+        }
+    }
+}
+
+fn monotonic() -> Duration {
+    static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    EPOCH.get_or_init(std::time::Instant::now).elapsed()
+}
@@ -0,0 +1,128 @@
+use crate::*;
+
+impl DecodedPrimitive {
+    /// Replaces the normals with ones derived from the geometry, for streams whose normals
+    /// were quantized too coarsely to keep.
+    ///
+    /// Each triangle corner averages the face normals, weighted by corner angle, of every
+    /// triangle around the same position whose normal is within `threshold_deg` of its
+    /// own. Faces meeting at a sharper angle keep a hard edge between them, and a vertex
+    /// used on both sides of one is split; vertices at the same position (UV seams) are
+    /// smoothed across. Split vertices copy every other attribute, tangents included, as
+    /// they are. Returns how many vertices were added; without positions nothing changes.
+    pub fn recompute_normals_with_angle(&mut self, threshold_deg: f32) -> usize {
+        let Some(positions) = self.positions.as_deref() else {
+            return 0;
+        };
+        let min_dot = threshold_deg.clamp(0.0, 180.0).to_radians().cos();
+        let corner_pos = |c: usize| positions.get(self.indices[c] as usize).copied();
+
+        let faces: Vec<[f32; 3]> = (0..self.indices.len() / 3)
+            .map(|t| match [0, 1, 2].map(|k| corner_pos(t * 3 + k)) {
+                [Some(a), Some(b), Some(c)] => normalize(cross(sub(b, a), sub(c, a))),
+                _ => [0.0; 3],
+            })
+            .collect();
+        let corners = faces.len() * 3;
+        let weights: Vec<f32> = (0..corners)
+            .map(|c| {
+                let t = c / 3;
+                let at = |k: usize| corner_pos(t * 3 + (c + k) % 3);
+                match (at(0), at(1), at(2)) {
+                    (Some(a), Some(b), Some(c)) => {
+                        let cos = dot(normalize(sub(b, a)), normalize(sub(c, a)));
+                        cos.clamp(-1.0, 1.0).acos()
+                    }
+                    _ => 0.0,
+                }
+            })
+            .collect();
+
+        // corners by position, so seams split for UVs still smooth together
+        let mut by_position: std::collections::HashMap<[u32; 3], Vec<usize>> =
+            std::collections::HashMap::new();
+        for c in 0..corners {
+            if let Some(p) = corner_pos(c) {
+                // -0.0 + 0.0 is 0.0, so both zeros share a key
+                let key = p.map(|x| (x + 0.0).to_bits());
+                by_position.entry(key).or_default().push(c);
+            }
+        }
+
+        let mut normals = vec![[0.0f32; 3]; corners];
+        for group in by_position.values() {
+            // corners with the same face normal smooth alike, so a group costs its distinct
+            // normals squared rather than its corners squared
+            let mut distinct: Vec<([f32; 3], f32)> = Vec::new();
+            let mut slots: std::collections::HashMap<[u32; 3], usize> =
+                std::collections::HashMap::new();
+            let slot_of: Vec<usize> = group
+                .iter()
+                .map(|&c| {
+                    let n = faces[c / 3];
+                    let slot = *slots
+                        .entry(n.map(|x| (x + 0.0).to_bits()))
+                        .or_insert_with(|| {
+                            distinct.push((n, 0.0));
+                            distinct.len() - 1
+                        });
+                    distinct[slot].1 += weights[c];
+                    slot
+                })
+                .collect();
+            let smoothed: Vec<[f32; 3]> = distinct
+                .iter()
+                .map(|&(own, _)| {
+                    let degenerate = own == [0.0; 3];
+                    let mut sum = [0.0f32; 3];
+                    for &(n, weight) in &distinct {
+                        if degenerate || dot(own, n) >= min_dot {
+                            sum = [0, 1, 2].map(|k| sum[k] + n[k] * weight);
+                        }
+                    }
+                    if sum == [0.0; 3] { own } else { normalize(sum) }
+                })
+                .collect();
+            for (&c, &slot) in group.iter().zip(&slot_of) {
+                normals[c] = smoothed[slot];
+            }
+        }
+
+        // one output vertex per distinct normal a vertex ends up with
+        let vertex_count = positions.len();
+        let mut keep: Vec<usize> = (0..vertex_count).collect();
+        let mut vertex_normals: Vec<Vec<([f32; 3], u32)>> = vec![Vec::new(); vertex_count];
+        let mut out_normals = self
+            .normals
+            .clone()
+            .unwrap_or_else(|| vec![[0.0; 3]; vertex_count]);
+        out_normals.resize(vertex_count, [0.0; 3]);
+        let mut indices = self.indices.clone();
+        for (c, index) in indices.iter_mut().enumerate().take(corners) {
+            let v = *index as usize;
+            if v >= vertex_count {
+                continue;
+            }
+            let n = normals[c];
+            let seen = &mut vertex_normals[v];
+            if let Some(&(_, at)) = seen.iter().find(|(m, _)| dot(*m, n) >= 1.0 - 1e-6) {
+                *index = at;
+            } else if seen.is_empty() {
+                seen.push((n, v as u32));
+                out_normals[v] = n;
+            } else {
+                let at = keep.len() as u32;
+                seen.push((n, at));
+                keep.push(v);
+                out_normals.push(n);
+                *index = at;
+            }
+        }
+
+        let added = keep.len() - vertex_count;
+        let mut out = pick_vertices(self, &keep, indices);
+        out.normals = Some(out_normals);
+        *self = out;
+        added
+    }
+}
@@ -0,0 +1,148 @@
+use crate::*;
+
+/// Number of decimals written for float components.
+pub const SNAPSHOT_DECIMALS: usize = 5;
+
+/// Renders a decoded primitive as a stable, line-oriented text snapshot.
+///
+/// Streams appear in a fixed order with attribute sets sorted, one vertex (or triangle)
+/// per line and floats printed with [`SNAPSHOT_DECIMALS`] decimals, so the output diffs
+/// cleanly and does not depend on `HashMap` iteration order.
+pub fn snapshot(d: &DecodedPrimitive) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("indices {}\n", d.indices.len()));
+    for tri in d.indices.chunks(3) {
+        let line: Vec<String> = tri.iter().map(|i| i.to_string()).collect();
+        out.push_str(&line.join(" "));
+        out.push('\n');
+    }
+    if let Some(v) = &d.positions {
+        section(&mut out, "POSITION", v);
+    }
+    if let Some(v) = &d.normals {
+        section(&mut out, "NORMAL", v);
+    }
+    if let Some(v) = &d.tangents {
+        section(&mut out, "TANGENT", v);
+    }
+    for (set, v) in sorted(&d.texcoords) {
+        section(&mut out, &format!("TEXCOORD_{set}"), v);
+    }
+    for (set, v) in sorted(&d.colors) {
+        section(&mut out, &format!("COLOR_{set}"), v);
+    }
+    for (set, v) in sorted(&d.joints) {
+        let as_f32: Vec<[f32; 4]> = v.iter().map(|j| j.map(|x| x as f32)).collect();
+        section(&mut out, &format!("JOINTS_{set}"), &as_f32);
+    }
+    for (set, v) in sorted(&d.weights) {
+        section(&mut out, &format!("WEIGHTS_{set}"), v);
+    }
+    out
+}
+
+/// Compares `d` against the golden snapshot at `path`.
+///
+/// Float components may differ by `tolerance`; everything else must match exactly. When
+/// the `UPDATE_SNAPSHOTS` environment variable is set the golden is (re)written instead and
+/// the comparison passes; otherwise a missing golden is [`SnapshotMismatch::Missing`], so a
+/// misspelled path can't pass by writing a fresh file.
+pub fn assert_snapshot(
+    d: &DecodedPrimitive,
+    path: impl AsRef<std::path::Path>,
+    tolerance: f32,
+) -> Result<(), SnapshotMismatch> {
+    let path = path.as_ref();
+    let actual = snapshot(d);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| SnapshotMismatch::io(path, e))?;
+        }
+        return std::fs::write(path, actual).map_err(|e| SnapshotMismatch::io(path, e));
+    }
+    let expected = std::fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => SnapshotMismatch::Missing(path.to_path_buf()),
+        _ => SnapshotMismatch::io(path, e),
+    })?;
+    compare_snapshots(&expected, &actual, tolerance)
+}
+
+/// Line-by-line comparison of two snapshots with a float tolerance.
+pub fn compare_snapshots(
+    expected: &str,
+    actual: &str,
+    tolerance: f32,
+) -> Result<(), SnapshotMismatch> {
+    let mut exp = expected.lines();
+    let mut act = actual.lines();
+    let mut line = 0;
+    loop {
+        line += 1;
+        match (exp.next(), act.next()) {
+            (None, None) => return Ok(()),
+            (e, a) => {
+                let (e, a) = (e.unwrap_or(""), a.unwrap_or(""));
+                if !lines_match(e, a, tolerance) {
+                    return Err(SnapshotMismatch::Line {
+                        line,
+                        expected: e.to_string(),
+                        actual: a.to_string(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotMismatch {
+    #[error("snapshot line {line} differs:\n  expected: {expected}\n  actual:   {actual}")]
+    Line {
+        line: usize,
+        expected: String,
+        actual: String,
+    },
+    #[error("snapshot {0} doesn't exist; run with UPDATE_SNAPSHOTS=1 to write it")]
+    Missing(std::path::PathBuf),
+    #[error("snapshot {0}: {1}")]
+    Io(std::path::PathBuf, std::io::Error),
+}
+
+impl SnapshotMismatch {
+    fn io(path: &std::path::Path, e: std::io::Error) -> Self {
+        SnapshotMismatch::Io(path.to_path_buf(), e)
+    }
+}
+
+fn lines_match(e: &str, a: &str, tolerance: f32) -> bool {
+    if e == a {
+        return true;
+    }
+    let (ew, aw): (Vec<&str>, Vec<&str>) = (e.split(' ').collect(), a.split(' ').collect());
+    ew.len() == aw.len()
+        && ew.iter().zip(&aw).all(|(x, y)| {
+            x == y
+                || matches!((x.parse::<f32>(), y.parse::<f32>()), (Ok(x), Ok(y)) if (x - y).abs() <= tolerance)
+        })
+}
+
+fn section<const N: usize>(out: &mut String, name: &str, v: &[[f32; N]]) {
+    out.push_str(&format!("{name} {}\n", v.len()));
+    for x in v {
+        let line: Vec<String> = x.iter().map(|c| fmt_f32(*c)).collect();
+        out.push_str(&line.join(" "));
+        out.push('\n');
+    }
+}
+
+fn fmt_f32(v: f32) -> String {
+    let s = format!("{v:.SNAPSHOT_DECIMALS$}");
+    // avoid "-0.00000" flapping against "0.00000"
+    if s.trim_start_matches('-')
+        .trim_matches(['0', '.'])
+        .is_empty()
+    {
+        return format!("{:.SNAPSHOT_DECIMALS$}", 0.0);
+    }
+    s
+}
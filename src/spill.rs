@@ -0,0 +1,205 @@
+use crate::*;
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Settings for [`DocumentIndex::decode_out_of_core`].
+#[derive(Debug, Clone)]
+pub struct SpillOptions {
+    /// Decoded bytes kept in memory; primitives past it are spilled.
+    pub ram_budget: usize,
+    /// Where spill files go; the system temp directory by default.
+    pub dir: PathBuf,
+    /// Passed to [`IndexedPrimitive::decode`] for every primitive.
+    pub decode: DecodeOptions,
+}
+
+impl SpillOptions {
+    pub fn new(ram_budget: usize) -> Self {
+        Self {
+            ram_budget,
+            dir: std::env::temp_dir(),
+            decode: DecodeOptions::default(),
+        }
+    }
+
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = dir.into();
+        self
+    }
+
+    pub fn with_decode_options(mut self, decode: DecodeOptions) -> Self {
+        self.decode = decode;
+        self
+    }
+}
+
+/// A primitive from [`DocumentIndex::decode_out_of_core`], in memory or on disk.
+#[derive(Debug)]
+pub enum StoredPrimitive {
+    Resident(DecodedPrimitive),
+    Spilled(SpilledPrimitive),
+}
+
+impl StoredPrimitive {
+    pub fn view(&self) -> PrimitiveView<'_> {
+        match self {
+            StoredPrimitive::Resident(d) => d.view(),
+            StoredPrimitive::Spilled(s) => s.view(),
+        }
+    }
+
+    pub fn is_spilled(&self) -> bool {
+        matches!(self, StoredPrimitive::Spilled(_))
+    }
+}
+
+/// A decoded primitive whose streams were written to a temporary file and mapped back,
+/// so the OS pages them in on access and can drop them under memory pressure. Custom
+/// attributes stay in memory. The file is deleted on drop.
+#[derive(Debug)]
+pub struct SpilledPrimitive {
+    map: Option<memmap2::Mmap>,
+    path: PathBuf,
+    streams: Vec<(UploadStream, Range<usize>)>,
+    custom: HashMap<String, CustomAttribute>,
+}
+
+impl SpilledPrimitive {
+    /// Writes `decoded`'s streams to a new file in `dir` and maps it.
+    pub fn spill(decoded: DecodedPrimitive, dir: &Path) -> Result<Self, DracoLoadError> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "draco-spill-{}-{}.bin",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = dir.join(name);
+
+        let mut streams = Vec::new();
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(io)?;
+        let mut file = std::io::BufWriter::new(file);
+        let mut offset = 0;
+        // every element size is a multiple of 4, so each stream stays aligned for casting
+        for (stream, bytes, _) in decoded.upload_streams() {
+            file.write_all(bytes).map_err(io)?;
+            streams.push((stream, offset..offset + bytes.len()));
+            offset += bytes.len();
+        }
+        let file = file.into_inner().map_err(|e| io(e.into_error()))?;
+        // SAFETY: the file is private to this value and never written again
+        let map = unsafe { memmap2::Mmap::map(&file) }.map_err(io)?;
+        Ok(Self {
+            map: Some(map),
+            path,
+            streams,
+            custom: decoded.custom,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Bytes on disk.
+    pub fn len(&self) -> usize {
+        self.streams.last().map_or(0, |(_, r)| r.end)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The mapped bytes of one stream.
+    pub fn stream(&self, stream: UploadStream) -> Option<&[u8]> {
+        let (_, range) = self.streams.iter().find(|(s, _)| *s == stream)?;
+        Some(&self.map.as_deref()?[range.clone()])
+    }
+
+    fn cast<T: bytemuck::Pod>(&self, stream: UploadStream) -> Option<&[T]> {
+        self.stream(stream).map(bytemuck::cast_slice)
+    }
+
+    pub fn view(&self) -> PrimitiveView<'_> {
+        let mut view = PrimitiveView {
+            indices: self.cast(UploadStream::Indices).unwrap_or_default(),
+            positions: self.cast(UploadStream::Positions),
+            normals: self.cast(UploadStream::Normals),
+            tangents: self.cast(UploadStream::Tangents),
+            texcoords: SetMap::new(),
+            colors: SetMap::new(),
+            joints: SetMap::new(),
+            weights: SetMap::new(),
+            custom: &self.custom,
+        };
+        for &(stream, _) in &self.streams {
+            let bytes = self.stream(stream).unwrap_or_default();
+            match stream {
+                UploadStream::TexCoords(set) => {
+                    view.texcoords.insert(set, bytemuck::cast_slice(bytes));
+                }
+                UploadStream::Colors(set) => {
+                    view.colors.insert(set, bytemuck::cast_slice(bytes));
+                }
+                UploadStream::Joints(set) => {
+                    view.joints.insert(set, bytemuck::cast_slice(bytes));
+                }
+                UploadStream::Weights(set) => {
+                    view.weights.insert(set, bytemuck::cast_slice(bytes));
+                }
+                _ => {}
+            }
+        }
+        view
+    }
+}
+
+impl Drop for SpilledPrimitive {
+    fn drop(&mut self) {
+        // unmap first; Windows won't delete a mapped file
+        self.map.take();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl DocumentIndex {
+    /// Decodes every indexed primitive one at a time, keeping results in memory until
+    /// `options.ram_budget` decoded bytes are held and spilling every later one to a
+    /// [`SpilledPrimitive`]. Peak memory is the budget plus one decode, so city-scale
+    /// datasets can be processed on machines with far less RAM than their decoded size.
+    pub async fn decode_out_of_core(
+        &self,
+        buffers: &[gltf::buffer::Data],
+        options: &SpillOptions,
+    ) -> Result<Vec<StoredPrimitive>, DracoLoadError> {
+        let mut resident = 0;
+        let mut out = Vec::with_capacity(self.primitives.len());
+        for entry in &self.primitives {
+            let decoded = entry.decode(buffers, &options.decode).await?;
+            let size: usize = decoded
+                .upload_streams()
+                .iter()
+                .map(|(_, b, _)| b.len())
+                .sum();
+            out.push(if size == 0 || resident + size <= options.ram_budget {
+                resident += size;
+                StoredPrimitive::Resident(decoded)
+            } else {
+                StoredPrimitive::Spilled(SpilledPrimitive::spill(decoded, &options.dir)?)
+            });
+        }
+        Ok(out)
+    }
+}
+
+fn io(e: std::io::Error) -> DracoLoadError {
+    DracoLoadError::Gltf(gltf::Error::Io(e))
+}
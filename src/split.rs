@@ -0,0 +1,108 @@
+use crate::*;
+
+/// One grid cell of a split primitive.
+#[derive(Debug, Clone)]
+pub struct GridCell {
+    pub cell: [i32; 3], // floor(centroid / cell_size) per axis
+    pub primitive: DecodedPrimitive,
+}
+
+impl DecodedPrimitive {
+    /// Splits the mesh into axis-aligned cells of `cell_size`, assigning each triangle to the
+    /// cell containing its centroid. Each cell keeps only the vertices it references, with
+    /// indices rebuilt; vertices on cell borders are duplicated into every cell that uses them.
+    ///
+    /// Cells come back sorted by coordinate. Without positions, or with a non-positive
+    /// `cell_size`, the whole primitive is returned as cell `[0, 0, 0]`.
+    pub fn split_by_grid(&self, cell_size: f32) -> Vec<GridCell> {
+        let Some(pos) = self.positions.as_deref() else {
+            return vec![self.whole_cell()];
+        };
+        if !(cell_size > 0.0 && cell_size.is_finite()) {
+            return vec![self.whole_cell()];
+        }
+
+        let mut cells: std::collections::BTreeMap<[i32; 3], Vec<u32>> = Default::default();
+        for tri in self.indices.chunks_exact(3) {
+            let mut c = [0.0f32; 3];
+            for &i in tri {
+                let p = pos[i as usize];
+                for k in 0..3 {
+                    c[k] += p[k] / 3.0;
+                }
+            }
+            let key = c.map(|x| (x / cell_size).floor() as i32);
+            cells.entry(key).or_default().extend_from_slice(tri);
+        }
+
+        cells
+            .into_iter()
+            .map(|(cell, indices)| GridCell {
+                cell,
+                primitive: gather_vertices(self, &indices),
+            })
+            .collect()
+    }
+
+    fn whole_cell(&self) -> GridCell {
+        GridCell {
+            cell: [0; 3],
+            primitive: self.clone(),
+        }
+    }
+}
+
+/// Builds a primitive from the vertices `indices` reference, in first-use order, with the
+/// indices rewritten to the compacted vertex list.
+pub(crate) fn gather_vertices(d: &DecodedPrimitive, indices: &[u32]) -> DecodedPrimitive {
+    let mut remap = std::collections::HashMap::new();
+    let mut keep: Vec<usize> = Vec::new();
+    let new_indices = indices
+        .iter()
+        .map(|&i| {
+            *remap.entry(i).or_insert_with(|| {
+                keep.push(i as usize);
+                (keep.len() - 1) as u32
+            })
+        })
+        .collect();
+    pick_vertices(d, &keep, new_indices)
+}
+
+/// Builds a primitive whose vertex `i` is a copy of `d`'s vertex `keep[i]`, with `indices`
+/// into the new vertex list.
+pub(crate) fn pick_vertices(
+    d: &DecodedPrimitive,
+    keep: &[usize],
+    indices: Vec<u32>,
+) -> DecodedPrimitive {
+    fn pick<T: Copy>(v: &[T], keep: &[usize]) -> Vec<T> {
+        keep.iter().map(|&i| v[i]).collect()
+    }
+    fn pick_sets<T: Copy>(m: &SetMap<Vec<T>>, keep: &[usize]) -> SetMap<Vec<T>> {
+        m.iter().map(|(k, v)| (*k, pick(v, keep))).collect()
+    }
+
+    DecodedPrimitive {
+        indices,
+        positions: d.positions.as_deref().map(|v| pick(v, keep)),
+        normals: d.normals.as_deref().map(|v| pick(v, keep)),
+        tangents: d.tangents.as_deref().map(|v| pick(v, keep)),
+        texcoords: pick_sets(&d.texcoords, keep),
+        colors: pick_sets(&d.colors, keep),
+        joints: pick_sets(&d.joints, keep),
+        weights: pick_sets(&d.weights, keep),
+        custom: d
+            .custom
+            .iter()
+            .map(|(name, c)| {
+                let picked = CustomAttribute {
+                    components: c.components,
+                    values: c.values.pick(c.components.max(1), keep),
+                };
+                (name.clone(), picked)
+            })
+            .collect(),
+        areas: None,
+    }
+}
@@ -0,0 +1,122 @@
+use crate::*;
+
+use std::path::Path;
+
+/// Target unit for STL export. glTF positions are in meters; slicers usually assume
+/// millimeters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthUnit {
+    Meters,
+    Centimeters,
+    Millimeters,
+    Inches,
+}
+
+impl LengthUnit {
+    pub fn per_meter(self) -> f32 {
+        match self {
+            LengthUnit::Meters => 1.0,
+            LengthUnit::Centimeters => 100.0,
+            LengthUnit::Millimeters => 1000.0,
+            LengthUnit::Inches => 1.0 / 0.0254,
+        }
+    }
+}
+
+/// Why [`DecodedPrimitive::to_stl`] produced no file.
+#[derive(Debug, thiserror::Error)]
+pub enum StlError {
+    #[error("mesh has no triangles")]
+    Empty,
+    #[error(
+        "mesh is not manifold: {} boundary, {} non-manifold and {} inconsistently wound edges",
+        .0.boundary_edges,
+        .0.non_manifold_edges,
+        .0.inconsistent_edges
+    )]
+    NotManifold(ManifoldStats),
+    #[error("writing the STL failed: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl DecodedPrimitive {
+    /// Binary STL of the triangles, scaled from meters to `units`, with facet normals
+    /// recomputed from the scaled positions (decoded normals are ignored).
+    ///
+    /// Fails with [`StlError::Empty`] without a triangle to write, and with
+    /// [`StlError::NotManifold`] unless the mesh is closed and consistently wound once
+    /// UV/normal seams are welded, or `force` is set.
+    pub fn to_stl(&self, units: LengthUnit, force: bool) -> Result<Vec<u8>, StlError> {
+        let positions = self.positions.as_deref().unwrap_or_default();
+        let resolvable = |t: &[u32]| t.iter().all(|&i| (i as usize) < positions.len());
+        if !self.indices.chunks_exact(3).any(resolvable) {
+            return Err(StlError::Empty);
+        }
+        if !force {
+            let (_, remap) = weld_positions(positions, 1e-5);
+            let welded = DecodedPrimitive {
+                indices: self
+                    .indices
+                    .iter()
+                    .map(|&i| remap.get(i as usize).copied().unwrap_or(i))
+                    .collect(),
+                ..Default::default()
+            };
+            let stats = welded.manifold_stats();
+            if !stats.is_manifold() {
+                return Err(StlError::NotManifold(stats));
+            }
+        }
+
+        let scale = units.per_meter();
+        let facets: Vec<[[f32; 3]; 3]> = self
+            .indices
+            .chunks_exact(3)
+            .filter_map(|t| {
+                let v = |i: u32| positions.get(i as usize).map(|p| p.map(|x| x * scale));
+                Some([v(t[0])?, v(t[1])?, v(t[2])?])
+            })
+            .collect();
+
+        let mut out = Vec::with_capacity(84 + facets.len() * 50);
+        let mut header = [0u8; 80];
+        let tag = b"binary STL from draco-gltf-rs";
+        header[..tag.len()].copy_from_slice(tag);
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&(facets.len() as u32).to_le_bytes());
+        for [a, b, c] in facets {
+            let n = normalize(cross(sub(b, a), sub(c, a)));
+            for x in [n, a, b, c].iter().flatten() {
+                out.extend_from_slice(&x.to_le_bytes());
+            }
+            out.extend_from_slice(&0u16.to_le_bytes());
+        }
+        Ok(out)
+    }
+
+    /// Writes [`to_stl`](Self::to_stl) to `path`, refusing empty and non-manifold geometry.
+    pub fn export_stl_for_printing(
+        &self,
+        path: impl AsRef<Path>,
+        units: LengthUnit,
+    ) -> Result<(), StlError> {
+        let path = path.as_ref();
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            let msg = format!("{} is not a file path", path.display());
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, msg).into());
+        };
+        let store = FsStore::new(dir);
+        block_on(self.write_stl_for_printing(&store, &name.to_string_lossy(), units))
+    }
+
+    /// [`export_stl_for_printing`](Self::export_stl_for_printing) to `store` under `key`.
+    pub async fn write_stl_for_printing(
+        &self,
+        store: &impl OutputStore,
+        key: &str,
+        units: LengthUnit,
+    ) -> Result<(), StlError> {
+        let stl = self.to_stl(units, false)?;
+        Ok(store.put(key, stl).await?)
+    }
+}
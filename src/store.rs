@@ -0,0 +1,97 @@
+use crate::*;
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+/// Destination for files the crate writes, such as [`StrippedDocument::write_glb`].
+///
+/// The crate ships only a filesystem and an in-memory store; it has no cloud SDK
+/// dependency. To stream outputs to S3, GCS and the like without temporary files,
+/// implement it over the object store client the application already uses. Keys are
+/// `/`-separated relative paths.
+pub trait OutputStore {
+    fn put(&self, key: &str, bytes: Vec<u8>) -> impl Future<Output = std::io::Result<()>> + Send;
+}
+
+/// Writes keys as files below `root`, creating directories as needed. Uses blocking
+/// `std::fs` calls, so run it off latency-sensitive executors.
+#[derive(Debug, Clone)]
+pub struct FsStore {
+    pub root: PathBuf,
+}
+
+impl FsStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// The file for `key`; keys that would leave `root` are rejected.
+    pub fn path(&self, key: &str) -> std::io::Result<PathBuf> {
+        let rel = Path::new(key);
+        if !rel.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("store key {key:?} must be a relative path without `..`"),
+            ));
+        }
+        Ok(self.root.join(rel))
+    }
+}
+
+impl OutputStore for FsStore {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> std::io::Result<()> {
+        let path = self.path(key)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, bytes)
+    }
+}
+
+/// Keeps outputs in memory, for tests and for handing results to another layer.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    files: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.lock().get(key).cloned()
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.lock().keys().cloned().collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Vec<u8>>> {
+        self.files.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl OutputStore for MemoryStore {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> std::io::Result<()> {
+        self.lock().insert(key.to_string(), bytes);
+        Ok(())
+    }
+}
+
+impl StrippedDocument {
+    /// Writes [`to_glb`](Self::to_glb) to `store` under `key`.
+    pub async fn write_glb(
+        &self,
+        store: &impl OutputStore,
+        key: &str,
+    ) -> Result<(), DracoLoadError> {
+        let glb = self.to_glb()?;
+        store
+            .put(key, glb)
+            .await
+            .map_err(|e| DracoLoadError::Gltf(gltf::Error::Io(e)))
+    }
+}
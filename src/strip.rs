@@ -0,0 +1,183 @@
+use crate::*;
+
+use gltf::json;
+use gltf::json::validation::{Checked, USize64};
+
+const EXTENSION: &str = "KHR_draco_mesh_compression";
+
+/// A document with the Draco extension removed from some primitives, from [`strip_draco`].
+#[derive(Debug, Clone)]
+pub struct StrippedDocument {
+    pub root: json::Root,
+    /// Data for every buffer in `root`; decoded accessors are appended to buffer 0.
+    pub buffers: Vec<Vec<u8>>,
+}
+
+impl StrippedDocument {
+    /// Packs the document as GLB with buffer 0 as the BIN chunk. Other buffers keep their
+    /// URIs and must be written next to the GLB.
+    pub fn to_glb(&self) -> Result<Vec<u8>, DracoLoadError> {
+        let mut root = self.root.clone();
+        if let Some(b) = root.buffers.first_mut() {
+            b.uri = None;
+        }
+        let json = serde_json::to_vec(&root)
+            .map_err(|e| DracoLoadError::Gltf(gltf::Error::Deserialize(e)))?;
+        let glb = gltf::binary::Glb {
+            header: gltf::binary::Header {
+                magic: *b"glTF",
+                version: 2,
+                length: 0, // computed by to_vec
+            },
+            json: json.into(),
+            bin: self.buffers.first().map(|b| b.as_slice().into()),
+        };
+        Ok(glb.to_vec()?)
+    }
+}
+
+/// Decodes the primitives `select(mesh, primitive)` picks and rewrites them as plain
+/// accessors, leaving every other primitive compressed and the rest of the document as is.
+///
+/// The primitive's accessors are redefined in place, keeping their names, unless an
+/// unselected primitive shares them. Draco bufferViews stay, now possibly unused; the
+/// extension is dropped from `extensionsUsed`/`extensionsRequired` once no primitive uses it.
+pub async fn strip_draco(
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    mut select: impl FnMut(usize, usize) -> bool,
+) -> Result<StrippedDocument, DracoLoadError> {
+    let mut decoded = Vec::new();
+    // accessors that must keep their current definition for unselected primitives
+    let mut shared = std::collections::HashSet::new();
+    for mesh in document.meshes() {
+        for p in mesh.primitives() {
+            if p.extension_value(EXTENSION).is_some() && select(mesh.index(), p.index()) {
+                let d = decode_draco_derived(&p, document, buffers).await?;
+                decoded.push((mesh.index(), p.index(), d));
+            } else {
+                shared.extend(p.attributes().map(|(_, a)| a.index()));
+                shared.extend(p.indices().map(|a| a.index()));
+            }
+        }
+    }
+
+    let (mut root, mut out) = editable(document, buffers);
+    for (mesh, primitive, d) in decoded {
+        splice_primitive(&mut root, &mut out[0], &shared, mesh, primitive, &d);
+    }
+    root.buffers[0].byte_length = USize64(out[0].len() as u64);
+
+    drop_unused_extension(&mut root);
+    Ok(StrippedDocument { root, buffers: out })
+}
+
+/// The document's JSON and buffer data, with a buffer 0 to append to.
+pub(crate) fn editable(
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+) -> (json::Root, Vec<Vec<u8>>) {
+    let mut root = document.clone().into_json();
+    let mut out: Vec<Vec<u8>> = buffers.iter().map(|b| b.0.clone()).collect();
+    if out.is_empty() {
+        out.push(Vec::new());
+        root.buffers.push(json::Buffer {
+            byte_length: USize64(0),
+            name: None,
+            uri: None,
+            extensions: None,
+            extras: Default::default(),
+        });
+    }
+    if let Some(len) = root.buffers.first().map(|b| b.byte_length.0 as usize) {
+        out[0].truncate(len);
+    }
+    (root, out)
+}
+
+/// Appends `d` to `bin` (buffer 0) and points primitive `primitive` of mesh `mesh` at it
+/// as plain accessors, removing its Draco extension. Returns the semantics written; buffer
+/// 0's byteLength is left to the caller.
+pub(crate) fn splice_primitive(
+    root: &mut json::Root,
+    bin: &mut Vec<u8>,
+    shared: &std::collections::HashSet<usize>,
+    mesh: usize,
+    primitive: usize,
+    d: &DecodedPrimitive,
+) -> Vec<Checked<gltf::Semantic>> {
+    while !bin.len().is_multiple_of(4) {
+        bin.push(0);
+    }
+    let base = bin.len() as u64;
+    let generated = build_accessors(d, 0, root.buffer_views.len() as u32);
+    bin.extend_from_slice(&generated.bytes);
+
+    root.buffer_views
+        .extend(generated.views.into_iter().map(|mut v| {
+            v.byte_offset = v.byte_offset.map(|o| USize64(o.0 + base));
+            v
+        }));
+
+    // overwrite the old accessors in place so none is left without a bufferView
+    let p = &root.meshes[mesh].primitives[primitive];
+    let old_indices = p.indices;
+    let old_attributes = p.attributes.clone();
+    let indices = generated.indices.map(|i| {
+        let new = generated.accessors[i].clone();
+        place(&mut root.accessors, shared, old_indices, new)
+    });
+    let attributes: Vec<_> = generated
+        .attributes
+        .into_iter()
+        .map(|(semantic, i)| {
+            let semantic = Checked::Valid(semantic);
+            let old = old_attributes.get(&semantic).copied();
+            let new = generated.accessors[i].clone();
+            (semantic, place(&mut root.accessors, shared, old, new))
+        })
+        .collect();
+
+    let written = attributes.iter().map(|(s, _)| s.clone()).collect();
+    let p = &mut root.meshes[mesh].primitives[primitive];
+    p.indices = indices;
+    p.attributes.extend(attributes);
+    if let Some(ext) = p.extensions.as_mut() {
+        ext.others.remove(EXTENSION);
+    }
+    written
+}
+
+/// Removes the Draco extension from `extensionsUsed`/`extensionsRequired` once no
+/// primitive uses it.
+pub(crate) fn drop_unused_extension(root: &mut json::Root) {
+    let still_used = root.meshes.iter().flat_map(|m| &m.primitives).any(|p| {
+        p.extensions
+            .as_ref()
+            .is_some_and(|e| e.others.contains_key(EXTENSION))
+    });
+    if !still_used {
+        root.extensions_used.retain(|e| e != EXTENSION);
+        root.extensions_required.retain(|e| e != EXTENSION);
+    }
+}
+
+/// Stores `new` over accessor `old` unless an unselected primitive still uses it.
+fn place(
+    accessors: &mut Vec<json::Accessor>,
+    shared: &std::collections::HashSet<usize>,
+    old: Option<json::Index<json::Accessor>>,
+    new: json::Accessor,
+) -> json::Index<json::Accessor> {
+    match old.map(|i| i.value()) {
+        Some(i) if i < accessors.len() && !shared.contains(&i) => {
+            let name = accessors[i].name.take();
+            accessors[i] = json::Accessor { name, ..new };
+            json::Index::new(i as u32)
+        }
+        _ => {
+            accessors.push(new);
+            json::Index::new(accessors.len() as u32 - 1)
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::*;
+
+/// Per-triangle result of [`analyze_texel_density`].
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct TriangleTexels {
+    /// Texels per model unit, `sqrt(texel area / surface area)`. Zero for triangles with
+    /// no UV area; infinite for triangles with no surface area.
+    pub density: f64,
+    /// Ratio of the largest to the smallest stretch of the UV-to-surface mapping; 1 means
+    /// no distortion, infinite for triangles degenerate in UV.
+    pub stretch: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct TexelAnalysis {
+    pub triangles: Vec<TriangleTexels>,
+    /// Median over triangles with a finite, non-zero density.
+    pub median_density: Option<f64>,
+}
+
+impl TexelAnalysis {
+    /// Triangles whose density is off the median by more than `density_factor` (either way)
+    /// or whose stretch exceeds `max_stretch`.
+    pub fn outliers(&self, density_factor: f64, max_stretch: f64) -> Vec<usize> {
+        self.triangles
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| {
+                let off = self.median_density.is_some_and(|m| {
+                    t.density * density_factor < m || t.density > m * density_factor
+                });
+                off || t.stretch > max_stretch
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Texel density and UV stretch of every triangle for texcoord set `set` mapped onto a
+/// `texture_size` (width, height) texture. `None` without positions or that set.
+pub fn analyze_texel_density(
+    d: &DecodedPrimitive,
+    set: u32,
+    texture_size: [u32; 2],
+) -> Option<TexelAnalysis> {
+    let positions = d.positions.as_deref()?;
+    let uvs = d.texcoords.get(&set)?;
+    let (w, h) = (texture_size[0] as f64, texture_size[1] as f64);
+
+    let triangles: Vec<TriangleTexels> = d
+        .indices
+        .chunks_exact(3)
+        .map(|tri| {
+            let idx = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+            let (Some(q), Some(uv)) = (
+                idx.iter()
+                    .map(|&i| positions.get(i).map(|p| p.map(f64::from)))
+                    .collect::<Option<Vec<[f64; 3]>>>(),
+                idx.iter()
+                    .map(|&i| uvs.get(i).map(|t| [t[0] as f64 * w, t[1] as f64 * h]))
+                    .collect::<Option<Vec<[f64; 2]>>>(),
+            ) else {
+                return TriangleTexels {
+                    density: 0.0,
+                    stretch: f64::INFINITY,
+                };
+            };
+            triangle_texels(&q, &uv)
+        })
+        .collect();
+
+    let mut densities: Vec<f64> = triangles
+        .iter()
+        .map(|t| t.density)
+        .filter(|d| d.is_finite() && *d > 0.0)
+        .collect();
+    densities.sort_by(f64::total_cmp);
+    let median_density = densities.get(densities.len() / 2).copied();
+    Some(TexelAnalysis {
+        triangles,
+        median_density,
+    })
+}
+
+/// `q` in model units, `uv` in texels. Stretch follows Sander et al., "Texture Mapping
+/// Progressive Meshes": the singular values of the texel-to-surface Jacobian.
+fn triangle_texels(q: &[[f64; 3]], uv: &[[f64; 2]]) -> TriangleTexels {
+    let sub = |a: [f64; 3], b: [f64; 3]| [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    let (e1, e2) = (sub(q[1], q[0]), sub(q[2], q[0]));
+    let cross = [
+        e1[1] * e2[2] - e1[2] * e2[1],
+        e1[2] * e2[0] - e1[0] * e2[2],
+        e1[0] * e2[1] - e1[1] * e2[0],
+    ];
+    let area = 0.5 * dot3(cross, cross).sqrt();
+
+    let [(s1, t1), (s2, t2), (s3, t3)] = [0, 1, 2].map(|i| (uv[i][0], uv[i][1]));
+    let uv_area2 = (s2 - s1) * (t3 - t1) - (s3 - s1) * (t2 - t1);
+    let density = (0.5 * uv_area2.abs() / area).sqrt();
+    if uv_area2 == 0.0 {
+        return TriangleTexels {
+            density,
+            stretch: f64::INFINITY,
+        };
+    }
+
+    let ss: [f64; 3] = std::array::from_fn(|k| {
+        (q[0][k] * (t2 - t3) + q[1][k] * (t3 - t1) + q[2][k] * (t1 - t2)) / uv_area2
+    });
+    let st: [f64; 3] = std::array::from_fn(|k| {
+        (q[0][k] * (s3 - s2) + q[1][k] * (s1 - s3) + q[2][k] * (s2 - s1)) / uv_area2
+    });
+    let (a, b, c) = (dot3(ss, ss), dot3(ss, st), dot3(st, st));
+    let root = ((a - c).powi(2) + 4.0 * b * b).sqrt();
+    let (big, small) = (
+        ((a + c + root) / 2.0).sqrt(),
+        ((a + c - root) / 2.0).max(0.0).sqrt(),
+    );
+    TriangleTexels {
+        density,
+        stretch: if small > 0.0 {
+            big / small
+        } else {
+            f64::INFINITY
+        },
+    }
+}
+
+fn dot3(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
@@ -0,0 +1,100 @@
+use crate::*;
+
+/// Per-semantic affine transforms for [`DecodeOptions::transforms`].
+pub type AttributeTransforms = std::collections::HashMap<gltf::Semantic, AttributeTransform>;
+
+/// `x * scale + offset` per component, applied while a stream is converted to `f32`, e.g.
+/// positions scaled from centimeters to meters or texcoords moved into an atlas slot.
+///
+/// Only the components the stream stores are touched: an RGB color keeps its alpha of 1 and
+/// a custom attribute with more than four components keeps the rest as decoded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttributeTransform {
+    pub scale: [f32; 4],
+    pub offset: [f32; 4],
+}
+
+impl Default for AttributeTransform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl AttributeTransform {
+    pub const IDENTITY: Self = Self {
+        scale: [1.0; 4],
+        offset: [0.0; 4],
+    };
+
+    pub fn new(scale: [f32; 4], offset: [f32; 4]) -> Self {
+        Self { scale, offset }
+    }
+
+    /// The same factor on every component.
+    pub fn uniform_scale(scale: f32) -> Self {
+        Self {
+            scale: [scale; 4],
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn with_offset(mut self, offset: [f32; 4]) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Transforms component `i` of a vertex.
+    #[inline]
+    pub fn apply(&self, i: usize, x: f32) -> f32 {
+        match (self.scale.get(i), self.offset.get(i)) {
+            (Some(s), Some(o)) => x * s + o,
+            _ => x,
+        }
+    }
+
+    fn apply_all<'a, const N: usize>(
+        &self,
+        values: impl IntoIterator<Item = &'a mut [f32; N]>,
+        stored: usize,
+    ) {
+        for v in values {
+            for (i, x) in v.iter_mut().enumerate().take(stored) {
+                *x = self.apply(i, *x);
+            }
+        }
+    }
+}
+
+impl DecodedPrimitive {
+    /// Applies `transforms` to streams read from `p`'s fallback accessors, which are
+    /// converted elsewhere; the Draco path transforms while converting.
+    pub(crate) fn transform_fallback(
+        &mut self,
+        p: &gltf::mesh::Primitive<'_>,
+        transforms: &AttributeTransforms,
+    ) {
+        for (sem, t) in transforms {
+            let Some(stored) = p.get(sem).map(|a| dims_count(a.dimensions())) else {
+                continue;
+            };
+            match sem {
+                gltf::Semantic::Positions => {
+                    t.apply_all(self.positions.iter_mut().flatten(), stored)
+                }
+                gltf::Semantic::Normals => t.apply_all(self.normals.iter_mut().flatten(), stored),
+                gltf::Semantic::Tangents => t.apply_all(self.tangents.iter_mut().flatten(), stored),
+                gltf::Semantic::TexCoords(set) => {
+                    t.apply_all(self.texcoords.get_mut(set).into_iter().flatten(), stored)
+                }
+                gltf::Semantic::Colors(set) => {
+                    t.apply_all(self.colors.get_mut(set).into_iter().flatten(), stored)
+                }
+                gltf::Semantic::Weights(set) => {
+                    t.apply_all(self.weights.get_mut(set).into_iter().flatten(), stored)
+                }
+                // joints aren't f32, and fallbacks carry no custom attributes
+                _ => {}
+            }
+        }
+    }
+}
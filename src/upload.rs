@@ -0,0 +1,101 @@
+use crate::*;
+
+/// Which GPU buffer an [`UploadChunk`] belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+pub enum UploadStream {
+    Indices,
+    Positions,
+    Normals,
+    Tangents,
+    TexCoords(u32),
+    Colors(u32),
+    Joints(u32),
+    Weights(u32),
+}
+
+/// A contiguous piece of one stream, to be written at `offset` bytes into its buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct UploadChunk<'a> {
+    pub stream: UploadStream,
+    pub offset: usize,
+    pub bytes: &'a [u8],
+}
+
+/// wgpu's `COPY_BUFFER_ALIGNMENT`; Vulkan and D3D12 staging copies have the same rule.
+const COPY_ALIGNMENT: usize = 4;
+
+impl DecodedPrimitive {
+    /// Splits every stream into chunks of at most `max_bytes`, for uploads through a fixed
+    /// size staging buffer.
+    ///
+    /// Chunk offsets and lengths are multiples of both the element size and the 4-byte
+    /// copy alignment, so no chunk splits a vertex or index. A budget smaller than that
+    /// unit splits elements at 4-byte boundaries instead, so chunks still fit it; only a
+    /// budget under the copy alignment itself yields 4-byte chunks. Indices are emitted as
+    /// `u32`.
+    pub fn upload_chunks(&self, max_bytes: usize) -> impl Iterator<Item = UploadChunk<'_>> {
+        self.upload_streams()
+            .into_iter()
+            .flat_map(move |(stream, bytes, elem)| {
+                let unit = lcm(elem, COPY_ALIGNMENT);
+                let chunk = if max_bytes >= unit {
+                    max_bytes / unit * unit
+                } else {
+                    (max_bytes / COPY_ALIGNMENT).max(1) * COPY_ALIGNMENT
+                };
+                bytes
+                    .chunks(chunk)
+                    .enumerate()
+                    .map(move |(i, bytes)| UploadChunk {
+                        stream,
+                        offset: i * chunk,
+                        bytes,
+                    })
+            })
+    }
+
+    /// Every stream as bytes with its element size: indices (`u32`) first, then the vertex
+    /// streams with sets in ascending order.
+    pub(crate) fn upload_streams(&self) -> Vec<(UploadStream, &[u8], usize)> {
+        let mut streams: Vec<(UploadStream, &[u8], usize)> = Vec::new();
+        streams.push((
+            UploadStream::Indices,
+            bytemuck::cast_slice(&self.indices),
+            4,
+        ));
+        if let Some(v) = &self.positions {
+            streams.push((UploadStream::Positions, bytemuck::cast_slice(v), 12));
+        }
+        if let Some(v) = &self.normals {
+            streams.push((UploadStream::Normals, bytemuck::cast_slice(v), 12));
+        }
+        if let Some(v) = &self.tangents {
+            streams.push((UploadStream::Tangents, bytemuck::cast_slice(v), 16));
+        }
+        for (set, v) in sorted(&self.texcoords) {
+            streams.push((UploadStream::TexCoords(set), bytemuck::cast_slice(v), 8));
+        }
+        for (set, v) in sorted(&self.colors) {
+            streams.push((UploadStream::Colors(set), bytemuck::cast_slice(v), 16));
+        }
+        for (set, v) in sorted(&self.joints) {
+            streams.push((UploadStream::Joints(set), bytemuck::cast_slice(v), 8));
+        }
+        for (set, v) in sorted(&self.weights) {
+            streams.push((UploadStream::Weights(set), bytemuck::cast_slice(v), 16));
+        }
+        streams
+    }
+}
+
+pub(crate) fn sorted<T>(map: &SetMap<Vec<T>>) -> Vec<(u32, &Vec<T>)> {
+    // SetMap already iterates in set order
+    map.iter().map(|(k, v)| (*k, v)).collect()
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    fn gcd(a: usize, b: usize) -> usize {
+        if b == 0 { a } else { gcd(b, a % b) }
+    }
+    a / gcd(a, b) * b
+}
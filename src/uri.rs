@@ -0,0 +1,202 @@
+use crate::*;
+
+use std::path::{Component, Path, PathBuf};
+
+/// Turns buffer and image URIs into file paths below a base directory.
+///
+/// Handles percent-encoding (including UTF-8 encoded non-ASCII names), `file:` URIs,
+/// Windows-style backslashes and drive letters. With [`sandboxed`](Self::sandboxed), URIs
+/// that are absolute or climb out of the base with `..` are rejected, for loading files
+/// whose names an attacker controls. Symlinks inside the base are not checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UriResolver {
+    pub base: PathBuf,
+    pub sandboxed: bool,
+}
+
+impl UriResolver {
+    pub fn new(base: impl Into<PathBuf>) -> Self {
+        Self {
+            base: base.into(),
+            sandboxed: false,
+        }
+    }
+
+    pub fn sandboxed(mut self, sandboxed: bool) -> Self {
+        self.sandboxed = sandboxed;
+        self
+    }
+
+    /// The file `uri` refers to. Data URIs and other schemes than `file:` are errors.
+    pub fn resolve(&self, uri: &str) -> Result<PathBuf, DracoLoadError> {
+        let bad = || DracoLoadError::BadUri(uri.to_string());
+        let (uri, file_scheme) = match uri.get(..5) {
+            Some(s) if s.eq_ignore_ascii_case("file:") => (&uri[5..], true),
+            _ => (uri, false),
+        };
+        if !file_scheme && has_scheme(uri) {
+            return Err(bad());
+        }
+        let mut path = percent_decode(uri).ok_or_else(bad)?.replace('\\', "/");
+        if file_scheme {
+            // file:///C:/x and file:///x; an authority other than localhost isn't a local file
+            let rest = path.strip_prefix("//").map(str::to_string);
+            if let Some(rest) = rest {
+                let (host, tail) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+                if !host.is_empty() && !host.eq_ignore_ascii_case("localhost") {
+                    return Err(bad());
+                }
+                path = tail.to_string();
+            }
+            if is_drive(path.trim_start_matches('/')) {
+                path = path.trim_start_matches('/').to_string();
+            }
+        }
+
+        let absolute = path.starts_with('/') || is_drive(&path);
+        if absolute {
+            return if self.sandboxed {
+                Err(bad())
+            } else {
+                Ok(PathBuf::from(path))
+            };
+        }
+
+        let mut rel = PathBuf::new();
+        for c in Path::new(&path).components() {
+            match c {
+                Component::Normal(part) => rel.push(part),
+                Component::CurDir => {}
+                Component::ParentDir if rel.file_name().is_some() => {
+                    rel.pop();
+                }
+                Component::ParentDir if !self.sandboxed => rel.push(".."),
+                _ => return Err(bad()),
+            }
+        }
+        Ok(self.base.join(rel))
+    }
+
+    /// Data for every buffer of `document`: the GLB `blob`, base64 data URIs, or files
+    /// resolved with [`resolve`](Self::resolve).
+    pub fn load_buffers(
+        &self,
+        document: &gltf::Document,
+        mut blob: Option<Vec<u8>>,
+    ) -> Result<Vec<gltf::buffer::Data>, DracoLoadError> {
+        let mut out = Vec::new();
+        for buffer in document.buffers() {
+            let data = match buffer.source() {
+                gltf::buffer::Source::Uri(uri) if !uri.starts_with("data:") => {
+                    std::fs::read(self.resolve(uri)?).map_err(gltf::Error::Io)?
+                }
+                _ => inline_buffer(&buffer, &mut blob)?,
+            };
+            out.push(finish_buffer(&buffer, data)?);
+        }
+        Ok(out)
+    }
+
+    /// [`load_buffers`](Self::load_buffers) reading files with `tokio::fs`.
+    #[cfg(feature = "tokio")]
+    pub async fn load_buffers_async(
+        &self,
+        document: &gltf::Document,
+        mut blob: Option<Vec<u8>>,
+    ) -> Result<Vec<gltf::buffer::Data>, DracoLoadError> {
+        let mut out = Vec::new();
+        for buffer in document.buffers() {
+            let data = match buffer.source() {
+                gltf::buffer::Source::Uri(uri) if !uri.starts_with("data:") => {
+                    tokio::fs::read(self.resolve(uri)?)
+                        .await
+                        .map_err(gltf::Error::Io)?
+                }
+                _ => inline_buffer(&buffer, &mut blob)?,
+            };
+            out.push(finish_buffer(&buffer, data)?);
+        }
+        Ok(out)
+    }
+}
+
+/// The GLB `blob` or a base64 data URI's bytes.
+pub(crate) fn inline_buffer(
+    buffer: &gltf::Buffer<'_>,
+    blob: &mut Option<Vec<u8>>,
+) -> Result<Vec<u8>, DracoLoadError> {
+    match buffer.source() {
+        gltf::buffer::Source::Bin => blob.take().ok_or(DracoLoadError::BadBuffer(buffer.index())),
+        gltf::buffer::Source::Uri(uri) => {
+            let bad = || DracoLoadError::BadUri(uri.to_string());
+            let (_, b64) = uri.split_once(";base64,").ok_or_else(bad)?;
+            base64_decode(b64).ok_or_else(bad)
+        }
+    }
+}
+
+pub(crate) fn finish_buffer(
+    buffer: &gltf::Buffer<'_>,
+    mut data: Vec<u8>,
+) -> Result<gltf::buffer::Data, DracoLoadError> {
+    if data.len() < buffer.length() {
+        return Err(DracoLoadError::BadBuffer(buffer.index()));
+    }
+    while !data.len().is_multiple_of(4) {
+        data.push(0);
+    }
+    Ok(gltf::buffer::Data(data))
+}
+
+fn has_scheme(uri: &str) -> bool {
+    // a scheme is at least two characters, so `C:` stays a drive letter
+    uri.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    })
+}
+
+fn is_drive(path: &str) -> bool {
+    let b = path.as_bytes();
+    b.len() >= 2 && b[0].is_ascii_alphabetic() && b[1] == b':' && (b.len() == 2 || b[2] == b'/')
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let mut out = Vec::with_capacity(s.len());
+    let mut bytes = s.bytes();
+    while let Some(b) = bytes.next() {
+        if b == b'%' {
+            let hex = [bytes.next()?, bytes.next()?];
+            out.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            out.push(b);
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    let (mut acc, mut bits) = (0u32, 0);
+    for c in s.bytes().filter(|c| !c.is_ascii_whitespace() && *c != b'=') {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
@@ -0,0 +1,63 @@
+use crate::*;
+
+impl DecodedPrimitive {
+    /// Shifts every UV chart of every texcoord set by whole texture repeats so its lower
+    /// corner lands in `[0, 1)`, returning how many charts moved.
+    ///
+    /// A chart is a set of vertices connected through triangles. Whole-number shifts
+    /// don't change what a `REPEAT` sampler fetches, but they bring UVs that drifted far
+    /// from the origin back to where `f32` (and later re-quantization) keeps full
+    /// precision. Don't use it with `CLAMP_TO_EDGE` or `MIRRORED_REPEAT` (odd shifts
+    /// mirror) samplers.
+    pub fn rebase_texcoords(&mut self) -> usize {
+        let Some(vertex_count) = self.texcoords.values().map(Vec::len).max() else {
+            return 0;
+        };
+        let charts = vertex_charts(&self.indices, vertex_count);
+
+        let mut moved = 0;
+        for uvs in self.texcoords.values_mut() {
+            let mut lo = vec![[f32::INFINITY; 2]; vertex_count];
+            for (v, uv) in uvs.iter().enumerate() {
+                let l = &mut lo[charts[v]];
+                *l = [l[0].min(uv[0]), l[1].min(uv[1])];
+            }
+            let shift: Vec<[f32; 2]> = lo
+                .iter()
+                .map(|l| l.map(|x| if x.is_finite() { x.floor() } else { 0.0 }))
+                .collect();
+            moved += shift.iter().filter(|s| **s != [0.0, 0.0]).count();
+            for (v, uv) in uvs.iter_mut().enumerate() {
+                let s = shift[charts[v]];
+                *uv = [uv[0] - s[0], uv[1] - s[1]];
+            }
+        }
+        moved
+    }
+}
+
+/// Chart id (the root vertex) of each vertex, from a union-find over triangle corners.
+/// Unreferenced vertices are charts of their own.
+fn vertex_charts(indices: &[u32], vertex_count: usize) -> Vec<usize> {
+    fn root(parent: &mut [usize], mut v: usize) -> usize {
+        while parent[v] != v {
+            parent[v] = parent[parent[v]];
+            v = parent[v];
+        }
+        v
+    }
+    let mut parent: Vec<usize> = (0..vertex_count).collect();
+    for tri in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| tri[k] as usize);
+        if a.max(b).max(c) >= vertex_count {
+            continue;
+        }
+        let ra = root(&mut parent, a);
+        for v in [b, c] {
+            let rv = root(&mut parent, v);
+            let ra = root(&mut parent, ra);
+            parent[rv] = ra;
+        }
+    }
+    (0..vertex_count).map(|v| root(&mut parent, v)).collect()
+}
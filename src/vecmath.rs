@@ -0,0 +1,25 @@
+pub(crate) fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub(crate) fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub(crate) fn length(v: [f32; 3]) -> f32 {
+    dot(v, v).sqrt()
+}
+
+/// `v` scaled to unit length; a zero vector is returned as is.
+pub(crate) fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let len = length(v);
+    if len > 0.0 { v.map(|x| x / len) } else { v }
+}
@@ -0,0 +1,63 @@
+use crate::*;
+
+use std::collections::HashMap;
+
+/// A [`DecodedPrimitive`]'s streams borrowed from wherever they live: the primitive's own
+/// vectors, or a spilled file mapped into memory (see
+/// [`DocumentIndex::decode_out_of_core`]).
+#[derive(Debug, Clone)]
+pub struct PrimitiveView<'a> {
+    pub indices: &'a [u32],
+    pub positions: Option<&'a [[f32; 3]]>,
+    pub normals: Option<&'a [[f32; 3]]>,
+    pub tangents: Option<&'a [[f32; 4]]>,
+    pub texcoords: SetMap<&'a [[f32; 2]]>,
+    pub colors: SetMap<&'a [[f32; 4]]>,
+    pub joints: SetMap<&'a [[u16; 4]]>,
+    pub weights: SetMap<&'a [[f32; 4]]>,
+    pub custom: &'a HashMap<String, CustomAttribute>,
+}
+
+impl DecodedPrimitive {
+    pub fn view(&self) -> PrimitiveView<'_> {
+        fn sets<T>(map: &SetMap<Vec<T>>) -> SetMap<&[T]> {
+            map.iter().map(|(set, v)| (*set, v.as_slice())).collect()
+        }
+        PrimitiveView {
+            indices: &self.indices,
+            positions: self.positions.as_deref(),
+            normals: self.normals.as_deref(),
+            tangents: self.tangents.as_deref(),
+            texcoords: sets(&self.texcoords),
+            colors: sets(&self.colors),
+            joints: sets(&self.joints),
+            weights: sets(&self.weights),
+            custom: &self.custom,
+        }
+    }
+}
+
+impl PrimitiveView<'_> {
+    pub fn vertex_count(&self) -> usize {
+        self.positions.map_or(0, <[_]>::len)
+    }
+
+    /// Copies the streams into an owned primitive.
+    pub fn to_decoded(&self) -> DecodedPrimitive {
+        fn sets<T: Clone>(map: &SetMap<&[T]>) -> SetMap<Vec<T>> {
+            map.iter().map(|(set, v)| (*set, v.to_vec())).collect()
+        }
+        DecodedPrimitive {
+            indices: self.indices.to_vec(),
+            positions: self.positions.map(<[_]>::to_vec),
+            normals: self.normals.map(<[_]>::to_vec),
+            tangents: self.tangents.map(<[_]>::to_vec),
+            texcoords: sets(&self.texcoords),
+            colors: sets(&self.colors),
+            joints: sets(&self.joints),
+            weights: sets(&self.weights),
+            custom: self.custom.clone(),
+            areas: None,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::*;
+
+/// Callbacks for [`DecodedScene::visit`], so an ECS-based engine (hecs, bevy_ecs, flecs
+/// bindings, ...) can populate its world from a decoded scene without this crate knowing
+/// about any of them. Every method defaults to doing nothing.
+///
+/// Nodes arrive in hierarchy order, parents before their children, so an engine can map
+/// [`SceneNode::parent`] to the entity it spawned for it. The node's other callbacks follow
+/// its [`node`](Self::node) call and come before the next node's.
+pub trait SceneVisitor {
+    fn node(&mut self, _node: &SceneNode) {}
+
+    /// The node's local and world transforms, column-major as in glTF.
+    fn transform(&mut self, _node: &SceneNode, _local: &Mat4, _world: &Mat4) {}
+
+    /// One decoded primitive of the node's mesh. `handle` identifies the primitive across
+    /// instances; `first_use` is set the first time it is seen, so the engine can create
+    /// the mesh asset once and share it.
+    fn mesh(
+        &mut self,
+        _node: &SceneNode,
+        _handle: MeshHandle,
+        _primitive: &ScenePrimitive,
+        _first_use: bool,
+    ) {
+    }
+
+    /// The skin the node's mesh is bound to. Its joints may be visited later than the node.
+    fn skin(&mut self, _node: &SceneNode, _skin: &SceneSkin) {}
+
+    fn camera(&mut self, _node: &SceneNode, _camera: &SceneCamera) {}
+
+    fn light(&mut self, _node: &SceneNode, _light: &SceneLight) {}
+}
+
+/// Identifies a decoded primitive shared by every node instancing its mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MeshHandle {
+    pub mesh: usize,
+    pub primitive: usize,
+}
+
+impl DecodedScene {
+    /// Walks the scene, calling `visitor` for every node and what is attached to it.
+    pub fn visit(&self, visitor: &mut impl SceneVisitor) {
+        use std::collections::HashMap;
+        // index everything by node once, so the walk stays linear in the scene's size
+        let mut primitives: HashMap<usize, Vec<&ScenePrimitive>> = HashMap::new();
+        for p in &self.primitives {
+            primitives.entry(p.node).or_default().push(p);
+        }
+        let mut cameras = HashMap::new();
+        for c in &self.cameras {
+            cameras.entry(c.node).or_insert(c);
+        }
+        let mut lights = HashMap::new();
+        for l in &self.lights {
+            lights.entry(l.node).or_insert(l);
+        }
+        let mut skins = HashMap::new();
+        for s in &self.skins {
+            skins.entry(s.index).or_insert(s);
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for node in &self.nodes {
+            visitor.node(node);
+            visitor.transform(node, &node.transform, &node.world_transform);
+            for p in primitives.get(&node.index).into_iter().flatten() {
+                let handle = MeshHandle {
+                    mesh: p.mesh,
+                    primitive: p.primitive,
+                };
+                visitor.mesh(node, handle, p, seen.insert(handle));
+            }
+            if let Some(skin) = node.skin.and_then(|i| skins.get(&i)) {
+                visitor.skin(node, skin);
+            }
+            if let Some(camera) = cameras.get(&node.index) {
+                visitor.camera(node, camera);
+            }
+            if let Some(light) = lights.get(&node.index) {
+                visitor.light(node, light);
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use crate::*;
+
+use std::collections::BTreeSet;
+
+/// Most lattice cells [`DecodedPrimitive::voxelize`] lets a mesh's bounds span, 256³.
+pub const MAX_VOXEL_CELLS: u64 = 1 << 24;
+
+/// Sparse voxel occupancy from [`DecodedPrimitive::voxelize`].
+///
+/// Voxel `[i, j, k]` spans `[i, j, k] * voxel_size` to `[i + 1, j + 1, k + 1] * voxel_size`,
+/// the same world-aligned lattice as [`DecodedPrimitive::split_by_grid`], so grids of
+/// several primitives with the same size can be merged by set union.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VoxelGrid {
+    pub voxel_size: f32,
+    pub occupied: BTreeSet<[i32; 3]>,
+}
+
+impl VoxelGrid {
+    pub fn len(&self) -> usize {
+        self.occupied.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.occupied.is_empty()
+    }
+
+    pub fn contains(&self, voxel: [i32; 3]) -> bool {
+        self.occupied.contains(&voxel)
+    }
+
+    /// Center of `voxel` in mesh space.
+    pub fn center(&self, voxel: [i32; 3]) -> [f32; 3] {
+        voxel.map(|i| (i as f32 + 0.5) * self.voxel_size)
+    }
+
+    /// Smallest and largest occupied voxel per axis, `None` for an empty grid.
+    pub fn voxel_bounds(&self) -> Option<([i32; 3], [i32; 3])> {
+        let mut it = self.occupied.iter();
+        let first = *it.next()?;
+        Some(it.fold((first, first), |(lo, hi), v| {
+            (
+                [0, 1, 2].map(|k| lo[k].min(v[k])),
+                [0, 1, 2].map(|k| hi[k].max(v[k])),
+            )
+        }))
+    }
+
+    /// Marks every voxel enclosed by the surface as occupied, returning how many were added.
+    ///
+    /// Flood-fills the empty space from outside the bounds; whatever the flood can't reach
+    /// is inside. A surface with holes wider than a voxel leaks and fills nothing.
+    pub fn fill_interior(&mut self) -> usize {
+        let Some((lo, hi)) = self.voxel_bounds() else {
+            return 0;
+        };
+        // one voxel of padding so the flood can go around the whole shape
+        let lo = lo.map(|x| x - 1);
+        let dims = [0, 1, 2].map(|k| (hi[k] + 2 - lo[k]) as usize);
+        let at = |v: [usize; 3]| (v[2] * dims[1] + v[1]) * dims[0] + v[0];
+        let mut outside = vec![false; dims[0] * dims[1] * dims[2]];
+        let mut stack = vec![[0usize; 3]];
+        outside[0] = true;
+        while let Some(v) = stack.pop() {
+            for k in 0..3 {
+                for step in [-1isize, 1] {
+                    let Some(x) = v[k].checked_add_signed(step).filter(|&x| x < dims[k]) else {
+                        continue;
+                    };
+                    let mut n = v;
+                    n[k] = x;
+                    let cell = [0, 1, 2].map(|a| lo[a] + n[a] as i32);
+                    if !outside[at(n)] && !self.occupied.contains(&cell) {
+                        outside[at(n)] = true;
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+
+        let before = self.occupied.len();
+        for z in 0..dims[2] {
+            for y in 0..dims[1] {
+                for x in 0..dims[0] {
+                    if !outside[at([x, y, z])] {
+                        let cell = [lo[0] + x as i32, lo[1] + y as i32, lo[2] + z as i32];
+                        self.occupied.insert(cell);
+                    }
+                }
+            }
+        }
+        self.occupied.len() - before
+    }
+}
+
+impl DecodedPrimitive {
+    /// Voxelizes the surface into cubes of edge `resolution` (in mesh units), marking every
+    /// voxel a triangle touches, even at a corner, so thin walls stay closed. Call
+    /// [`VoxelGrid::fill_interior`] for solid occupancy.
+    ///
+    /// Without positions, or with a non-positive `resolution`, the grid is empty. A
+    /// `resolution` so fine that the bounds would span more than [`MAX_VOXEL_CELLS`] is
+    /// doubled until they fit; [`VoxelGrid::voxel_size`] holds the size used.
+    pub fn voxelize(&self, resolution: f32) -> VoxelGrid {
+        let mut grid = VoxelGrid {
+            voxel_size: resolution,
+            occupied: BTreeSet::new(),
+        };
+        let Some(pos) = self.positions.as_deref() else {
+            return grid;
+        };
+        if !(resolution > 0.0 && resolution.is_finite()) {
+            return grid;
+        }
+        let mut resolution = resolution;
+        let finite = pos.iter().filter(|p| p.iter().all(|x| x.is_finite()));
+        if let Some((lo, hi)) = finite.clone().next().map(|&p| {
+            finite.fold((p, p), |(lo, hi), p| {
+                (
+                    [0, 1, 2].map(|k| lo[k].min(p[k])),
+                    [0, 1, 2].map(|k| hi[k].max(p[k])),
+                )
+            })
+        }) {
+            let cells = |r: f32| -> f64 {
+                (0..3)
+                    .map(|k| ((hi[k] / r).floor() as f64 - (lo[k] / r).floor() as f64) + 1.0)
+                    .product()
+            };
+            while cells(resolution) > MAX_VOXEL_CELLS as f64 {
+                resolution *= 2.0;
+                if !resolution.is_finite() {
+                    return grid;
+                }
+            }
+            grid.voxel_size = resolution;
+        }
+
+        for tri in self.indices.chunks_exact(3) {
+            let Some(t) = tri
+                .iter()
+                .map(|&i| pos.get(i as usize).copied())
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            let t = [t[0], t[1], t[2]].map(|p| p.map(|x| x / resolution));
+            let lo = [0, 1, 2].map(|k| t.iter().fold(f32::INFINITY, |a, p| a.min(p[k])));
+            let hi = [0, 1, 2].map(|k| t.iter().fold(f32::NEG_INFINITY, |a, p| a.max(p[k])));
+            if lo.iter().chain(&hi).any(|x| !x.is_finite()) {
+                continue;
+            }
+            let lo = lo.map(|x| x.floor() as i32);
+            let hi = hi.map(|x| x.floor() as i32);
+            for z in lo[2]..=hi[2] {
+                for y in lo[1]..=hi[1] {
+                    for x in lo[0]..=hi[0] {
+                        let center = [x, y, z].map(|i| i as f32 + 0.5);
+                        if triangle_overlaps_box(&t, center, 0.5) {
+                            grid.occupied.insert([x, y, z]);
+                        }
+                    }
+                }
+            }
+        }
+        grid
+    }
+}
+
+/// Separating-axis test of a triangle against the cube at `center` with half-edge `half`
+/// (Akenine-Möller): the box axes, the triangle normal and the nine edge cross products.
+fn triangle_overlaps_box(t: &[[f32; 3]; 3], center: [f32; 3], half: f32) -> bool {
+    // a little slack so faces lying exactly on a voxel boundary mark both sides
+    let half = half * (1.0 + 1e-5);
+    let v = t.map(|p| [0, 1, 2].map(|k| p[k] - center[k]));
+    let e = [0, 1, 2].map(|i| [0, 1, 2].map(|k| v[(i + 1) % 3][k] - v[i][k]));
+    let separated = |axis: [f32; 3]| {
+        let proj = v.map(|p| dot(p, axis));
+        let r = half * (axis[0].abs() + axis[1].abs() + axis[2].abs());
+        let lo = proj.iter().fold(f32::INFINITY, |a, &x| a.min(x));
+        let hi = proj.iter().fold(f32::NEG_INFINITY, |a, &x| a.max(x));
+        lo > r || hi < -r
+    };
+    let box_axes = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    if box_axes.iter().any(|&a| separated(a)) {
+        return false;
+    }
+    if separated(cross(e[0], e[1])) {
+        return false;
+    }
+    !box_axes
+        .iter()
+        .any(|&a| e.iter().any(|&edge| separated(cross(a, edge))))
+}
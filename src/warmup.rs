@@ -0,0 +1,54 @@
+use crate::*;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static READY: AtomicBool = AtomicBool::new(false);
+
+/// A 4x4 grid with positions, texcoords and normals: 25 vertices, 32 triangles.
+const WARMUP_STREAM: &[u8] = include_bytes!("../examples/streams/grid_edgebreaker.drc");
+
+/// Pays the backend's one-time setup cost (WASM module instantiation on the web, allocator
+/// and table setup natively) by decoding a tiny embedded mesh, so the first real request
+/// doesn't. Safe to call repeatedly; later calls return immediately.
+///
+/// Fails with [`DracoDecode`](DracoLoadError::DracoDecode), leaving the decoder not ready,
+/// if the backend can't decode that mesh.
+pub async fn init_decoder() -> Result<(), DracoLoadError> {
+    if decoder_ready() {
+        return Ok(());
+    }
+    let infos = stream_attr_infos(WARMUP_STREAM).ok_or(DracoLoadError::DracoDecode)?;
+    let cfg = decode_config(25, 96, &infos);
+    draco_decoder::decode_mesh(WARMUP_STREAM, &cfg)
+        .await
+        .filter(|raw| raw.len() == cfg.estimate_buffer_size())
+        .ok_or(DracoLoadError::DracoDecode)?;
+    mark_ready();
+    Ok(())
+}
+
+/// Whether [`init_decoder`] or any decode has succeeded.
+pub fn decoder_ready() -> bool {
+    READY.load(Ordering::Acquire)
+}
+
+pub(crate) fn mark_ready() {
+    READY.store(true, Ordering::Release);
+}
+
+/// Decoder state for service health checks, serializable as a JSON response body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct DecoderHealth {
+    pub ready: bool,
+    pub decodes_in_flight: usize,
+    /// The crate-wide cap from [`set_max_concurrent_decodes`].
+    pub max_concurrent_decodes: Option<usize>,
+}
+
+pub fn decoder_health() -> DecoderHealth {
+    DecoderHealth {
+        ready: decoder_ready(),
+        decodes_in_flight: decodes_in_flight(),
+        max_concurrent_decodes: max_concurrent_decodes(),
+    }
+}
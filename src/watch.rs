@@ -0,0 +1,111 @@
+use crate::*;
+use std::hash::{Hash, Hasher};
+
+/// Re-decodes the Draco primitives of a glTF/GLB file whenever their data changes on disk.
+///
+/// The reloader polls the file's modification time; call [`HotReloader::poll`] from your own
+/// timer or file-watcher callback. Only primitives whose compressed bytes or index entry
+/// changed since the previous poll are decoded again.
+pub struct HotReloader {
+    path: std::path::PathBuf,
+    modified: Option<std::time::SystemTime>,
+    primitives: Vec<(IndexedPrimitive, u64)>, // entry + hash of its compressed bytes
+    options: DecodeOptions,
+}
+
+#[derive(Debug, Clone)]
+pub enum ReloadEvent {
+    Changed {
+        mesh: usize,
+        primitive: usize,
+        decoded: Box<DecodedPrimitive>,
+    },
+    Removed {
+        mesh: usize,
+        primitive: usize,
+    },
+}
+
+impl HotReloader {
+    /// The first [`poll`](Self::poll) reports every Draco primitive as changed.
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            modified: None,
+            primitives: Vec::new(),
+            options: DecodeOptions::default(),
+        }
+    }
+
+    /// Options for every decode, see [`IndexedPrimitive::decode`].
+    pub fn with_options(mut self, options: DecodeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    pub async fn poll(&mut self) -> Result<Vec<ReloadEvent>, DracoLoadError> {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|m| m.modified())
+            .map_err(gltf::Error::Io)?;
+        if self.modified == Some(modified) {
+            return Ok(Vec::new());
+        }
+
+        // Draco files fail gltf's validation (accessors without bufferViews), so skip it
+        let file = std::fs::File::open(&self.path).map_err(gltf::Error::Io)?;
+        let gltf = gltf::Gltf::from_reader_without_validation(std::io::BufReader::new(file))?;
+        let base = self.path.parent().unwrap_or(std::path::Path::new("."));
+        let buffers = UriResolver::new(base).load_buffers(&gltf.document, gltf.blob)?;
+        let index = DocumentIndex::build(&gltf.document)?;
+
+        let mut current = Vec::with_capacity(index.primitives.len());
+        let mut events = Vec::new();
+        for entry in index.primitives {
+            let hash = view_hash(&entry, &buffers)?;
+            let unchanged = self
+                .primitives
+                .iter()
+                .any(|(old, old_hash)| *old == entry && *old_hash == hash);
+            if !unchanged {
+                events.push(ReloadEvent::Changed {
+                    mesh: entry.mesh,
+                    primitive: entry.primitive,
+                    decoded: Box::new(entry.decode(&buffers, &self.options).await?),
+                });
+            }
+            current.push((entry, hash));
+        }
+        for (old, _) in &self.primitives {
+            if !current
+                .iter()
+                .any(|(e, _)| e.mesh == old.mesh && e.primitive == old.primitive)
+            {
+                events.push(ReloadEvent::Removed {
+                    mesh: old.mesh,
+                    primitive: old.primitive,
+                });
+            }
+        }
+
+        self.modified = Some(modified);
+        self.primitives = current;
+        Ok(events)
+    }
+}
+
+fn view_hash(
+    entry: &IndexedPrimitive,
+    buffers: &[gltf::buffer::Data],
+) -> Result<u64, DracoLoadError> {
+    let buf = buffers
+        .get(entry.buffer)
+        .ok_or(DracoLoadError::BadBuffer(entry.buffer))?;
+    let bytes = view_bytes(buf, entry.buffer_view, entry.byte_offset, entry.byte_length)?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    Ok(hasher.finish())
+}
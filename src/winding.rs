@@ -0,0 +1,82 @@
+use crate::*;
+
+/// Agreement between triangle winding and stored vertex normals, from
+/// [`DecodedPrimitive::winding_report`].
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct WindingReport {
+    pub triangles: usize,
+    /// Triangles with non-zero area whose corner normals don't cancel out; the rest can't
+    /// be judged and count as neither agreeing nor flipped.
+    pub checked: usize,
+    /// Indices (in triangles, not index-buffer slots) of faces whose geometric normal
+    /// points away from their averaged vertex normals.
+    pub flipped: Vec<usize>,
+}
+
+impl WindingReport {
+    /// Share of checked triangles that are flipped, 0 to 100.
+    pub fn flipped_percent(&self) -> f64 {
+        if self.checked == 0 {
+            0.0
+        } else {
+            self.flipped.len() as f64 * 100.0 / self.checked as f64
+        }
+    }
+}
+
+impl DecodedPrimitive {
+    /// Compares each triangle's counter-clockwise face normal with the mean of its three
+    /// stored normals; a negative dot product means the face was flipped by an exporter
+    /// or a compression round trip. `None` without positions or normals.
+    pub fn winding_report(&self) -> Option<WindingReport> {
+        let positions = self.positions.as_deref()?;
+        let normals = self.normals.as_deref()?;
+        let mut report = WindingReport {
+            triangles: self.indices.len() / 3,
+            ..Default::default()
+        };
+        for (t, tri) in self.indices.chunks_exact(3).enumerate() {
+            let [a, b, c] = [0, 1, 2].map(|k| tri[k] as usize);
+            let (Some(pa), Some(pb), Some(pc)) =
+                (positions.get(a), positions.get(b), positions.get(c))
+            else {
+                continue;
+            };
+            let e1 = [0, 1, 2].map(|k| pb[k] - pa[k]);
+            let e2 = [0, 1, 2].map(|k| pc[k] - pa[k]);
+            let face = [
+                e1[1] * e2[2] - e1[2] * e2[1],
+                e1[2] * e2[0] - e1[0] * e2[2],
+                e1[0] * e2[1] - e1[1] * e2[0],
+            ];
+            let mut stored = [0.0f32; 3];
+            for n in [a, b, c].iter().filter_map(|&i| normals.get(i)) {
+                for k in 0..3 {
+                    stored[k] += n[k];
+                }
+            }
+            let dot: f32 = (0..3).map(|k| face[k] * stored[k]).sum();
+            if dot == 0.0 || !dot.is_finite() {
+                continue;
+            }
+            report.checked += 1;
+            if dot < 0.0 {
+                report.flipped.push(t);
+            }
+        }
+        Some(report)
+    }
+
+    /// Reverses the winding of every triangle [`winding_report`](Self::winding_report)
+    /// finds flipped, trusting the normals over the index order. Returns how many faces
+    /// were flipped.
+    pub fn fix_winding_from_normals(&mut self) -> usize {
+        let Some(report) = self.winding_report() else {
+            return 0;
+        };
+        for &t in &report.flipped {
+            self.indices.swap(t * 3 + 1, t * 3 + 2);
+        }
+        report.flipped.len()
+    }
+}